@@ -0,0 +1,316 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use helix_storage::cpi::accounts::{CreateShare, RegisterFile, RevokeShare};
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::program::HelixStorage;
+use helix_storage::state::StorageLocationArg;
+use helix_storage::types::{MimeType, TxId};
+
+declare_id!("EBeVAmd3xgDrhoBXh9Zgm2xFD6XpEKzv4TNj1re5N1UH");
+
+/// Seed of the vault PDA through which this program owns Helix files
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Derive the vault PDA
+pub fn find_vault_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[VAULT_SEED], &crate::ID)
+}
+
+/// Example of a third-party program driving Helix through CPI.
+/// 
+/// Instructions build their calls with the `cpi` module Helix exports under
+/// its `cpi` feature, forwarding the accounts they are passed. Helix applies
+/// its registry's CPI policy to them, identifying this program as the caller
+/// through the instructions sysvar.
+/// 
+/// The vault instructions show a program owning files itself: its vault PDA
+/// is the Helix owner, and signs each call with `invoke_signed`.
+#[program]
+pub mod helix_caller_example {
+    use super::*;
+
+    /// Register an unencrypted Arweave file owned by the signer.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the Helix accounts and the owner
+    /// * `transaction_id` - Arweave transaction ID of the file
+    /// * `mime_type` - MIME type of the file
+    /// * `size` - File size in bytes
+    pub fn register_file(
+        ctx: Context<ForwardRegisterFile>,
+        transaction_id: TxId,
+        mime_type: MimeType,
+        size: u64,
+    ) -> Result<()> {
+        let accounts = RegisterFile {
+            registry: ctx.accounts.registry.to_account_info(),
+            file_record: ctx.accounts.file_record.to_account_info(),
+            owner: ctx.accounts.owner.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            memo_program: None,
+            registry_ext: None,
+            receipt_merkle_tree: None,
+            receipt_tree_authority: None,
+            bubblegum_program: None,
+            log_wrapper: None,
+            compression_program: None,
+            source_record: None,
+            user_profile: None,
+            treasury: None,
+            instructions: Some(ctx.accounts.instructions.to_account_info()),
+            event_authority: ctx.accounts.helix_event_authority.to_account_info(),
+            program: ctx.accounts.helix_program.to_account_info(),
+        };
+
+        helix_storage::cpi::register_file(
+            CpiContext::new(ctx.accounts.helix_program.to_account_info(), accounts),
+            transaction_id,
+            None,
+            mime_type,
+            size,
+            false,
+            None,
+            StorageLocationArg::ArweaveL1,
+        )
+    }
+
+    /// Register an unencrypted Arweave file owned by the vault, which pays
+    /// for the record.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the Helix accounts and the vault
+    /// * `transaction_id` - Arweave transaction ID of the file
+    /// * `mime_type` - MIME type of the file
+    /// * `size` - File size in bytes
+    pub fn register_vault_file(
+        ctx: Context<VaultRegisterFile>,
+        transaction_id: TxId,
+        mime_type: MimeType,
+        size: u64,
+    ) -> Result<()> {
+        let accounts = RegisterFile {
+            registry: ctx.accounts.registry.to_account_info(),
+            file_record: ctx.accounts.file_record.to_account_info(),
+            owner: ctx.accounts.vault.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            memo_program: None,
+            registry_ext: None,
+            receipt_merkle_tree: None,
+            receipt_tree_authority: None,
+            bubblegum_program: None,
+            log_wrapper: None,
+            compression_program: None,
+            source_record: None,
+            user_profile: None,
+            treasury: None,
+            instructions: None,
+            event_authority: ctx.accounts.helix_event_authority.to_account_info(),
+            program: ctx.accounts.helix_program.to_account_info(),
+        };
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+        helix_storage::cpi::register_file(
+            CpiContext::new_with_signer(
+                ctx.accounts.helix_program.to_account_info(),
+                accounts,
+                signer_seeds,
+            ),
+            transaction_id,
+            None,
+            mime_type,
+            size,
+            false,
+            None,
+            StorageLocationArg::ArweaveL1,
+        )
+    }
+
+    /// Share a file owned by the vault, which pays for the link.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the Helix accounts and the vault
+    /// * `args` - Terms of the share link
+    pub fn share_vault_file(ctx: Context<VaultShareFile>, args: CreateShareArgs) -> Result<()> {
+        let accounts = CreateShare {
+            registry: ctx.accounts.registry.to_account_info(),
+            file_record: ctx.accounts.file_record.to_account_info(),
+            share_link: ctx.accounts.share_link.to_account_info(),
+            owner: ctx.accounts.vault.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            memo_program: None,
+            recipient_profile: None,
+            inbox_entry: None,
+            contact: None,
+            owner_profile: ctx.accounts.vault_profile.to_account_info(),
+            access_log: None,
+            template: None,
+            instructions: None,
+            owner_token_account: None,
+            event_authority: ctx.accounts.helix_event_authority.to_account_info(),
+            program: ctx.accounts.helix_program.to_account_info(),
+        };
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+        helix_storage::cpi::create_share(
+            CpiContext::new_with_signer(
+                ctx.accounts.helix_program.to_account_info(),
+                accounts,
+                signer_seeds,
+            ),
+            args,
+        )
+    }
+
+    /// Revoke a share link created by the vault.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the Helix accounts and the vault
+    pub fn revoke_vault_share(ctx: Context<VaultRevokeShare>) -> Result<()> {
+        let accounts = RevokeShare {
+            registry: ctx.accounts.registry.to_account_info(),
+            file_record: ctx.accounts.file_record.to_account_info(),
+            share_link: ctx.accounts.share_link.to_account_info(),
+            owner: ctx.accounts.vault.to_account_info(),
+            recipient_profile: None,
+            notification: None,
+            system_program: None,
+            event_authority: ctx.accounts.helix_event_authority.to_account_info(),
+            program: ctx.accounts.helix_program.to_account_info(),
+        };
+        let signer_seeds: &[&[&[u8]]] = &[&[VAULT_SEED, &[ctx.bumps.vault]]];
+
+        helix_storage::cpi::revoke_share(CpiContext::new_with_signer(
+            ctx.accounts.helix_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        ))
+    }
+}
+
+/// Accounts for registering a Helix file through CPI
+#[derive(Accounts)]
+pub struct ForwardRegisterFile<'info> {
+    /// The Helix storage registry
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub registry: UncheckedAccount<'info>,
+
+    /// The Helix file record to create
+    /// CHECK: Created and validated by Helix
+    #[account(mut)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The file owner (pays for the record)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Instructions sysvar, from which Helix identifies this program
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Helix's event authority, which signs its self-CPI events
+    /// CHECK: Validated by Helix
+    pub helix_event_authority: UncheckedAccount<'info>,
+
+    /// The Helix Storage program
+    pub helix_program: Program<'info, HelixStorage>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for registering a Helix file owned by the vault
+#[derive(Accounts)]
+pub struct VaultRegisterFile<'info> {
+    /// The Helix storage registry
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub registry: UncheckedAccount<'info>,
+
+    /// The Helix file record to create
+    /// CHECK: Created and validated by Helix
+    #[account(mut)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The vault, owner of the file (pays for the record)
+    #[account(mut, seeds = [VAULT_SEED], bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// Helix's event authority, which signs its self-CPI events
+    /// CHECK: Validated by Helix
+    pub helix_event_authority: UncheckedAccount<'info>,
+
+    /// The Helix Storage program
+    pub helix_program: Program<'info, HelixStorage>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for sharing a Helix file owned by the vault
+#[derive(Accounts)]
+pub struct VaultShareFile<'info> {
+    /// The Helix storage registry
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub registry: UncheckedAccount<'info>,
+
+    /// The vault's Helix file record
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The Helix share link to create
+    /// CHECK: Created and validated by Helix
+    #[account(mut)]
+    pub share_link: UncheckedAccount<'info>,
+
+    /// The vault, owner of the file (pays for the link)
+    #[account(mut, seeds = [VAULT_SEED], bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// The vault's Helix user profile, if it has one
+    /// CHECK: Validated by Helix
+    pub vault_profile: UncheckedAccount<'info>,
+
+    /// Helix's event authority, which signs its self-CPI events
+    /// CHECK: Validated by Helix
+    pub helix_event_authority: UncheckedAccount<'info>,
+
+    /// The Helix Storage program
+    pub helix_program: Program<'info, HelixStorage>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for revoking a Helix share link created by the vault
+#[derive(Accounts)]
+pub struct VaultRevokeShare<'info> {
+    /// The Helix storage registry
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub registry: UncheckedAccount<'info>,
+
+    /// The vault's Helix file record
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The Helix share link to revoke
+    /// CHECK: Validated by Helix
+    #[account(mut)]
+    pub share_link: UncheckedAccount<'info>,
+
+    /// The vault, owner of the share
+    #[account(mut, seeds = [VAULT_SEED], bump)]
+    pub vault: SystemAccount<'info>,
+
+    /// Helix's event authority, which signs its self-CPI events
+    /// CHECK: Validated by Helix
+    pub helix_event_authority: UncheckedAccount<'info>,
+
+    /// The Helix Storage program
+    pub helix_program: Program<'info, HelixStorage>,
+}
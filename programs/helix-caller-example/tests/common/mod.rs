@@ -0,0 +1,72 @@
+// Each test binary uses only some of these helpers
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData};
+use helix_storage::client::{
+    find_event_authority_address, find_file_address, find_registry_address,
+};
+use helix_storage::test_utils::HelixTestContext;
+use helix_storage::types::{MimeType, TxId};
+use solana_program_test::processor;
+use solana_sdk::signature::{Keypair, Signer};
+
+// Shared by the caller's integration tests: a bank running Helix and this
+// program, and instructions driving Helix through it.
+
+/// Run the caller's entrypoint, leaking the accounts as the Helix harness does
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    helix_caller_example::entry(program_id, accounts, data)
+}
+
+/// A bank running Helix and the caller program
+pub async fn start() -> HelixTestContext {
+    let mut program_test = HelixTestContext::program_test();
+    program_test.add_program(
+        "helix_caller_example",
+        helix_caller_example::ID,
+        processor!(process_instruction),
+    );
+    HelixTestContext::start_with(program_test).await
+}
+
+/// A caller `register_file` instruction registering `transaction_id` for `owner`
+pub fn register_through_caller_ix(owner: &Pubkey, transaction_id: &TxId, size: u64) -> Instruction {
+    Instruction {
+        program_id: helix_caller_example::ID,
+        accounts: helix_caller_example::accounts::ForwardRegisterFile {
+            registry: find_registry_address().0,
+            file_record: find_file_address(transaction_id).0,
+            owner: *owner,
+            instructions: sysvar::instructions::ID,
+            helix_event_authority: find_event_authority_address().0,
+            helix_program: helix_storage::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: helix_caller_example::instruction::RegisterFile {
+            transaction_id: transaction_id.clone(),
+            mime_type: MimeType::try_from("text/plain".to_string()).unwrap(),
+            size,
+        }
+        .data(),
+    }
+}
+
+/// Register a fresh file for `owner` through the caller
+pub async fn register_through_caller(
+    test: &mut HelixTestContext,
+    owner: &Keypair,
+) -> std::result::Result<(), solana_program_test::BanksClientError> {
+    let transaction_id = test.next_transaction_id();
+    let register = register_through_caller_ix(&owner.pubkey(), &transaction_id, 1024);
+    test.process(&[register], &[owner]).await
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use helix_storage::error::HelixError;
+use helix_storage::state::CpiPolicy;
+use helix_storage::test_utils::{custom_error, HelixTestContext};
+
+mod common;
+
+use common::{register_through_caller, start};
+
+/// Set the registry's CPI policy as its authority
+async fn set_cpi_policy(test: &mut HelixTestContext, policy: CpiPolicy, allowlist: Vec<Pubkey>) {
+    let set_policy =
+        test.authority_ix(helix_storage::instruction::SetCpiPolicy { policy, allowlist });
+    test.process(&[set_policy], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn open_policy_admits_any_caller() {
+    let mut test = start().await;
+    let owner = test.funded_wallet().await;
+
+    register_through_caller(&mut test, &owner).await.unwrap();
+    assert_eq!(test.registry().await.total_files, 1);
+}
+
+#[tokio::test]
+async fn deny_policy_rejects_callers_but_not_transactions() {
+    let mut test = start().await;
+    set_cpi_policy(&mut test, CpiPolicy::Deny, vec![]).await;
+    let owner = test.funded_wallet().await;
+
+    let result = register_through_caller(&mut test, &owner).await;
+    assert_eq!(custom_error(result), Some(HelixError::CpiNotAllowed.into()));
+
+    test.register_test_file(&owner, 1024).await;
+    assert_eq!(test.registry().await.total_files, 1);
+}
+
+#[tokio::test]
+async fn allowlist_policy_admits_only_listed_callers() {
+    let mut test = start().await;
+    let owner = test.funded_wallet().await;
+
+    set_cpi_policy(&mut test, CpiPolicy::Allowlist, vec![Pubkey::new_unique()]).await;
+    let result = register_through_caller(&mut test, &owner).await;
+    assert_eq!(custom_error(result), Some(HelixError::CpiNotAllowed.into()));
+
+    set_cpi_policy(&mut test, CpiPolicy::Allowlist, vec![helix_caller_example::ID]).await;
+    register_through_caller(&mut test, &owner).await.unwrap();
+    assert_eq!(test.registry().await.total_files, 1);
+}
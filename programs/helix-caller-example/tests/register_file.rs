@@ -0,0 +1,32 @@
+use helix_storage::client::find_file_address;
+use helix_storage::state::StorageLocation;
+use solana_sdk::signature::Signer;
+
+mod common;
+
+use common::{register_through_caller_ix, start};
+
+#[tokio::test]
+async fn cpi_registration_records_the_file() {
+    let mut test = start().await;
+    let owner = test.funded_wallet().await;
+    let transaction_id = test.next_transaction_id();
+
+    let register = register_through_caller_ix(&owner.pubkey(), &transaction_id, 4096);
+    test.process(&[register], &[&owner]).await.unwrap();
+
+    let file_record = test.file_record(&find_file_address(&transaction_id).0).await;
+    assert_eq!(file_record.owner, owner.pubkey());
+    assert_eq!(file_record.transaction_id, &*transaction_id);
+    assert_eq!(file_record.encrypted_name, None);
+    assert_eq!(file_record.mime_type, "text/plain");
+    assert_eq!(file_record.size, 4096);
+    assert!(!file_record.is_encrypted());
+    assert_eq!(file_record.storage_location, StorageLocation::ArweaveL1);
+    assert_eq!(file_record.rent_payer, owner.pubkey());
+    assert_eq!(file_record.created_at, test.now().await);
+    assert!(file_record.is_active());
+    let registry = test.registry().await;
+    assert_eq!(registry.total_files, 1);
+    assert_eq!(registry.total_bytes, 4096);
+}
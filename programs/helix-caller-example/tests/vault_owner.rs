@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::{system_program, InstructionData};
+use helix_caller_example::find_vault_address;
+use helix_storage::client::{
+    find_event_authority_address, find_file_address, find_registry_address, find_share_address,
+    find_user_profile_address,
+};
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::types::MimeType;
+
+mod common;
+
+use common::start;
+
+/// A caller `share_vault_file` instruction for the vault's `file`
+fn share_vault_file_ix(file: &Pubkey, share: &Pubkey) -> Instruction {
+    let vault = find_vault_address().0;
+    Instruction {
+        program_id: helix_caller_example::ID,
+        accounts: helix_caller_example::accounts::VaultShareFile {
+            registry: find_registry_address().0,
+            file_record: *file,
+            share_link: *share,
+            vault,
+            vault_profile: find_user_profile_address(&vault).0,
+            helix_event_authority: find_event_authority_address().0,
+            helix_program: helix_storage::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: helix_caller_example::instruction::ShareVaultFile {
+            args: CreateShareArgs::default(),
+        }
+        .data(),
+    }
+}
+
+/// A caller `revoke_vault_share` instruction for `share` of the vault's `file`
+fn revoke_vault_share_ix(file: &Pubkey, share: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: helix_caller_example::ID,
+        accounts: helix_caller_example::accounts::VaultRevokeShare {
+            registry: find_registry_address().0,
+            file_record: *file,
+            share_link: *share,
+            vault: find_vault_address().0,
+            helix_event_authority: find_event_authority_address().0,
+            helix_program: helix_storage::ID,
+        }
+        .to_account_metas(None),
+        data: helix_caller_example::instruction::RevokeVaultShare {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn program_vault_shares_and_revokes_its_file() {
+    let mut test = start().await;
+    let vault = find_vault_address().0;
+    let fund = system_instruction::transfer(&test.authority(), &vault, 1_000_000_000);
+    test.process(&[fund], &[]).await.unwrap();
+
+    // The vault registers a file it owns, signing through the caller
+    let transaction_id = test.next_transaction_id();
+    let file = find_file_address(&transaction_id).0;
+    let register = Instruction {
+        program_id: helix_caller_example::ID,
+        accounts: helix_caller_example::accounts::VaultRegisterFile {
+            registry: find_registry_address().0,
+            file_record: file,
+            vault,
+            helix_event_authority: find_event_authority_address().0,
+            helix_program: helix_storage::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: helix_caller_example::instruction::RegisterVaultFile {
+            transaction_id,
+            mime_type: MimeType::try_from("text/plain".to_string()).unwrap(),
+            size: 1024,
+        }
+        .data(),
+    };
+    test.process(&[register], &[]).await.unwrap();
+    assert_eq!(test.file_record(&file).await.owner, vault);
+
+    let share = find_share_address(&file, 0).0;
+    test.process(&[share_vault_file_ix(&file, &share)], &[])
+        .await
+        .unwrap();
+    assert_eq!(test.share_link(&share).await.owner, vault);
+    assert_eq!(test.file_record(&file).await.share_count, 1);
+    assert_eq!(test.registry().await.total_shares, 1);
+
+    test.process(&[revoke_vault_share_ix(&file, &share)], &[])
+        .await
+        .unwrap();
+    assert!(test.share_link(&share).await.is_revoked());
+    assert_eq!(test.file_record(&file).await.share_count, 0);
+    assert_eq!(test.registry().await.total_shares, 0);
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use helix_storage::readers::{assert_file_owner, assert_share_owner};
+use helix_storage::HelixError;
+
+declare_id!("DpgVXPUBKNwnHfv4owFW44ZZDsPxQu3J8mRXqjXDMNii");
+
+/// Example of a third-party program reading Helix accounts it is passed.
+/// 
+/// The Helix accounts arrive as `UncheckedAccount`s and are read with the
+/// checked readers from `helix_storage::readers`, so an account that merely
+/// copies a Helix layout under another program is rejected.
+#[program]
+pub mod helix_reader_example {
+    use super::*;
+
+    /// Succeed only if `file_record` is a live Helix file owned by the signer.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and its owner
+    pub fn check_file_owner(ctx: Context<CheckFileOwner>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let file_record = assert_file_owner(&ctx.accounts.file_record, &owner)?;
+        require!(file_record.is_accessible(), HelixError::FileNotActive);
+
+        msg!("{} owns {}", owner, file_record.transaction_id);
+
+        Ok(())
+    }
+
+    /// Succeed only if `share_link` is an unrevoked Helix share created by the
+    /// signer.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the share link and its owner
+    pub fn check_share_owner(ctx: Context<CheckShareOwner>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let share_link = assert_share_owner(&ctx.accounts.share_link, &owner)?;
+        require!(!share_link.is_revoked(), HelixError::ShareRevoked);
+
+        msg!("{} owns share of {}", owner, share_link.file);
+
+        Ok(())
+    }
+}
+
+/// Accounts for checking ownership of a Helix file
+#[derive(Accounts)]
+pub struct CheckFileOwner<'info> {
+    /// The Helix file record
+    /// CHECK: Owner program, discriminator and owner are checked by `assert_file_owner`
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The wallet claiming to own the file
+    pub owner: Signer<'info>,
+}
+
+/// Accounts for checking ownership of a Helix share link
+#[derive(Accounts)]
+pub struct CheckShareOwner<'info> {
+    /// The Helix share link
+    /// CHECK: Owner program, discriminator and owner are checked by `assert_share_owner`
+    pub share_link: UncheckedAccount<'info>,
+
+    /// The wallet claiming to have created the share
+    pub owner: Signer<'info>,
+}
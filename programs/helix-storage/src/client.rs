@@ -0,0 +1,444 @@
+use anchor_lang::prelude::*;
+
+use crate::compact::{
+    CompactDefaults, CompactFile, COMPACT_BATCH_VERSION, COMPACT_HEADER_LEN,
+    COMPACT_OVERRIDE_FLAGS, COMPACT_OVERRIDE_MIME_CODE, COMPACT_OVERRIDE_MIME_LITERAL,
+};
+use crate::error::HelixError;
+use crate::events::EVENT_AUTHORITY_SEED;
+use crate::state::{
+    FileRecord, MimeTypeArg, ShareLink, StorageRegistry, chunk_leaf_hash, chunk_node_hash,
+    file_seed, ALIAS_SEED, ATTESTATION_SEED, AUDIT_GRANT_SEED, CHALLENGE_SEED, FILE_MINT_SEED,
+    FILE_SEED, INBOX_SEED, MAX_FILES_PER_COMPACT_BATCH, MIME_TABLE_SEED, NAME_SEED,
+    OWNER_FILE_SEED, REFERENCE_SEED, REGISTRY_EXT_SEED, REGISTRY_SEED, SHARE_SEED, SHORT_CODE_SEED,
+    TREASURY_SEED, USER_PROFILE_SEED,
+};
+
+/// Derive the storage registry PDA
+pub fn find_registry_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_SEED], &crate::ID)
+}
+
+/// Derive the registry extension PDA
+pub fn find_registry_ext_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REGISTRY_EXT_SEED], &crate::ID)
+}
+
+/// Derive the treasury PDA
+pub fn find_treasury_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], &crate::ID)
+}
+
+/// Derive the MIME table PDA
+pub fn find_mime_table_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MIME_TABLE_SEED], &crate::ID)
+}
+
+/// Derive the file record PDA for an Arweave transaction ID
+pub fn find_file_address(transaction_id: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FILE_SEED, &file_seed(transaction_id)], &crate::ID)
+}
+
+/// Derive the share link PDA for a file.
+/// The nonce is the file's `share_nonce` at the time the share is created.
+pub fn find_share_address(file: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SHARE_SEED, file.as_ref(), &nonce.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Derive the ownership token mint PDA of a tokenized file
+pub fn find_file_mint_address(file: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FILE_MINT_SEED, file.as_ref()], &crate::ID)
+}
+
+/// Derive the user profile PDA for a wallet
+pub fn find_user_profile_address(wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[USER_PROFILE_SEED, wallet.as_ref()], &crate::ID)
+}
+
+/// Derive the file index entry PDA for a wallet and sequence number
+pub fn find_owner_file_address(owner: &Pubkey, seq: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[OWNER_FILE_SEED, owner.as_ref(), &seq.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Derive the file alias PDA for a wallet and alias hash
+pub fn find_alias_address(owner: &Pubkey, alias_hash: &[u8; 32]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ALIAS_SEED, owner.as_ref(), alias_hash], &crate::ID)
+}
+
+/// Derive the file reference PDA for a wallet and file
+pub fn find_reference_address(referrer: &Pubkey, file: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REFERENCE_SEED, referrer.as_ref(), file.as_ref()], &crate::ID)
+}
+
+/// Derive the name record PDA for a name in a wallet's namespace
+pub fn find_name_address(owner: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NAME_SEED, owner.as_ref(), name.as_bytes()], &crate::ID)
+}
+
+/// Derive the short code PDA for a code
+pub fn find_short_code_address(code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SHORT_CODE_SEED, code.as_bytes()], &crate::ID)
+}
+
+/// Derive the attestation PDA for a file and attestor
+pub fn find_attestation_address(file: &Pubkey, attestor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ATTESTATION_SEED, file.as_ref(), attestor.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// Derive the challenge PDA for a file and challenger
+pub fn find_challenge_address(file: &Pubkey, challenger: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CHALLENGE_SEED, file.as_ref(), challenger.as_ref()],
+        &crate::ID,
+    )
+}
+
+/// Derive the audit grant PDA for an owner and grant ID
+pub fn find_audit_grant_address(owner: &Pubkey, grant_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[AUDIT_GRANT_SEED, owner.as_ref(), &grant_id.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Derive a recipient's inbox entry PDA for an inbox sequence number
+pub fn find_inbox_address(recipient: &Pubkey, seq: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[INBOX_SEED, recipient.as_ref(), &seq.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Derive the event authority PDA that signs self-CPI events
+pub fn find_event_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &crate::ID)
+}
+
+/// Hash every level of the chunk Merkle tree, leaves first. Odd levels pair
+/// their last node with itself.
+fn chunk_tree_levels(chunk_tx_ids: &[&str]) -> Option<Vec<Vec<[u8; 32]>>> {
+    if chunk_tx_ids.is_empty() || chunk_tx_ids.len() > usize::from(u16::MAX) + 1 {
+        return None;
+    }
+
+    let leaves = chunk_tx_ids
+        .iter()
+        .enumerate()
+        .map(|(index, tx_id)| chunk_leaf_hash(index as u16, tx_id))
+        .collect::<Vec<_>>();
+    let mut levels = vec![leaves];
+    while levels[levels.len() - 1].len() > 1 {
+        let next = levels[levels.len() - 1]
+            .chunks(2)
+            .map(|pair| chunk_node_hash(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+            .collect();
+        levels.push(next);
+    }
+    Some(levels)
+}
+
+/// Build the Merkle root stored by `set_chunk_root` for a chunked file's
+/// transaction IDs, in chunk order. Returns None for an empty list or more
+/// chunks than a `u16` index can address.
+pub fn chunk_merkle_root(chunk_tx_ids: &[&str]) -> Option<[u8; 32]> {
+    let levels = chunk_tree_levels(chunk_tx_ids)?;
+    Some(levels[levels.len() - 1][0])
+}
+
+/// Build the proof `verify_chunk` expects for the chunk at `index`
+pub fn chunk_merkle_proof(chunk_tx_ids: &[&str], index: u16) -> Option<Vec<[u8; 32]>> {
+    let levels = chunk_tree_levels(chunk_tx_ids)?;
+    let mut position = usize::from(index);
+    if position >= chunk_tx_ids.len() {
+        return None;
+    }
+
+    let mut proof = Vec::with_capacity(levels.len() - 1);
+    for level in &levels[..levels.len() - 1] {
+        let sibling = level.get(position ^ 1).unwrap_or(&level[position]);
+        proof.push(*sibling);
+        position /= 2;
+    }
+    Some(proof)
+}
+
+/// Check if a wallet can access a shared file at the given timestamp.
+/// Mirrors the on-chain `validate_access` check.
+pub fn can_access_file(
+    file_record: &FileRecord,
+    share_link: &ShareLink,
+    wallet: &Pubkey,
+    current_timestamp: i64,
+) -> bool {
+    file_record.is_accessible() && share_link.can_access(wallet, current_timestamp)
+}
+
+/// Encode files as the compact batch `register_files_compact` takes.
+///
+/// Each entry carries only what differs from `defaults`, so files sharing
+/// the default MIME code and flags cost 41 bytes each. Fails with
+/// `InvalidCompactBatch` for an empty or oversized batch or a MIME code of
+/// 0, which the decoder would read as "no MIME type".
+pub fn encode_compact_batch(defaults: CompactDefaults, files: &[CompactFile]) -> Result<Vec<u8>> {
+    require!(
+        !files.is_empty() && files.len() <= MAX_FILES_PER_COMPACT_BATCH,
+        HelixError::InvalidCompactBatch
+    );
+
+    let mut data = Vec::with_capacity(COMPACT_HEADER_LEN + files.len() * 41);
+    data.push(COMPACT_BATCH_VERSION);
+    data.push(files.len() as u8);
+    data.extend_from_slice(&defaults.mime_code.to_le_bytes());
+    data.push(defaults.flags);
+
+    for file in files {
+        let mut overrides = 0u8;
+        match &file.mime_type {
+            MimeTypeArg::Code(0) => return Err(HelixError::InvalidCompactBatch.into()),
+            MimeTypeArg::Code(code) if *code == defaults.mime_code => {}
+            MimeTypeArg::Code(_) => overrides |= COMPACT_OVERRIDE_MIME_CODE,
+            MimeTypeArg::Literal(_) => overrides |= COMPACT_OVERRIDE_MIME_LITERAL,
+        }
+        if file.flags != defaults.flags {
+            overrides |= COMPACT_OVERRIDE_FLAGS;
+        }
+
+        data.push(overrides);
+        data.extend_from_slice(&file.transaction_id);
+        data.extend_from_slice(&file.size.to_le_bytes());
+        match &file.mime_type {
+            MimeTypeArg::Code(code) if overrides & COMPACT_OVERRIDE_MIME_CODE != 0 => {
+                data.extend_from_slice(&code.to_le_bytes());
+            }
+            MimeTypeArg::Literal(mime_type) => {
+                // MimeType is at most MAX_MIME_TYPE_LEN (128) bytes
+                data.push(mime_type.len() as u8);
+                data.extend_from_slice(mime_type.as_bytes());
+            }
+            MimeTypeArg::Code(_) => {}
+        }
+        if overrides & COMPACT_OVERRIDE_FLAGS != 0 {
+            data.push(file.flags);
+        }
+    }
+
+    Ok(data)
+}
+
+impl StorageRegistry {
+    /// Deserialize a registry from raw account data, checking the discriminator
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self> {
+        Self::try_deserialize(&mut &data[..])
+    }
+}
+
+impl FileRecord {
+    /// Deserialize a file record from raw account data, checking the discriminator
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self> {
+        Self::try_deserialize(&mut &data[..])
+    }
+}
+
+impl ShareLink {
+    /// Deserialize a share link from raw account data, checking the discriminator
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self> {
+        Self::try_deserialize(&mut &data[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{verify_chunk_proof, FileStatus, MAX_CHUNK_PROOF_DEPTH};
+    use crate::test_utils::{account_data, sample_file, sample_share, NOW, SAMPLE_TX_ID};
+    use crate::types::TxId;
+
+    /// The address the on-chain `seeds = [...], bump` constraint accepts for
+    /// these seeds and bump
+    fn seeds_address(seeds: &[&[u8]], bump: u8) -> Pubkey {
+        let bump = [bump];
+        let mut seeds = seeds.to_vec();
+        seeds.push(&bump);
+        Pubkey::create_program_address(&seeds, &crate::ID).unwrap()
+    }
+
+    #[test]
+    fn registry_addresses_match_their_seeds() {
+        let (registry, bump) = find_registry_address();
+        assert_eq!(registry, seeds_address(&[REGISTRY_SEED], bump));
+
+        let (treasury, bump) = find_treasury_address();
+        assert_eq!(treasury, seeds_address(&[TREASURY_SEED], bump));
+
+        let (mime_table, bump) = find_mime_table_address();
+        assert_eq!(mime_table, seeds_address(&[MIME_TABLE_SEED], bump));
+    }
+
+    #[test]
+    fn file_addresses_match_their_seeds() {
+        let file_record = sample_file(Pubkey::new_unique(), SAMPLE_TX_ID, 10);
+        let (file, bump) = find_file_address(SAMPLE_TX_ID);
+        assert_eq!(file, seeds_address(&[FILE_SEED, &file_record.file_seed()], bump));
+
+        // The ID argument of register_file derives the same seed
+        let transaction_id = TxId::try_from(SAMPLE_TX_ID.to_string()).unwrap();
+        assert_eq!(transaction_id.file_seed(), file_record.file_seed());
+
+        // IDs differing only in the bits past the 256th still get their own record
+        let (other, _) = find_file_address(&format!("{}B", &SAMPLE_TX_ID[..42]));
+        assert_ne!(other, file);
+    }
+
+    #[test]
+    fn share_addresses_match_their_seeds() {
+        for nonce in [0u64, 1, 255, 256, u64::MAX] {
+            let file = Pubkey::new_unique();
+            let (share, bump) = find_share_address(&file, nonce);
+            assert_eq!(
+                share,
+                seeds_address(&[SHARE_SEED, file.as_ref(), &nonce.to_le_bytes()], bump)
+            );
+        }
+    }
+
+    #[test]
+    fn wallet_scoped_addresses_match_their_seeds() {
+        for _ in 0..4 {
+            let wallet = Pubkey::new_unique();
+
+            let (profile, bump) = find_user_profile_address(&wallet);
+            assert_eq!(profile, seeds_address(&[USER_PROFILE_SEED, wallet.as_ref()], bump));
+
+            let (entry, bump) = find_owner_file_address(&wallet, 42);
+            assert_eq!(
+                entry,
+                seeds_address(&[OWNER_FILE_SEED, wallet.as_ref(), &42u64.to_le_bytes()], bump)
+            );
+
+            let (name, bump) = find_name_address(&wallet, "reports");
+            assert_eq!(name, seeds_address(&[NAME_SEED, wallet.as_ref(), b"reports"], bump));
+        }
+    }
+
+    #[test]
+    fn distinct_inputs_derive_distinct_addresses() {
+        let file = Pubkey::new_unique();
+        assert_ne!(find_share_address(&file, 0).0, find_share_address(&file, 1).0);
+        assert_ne!(
+            find_share_address(&file, 0).0,
+            find_share_address(&Pubkey::new_unique(), 0).0
+        );
+    }
+
+    #[test]
+    fn try_from_bytes_checks_the_discriminator() {
+        let owner = Pubkey::new_unique();
+        let file_record = sample_file(owner, SAMPLE_TX_ID, 10);
+        let data = account_data(&file_record, FileRecord::LEN);
+
+        assert_eq!(FileRecord::try_from_bytes(&data).unwrap().owner, owner);
+        assert!(ShareLink::try_from_bytes(&data).is_err());
+        assert!(StorageRegistry::try_from_bytes(&data).is_err());
+        assert!(FileRecord::try_from_bytes(&data[..7]).is_err());
+    }
+
+    #[test]
+    fn can_access_file_takes_an_explicit_timestamp() {
+        let owner = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 10);
+        let (_, mut share_link) = sample_share(&Pubkey::new_unique(), owner, Some(recipient), 0);
+        share_link.expires_at = Some(NOW + 60);
+
+        assert!(can_access_file(&file_record, &share_link, &recipient, NOW + 60));
+        assert!(!can_access_file(&file_record, &share_link, &recipient, NOW + 61));
+        assert!(!can_access_file(&file_record, &share_link, &owner, NOW));
+
+        file_record.set_status(FileStatus::Deleted).unwrap();
+        assert!(!can_access_file(&file_record, &share_link, &recipient, NOW));
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn chunk_root_matches_the_pinned_vector() {
+        // SHA-256 of the tagged leaves and nodes, computed independently;
+        // the odd third leaf is paired with itself
+        let chunk_tx_ids = ["chunk-0", "chunk-1", "chunk-2"];
+        assert_eq!(
+            hex(&chunk_leaf_hash(0, "chunk-0")),
+            "f735579caa62f42433d4140d341c7c79043660710182a331e5c65010f68f3155"
+        );
+        assert_eq!(
+            hex(&chunk_merkle_root(&chunk_tx_ids).unwrap()),
+            "a079ec877ee8ea82385c03530937c06587df0934b84c7a5bf9013e6703c68b2d"
+        );
+        assert_eq!(chunk_merkle_root(&[]), None);
+    }
+
+    #[test]
+    fn every_chunk_proof_verifies() {
+        let names = (0..9).map(|index| format!("chunk-{}", index)).collect::<Vec<_>>();
+        for len in 1..=names.len() {
+            let chunk_tx_ids = names[..len].iter().map(String::as_str).collect::<Vec<_>>();
+            let root = chunk_merkle_root(&chunk_tx_ids).unwrap();
+            for (index, chunk_tx_id) in chunk_tx_ids.iter().enumerate() {
+                let index = index as u16;
+                let proof = chunk_merkle_proof(&chunk_tx_ids, index).unwrap();
+                assert!(verify_chunk_proof(&root, index, chunk_tx_id, &proof), "{len}/{index}");
+            }
+            assert_eq!(chunk_merkle_proof(&chunk_tx_ids, len as u16), None);
+        }
+
+        // A single chunk is its own root
+        assert!(verify_chunk_proof(&chunk_leaf_hash(0, "solo"), 0, "solo", &[]));
+    }
+
+    #[test]
+    fn tampered_chunk_proofs_fail() {
+        let chunk_tx_ids = ["chunk-0", "chunk-1", "chunk-2", "chunk-3", "chunk-4"];
+        let root = chunk_merkle_root(&chunk_tx_ids).unwrap();
+        let proof = chunk_merkle_proof(&chunk_tx_ids, 2).unwrap();
+        assert!(verify_chunk_proof(&root, 2, "chunk-2", &proof));
+
+        // Wrong chunk, index or root
+        assert!(!verify_chunk_proof(&root, 2, "chunk-3", &proof));
+        assert!(!verify_chunk_proof(&root, 3, "chunk-2", &proof));
+        assert!(!verify_chunk_proof(&root, 2 + 8, "chunk-2", &proof));
+        let mut wrong_root = root;
+        wrong_root[31] ^= 1;
+        assert!(!verify_chunk_proof(&wrong_root, 2, "chunk-2", &proof));
+
+        // A flipped bit in any sibling, or siblings out of order
+        for level in 0..proof.len() {
+            let mut tampered = proof.clone();
+            tampered[level][0] ^= 0x80;
+            assert!(!verify_chunk_proof(&root, 2, "chunk-2", &tampered), "level {level}");
+        }
+        let mut reversed = proof.clone();
+        reversed.reverse();
+        assert!(!verify_chunk_proof(&root, 2, "chunk-2", &reversed));
+
+        // Truncated or padded proofs
+        assert!(!verify_chunk_proof(&root, 2, "chunk-2", &proof[..proof.len() - 1]));
+        let mut padded = proof.clone();
+        padded.push(root);
+        assert!(!verify_chunk_proof(&root, 2, "chunk-2", &padded));
+
+        // Too shallow to address the index, or deeper than allowed
+        assert!(!verify_chunk_proof(&chunk_leaf_hash(1, "solo"), 1, "solo", &[]));
+        let too_deep = vec![[0u8; 32]; MAX_CHUNK_PROOF_DEPTH + 1];
+        assert!(!verify_chunk_proof(&root, 0, "chunk-0", &too_deep));
+    }
+}
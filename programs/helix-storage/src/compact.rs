@@ -0,0 +1,364 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{MimeTypeArg, StorageLocation, MAX_FILES_PER_COMPACT_BATCH};
+use crate::types::{MimeType, TxId};
+
+// Compact wire format of `register_files_compact`.
+//
+// A batch is a header followed by its entries, integers little-endian:
+//
+//   version: u8 | count: u8 | mime_code: u16 | flags: u8
+//
+// and for each entry:
+//
+//   overrides: u8 | transaction_id: [u8; 32] | size: u64
+//   mime_code: u16               if COMPACT_OVERRIDE_MIME_CODE
+//   len: u8, mime: [u8; len]     if COMPACT_OVERRIDE_MIME_LITERAL
+//   flags: u8                    if COMPACT_OVERRIDE_FLAGS
+//
+// The header's MIME code and flags are the batch defaults; an entry carries
+// a field only when its override bit is set. A default MIME code of 0 means
+// no default, so every entry must then carry its own MIME type.
+
+/// Version byte leading every compact batch
+pub const COMPACT_BATCH_VERSION: u8 = 1;
+
+/// Size of the batch header
+pub const COMPACT_HEADER_LEN: usize = 1 + 1 + 2 + 1;
+
+/// File flag: the content is encrypted
+pub const COMPACT_FLAG_ENCRYPTED: u8 = 1 << 0;
+
+/// File flag: the ID is an Irys data item (bundle not recorded)
+pub const COMPACT_FLAG_IRYS_DATA_ITEM: u8 = 1 << 1;
+
+/// All defined file flag bits
+pub const COMPACT_FLAGS_ALL: u8 = COMPACT_FLAG_ENCRYPTED | COMPACT_FLAG_IRYS_DATA_ITEM;
+
+/// Override bit: the entry carries its own MIME table code
+pub const COMPACT_OVERRIDE_MIME_CODE: u8 = 1 << 0;
+
+/// Override bit: the entry carries a literal MIME type
+pub const COMPACT_OVERRIDE_MIME_LITERAL: u8 = 1 << 1;
+
+/// Override bit: the entry carries its own file flags
+pub const COMPACT_OVERRIDE_FLAGS: u8 = 1 << 2;
+
+/// All defined override bits
+pub const COMPACT_OVERRIDES_ALL: u8 =
+    COMPACT_OVERRIDE_MIME_CODE | COMPACT_OVERRIDE_MIME_LITERAL | COMPACT_OVERRIDE_FLAGS;
+
+/// Batch defaults declared once in the header
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompactDefaults {
+    /// MIME table code of entries without their own (0 = no default)
+    pub mime_code: u16,
+    /// `COMPACT_FLAG_*` bits of entries without their own
+    pub flags: u8,
+}
+
+/// One file of a compact batch, with the batch defaults applied
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactFile {
+    /// The 32 bytes the Arweave transaction ID encodes
+    pub transaction_id: [u8; 32],
+    pub size: u64,
+    pub mime_type: MimeTypeArg,
+    /// `COMPACT_FLAG_*` bits
+    pub flags: u8,
+}
+
+impl CompactFile {
+    /// The transaction ID as its 43 base64url characters
+    pub fn tx_id(&self) -> TxId {
+        TxId::from_bytes(&self.transaction_id)
+    }
+
+    /// Whether the content is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & COMPACT_FLAG_ENCRYPTED != 0
+    }
+
+    /// Where the transaction ID lives on Arweave
+    pub fn storage_location(&self) -> StorageLocation {
+        if self.flags & COMPACT_FLAG_IRYS_DATA_ITEM != 0 {
+            StorageLocation::IrysDataItem { bundle_tx: None }
+        } else {
+            StorageLocation::ArweaveL1
+        }
+    }
+}
+
+/// Bounds-checked reader over a compact batch
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Take the next `len` bytes, failing if fewer are left
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.data.len());
+        let Some(end) = end else {
+            msg!("compact: truncated at byte {}", self.pos);
+            return Err(HelixError::InvalidCompactBatch.into());
+        };
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn array32(&mut self) -> Result<[u8; 32]> {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(self.take(32)?);
+        Ok(bytes)
+    }
+}
+
+/// Check `flags` holds only defined file flag bits
+fn require_known_flags(flags: u8) -> Result<u8> {
+    if flags & !COMPACT_FLAGS_ALL != 0 {
+        msg!("compact: unknown flag bits {:#04x}", flags);
+        return Err(HelixError::InvalidCompactBatch.into());
+    }
+    Ok(flags)
+}
+
+/// Decode a compact batch, applying the header defaults to every entry.
+///
+/// Every read is bounds-checked, and the batch must be consumed exactly:
+/// truncated input, trailing bytes, an unknown version, flag or override
+/// bit, a count outside `1..=MAX_FILES_PER_COMPACT_BATCH`, conflicting MIME
+/// overrides or an entry left without a MIME type fail with
+/// `InvalidCompactBatch`. Literal MIME types are validated like
+/// `register_file_v2`'s.
+pub fn decode_compact_batch(data: &[u8]) -> Result<Vec<CompactFile>> {
+    let mut reader = Reader { data, pos: 0 };
+
+    let version = reader.u8()?;
+    if version != COMPACT_BATCH_VERSION {
+        msg!("compact: unsupported version {}", version);
+        return Err(HelixError::InvalidCompactBatch.into());
+    }
+    let count = usize::from(reader.u8()?);
+    if count == 0 || count > MAX_FILES_PER_COMPACT_BATCH {
+        msg!("compact: {} entries is out of bounds", count);
+        return Err(HelixError::InvalidCompactBatch.into());
+    }
+    let defaults = CompactDefaults {
+        mime_code: reader.u16()?,
+        flags: require_known_flags(reader.u8()?)?,
+    };
+
+    let mut files = Vec::with_capacity(count);
+    for index in 0..count {
+        let overrides = reader.u8()?;
+        let both_mimes = COMPACT_OVERRIDE_MIME_CODE | COMPACT_OVERRIDE_MIME_LITERAL;
+        if overrides & !COMPACT_OVERRIDES_ALL != 0 || overrides & both_mimes == both_mimes {
+            msg!("compact: entry {} has invalid overrides {:#04x}", index, overrides);
+            return Err(HelixError::InvalidCompactBatch.into());
+        }
+
+        let transaction_id = reader.array32()?;
+        let size = reader.u64()?;
+
+        let mime_code = if overrides & COMPACT_OVERRIDE_MIME_CODE != 0 {
+            reader.u16()?
+        } else {
+            defaults.mime_code
+        };
+        let mime_type = if overrides & COMPACT_OVERRIDE_MIME_LITERAL != 0 {
+            let len = usize::from(reader.u8()?);
+            let bytes = reader.take(len)?.to_vec();
+            let literal = String::from_utf8(bytes)
+                .map_err(|_| error!(HelixError::InvalidCompactBatch))
+                .and_then(MimeType::try_from)?;
+            MimeTypeArg::Literal(literal)
+        } else if mime_code != 0 {
+            MimeTypeArg::Code(mime_code)
+        } else {
+            msg!("compact: entry {} has no MIME type", index);
+            return Err(HelixError::InvalidCompactBatch.into());
+        };
+
+        let flags = if overrides & COMPACT_OVERRIDE_FLAGS != 0 {
+            require_known_flags(reader.u8()?)?
+        } else {
+            defaults.flags
+        };
+
+        files.push(CompactFile { transaction_id, size, mime_type, flags });
+    }
+
+    if reader.pos != data.len() {
+        msg!("compact: {} trailing bytes", data.len() - reader.pos);
+        return Err(HelixError::InvalidCompactBatch.into());
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::encode_compact_batch;
+
+    const DEFAULTS: CompactDefaults = CompactDefaults {
+        mime_code: 3,
+        flags: COMPACT_FLAG_ENCRYPTED,
+    };
+
+    fn file(seed: u8, mime_type: MimeTypeArg, flags: u8) -> CompactFile {
+        CompactFile {
+            transaction_id: [seed; 32],
+            size: u64::from(seed) << 20,
+            mime_type,
+            flags,
+        }
+    }
+
+    fn literal(mime: &str) -> MimeTypeArg {
+        MimeTypeArg::Literal(MimeType::try_from(mime.to_string()).unwrap())
+    }
+
+    /// One entry of every shape: defaults only, each override, and all
+    /// overrides at once
+    fn mixed_batch() -> Vec<CompactFile> {
+        vec![
+            file(1, MimeTypeArg::Code(3), COMPACT_FLAG_ENCRYPTED),
+            file(2, MimeTypeArg::Code(7), COMPACT_FLAG_ENCRYPTED),
+            file(3, literal("application/x-custom"), COMPACT_FLAG_ENCRYPTED),
+            file(4, MimeTypeArg::Code(3), COMPACT_FLAG_IRYS_DATA_ITEM),
+            file(5, literal("text/plain"), 0),
+        ]
+    }
+
+    fn invalid() -> Result<Vec<CompactFile>> {
+        Err(HelixError::InvalidCompactBatch.into())
+    }
+
+    #[test]
+    fn batches_round_trip() {
+        let files = mixed_batch();
+        let data = encode_compact_batch(DEFAULTS, &files).unwrap();
+        assert_eq!(decode_compact_batch(&data), Ok(files));
+
+        let max = vec![file(9, MimeTypeArg::Code(3), 0); MAX_FILES_PER_COMPACT_BATCH];
+        let data = encode_compact_batch(CompactDefaults { mime_code: 3, flags: 0 }, &max).unwrap();
+        assert_eq!(data.len(), COMPACT_HEADER_LEN + MAX_FILES_PER_COMPACT_BATCH * 41);
+        assert_eq!(decode_compact_batch(&data), Ok(max));
+    }
+
+    #[test]
+    fn defaults_apply_to_entries_without_overrides() {
+        let data = encode_compact_batch(DEFAULTS, &mixed_batch()[..1]).unwrap();
+        assert_eq!(data[COMPACT_HEADER_LEN], 0);
+
+        let decoded = decode_compact_batch(&data).unwrap();
+        assert!(decoded[0].is_encrypted());
+        assert_eq!(decoded[0].mime_type, MimeTypeArg::Code(3));
+        assert_eq!(decoded[0].storage_location(), StorageLocation::ArweaveL1);
+        assert_eq!(decoded[0].tx_id(), TxId::from_bytes(&[1; 32]));
+    }
+
+    #[test]
+    fn truncated_batches_fail_without_panicking() {
+        let data = encode_compact_batch(DEFAULTS, &mixed_batch()).unwrap();
+        for len in 0..data.len() {
+            assert_eq!(decode_compact_batch(&data[..len]), invalid(), "prefix of {len}");
+        }
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        let mut data = encode_compact_batch(DEFAULTS, &mixed_batch()).unwrap();
+        data.push(0);
+        assert_eq!(decode_compact_batch(&data), invalid());
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        let data = encode_compact_batch(DEFAULTS, &mixed_batch()[..1]).unwrap();
+        let patched = |index: usize, value: u8| {
+            let mut data = data.clone();
+            data[index] = value;
+            decode_compact_batch(&data)
+        };
+
+        assert_eq!(patched(0, COMPACT_BATCH_VERSION + 1), invalid());
+        assert_eq!(patched(1, 0), invalid());
+        assert_eq!(patched(1, MAX_FILES_PER_COMPACT_BATCH as u8 + 1), invalid());
+        // A count larger than the entries present reads past the end
+        assert_eq!(patched(1, 2), invalid());
+        assert_eq!(patched(4, 1 << 7), invalid());
+    }
+
+    #[test]
+    fn malformed_entries_are_rejected() {
+        let data = encode_compact_batch(DEFAULTS, &mixed_batch()[..1]).unwrap();
+        let with_overrides = |overrides: u8, extra: &[u8]| {
+            let mut data = data.clone();
+            data[COMPACT_HEADER_LEN] = overrides;
+            data.extend_from_slice(extra);
+            decode_compact_batch(&data)
+        };
+
+        assert_eq!(with_overrides(1 << 3, &[]), invalid());
+        let both = COMPACT_OVERRIDE_MIME_CODE | COMPACT_OVERRIDE_MIME_LITERAL;
+        assert_eq!(with_overrides(both, &[7, 0, 1, b'a']), invalid());
+        assert_eq!(with_overrides(COMPACT_OVERRIDE_FLAGS, &[1 << 7]), invalid());
+        // A literal length running past the end of the batch
+        assert_eq!(with_overrides(COMPACT_OVERRIDE_MIME_LITERAL, &[200, b'a']), invalid());
+        assert!(with_overrides(COMPACT_OVERRIDE_MIME_LITERAL, &[2, 0xff, 0xfe]).is_err());
+        assert!(with_overrides(COMPACT_OVERRIDE_MIME_LITERAL, b"\x04none").is_err());
+    }
+
+    #[test]
+    fn entries_need_a_mime_type() {
+        let no_default = CompactDefaults { mime_code: 0, flags: 0 };
+        let files = [file(1, MimeTypeArg::Code(7), 0)];
+        let mut data = encode_compact_batch(no_default, &files).unwrap();
+        assert_eq!(decode_compact_batch(&data), Ok(files.to_vec()));
+
+        // Dropping the entry's MIME code leaves it without one
+        data[COMPACT_HEADER_LEN] = 0;
+        data.truncate(data.len() - 2);
+        assert_eq!(decode_compact_batch(&data), invalid());
+    }
+
+    #[test]
+    fn single_byte_corruptions_never_panic() {
+        let data = encode_compact_batch(DEFAULTS, &mixed_batch()).unwrap();
+        for index in 0..data.len() {
+            for value in 0..=u8::MAX {
+                let mut corrupted = data.clone();
+                corrupted[index] = value;
+                let _ = decode_compact_batch(&corrupted);
+            }
+        }
+    }
+
+    #[test]
+    fn encoder_rejects_batches_the_decoder_would() {
+        assert!(encode_compact_batch(DEFAULTS, &[]).is_err());
+        let too_many = vec![file(1, MimeTypeArg::Code(3), 0); MAX_FILES_PER_COMPACT_BATCH + 1];
+        assert!(encode_compact_batch(DEFAULTS, &too_many).is_err());
+        assert!(encode_compact_batch(DEFAULTS, &[file(1, MimeTypeArg::Code(0), 0)]).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT};
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::error::HelixError;
+use crate::state::StorageRegistry;
+
+/// Enforce the registry's CPI policy on the current instruction.
+///
+/// Instructions at transaction level are always allowed. Under CPI the
+/// caller is taken to be the program of the transaction's current top-level
+/// instruction, read from the instructions sysvar; without the sysvar it
+/// cannot be determined, and only an open policy lets the call through.
+pub fn require_cpi_allowed(
+    registry: &StorageRegistry,
+    instructions_sysvar: Option<&AccountInfo>,
+) -> Result<()> {
+    if get_stack_height() <= TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+
+    let caller = match instructions_sysvar {
+        Some(sysvar) => Some(top_level_program(sysvar)?),
+        None => None,
+    };
+    if !registry.allows_cpi_from(caller.as_ref()) {
+        msg!("cpi: caller {:?} is not allowed", caller);
+        return Err(HelixError::CpiNotAllowed.into());
+    }
+
+    Ok(())
+}
+
+/// Program invoked by the transaction's current top-level instruction
+fn top_level_program(instructions_sysvar: &AccountInfo) -> Result<Pubkey> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let ix = load_instruction_at_checked(current_index as usize, instructions_sysvar)?;
+    Ok(ix.program_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_stubs::{set_syscall_stubs, SyscallStubs};
+    use anchor_lang::solana_program::sysvar::instructions::{
+        construct_instructions_data, store_current_index, BorrowedInstruction,
+    };
+    use anchor_lang::solana_program::sysvar;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use crate::state::CpiPolicy;
+    use crate::test_utils::sample_registry;
+
+    /// Stack height the stubbed runtime reports
+    static STACK_HEIGHT: AtomicU64 = AtomicU64::new(0);
+
+    struct StackHeightStub;
+
+    impl SyscallStubs for StackHeightStub {
+        fn sol_get_stack_height(&self) -> u64 {
+            STACK_HEIGHT.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Instructions sysvar data for a transaction whose current top-level
+    /// instruction invokes `caller`
+    fn sysvar_data(caller: &Pubkey) -> Vec<u8> {
+        let ed25519 = anchor_lang::solana_program::ed25519_program::ID;
+        let mut data = construct_instructions_data(&[
+            BorrowedInstruction { program_id: &ed25519, accounts: vec![], data: &[] },
+            BorrowedInstruction { program_id: caller, accounts: vec![], data: &[1] },
+        ]);
+        store_current_index(&mut data, 1);
+        data
+    }
+
+    fn check(registry: &StorageRegistry, caller: Option<&Pubkey>) -> Result<()> {
+        let key = sysvar::instructions::ID;
+        let owner = sysvar::ID;
+        let mut lamports = 0;
+        let mut data = caller.map(sysvar_data).unwrap_or_default();
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        require_cpi_allowed(registry, caller.map(|_| &info))
+    }
+
+    fn registry(policy: CpiPolicy, allowlist: Vec<Pubkey>) -> StorageRegistry {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        registry.cpi_policy = policy;
+        registry.cpi_allowlist = allowlist;
+        registry
+    }
+
+    #[test]
+    fn zeroed_registry_policy_is_open() {
+        let (_, registry) = sample_registry(Pubkey::new_unique(), 0);
+        assert_eq!(registry.cpi_policy, CpiPolicy::Open);
+        assert!(registry.cpi_allowlist.is_empty());
+    }
+
+    #[test]
+    fn policy_gates_cpi_callers_only() {
+        set_syscall_stubs(Box::new(StackHeightStub));
+        let frontend = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let denied = Err(HelixError::CpiNotAllowed.into());
+        let open = registry(CpiPolicy::Open, vec![]);
+        let deny = registry(CpiPolicy::Deny, vec![frontend]);
+        let allowlist = registry(CpiPolicy::Allowlist, vec![frontend]);
+
+        // Top-level calls pass every policy, with or without the sysvar
+        STACK_HEIGHT.store(TRANSACTION_LEVEL_STACK_HEIGHT as u64, Ordering::SeqCst);
+        for registry in [&open, &deny, &allowlist] {
+            assert_eq!(check(registry, None), Ok(()));
+            assert_eq!(check(registry, Some(&stranger)), Ok(()));
+        }
+
+        STACK_HEIGHT.store(TRANSACTION_LEVEL_STACK_HEIGHT as u64 + 1, Ordering::SeqCst);
+        assert_eq!(check(&open, Some(&stranger)), Ok(()));
+        assert_eq!(check(&open, None), Ok(()));
+        assert_eq!(check(&deny, Some(&frontend)), denied);
+        assert_eq!(check(&allowlist, Some(&frontend)), Ok(()));
+        assert_eq!(check(&allowlist, Some(&crate::ID)), Ok(()));
+        assert_eq!(check(&allowlist, Some(&stranger)), denied);
+        assert_eq!(check(&allowlist, None), denied);
+
+        STACK_HEIGHT.store(0, Ordering::SeqCst);
+    }
+}
@@ -4,19 +4,19 @@ use anchor_lang::prelude::*;
 #[error_code]
 pub enum HelixError {
     /// The transaction ID exceeds maximum allowed length
-    #[msg("Transaction ID exceeds maximum length of 43 characters")]
+    #[msg("Transaction ID exceeds maximum length of 43 bytes")]
     TransactionIdTooLong,
 
     /// The encrypted name exceeds maximum allowed length
-    #[msg("Encrypted name exceeds maximum length of 256 characters")]
+    #[msg("Encrypted name exceeds the configured maximum length in bytes")]
     EncryptedNameTooLong,
 
     /// The MIME type exceeds maximum allowed length
-    #[msg("MIME type exceeds maximum length of 128 characters")]
+    #[msg("MIME type exceeds maximum length of 128 bytes")]
     MimeTypeTooLong,
 
     /// The encrypted key exceeds maximum allowed length
-    #[msg("Encrypted key exceeds maximum length of 512 characters")]
+    #[msg("Encrypted key exceeds maximum length of 512 bytes")]
     EncryptedKeyTooLong,
 
     /// The file size must be greater than zero
@@ -59,7 +59,8 @@ pub enum HelixError {
     #[msg("Share link does not permit access from this wallet")]
     ShareAccessDenied,
 
-    /// The share link is not valid
+    /// The share link is not valid (kept for error code stability; the
+    /// specific share errors are returned instead)
     #[msg("Share link is not valid")]
     InvalidShareLink,
 
@@ -94,20 +95,578 @@ pub enum HelixError {
     /// Invalid MIME type format
     #[msg("Invalid MIME type format")]
     InvalidMimeType,
+
+    /// The file is not marked as deleted
+    #[msg("File is not deleted")]
+    FileNotDeleted,
+
+    /// The caller is neither the authority nor a permitted operator
+    #[msg("Unauthorized: caller is not the program authority or a permitted operator")]
+    UnauthorizedOperator,
+
+    /// The operator list is full
+    #[msg("Maximum number of operators reached")]
+    TooManyOperators,
+
+    /// The operator is already registered
+    #[msg("Operator already exists")]
+    DuplicateOperator,
+
+    /// The operator is not registered
+    #[msg("Operator not found")]
+    OperatorNotFound,
+
+    /// The operator permission mask is empty or has unknown bits
+    #[msg("Invalid operator permissions")]
+    InvalidOperatorPermissions,
+
+    /// The memo is empty
+    #[msg("Memo must not be empty")]
+    EmptyMemo,
+
+    /// The memo exceeds maximum allowed length
+    #[msg("Memo exceeds maximum length of 256 bytes")]
+    MemoTooLong,
+
+    /// A memo was supplied without the memo program account
+    #[msg("Memo program account is required when a memo is supplied")]
+    MemoProgramMissing,
+
+    /// The encrypted name limit is zero or above the hard ceiling
+    #[msg("Encrypted name limit must be between 1 and 1024 bytes")]
+    InvalidEncryptedNameLimit,
+
+    /// The file's ownership is already represented by a token
+    #[msg("File is already tokenized")]
+    FileAlreadyTokenized,
+
+    /// The file's ownership is not represented by a token
+    #[msg("File is not tokenized")]
+    FileNotTokenized,
+
+    /// The token account does not hold the file's ownership token
+    #[msg("Token account does not hold the file ownership token")]
+    InvalidFileToken,
+
+    /// A receipt was requested but the registry has no receipt tree configured
+    #[msg("Registration receipts are not configured")]
+    ReceiptNotConfigured,
+
+    /// A receipt was requested without all the accounts needed to mint it
+    #[msg("Missing accounts required to mint a registration receipt")]
+    ReceiptAccountsMissing,
+
+    /// A receipt account does not match the program or configured tree
+    #[msg("Invalid registration receipt account")]
+    InvalidReceiptAccount,
+
+    /// The session scope is empty or contains undefined bits
+    #[msg("Invalid session scope")]
+    InvalidSessionScope,
+
+    /// The session expiry is in the past or beyond the registry's lifetime cap
+    #[msg("Session expiry is in the past or exceeds the maximum lifetime")]
+    InvalidSessionExpiry,
+
+    /// The session lifetime limit must be positive
+    #[msg("Session lifetime limit must be positive")]
+    InvalidSessionLifetime,
+
+    /// The session does not authorize this signer, action or time
+    #[msg("Session key is not authorized for this action")]
+    SessionNotAuthorized,
+
+    /// The permit deadline has passed
+    #[msg("Permit deadline has passed")]
+    PermitExpired,
+
+    /// The permit nonce does not match the owner's next nonce
+    #[msg("Invalid permit nonce")]
+    InvalidPermitNonce,
+
+    /// No matching Ed25519 signature by the owner precedes the instruction
+    #[msg("Missing or invalid permit signature")]
+    InvalidPermitSignature,
+
+    /// The registry's CPI policy does not allow the calling program
+    #[msg("Calling program is not allowed to invoke this instruction")]
+    CpiNotAllowed,
+
+    /// The CPI allowlist is too long or lists a program twice
+    #[msg("Invalid CPI allowlist")]
+    InvalidCpiAllowlist,
+
+    /// The MIME table has no room for more entries
+    #[msg("MIME table is full")]
+    MimeTableFull,
+
+    /// The MIME type is already interned
+    #[msg("MIME type is already in the table")]
+    DuplicateMimeType,
+
+    /// The MIME code is not in the table
+    #[msg("Unknown MIME type code")]
+    UnknownMimeCode,
+
+    /// An interned MIME code was used without supplying the MIME table
+    #[msg("MIME table account is required for MIME type codes")]
+    MimeTableMissing,
+
+    /// Event bytes do not decode as any known version of the event
+    #[msg("Invalid event data")]
+    InvalidEventData,
+
+    /// The share link belongs to a different file than the one supplied
+    #[msg("Share link does not belong to this file")]
+    ShareFileMismatch,
+
+    /// The permit names a different file than the one supplied
+    #[msg("Permit does not match this file")]
+    PermitFileMismatch,
+
+    /// The share link's creation time is in the future
+    #[msg("Share link is not yet active")]
+    ShareNotYetActive,
+
+    /// The file behind the share link has been deleted
+    #[msg("Shared file has been deleted")]
+    SharedFileDeleted,
+
+    /// Encrypted data exceeds the bound of its argument type
+    #[msg("Encrypted data exceeds maximum length in bytes")]
+    EncryptedDataTooLong,
+
+    /// The file already has an entry in its owner's file index
+    #[msg("File is already indexed")]
+    FileAlreadyIndexed,
+
+    /// An index entry does not match the owner, sequence or file account
+    #[msg("Invalid owner file index entry")]
+    InvalidIndexEntry,
+
+    /// The encryption public key is all zeros
+    #[msg("Encryption public key must not be all zeros")]
+    InvalidEncryptionKey,
+
+    /// Recipient accounts were supplied for a share without a recipient
+    #[msg("Recipient accounts supplied for a share without a recipient")]
+    RecipientProfileMismatch,
+
+    /// The share recipient is not the supplied contact, or no contact was
+    /// supplied when one is required
+    #[msg("Recipient is not in the owner's contacts")]
+    RecipientNotInContacts,
+
+    /// A share with a recipient was created without its inbox accounts
+    #[msg("Recipient profile and inbox entry are required for shares with a recipient")]
+    InboxAccountsMissing,
+
+    /// The inbox entry's share is still active
+    #[msg("Share is still active")]
+    ShareStillActive,
+
+    /// Display name exceeds maximum length
+    #[msg("Display name exceeds maximum length in bytes")]
+    DisplayNameTooLong,
+
+    /// Display name contains control characters
+    #[msg("Display name contains invalid characters")]
+    InvalidDisplayName,
+
+    /// Avatar is not a live, public image file owned by the wallet
+    #[msg("Avatar must be a public image file owned by the wallet")]
+    InvalidAvatarFile,
+
+    /// The deleted file is past its trash retention window
+    #[msg("Trash retention period has expired")]
+    RetentionExpired,
+
+    /// The deleted file is still within its trash retention window
+    #[msg("File is still within its trash retention period")]
+    RetentionActive,
+
+    /// The file still has active share links
+    #[msg("File still has active share links")]
+    FileHasActiveShares,
+
+    /// Trash retention window must be positive
+    #[msg("Trash retention period must be positive")]
+    InvalidTrashRetention,
+
+    /// The file already has the maximum number of aliases
+    #[msg("Maximum number of aliases for this file reached")]
+    AliasLimitReached,
+
+    /// Alias limit must be positive
+    #[msg("Alias limit must be positive")]
+    InvalidAliasLimit,
+
+    /// The file still has aliases pointing at it
+    #[msg("File still has aliases")]
+    FileHasAliases,
+
+    /// The alias does not point at the supplied file record
+    #[msg("Alias does not belong to this file")]
+    AliasFileMismatch,
+
+    /// Name is empty, too long or contains characters outside `[a-z0-9._-]`
+    #[msg("Name must be 1-32 bytes of lowercase letters, digits, '.', '_' or '-'")]
+    InvalidName,
+
+    /// Short code is not 4-16 lowercase alphanumerics
+    #[msg("Short code must be 4-16 lowercase letters or digits")]
+    InvalidShortCode,
+
+    /// A fee is due but the treasury account was not supplied
+    #[msg("Treasury account is required when a fee is charged")]
+    TreasuryMissing,
+
+    /// A fee is due but the payer's profile, which records it, was not
+    /// supplied
+    #[msg("Payer profile is required when a fee is charged")]
+    FeeProfileMissing,
+
+    /// The share link has a recipient
+    #[msg("Share link is not public")]
+    ShareNotPublic,
+
+    /// The wallet is not in the attestor set
+    #[msg("Wallet is not a listed attestor")]
+    AttestorNotListed,
+
+    /// The attestor set is full
+    #[msg("Maximum number of attestors reached")]
+    TooManyAttestors,
+
+    /// The attestor is already listed
+    #[msg("Attestor already exists")]
+    DuplicateAttestor,
+
+    /// The challenged byte range is empty or outside the file
+    #[msg("Invalid challenge range")]
+    InvalidChallengeRange,
+
+    /// The challenge deadline is outside the allowed response window
+    #[msg("Invalid challenge deadline")]
+    InvalidChallengeDeadline,
+
+    /// The challenge has already been answered
+    #[msg("Challenge already answered")]
+    ChallengeAlreadyAnswered,
+
+    /// The challenge has not been answered yet
+    #[msg("Challenge not answered")]
+    ChallengeNotAnswered,
+
+    /// The challenge deadline has passed
+    #[msg("Challenge deadline passed")]
+    ChallengeDeadlinePassed,
+
+    /// The challenge cannot be settled yet
+    #[msg("Challenge still open")]
+    ChallengeStillOpen,
+
+    /// No matching Ed25519 signature by the rights holder precedes the instruction
+    #[msg("Missing or invalid copyright signature")]
+    InvalidCopyrightSignature,
+
+    /// The signer and salt do not match the record's owner commitment
+    #[msg("Owner commitment mismatch")]
+    OwnerCommitmentMismatch,
+
+    /// A share cannot have both a plaintext recipient and a recipient commitment
+    #[msg("Recipient and recipient commitment are mutually exclusive")]
+    RecipientModeConflict,
+
+    /// The audit grant's file is missing or not owned by the granting owner
+    #[msg("Audit scope does not match an owned file")]
+    AuditScopeMismatch,
+
+    /// The audit grant has already been revoked
+    #[msg("Audit grant already revoked")]
+    AuditGrantAlreadyRevoked,
+
+    /// The file has no chunk Merkle root
+    #[msg("File has no chunk root")]
+    ChunkRootMissing,
+
+    /// The chunk proof exceeds the maximum tree depth
+    #[msg("Chunk proof too long")]
+    ChunkProofTooLong,
+
+    /// The chunk index cannot be addressed by a proof of this depth
+    #[msg("Chunk index out of range for proof")]
+    ChunkIndexOutOfRange,
+
+    /// The number of batch entries does not match the number of accounts
+    #[msg("Batch entries do not match the supplied accounts")]
+    BatchLengthMismatch,
+
+    /// The batch holds more entries than one call may process
+    #[msg("Too many batch entries")]
+    TooManyBatchEntries,
+
+    /// The file's lifecycle does not allow moving to the requested status
+    #[msg("Invalid file status transition")]
+    InvalidStatusTransition,
+
+    /// The operation requires an active or archived file
+    #[msg("File is not active")]
+    FileNotActive,
+
+    /// An indexed file's index entry was not supplied
+    #[msg("Index entry required for indexed files")]
+    IndexEntryMissing,
+
+    /// The owner's profile was not supplied for an archived file
+    #[msg("User profile required for archived files")]
+    ProfileMissing,
+
+    /// Default share limits must be positive
+    #[msg("Default share limits must be greater than zero")]
+    InvalidShareDefaults,
+
+    /// Share defaults were requested without the owner's profile
+    #[msg("Owner profile required to apply share defaults")]
+    ShareDefaultsMissing,
+
+    /// The owner's defaults require shares to name a recipient
+    #[msg("Share must have a recipient")]
+    ShareRecipientRequired,
+
+    /// The supplied profile belongs to another wallet
+    #[msg("Profile does not belong to the acting wallet")]
+    ProfileOwnerMismatch,
+
+    /// Only unencrypted files can be listed publicly
+    #[msg("File is not public")]
+    FileNotPublic,
+
+    /// Endowment amounts must be positive
+    #[msg("Amount must be greater than zero")]
+    InvalidEndowmentAmount,
+
+    /// The withdrawal would dip into the endowment's rent reserve
+    #[msg("Insufficient endowment balance")]
+    InsufficientEndowment,
+
+    /// The file's endowment must be closed before it is purged
+    #[msg("File still has an endowment")]
+    FileHasEndowment,
+
+    /// The attested size differs from the size on the record
+    #[msg("Attested size does not match the recorded size")]
+    SizeMismatch,
+
+    /// The file owner's account is frozen
+    #[msg("Owner account is frozen")]
+    OwnerFrozen,
+
+    /// The account is not frozen
+    #[msg("Account is not frozen")]
+    AccountNotFrozen,
+
+    /// Only the wallet or its guardian can freeze the account
+    #[msg("Unauthorized: signer is neither the wallet nor its guardian")]
+    UnauthorizedGuardian,
+
+    /// Only encrypted files have a key to release
+    #[msg("File is not encrypted")]
+    FileNotEncrypted,
+
+    /// The key release deadline must be in the future
+    #[msg("Release time must be in the future")]
+    ReleaseTimeInPast,
+
+    /// The key release deadline has not been reached
+    #[msg("Key release deadline has not passed")]
+    ReleaseNotDue,
+
+    /// The key release deadline has passed, so it can no longer be cancelled
+    #[msg("Key release deadline has passed")]
+    ReleaseDeadlinePassed,
+
+    /// The key has already been released
+    #[msg("Key already released")]
+    KeyAlreadyReleased,
+
+    /// The key has not been released yet
+    #[msg("Key not released")]
+    KeyNotReleased,
+
+    /// The share has no pending claim (never claimable or already claimed)
+    #[msg("Share not claimable")]
+    ShareNotClaimable,
+
+    /// The preimage does not match the share's claim hash
+    #[msg("Invalid claim secret")]
+    InvalidClaimSecret,
+
+    /// The expiry warning window cannot be negative
+    #[msg("Invalid expiry warning window")]
+    InvalidExpiryWarningWindow,
+
+    /// The expiry warning bounty must be below the rent of a share link
+    #[msg("Expiry warning bounty too high")]
+    ExpiryWarningBountyTooHigh,
+
+    /// Expiry warnings are disabled in the registry
+    #[msg("Expiry warnings disabled")]
+    ExpiryWarningsDisabled,
+
+    /// The share has no expiration
+    #[msg("Share never expires")]
+    ShareNeverExpires,
+
+    /// The share's expiry is outside the warning window
+    #[msg("Share not expiring soon")]
+    ShareNotExpiringSoon,
+
+    /// The share's recipient was already warned
+    #[msg("Share already warned")]
+    ShareAlreadyWarned,
+
+    /// The IPFS CID is not a valid CIDv0 or base32 CIDv1
+    #[msg("Invalid IPFS CID")]
+    InvalidCid,
+
+    /// The transaction ID is not the record ID of the content locator
+    #[msg("Transaction ID does not match the content locator")]
+    ContentLocatorMismatch,
+
+    /// IPFS content was registered with an Irys storage location
+    #[msg("IPFS content cannot have an Irys storage location")]
+    IpfsStorageLocation,
+
+    /// The mirror limit must be greater than zero
+    #[msg("Mirror limit must be greater than zero")]
+    InvalidMirrorLimit,
+
+    /// The file already has the maximum number of mirrors
+    #[msg("Maximum number of mirrors for this file reached")]
+    MirrorLimitReached,
+
+    /// The mirror locates the file's own content
+    #[msg("Mirror matches the file's primary content")]
+    MirrorIsPrimary,
+
+    /// The mirror does not belong to the given file
+    #[msg("Mirror does not belong to this file")]
+    MirrorFileMismatch,
+
+    /// Not every mirror of the file was supplied exactly once
+    #[msg("Mirror accounts missing or repeated")]
+    MirrorsIncomplete,
+
+    /// The file still has mirrors attached
+    #[msg("File still has mirrors")]
+    FileHasMirrors,
+
+    /// The account receiving the rent is not the recorded rent payer
+    #[msg("Rent payer does not match the account's recorded payer")]
+    RentPayerMismatch,
+
+    /// The proposed owner is already the file's owner
+    #[msg("New owner must differ from the current owner")]
+    InvalidNewOwner,
+
+    /// The file has no ownership transfer that can be accepted
+    #[msg("No pending ownership transfer")]
+    NoPendingTransfer,
+
+    /// The action is blocked while an ownership transfer is pending
+    #[msg("Ownership transfer pending")]
+    OwnershipTransferPending,
+
+    /// A batch share account is not the PDA of its file's share nonce
+    #[msg("Share account does not match the file's share nonce")]
+    ShareAddressMismatch,
+
+    /// Share template limits must be positive and agree with single use
+    #[msg("Invalid share template settings")]
+    InvalidShareTemplate,
+
+    /// A template was requested without the template account
+    #[msg("Share template account required to apply a template")]
+    ShareTemplateMissing,
+
+    /// A template and the profile defaults were both requested
+    #[msg("Cannot apply both a share template and profile defaults")]
+    ShareTemplateConflict,
+
+    /// The download throttle's cooldown or fee must be positive
+    #[msg("Invalid download throttle")]
+    InvalidDownloadThrottle,
+
+    /// The wallet downloaded this public share too recently
+    #[msg("Download cooldown has not elapsed for this share")]
+    DownloadCooldownActive,
+
+    /// Reconciled counters must be consistent with each other
+    #[msg("Registry counters are not plausible")]
+    ImplausibleCounters,
+
+    /// A withdrawal would take the treasury below its rent-exempt minimum
+    #[msg("Withdrawal exceeds the treasury balance above its rent-exempt minimum")]
+    WithdrawalBelowRentExempt,
+
+    /// Nothing to withdraw
+    #[msg("Withdrawal amount must be greater than zero")]
+    InvalidWithdrawalAmount,
+
+    /// The proposed authority is already the authority or the default key
+    #[msg("New authority must differ from the current authority")]
+    InvalidNewAuthority,
+
+    /// The registry has no authority transfer the signer can accept
+    #[msg("No pending authority transfer")]
+    NoPendingAuthority,
+
+    /// The account already deserializes under the current layout
+    #[msg("Account is already migrated to the current layout")]
+    AccountAlreadyMigrated,
+
+    /// A compact batch payload is truncated, malformed or has trailing bytes
+    #[msg("Invalid compact batch")]
+    InvalidCompactBatch,
+
+    /// A batch file account is not the file PDA of its transaction ID
+    #[msg("File account does not match the transaction ID")]
+    FileAddressMismatch,
+
+    /// The file already has the registry's maximum number of live shares
+    #[msg("File has reached the maximum number of shares")]
+    ShareLimitReached,
+
+    /// The file is tokenized and the signer did not present the ownership token
+    #[msg("Tokenized file requires the signer to hold its ownership token")]
+    FileTokenRequired,
 }
 
+/// Lookup table of base64url characters, indexed by byte
+const BASE64URL_CHARS: [bool; 256] = {
+    let mut table = [false; 256];
+    let mut i = 0;
+    while i < 256 {
+        let c = i as u8;
+        table[i] = c.is_ascii_alphanumeric() || c == b'-' || c == b'_';
+        i += 1;
+    }
+    table
+};
+
 /// Validate Arweave transaction ID format
 /// Transaction IDs are 43 characters, base64url encoded
 pub fn validate_transaction_id(tx_id: &str) -> Result<()> {
     if tx_id.len() != 43 {
+        msg!("transaction_id: length {} must be 43", tx_id.len());
         return Err(HelixError::InvalidTransactionId.into());
     }
 
     // Check for valid base64url characters
-    for c in tx_id.chars() {
-        if !c.is_ascii_alphanumeric() && c != '-' && c != '_' {
-            return Err(HelixError::InvalidTransactionId.into());
-        }
+    if let Some(index) = tx_id.bytes().position(|b| !BASE64URL_CHARS[b as usize]) {
+        msg!("transaction_id: invalid base64url character at byte {}", index);
+        return Err(HelixError::InvalidTransactionId.into());
     }
 
     Ok(())
@@ -116,34 +675,291 @@ pub fn validate_transaction_id(tx_id: &str) -> Result<()> {
 /// Validate MIME type format
 /// Basic validation for "type/subtype" format
 pub fn validate_mime_type(mime: &str) -> Result<()> {
-    if mime.is_empty() || !mime.contains('/') {
-        return Err(HelixError::InvalidMimeType.into());
-    }
+    let valid = match mime.split_once('/') {
+        Some((kind, subtype)) => !kind.is_empty() && !subtype.is_empty() && !subtype.contains('/'),
+        None => false,
+    };
 
-    let parts: Vec<&str> = mime.split('/').collect();
-    if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
+    if !valid {
+        msg!("mime_type: expected \"type/subtype\", got {} bytes", mime.len());
         return Err(HelixError::InvalidMimeType.into());
     }
 
     Ok(())
 }
 
-/// Validate string length against maximum
-pub fn validate_string_length(s: &str, max_len: usize, error: HelixError) -> Result<()> {
+/// Validate a string's length in bytes of UTF-8, the unit of every length
+/// limit in the program, logging the field name, actual length and limit on
+/// failure. Multi-byte characters count once per byte, so clients must
+/// check the encoded length rather than the character count.
+/// 
+/// No separate encoding check is needed: borsh rejects string arguments
+/// that are not valid UTF-8, and valid UTF-8 cannot encode surrogates.
+pub fn validate_byte_length(
+    field: &str,
+    s: &str,
+    max_len: usize,
+    error: HelixError,
+) -> Result<()> {
     if s.len() > max_len {
+        msg!("{}: {} bytes exceeds maximum of {} bytes", field, s.len(), max_len);
         return Err(error.into());
     }
     Ok(())
 }
 
-/// Validate optional string length against maximum
-pub fn validate_optional_string_length(
-    s: &Option<String>,
+/// Validate that a plaintext string contains no control characters,
+/// logging the byte offset of the first one on failure
+pub fn validate_no_control_chars(field: &str, s: &str, error: HelixError) -> Result<()> {
+    if let Some((index, _)) = s.char_indices().find(|(_, c)| c.is_control()) {
+        msg!("{}: control character at byte {}", field, index);
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Validate a namespace name: 1 to `max_len` bytes of lowercase ASCII
+/// letters, digits, '.', '_' or '-'
+pub fn validate_name(name: &str, max_len: usize) -> Result<()> {
+    if name.is_empty() || name.len() > max_len {
+        msg!("name: length {} must be between 1 and {}", name.len(), max_len);
+        return Err(HelixError::InvalidName.into());
+    }
+
+    let valid = |b: u8| b.is_ascii_lowercase() || b.is_ascii_digit() || b"._-".contains(&b);
+    if let Some(index) = name.bytes().position(|b| !valid(b)) {
+        msg!("name: invalid character at byte {}", index);
+        return Err(HelixError::InvalidName.into());
+    }
+
+    Ok(())
+}
+
+/// Validate a short code: `min_len` to `max_len` lowercase ASCII letters
+/// or digits
+pub fn validate_short_code(code: &str, min_len: usize, max_len: usize) -> Result<()> {
+    if code.len() < min_len || code.len() > max_len {
+        msg!("code: length {} must be between {} and {}", code.len(), min_len, max_len);
+        return Err(HelixError::InvalidShortCode.into());
+    }
+
+    if let Some(index) = code
+        .bytes()
+        .position(|b| !(b.is_ascii_lowercase() || b.is_ascii_digit()))
+    {
+        msg!("code: invalid character at byte {}", index);
+        return Err(HelixError::InvalidShortCode.into());
+    }
+
+    Ok(())
+}
+
+/// Validate an IPFS CID string: a CIDv0 of "Qm" and 44 more base58btc
+/// characters, or a base32 CIDv1 of `min_v1_len` to `max_len` characters
+/// with the "b" (lowercase) or "B" (uppercase) multibase prefix, whose
+/// version and codec bits read "baf" once lowercased
+pub fn validate_cid(cid: &str, min_v1_len: usize, max_len: usize) -> Result<()> {
+    let (valid_len, valid_char): (bool, fn(&u8) -> bool) = match cid.as_bytes().first() {
+        Some(b'Q') => (cid.len() == 46 && cid.starts_with("Qm"), |b| {
+            b.is_ascii_alphanumeric() && !b"0OIl".contains(b)
+        }),
+        Some(b'b') => (cid.len() >= min_v1_len && cid.len() <= max_len, |b| {
+            b.is_ascii_lowercase() || (b'2'..=b'7').contains(b)
+        }),
+        Some(b'B') => (cid.len() >= min_v1_len && cid.len() <= max_len, |b| {
+            b.is_ascii_uppercase() || (b'2'..=b'7').contains(b)
+        }),
+        _ => {
+            msg!("cid: expected a \"Qm\", \"b\" or \"B\" prefix");
+            return Err(HelixError::InvalidCid.into());
+        }
+    };
+
+    if !valid_len {
+        msg!("cid: length {} is out of bounds for its version", cid.len());
+        return Err(HelixError::InvalidCid.into());
+    }
+
+    if let Some(index) = cid.bytes().position(|b| !valid_char(&b)) {
+        msg!("cid: invalid character at byte {}", index);
+        return Err(HelixError::InvalidCid.into());
+    }
+
+    if !cid.starts_with("Qm") && !cid[1..3].eq_ignore_ascii_case("af") {
+        msg!("cid: base32 CID is not a CIDv1");
+        return Err(HelixError::InvalidCid.into());
+    }
+
+    Ok(())
+}
+
+/// Validate an optional string's length in bytes against maximum
+pub fn validate_optional_byte_length<S: AsRef<str>>(
+    field: &str,
+    s: &Option<S>,
     max_len: usize,
     error: HelixError,
 ) -> Result<()> {
     if let Some(val) = s {
-        validate_string_length(val, max_len, error)?;
+        validate_byte_length(field, val.as_ref(), max_len, error)?;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::SAMPLE_TX_ID;
+
+    #[test]
+    fn base64url_table_matches_the_alphabet() {
+        for byte in 0..=u8::MAX {
+            let expected = byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_';
+            assert_eq!(BASE64URL_CHARS[byte as usize], expected, "byte {byte:#04x}");
+        }
+    }
+
+    #[test]
+    fn transaction_ids_are_43_base64url_bytes() {
+        let invalid = Err(HelixError::InvalidTransactionId.into());
+        assert_eq!(validate_transaction_id(SAMPLE_TX_ID), Ok(()));
+        assert_eq!(validate_transaction_id(&"-_".repeat(22)[..43]), Ok(()));
+        assert_eq!(validate_transaction_id(&SAMPLE_TX_ID[..42]), invalid);
+        assert_eq!(validate_transaction_id(&format!("{SAMPLE_TX_ID}A")), invalid);
+        assert_eq!(validate_transaction_id(""), invalid);
+
+        for byte in (0..0x80u8).filter(|b| !BASE64URL_CHARS[*b as usize]) {
+            let tx_id = format!("{}{}", &SAMPLE_TX_ID[..42], byte as char);
+            assert_eq!(validate_transaction_id(&tx_id), invalid, "byte {byte:#04x}");
+        }
+        // 43 bytes, but one character is multi-byte
+        assert_eq!(validate_transaction_id(&format!("{}é", &SAMPLE_TX_ID[..41])), invalid);
+    }
+
+    #[test]
+    fn error_codes_are_stable() {
+        // Clients match on these numbers; new variants only ever append
+        let codes = [
+            (HelixError::TransactionIdTooLong, 6000),
+            (HelixError::InvalidTransactionId, 6005),
+            (HelixError::ShareAccessDenied, 6013),
+            (HelixError::InvalidShareLink, 6014),
+            (HelixError::InvalidMimeType, 6022),
+            (HelixError::UnknownMimeCode, 6050),
+            (HelixError::ShareFileMismatch, 6053),
+            (HelixError::PermitFileMismatch, 6054),
+            (HelixError::ShareNotYetActive, 6055),
+            (HelixError::SharedFileDeleted, 6056),
+        ];
+        for (error, code) in codes {
+            assert_eq!(u32::from(error), code, "{error:?}");
+        }
+    }
+
+    #[test]
+    fn mime_types_need_one_type_and_subtype() {
+        for mime in ["image/png", "application/vnd.api+json", "a/b"] {
+            assert_eq!(validate_mime_type(mime), Ok(()), "{mime}");
+        }
+        for mime in ["", "/", "image", "image/", "/png", "image/png/x", "a//b"] {
+            assert_eq!(
+                validate_mime_type(mime),
+                Err(HelixError::InvalidMimeType.into()),
+                "{mime}"
+            );
+        }
+    }
+
+    #[test]
+    fn length_checks_return_the_callers_error() {
+        for error in [HelixError::EncryptedNameTooLong, HelixError::MimeTypeTooLong] {
+            assert_eq!(validate_byte_length("field", "abcd", 4, error), Ok(()));
+            assert_eq!(validate_byte_length("field", "abcde", 4, error), Err(error.into()));
+            assert_eq!(
+                validate_optional_byte_length("field", &Some("abcde"), 4, error),
+                Err(error.into())
+            );
+        }
+    }
+
+    #[test]
+    fn each_validator_fails_with_its_own_error() {
+        let control = HelixError::InvalidDisplayName;
+        assert_eq!(validate_no_control_chars("field", "tab\there", control), Err(control.into()));
+        assert_eq!(validate_no_control_chars("field", "plain é 名", control), Ok(()));
+
+        let invalid_name = Err(HelixError::InvalidName.into());
+        assert_eq!(validate_name("reports-2024.q1_final", 32), Ok(()));
+        assert_eq!(validate_name("", 32), invalid_name);
+        assert_eq!(validate_name("Reports", 32), invalid_name);
+        assert_eq!(validate_name(&"a".repeat(33), 32), invalid_name);
+
+        let invalid_code = Err(HelixError::InvalidShortCode.into());
+        assert_eq!(validate_short_code("abc123", 4, 8), Ok(()));
+        assert_eq!(validate_short_code("abc", 4, 8), invalid_code);
+        assert_eq!(validate_short_code("abc-123", 4, 8), invalid_code);
+        assert_eq!(validate_short_code("abcdefghi", 4, 8), invalid_code);
+
+        let invalid_cid = Err(HelixError::InvalidCid.into());
+        let v0 = format!("Qm{}", "a".repeat(44));
+        let v1 = format!("bafy{}", "a".repeat(55));
+        assert_eq!(validate_cid(&v0, 59, 100), Ok(()));
+        assert_eq!(validate_cid(&v1, 59, 100), Ok(()));
+        assert_eq!(validate_cid(&v1.to_uppercase(), 59, 100), Ok(()));
+        assert_eq!(validate_cid(&v0[..45], 59, 100), invalid_cid);
+        assert_eq!(validate_cid(&format!("Qm{}0", "a".repeat(43)), 59, 100), invalid_cid);
+        assert_eq!(validate_cid(&v1[..58], 59, 100), invalid_cid);
+        assert_eq!(validate_cid(&format!("bbfy{}", "a".repeat(55)), 59, 100), invalid_cid);
+        assert_eq!(validate_cid("zQm", 59, 100), invalid_cid);
+        assert_eq!(validate_cid("", 59, 100), invalid_cid);
+    }
+
+    #[test]
+    fn lengths_are_counted_in_utf8_bytes() {
+        let too_long = Err(HelixError::EncryptedNameTooLong.into());
+        let check = |s: &str, max| {
+            validate_byte_length("name", s, max, HelixError::EncryptedNameTooLong)
+        };
+
+        // 🚀 is 4 bytes and 名 is 3, so both count past their one character
+        assert_eq!("🚀".len(), 4);
+        assert_eq!(check("🚀", 4), Ok(()));
+        assert_eq!(check("🚀", 3), too_long);
+        assert_eq!(check(&"名".repeat(3), 9), Ok(()));
+        assert_eq!(check(&"名".repeat(3), 8), too_long);
+
+        // Exactly at the limit passes and one byte over fails, however the
+        // bytes are split into characters
+        let mixed = format!("ab{}é", "🚀".repeat(2));
+        for s in ["a".repeat(12), "名".repeat(4), "🚀".repeat(3), mixed] {
+            assert_eq!(s.len(), 12, "{s}");
+            assert_eq!(check(&s, 12), Ok(()));
+            assert_eq!(check(&s, 11), too_long);
+        }
+        assert_eq!(check("", 0), Ok(()));
+    }
+
+    #[test]
+    fn optional_lengths_skip_only_none() {
+        let too_long = Err(HelixError::EncryptedNameTooLong.into());
+        let check = |s: &Option<String>, max| {
+            validate_optional_byte_length("name", s, max, HelixError::EncryptedNameTooLong)
+        };
+
+        assert_eq!(check(&None, 0), Ok(()));
+        assert_eq!(check(&Some(String::new()), 0), Ok(()));
+        assert_eq!(check(&Some("名🚀".to_string()), 7), Ok(()));
+        assert_eq!(check(&Some("名🚀".to_string()), 6), too_long);
+    }
+
+    #[test]
+    fn strings_that_are_not_utf8_never_reach_the_validators() {
+        // Borsh rejects overlong encodings, lone surrogates (as CESU-8) and
+        // truncated sequences before any length check runs
+        for bytes in [&[0xc0, 0xaf][..], &[0xed, 0xa0, 0x80], &[0xe5, 0x90]] {
+            let mut data = (bytes.len() as u32).to_le_bytes().to_vec();
+            data.extend_from_slice(bytes);
+            assert!(String::try_from_slice(&data).is_err(), "{bytes:x?}");
+        }
+    }
+}
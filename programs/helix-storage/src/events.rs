@@ -0,0 +1,366 @@
+use anchor_lang::prelude::*;
+#[cfg(all(feature = "event-cpi", not(feature = "wasm-client")))]
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+#[cfg(all(feature = "event-cpi", not(feature = "wasm-client")))]
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::Discriminator;
+
+use crate::error::HelixError;
+use crate::state::{FileRegistered, ShareCreated, StorageLocation};
+
+/// Seed for the event authority PDA that signs self-CPI events
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+
+/// Emit an event as self-CPI instruction data signed by the event authority.
+/// 
+/// Equivalent to Anchor's `emit_cpi!`, but takes the event authority
+/// explicitly so it can be invoked through the `emit_event!` macro.
+#[cfg(all(feature = "event-cpi", not(feature = "wasm-client")))]
+pub fn emit_via_cpi<E: anchor_lang::Event>(
+    event_authority: &AccountInfo,
+    bump: u8,
+    event: &E,
+) -> Result<()> {
+    let data: Vec<u8> = anchor_lang::event::EVENT_IX_TAG_LE
+        .into_iter()
+        .chain(event.data())
+        .collect();
+
+    let ix = Instruction::new_with_bytes(
+        crate::ID,
+        &data,
+        vec![AccountMeta::new_readonly(*event_authority.key, true)],
+    );
+
+    invoke_signed(
+        &ix,
+        std::slice::from_ref(event_authority),
+        &[&[EVENT_AUTHORITY_SEED, &[bump]]],
+    )
+    .map_err(Into::into)
+}
+
+/// Original `FileRegistered` layout, emitted before events carried a version
+#[derive(AnchorDeserialize)]
+struct FileRegisteredV1 {
+    owner: Pubkey,
+    transaction_id: String,
+    size: u64,
+    is_encrypted: bool,
+    timestamp: i64,
+}
+
+impl From<FileRegisteredV1> for FileRegistered {
+    fn from(v1: FileRegisteredV1) -> Self {
+        Self {
+            version: 1,
+            owner: v1.owner,
+            transaction_id: v1.transaction_id,
+            size: v1.size,
+            is_encrypted: v1.is_encrypted,
+            timestamp: v1.timestamp,
+            storage_location: StorageLocation::ArweaveL1,
+            owner_seq: 0,
+        }
+    }
+}
+
+/// `FileRegistered` layout of event versions 2 to 4, before
+/// `storage_location`
+#[derive(AnchorDeserialize)]
+struct FileRegisteredV4 {
+    version: u8,
+    owner: Pubkey,
+    transaction_id: String,
+    size: u64,
+    is_encrypted: bool,
+    timestamp: i64,
+}
+
+impl From<FileRegisteredV4> for FileRegistered {
+    fn from(v4: FileRegisteredV4) -> Self {
+        Self {
+            version: v4.version,
+            owner: v4.owner,
+            transaction_id: v4.transaction_id,
+            size: v4.size,
+            is_encrypted: v4.is_encrypted,
+            timestamp: v4.timestamp,
+            storage_location: StorageLocation::ArweaveL1,
+            owner_seq: 0,
+        }
+    }
+}
+
+/// `FileRegistered` layout of event versions 5 and 6, before `owner_seq`
+#[derive(AnchorDeserialize)]
+struct FileRegisteredV6 {
+    version: u8,
+    owner: Pubkey,
+    transaction_id: String,
+    size: u64,
+    is_encrypted: bool,
+    timestamp: i64,
+    storage_location: StorageLocation,
+}
+
+impl From<FileRegisteredV6> for FileRegistered {
+    fn from(v6: FileRegisteredV6) -> Self {
+        Self {
+            version: v6.version,
+            owner: v6.owner,
+            transaction_id: v6.transaction_id,
+            size: v6.size,
+            is_encrypted: v6.is_encrypted,
+            timestamp: v6.timestamp,
+            storage_location: v6.storage_location,
+            owner_seq: 0,
+        }
+    }
+}
+
+/// Original `ShareCreated` layout, emitted before events carried a version
+#[derive(AnchorDeserialize)]
+struct ShareCreatedV1 {
+    file: Pubkey,
+    owner: Pubkey,
+    recipient: Option<Pubkey>,
+    expires_at: Option<i64>,
+    timestamp: i64,
+}
+
+impl From<ShareCreatedV1> for ShareCreated {
+    fn from(v1: ShareCreatedV1) -> Self {
+        Self {
+            version: 1,
+            file: v1.file,
+            owner: v1.owner,
+            recipient: v1.recipient,
+            expires_at: v1.expires_at,
+            timestamp: v1.timestamp,
+            has_message: false,
+            template_id: None,
+        }
+    }
+}
+
+/// `ShareCreated` layout of event versions 2 and 3, before `has_message`
+#[derive(AnchorDeserialize)]
+struct ShareCreatedV3 {
+    version: u8,
+    file: Pubkey,
+    owner: Pubkey,
+    recipient: Option<Pubkey>,
+    expires_at: Option<i64>,
+    timestamp: i64,
+}
+
+impl From<ShareCreatedV3> for ShareCreated {
+    fn from(v3: ShareCreatedV3) -> Self {
+        Self {
+            version: v3.version,
+            file: v3.file,
+            owner: v3.owner,
+            recipient: v3.recipient,
+            expires_at: v3.expires_at,
+            timestamp: v3.timestamp,
+            has_message: false,
+            template_id: None,
+        }
+    }
+}
+
+/// `ShareCreated` layout of event versions 4 and 5, before `template_id`
+#[derive(AnchorDeserialize)]
+struct ShareCreatedV5 {
+    version: u8,
+    file: Pubkey,
+    owner: Pubkey,
+    recipient: Option<Pubkey>,
+    expires_at: Option<i64>,
+    timestamp: i64,
+    has_message: bool,
+}
+
+impl From<ShareCreatedV5> for ShareCreated {
+    fn from(v5: ShareCreatedV5) -> Self {
+        Self {
+            version: v5.version,
+            file: v5.file,
+            owner: v5.owner,
+            recipient: v5.recipient,
+            expires_at: v5.expires_at,
+            timestamp: v5.timestamp,
+            has_message: v5.has_message,
+            template_id: None,
+        }
+    }
+}
+
+/// Decode an event from its discriminator-prefixed bytes, accepting either
+/// the current versioned layout or the original unversioned one.
+/// 
+/// The versioned layout is tried first and must consume the whole payload.
+/// A v1 payload is shifted by the missing version byte, so its length
+/// prefixes and total size do not line up with the current layout.
+fn decode_versioned<E, V1>(data: &[u8]) -> Result<E>
+where
+    E: AnchorDeserialize + Discriminator,
+    V1: AnchorDeserialize + Into<E>,
+{
+    let payload = data
+        .strip_prefix(&E::DISCRIMINATOR[..])
+        .ok_or(HelixError::InvalidEventData)?;
+
+    if let Ok(event) = E::try_from_slice(payload) {
+        return Ok(event);
+    }
+
+    V1::try_from_slice(payload)
+        .map(Into::into)
+        .map_err(|_| HelixError::InvalidEventData.into())
+}
+
+/// Decode a `FileRegistered` event of any version.
+/// Events decoded from v1 payloads report `version == 1`; events from
+/// before version 5 report `storage_location == ArweaveL1`, and events from
+/// before version 7 report `owner_seq == 0`.
+pub fn decode_file_registered(data: &[u8]) -> Result<FileRegistered> {
+    decode_versioned::<FileRegistered, FileRegisteredV6>(data)
+        .or_else(|_| decode_versioned::<FileRegistered, FileRegisteredV4>(data))
+        .or_else(|_| decode_versioned::<FileRegistered, FileRegisteredV1>(data))
+}
+
+/// Decode a `ShareCreated` event of any version.
+/// Events decoded from v1 payloads report `version == 1`; events from
+/// before version 4 report `has_message == false`, and events from before
+/// version 6 report `template_id == None`.
+pub fn decode_share_created(data: &[u8]) -> Result<ShareCreated> {
+    decode_versioned::<ShareCreated, ShareCreatedV5>(data)
+        .or_else(|_| decode_versioned::<ShareCreated, ShareCreatedV3>(data))
+        .or_else(|_| decode_versioned::<ShareCreated, ShareCreatedV1>(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::EVENT_VERSION;
+    use crate::test_utils::{NOW, SAMPLE_TX_ID};
+    use anchor_lang::Event;
+
+    /// Event bytes as an indexer receives them: the discriminator, then the
+    /// borsh-encoded fields of the layout that was emitted
+    fn payload<E: Discriminator>(fields: impl AnchorSerialize) -> Vec<u8> {
+        [&E::DISCRIMINATOR[..], &fields.try_to_vec().unwrap()].concat()
+    }
+
+    #[test]
+    fn v1_file_registered_decodes_through_the_compat_path() {
+        let owner = Pubkey::new_unique();
+        let data = payload::<FileRegistered>((owner, SAMPLE_TX_ID, 4096u64, true, NOW));
+
+        let event = decode_file_registered(&data).unwrap();
+        assert_eq!(event.version, 1);
+        assert_eq!(event.owner, owner);
+        assert_eq!(event.transaction_id, SAMPLE_TX_ID);
+        assert_eq!(event.size, 4096);
+        assert!(event.is_encrypted);
+        assert_eq!(event.timestamp, NOW);
+        assert_eq!(event.storage_location, StorageLocation::ArweaveL1);
+        assert_eq!(event.owner_seq, 0);
+    }
+
+    #[test]
+    fn every_file_registered_version_decodes() {
+        let owner = Pubkey::new_unique();
+        let irys = StorageLocation::IrysDataItem { bundle_tx: Some([9; 32]) };
+
+        let v4 = payload::<FileRegistered>((4u8, owner, SAMPLE_TX_ID, 1u64, false, NOW));
+        let event = decode_file_registered(&v4).unwrap();
+        assert_eq!((event.version, event.owner_seq), (4, 0));
+        assert_eq!(event.storage_location, StorageLocation::ArweaveL1);
+
+        let v6 = payload::<FileRegistered>((6u8, owner, SAMPLE_TX_ID, 1u64, false, NOW, irys));
+        let event = decode_file_registered(&v6).unwrap();
+        assert_eq!((event.version, event.owner_seq), (6, 0));
+        assert_eq!(event.storage_location, irys);
+
+        let current = FileRegistered {
+            version: EVENT_VERSION,
+            owner,
+            transaction_id: SAMPLE_TX_ID.to_string(),
+            size: 1,
+            is_encrypted: false,
+            timestamp: NOW,
+            storage_location: irys,
+            owner_seq: 42,
+        };
+        let event = decode_file_registered(&current.data()).unwrap();
+        assert_eq!((event.version, event.owner_seq), (EVENT_VERSION, 42));
+        assert_eq!(event.storage_location, irys);
+    }
+
+    #[test]
+    fn v1_share_created_decodes_through_the_compat_path() {
+        let (file, owner, recipient) =
+            (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let data = payload::<ShareCreated>((file, owner, Some(recipient), Some(NOW + 60), NOW));
+
+        let event = decode_share_created(&data).unwrap();
+        assert_eq!(event.version, 1);
+        assert_eq!((event.file, event.owner), (file, owner));
+        assert_eq!(event.recipient, Some(recipient));
+        assert_eq!(event.expires_at, Some(NOW + 60));
+        assert_eq!(event.timestamp, NOW);
+        assert!(!event.has_message);
+        assert_eq!(event.template_id, None);
+
+        // A public share without expiry has the shortest v1 payload
+        let data = payload::<ShareCreated>((file, owner, None::<Pubkey>, None::<i64>, NOW));
+        let event = decode_share_created(&data).unwrap();
+        assert_eq!((event.version, event.recipient, event.expires_at), (1, None, None));
+    }
+
+    #[test]
+    fn every_share_created_version_decodes() {
+        let (file, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let recipient = Some(Pubkey::new_unique());
+
+        let v3 = payload::<ShareCreated>((3u8, file, owner, recipient, None::<i64>, NOW));
+        let event = decode_share_created(&v3).unwrap();
+        assert_eq!((event.version, event.has_message, event.template_id), (3, false, None));
+
+        let v5 = payload::<ShareCreated>((5u8, file, owner, recipient, None::<i64>, NOW, true));
+        let event = decode_share_created(&v5).unwrap();
+        assert_eq!((event.version, event.has_message, event.template_id), (5, true, None));
+
+        let current = ShareCreated {
+            version: EVENT_VERSION,
+            file,
+            owner,
+            recipient,
+            expires_at: None,
+            timestamp: NOW,
+            has_message: true,
+            template_id: Some(7),
+        };
+        let event = decode_share_created(&current.data()).unwrap();
+        assert_eq!((event.version, event.template_id), (EVENT_VERSION, Some(7)));
+    }
+
+    #[test]
+    fn foreign_or_truncated_events_are_rejected() {
+        let invalid = || Some(Error::from(HelixError::InvalidEventData));
+        let data = payload::<FileRegistered>((Pubkey::new_unique(), SAMPLE_TX_ID, 1u64, true, NOW));
+
+        // A FileRegistered payload under the ShareCreated discriminator
+        let mut foreign = data.clone();
+        foreign[..8].copy_from_slice(&ShareCreated::DISCRIMINATOR);
+        assert_eq!(decode_share_created(&foreign).err(), invalid());
+        assert_eq!(decode_share_created(&data).err(), invalid());
+        assert_eq!(decode_file_registered(&[]).err(), invalid());
+        for len in 0..data.len() {
+            assert_eq!(decode_file_registered(&data[..len]).err(), invalid(), "prefix of {len}");
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    AccessLogClosed, AccessLogEntry, FileAccessLog, FileRecord, ACCESS_LOG_ENTRIES,
+    ACCESS_LOG_SEED, EVENT_VERSION, FILE_SEED,
+};
+use crate::views::{AccessLogView, VIEW_VERSION};
+
+/// Accounts required for creating a file's access log
+#[derive(Accounts)]
+pub struct CreateAccessLog<'info> {
+    /// The file record to log accesses of
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The access log to create (PDA derived from the file)
+    #[account(
+        init,
+        payer = owner,
+        space = FileAccessLog::LEN,
+        seeds = [ACCESS_LOG_SEED, file_record.key().as_ref()],
+        bump
+    )]
+    pub access_log: Box<Account<'info, FileAccessLog>>,
+
+    /// The file owner (pays for the log)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the create_access_log instruction
+/// 
+/// Creates the file's access log. From then on `create_share` and
+/// `record_download` append to it whenever it is passed to them.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_access_log_handler(ctx: Context<CreateAccessLog>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let access_log = &mut ctx.accounts.access_log;
+
+    access_log.file = file_key;
+    access_log.owner = owner;
+    access_log.entries = [AccessLogEntry::default(); ACCESS_LOG_ENTRIES];
+    access_log.head = 0;
+    access_log.bump = ctx.bumps.access_log;
+    access_log._reserved = [0u8; 8];
+
+    private_msg!("Access log created"; "Access log created for {} by {}", file_key, owner);
+
+    Ok(())
+}
+
+/// Accounts required for closing a file's access log
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CloseAccessLog<'info> {
+    /// The access log to close
+    #[account(
+        mut,
+        seeds = [ACCESS_LOG_SEED, access_log.file.as_ref()],
+        bump = access_log.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        close = owner
+    )]
+    pub access_log: Box<Account<'info, FileAccessLog>>,
+
+    /// The wallet that created the log (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the close_access_log instruction
+/// 
+/// Closes the log, returning its rent to the wallet that created it. Works
+/// whether or not the file still exists.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn close_access_log_handler(ctx: Context<CloseAccessLog>) -> Result<()> {
+    let file = ctx.accounts.access_log.file;
+    let owner = ctx.accounts.owner.key();
+
+    emit_event!(ctx, AccessLogClosed {
+        version: EVENT_VERSION,
+        file,
+        owner,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    private_msg!("Access log closed"; "Access log of {} closed by {}", file, owner);
+
+    Ok(())
+}
+
+/// Accounts for reading a file's access log (read-only)
+#[derive(Accounts)]
+pub struct GetAccessLog<'info> {
+    /// The access log to read
+    #[account(
+        seeds = [ACCESS_LOG_SEED, access_log.file.as_ref()],
+        bump = access_log.bump
+    )]
+    pub access_log: Box<Account<'info, FileAccessLog>>,
+}
+
+/// Return a file's recorded accesses, newest first
+pub fn get_access_log_handler(ctx: Context<GetAccessLog>) -> Result<AccessLogView> {
+    let access_log = &ctx.accounts.access_log;
+
+    Ok(AccessLogView {
+        version: VIEW_VERSION,
+        file: access_log.file,
+        entries: access_log.recent().copied().collect(),
+    })
+}
@@ -0,0 +1,461 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::instructions::create_share::{release_share_counters, revoke_and_clear_key};
+use crate::instructions::initialize::UpdateRegistry;
+use crate::instructions::lifecycle::release_file_count;
+use crate::instructions::register_file::{scrub_record, scrub_shares};
+use crate::state::{
+    CountersReconciled, FileAdminDeleted, FileRecord, FileScrubbed, FileSizeAttested,
+    FileSizeCorrected, FileStatus, Operator, OperatorAdded, OperatorRemoved, RegistryCounters,
+    ShareForceRevoked, ShareLink, StorageRegistry, UserProfile, EVENT_VERSION, FILE_SEED,
+    MAX_OPERATORS, OPERATOR_ALL_PERMISSIONS, OPERATOR_DELETE_FILE, OPERATOR_REVOKE_SHARE,
+    REGISTRY_SEED, USER_PROFILE_SEED,
+};
+
+/// Accounts required for a moderator to force-delete a file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AdminDeleteFile<'info> {
+    /// The storage registry (for moderator check and stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to mark as deleted
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The program authority or an operator with delete permission
+    #[account(
+        constraint = registry.is_moderator(&moderator.key(), OPERATOR_DELETE_FILE)
+            @ HelixError::UnauthorizedOperator
+    )]
+    pub moderator: Signer<'info>,
+
+    /// The file owner's profile (required only for archived files)
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, file_record.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+}
+
+/// Handler for the admin_delete_file instruction
+/// 
+/// Marks a file as deleted without the owner's signature, e.g. for legal
+/// takedowns. The record is flagged so the owner cannot restore it alone.
+/// Under the registry's `scrub_on_delete` policy, metadata and the keys of
+/// share links passed in `remaining_accounts` are wiped as on owner deletion.
+/// 
+/// # Arguments
+/// * `ctx` - The AdminDeleteFile context
+/// * `reason` - Moderation reason code, recorded in the event
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn admin_delete_handler(ctx: Context<AdminDeleteFile>, reason: u8) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate file is not already deleted
+    require!(!file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Mark as deleted by the authority
+    let previous_status = file_record.file_status();
+    file_record.set_status(FileStatus::Deleted)?;
+    file_record.set_deleted_by_authority(true);
+    file_record.updated_at = clock.unix_timestamp;
+    file_record.deleted_at = clock.unix_timestamp;
+
+    // Update registry stats (mirrors owner deletion)
+    release_file_count(registry, previous_status, ctx.accounts.user_profile.as_mut())?;
+    registry.total_bytes = registry.total_bytes.saturating_sub(file_record.size);
+
+    // Emit event
+    emit_event!(ctx, FileAdminDeleted {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        moderator: ctx.accounts.moderator.key(),
+        reason,
+        timestamp: clock.unix_timestamp,
+    });
+
+    // Wipe metadata and share keys under the scrub policy
+    if registry.scrub_on_delete {
+        scrub_record(file_record)?;
+        let shares_scrubbed = scrub_shares(&file_key, ctx.remaining_accounts)?;
+
+        emit_event!(ctx, FileScrubbed {
+            version: EVENT_VERSION,
+            file: file_key,
+            owner: file_record.owner,
+            shares_scrubbed,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    private_msg!(
+        "File force-deleted";
+        "File force-deleted: {} by {} (reason {})",
+        file_record.transaction_id,
+        ctx.accounts.moderator.key(),
+        reason
+    );
+
+    Ok(())
+}
+
+/// Accounts required for the authority to correct a file's recorded size
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AdminCorrectSize<'info> {
+    /// The storage registry (for authority check and stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority @ HelixError::UnauthorizedAuthority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to correct
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The program authority
+    pub authority: Signer<'info>,
+}
+
+/// Handler for the admin_correct_size instruction
+/// 
+/// Rewrites the size of a record registered with an implausible value and
+/// adjusts the registry byte total by the difference. Deleted files are
+/// already excluded from the total, so only the record is changed for them.
+/// 
+/// # Arguments
+/// * `ctx` - The AdminCorrectSize context
+/// * `new_size` - The corrected file size in bytes
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn admin_correct_size_handler(ctx: Context<AdminCorrectSize>, new_size: u64) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    require!(new_size > 0, HelixError::InvalidFileSize);
+
+    let old_size = file_record.size;
+
+    // Apply the delta to the registry byte total
+    if !file_record.is_deleted() {
+        registry.total_bytes = if new_size >= old_size {
+            registry.total_bytes.checked_add(new_size - old_size)
+        } else {
+            registry.total_bytes.checked_sub(old_size - new_size)
+        }
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    // Update only the size and timestamp
+    file_record.size = new_size;
+    file_record.updated_at = clock.unix_timestamp;
+
+    // Emit event
+    emit_event!(ctx, FileSizeCorrected {
+        version: EVENT_VERSION,
+        file: file_key,
+        authority: ctx.accounts.authority.key(),
+        old_size,
+        new_size,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File size corrected";
+        "File size corrected: {} from {} to {} bytes",
+        file_record.transaction_id,
+        old_size,
+        new_size
+    );
+
+    Ok(())
+}
+
+/// Accounts required for the authority to attest a file's recorded size
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AttestSize<'info> {
+    /// The storage registry (for authority check)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority @ HelixError::UnauthorizedAuthority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to attest
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The program authority
+    pub authority: Signer<'info>,
+}
+
+/// Handler for the attest_size instruction
+/// 
+/// Marks the recorded size as confirmed, e.g. after checking it against the
+/// Arweave gateway. Registration only ever stores a self-reported size, so
+/// consumers that bill on `size` should trust it only once attested.
+/// 
+/// # Arguments
+/// * `ctx` - The AttestSize context
+/// * `size` - The size the authority confirmed; must match the record
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn attest_size_handler(ctx: Context<AttestSize>, size: u64) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    require!(size == file_record.size, HelixError::SizeMismatch);
+
+    file_record.set_size_attested(true);
+
+    // Emit event
+    emit_event!(ctx, FileSizeAttested {
+        version: EVENT_VERSION,
+        file: file_key,
+        authority: ctx.accounts.authority.key(),
+        size,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File size attested";
+        "File size attested: {} at {} bytes",
+        file_record.transaction_id,
+        size
+    );
+
+    Ok(())
+}
+
+/// Accounts required for a moderator to force-revoke a share link
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ForceRevokeShare<'info> {
+    /// The storage registry (for moderator check and stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The share link to revoke
+    #[account(
+        mut,
+        constraint = share_link.file == file_record.key() @ HelixError::ShareFileMismatch
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The program authority or an operator with revoke permission
+    #[account(
+        constraint = registry.is_moderator(&moderator.key(), OPERATOR_REVOKE_SHARE)
+            @ HelixError::UnauthorizedOperator
+    )]
+    pub moderator: Signer<'info>,
+}
+
+/// Handler for the force_revoke_share instruction
+/// 
+/// Revokes a share link without the owner's signature.
+/// 
+/// # Arguments
+/// * `ctx` - The ForceRevokeShare context
+/// * `reason` - Moderation reason code, recorded in the event
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn force_revoke_share_handler(ctx: Context<ForceRevokeShare>, reason: u8) -> Result<()> {
+    let share_key = ctx.accounts.share_link.key();
+    let file_key = ctx.accounts.file_record.key();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate share is not already revoked
+    require!(!share_link.is_revoked(), HelixError::ShareRevoked);
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Mark as revoked and drop the wrapped key
+    revoke_and_clear_key(share_link)?;
+
+    // Update file record share count and registry stats
+    release_share_counters(share_link, file_record, registry);
+
+    // Emit event
+    emit_event!(ctx, ShareForceRevoked {
+        version: EVENT_VERSION,
+        share: share_key,
+        file: file_key,
+        moderator: ctx.accounts.moderator.key(),
+        reason,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Share link force-revoked";
+        "Share link force-revoked: {} by {} (reason {})",
+        share_key,
+        ctx.accounts.moderator.key(),
+        reason
+    );
+
+    Ok(())
+}
+
+/// Add an operator with the given permission mask
+pub fn add_operator_handler(
+    ctx: Context<UpdateRegistry>,
+    operator: Pubkey,
+    permissions: u8,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    require!(
+        permissions != 0 && permissions & !OPERATOR_ALL_PERMISSIONS == 0,
+        HelixError::InvalidOperatorPermissions
+    );
+    require!(
+        registry.operators.len() < MAX_OPERATORS,
+        HelixError::TooManyOperators
+    );
+    require!(
+        !registry.operators.iter().any(|op| op.key == operator),
+        HelixError::DuplicateOperator
+    );
+
+    registry.operators.push(Operator {
+        key: operator,
+        permissions,
+    });
+
+    emit_event!(ctx, OperatorAdded {
+        version: EVENT_VERSION,
+        operator,
+        permissions,
+        authority: registry.authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Operator {} added with permissions {:#04x}",
+        operator,
+        permissions
+    );
+
+    Ok(())
+}
+
+/// Remove an operator
+pub fn remove_operator_handler(ctx: Context<UpdateRegistry>, operator: Pubkey) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    let index = registry
+        .operators
+        .iter()
+        .position(|op| op.key == operator)
+        .ok_or(HelixError::OperatorNotFound)?;
+    registry.operators.remove(index);
+
+    emit_event!(ctx, OperatorRemoved {
+        version: EVENT_VERSION,
+        operator,
+        authority: registry.authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Operator {} removed", operator);
+
+    Ok(())
+}
+
+/// Overwrite the registry's lifetime counters after they have drifted from
+/// the accounts on chain.
+/// 
+/// The old and new values are emitted with `reason_hash`, the hash of an
+/// off-chain explanation, so every correction can be audited. Epoch stats
+/// are left alone.
+pub fn reconcile_counters_handler(
+    ctx: Context<UpdateRegistry>,
+    counters: RegistryCounters,
+    reason_hash: [u8; 32],
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(
+        counters.is_plausible(registry.share_limit()),
+        HelixError::ImplausibleCounters
+    );
+
+    let clock = Clock::get()?;
+
+    let old_counters = registry.counters();
+    registry.set_counters(counters);
+
+    emit_event!(ctx, CountersReconciled {
+        version: EVENT_VERSION,
+        authority: registry.authority,
+        old_counters,
+        new_counters: counters,
+        reason_hash,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Registry counters reconciled from {:?} to {:?}",
+        old_counters,
+        counters
+    );
+
+    Ok(())
+}
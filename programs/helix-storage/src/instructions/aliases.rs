@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    AliasCreated, AliasRemoved, FileAlias, FileRecord, StorageRegistry, ALIAS_SEED,
+    EVENT_VERSION, FILE_SEED, REGISTRY_SEED,
+};
+use crate::types::EncryptedFileAlias;
+
+/// Accounts required for adding an alias to a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(alias_hash: [u8; 32])]
+pub struct CreateAlias<'info> {
+    /// The storage registry (for the alias limit)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record the alias points to
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The alias account to create (PDA derived from owner and alias hash)
+    #[account(
+        init,
+        payer = owner,
+        space = FileAlias::LEN,
+        seeds = [ALIAS_SEED, owner.key().as_ref(), alias_hash.as_ref()],
+        bump
+    )]
+    pub alias: Account<'info, FileAlias>,
+
+    /// The file owner (pays for the alias)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the create_alias instruction
+/// 
+/// Adds a lookup handle for the file under a client-computed hash of the
+/// alias, so the file can be found by deriving a single address.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `alias_hash` - Client-computed hash of the plaintext alias
+/// * `encrypted_alias` - Client-encrypted alias name
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_alias_handler(
+    ctx: Context<CreateAlias>,
+    alias_hash: [u8; 32],
+    encrypted_alias: EncryptedFileAlias,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let alias_key = ctx.accounts.alias.key();
+    let alias_limit = ctx.accounts.registry.alias_limit();
+    let file_record = &mut ctx.accounts.file_record;
+    let alias = &mut ctx.accounts.alias;
+    let clock = Clock::get()?;
+
+    // Validate the file is live and below its alias limit
+    require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+    require!(file_record.alias_count < alias_limit, HelixError::AliasLimitReached);
+
+    // Initialize alias
+    alias.owner = owner;
+    alias.file = file_key;
+    alias.alias_hash = alias_hash;
+    alias.encrypted_alias = encrypted_alias.into_inner();
+    alias.created_at = clock.unix_timestamp;
+    alias.bump = ctx.bumps.alias;
+    alias._reserved = [0u8; 16];
+
+    file_record.alias_count = file_record
+        .alias_count
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, AliasCreated {
+        version: EVENT_VERSION,
+        alias: alias_key,
+        file: file_key,
+        owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Alias created";
+        "Alias created for {} by {}",
+        file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for removing an alias from a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RemoveAlias<'info> {
+    /// The file record the alias points to
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The alias account to close
+    #[account(
+        mut,
+        seeds = [ALIAS_SEED, owner.key().as_ref(), alias.alias_hash.as_ref()],
+        bump = alias.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = alias.file == file_record.key() @ HelixError::AliasFileMismatch,
+        close = owner
+    )]
+    pub alias: Account<'info, FileAlias>,
+
+    /// The wallet that created the alias (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the remove_alias instruction
+/// 
+/// Closes the alias account, returning its rent to the wallet that created
+/// it. Works even if the file has since changed owner.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn remove_alias_handler(ctx: Context<RemoveAlias>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let alias_key = ctx.accounts.alias.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    file_record.alias_count = file_record.alias_count.saturating_sub(1);
+
+    emit_event!(ctx, AliasRemoved {
+        version: EVENT_VERSION,
+        alias: alias_key,
+        file: file_key,
+        owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Alias removed";
+        "Alias removed from {} by {}",
+        file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
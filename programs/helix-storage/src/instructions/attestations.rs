@@ -0,0 +1,281 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    Attestation, AttestationRevoked, AttestorAdded, AttestorRemoved, FileAttested, FileRecord,
+    RegistryExt, StorageRegistry, ATTESTATION_SEED, EVENT_VERSION, FILE_SEED, MAX_ATTESTORS,
+    REGISTRY_EXT_SEED, REGISTRY_SEED,
+};
+
+/// Accounts required for managing the gateway attestor set
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateAttestors<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The registry extension holding the attestor set
+    #[account(
+        mut,
+        seeds = [REGISTRY_EXT_SEED],
+        bump
+    )]
+    pub registry_ext: AccountLoader<'info, RegistryExt>,
+
+    /// The authority updating the attestor set
+    pub authority: Signer<'info>,
+}
+
+/// Add a gateway operator to the attestor set
+pub fn add_attestor_handler(ctx: Context<UpdateAttestors>, attestor: Pubkey) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let clock = Clock::get()?;
+
+    {
+        let mut registry_ext = ctx.accounts.registry_ext.load_mut()?;
+        let count = registry_ext.attestor_count as usize;
+
+        require!(count < MAX_ATTESTORS, HelixError::TooManyAttestors);
+        require!(!registry_ext.is_attestor(&attestor), HelixError::DuplicateAttestor);
+
+        registry_ext.attestors[count] = attestor;
+        registry_ext.attestor_count += 1;
+    }
+
+    emit_event!(ctx, AttestorAdded {
+        version: EVENT_VERSION,
+        attestor,
+        authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Attestor {} added", attestor);
+
+    Ok(())
+}
+
+/// Remove a gateway operator from the attestor set.
+/// 
+/// Attestations it already made stay in place until revoked.
+pub fn remove_attestor_handler(ctx: Context<UpdateAttestors>, attestor: Pubkey) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let clock = Clock::get()?;
+
+    {
+        let mut registry_ext = ctx.accounts.registry_ext.load_mut()?;
+        let count = registry_ext.attestor_count as usize;
+
+        let index = registry_ext
+            .attestors()
+            .iter()
+            .position(|key| *key == attestor)
+            .ok_or(HelixError::AttestorNotListed)?;
+
+        // Swap-remove to keep the set contiguous
+        registry_ext.attestors[index] = registry_ext.attestors[count - 1];
+        registry_ext.attestors[count - 1] = Pubkey::default();
+        registry_ext.attestor_count -= 1;
+    }
+
+    emit_event!(ctx, AttestorRemoved {
+        version: EVENT_VERSION,
+        attestor,
+        authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Attestor {} removed", attestor);
+
+    Ok(())
+}
+
+/// Accounts required for attesting a file replica
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AttestFile<'info> {
+    /// The registry extension holding the attestor set
+    #[account(
+        seeds = [REGISTRY_EXT_SEED],
+        bump
+    )]
+    pub registry_ext: AccountLoader<'info, RegistryExt>,
+
+    /// The file record being attested
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The attestation (created on first attestation)
+    #[account(
+        init_if_needed,
+        payer = attestor,
+        space = Attestation::LEN,
+        seeds = [ATTESTATION_SEED, file_record.key().as_ref(), attestor.key().as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    /// The gateway operator attesting (pays for the attestation)
+    #[account(mut)]
+    pub attestor: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the attest_file instruction
+/// 
+/// Records that a listed gateway operator holds a replica of the file. Calling
+/// it again refreshes the check time and content hash.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `content_hash` - Hash of the content the attestor verified (optional)
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn attest_file_handler(
+    ctx: Context<AttestFile>,
+    content_hash: Option<[u8; 32]>,
+) -> Result<()> {
+    let attestor = ctx.accounts.attestor.key();
+    let file_key = ctx.accounts.file_record.key();
+    let attestation_key = ctx.accounts.attestation.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let attestation = &mut ctx.accounts.attestation;
+    let clock = Clock::get()?;
+
+    // Validate the attestor is listed and the file is live
+    require!(
+        ctx.accounts.registry_ext.load()?.is_attestor(&attestor),
+        HelixError::AttestorNotListed
+    );
+    require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    if attestation.attestor == Pubkey::default() {
+        attestation.file = file_key;
+        attestation.attestor = attestor;
+        attestation.bump = ctx.bumps.attestation;
+        attestation._reserved = [0u8; 16];
+
+        file_record.attestation_count = file_record
+            .attestation_count
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    attestation.checked_at = clock.unix_timestamp;
+    attestation.content_hash = content_hash;
+
+    emit_event!(ctx, FileAttested {
+        version: EVENT_VERSION,
+        attestation: attestation_key,
+        file: file_key,
+        attestor,
+        content_hash,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File attested";
+        "File {} attested by {}",
+        file_record.transaction_id,
+        attestor
+    );
+
+    Ok(())
+}
+
+/// Accounts required for revoking an attestation
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeAttestation<'info> {
+    /// The storage registry (for the authority)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The attestation to close
+    #[account(
+        mut,
+        seeds = [ATTESTATION_SEED, attestation.file.as_ref(), attestor.key().as_ref()],
+        bump = attestation.bump,
+        has_one = attestor,
+        close = attestor
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    /// The attested file record
+    /// CHECK: May have been purged; its counter is updated in the handler
+    #[account(mut, address = attestation.file)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The gateway operator that attested (receives the rent)
+    /// CHECK: Must match the attestor recorded on the attestation
+    #[account(mut)]
+    pub attestor: UncheckedAccount<'info>,
+
+    /// The attestor or the program authority
+    #[account(
+        constraint = revoker.key() == attestor.key()
+            || revoker.key() == registry.authority @ HelixError::UnauthorizedAuthority
+    )]
+    pub revoker: Signer<'info>,
+}
+
+/// Handler for the revoke_attestation instruction
+/// 
+/// Closes the attestation, returning its rent to the attestor. The attestor
+/// can revoke its own attestations; the program authority can revoke any,
+/// e.g. after removing an attestor from the set.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn revoke_attestation_handler(ctx: Context<RevokeAttestation>) -> Result<()> {
+    let revoker = ctx.accounts.revoker.key();
+    let attestation_key = ctx.accounts.attestation.key();
+    let attestor = ctx.accounts.attestation.attestor;
+    let file_info = ctx.accounts.file_record.to_account_info();
+    let clock = Clock::get()?;
+
+    // Release the file's counter unless the file has been purged
+    if file_info.owner == &crate::ID {
+        let mut data = file_info.try_borrow_mut_data()?;
+        if let Ok(mut file_record) = FileRecord::try_deserialize(&mut &data[..]) {
+            file_record.attestation_count = file_record.attestation_count.saturating_sub(1);
+            file_record.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit_event!(ctx, AttestationRevoked {
+        version: EVENT_VERSION,
+        attestation: attestation_key,
+        file: file_info.key(),
+        attestor,
+        revoked_by: revoker,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Attestation revoked";
+        "Attestation of {} by {} revoked by {}",
+        file_info.key(),
+        attestor,
+        revoker
+    );
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    AuditGrant, AuditGrantCreated, AuditGrantRevoked, AuditScope, FileRecord, AUDIT_GRANT_SEED,
+    EVENT_VERSION,
+};
+
+/// Accounts required for creating an audit grant
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(grant_id: u64)]
+pub struct CreateAuditGrant<'info> {
+    /// The audit grant to create (PDA derived from the owner and grant ID)
+    #[account(
+        init,
+        payer = owner,
+        space = AuditGrant::LEN,
+        seeds = [AUDIT_GRANT_SEED, owner.key().as_ref(), &grant_id.to_le_bytes()],
+        bump
+    )]
+    pub audit_grant: Account<'info, AuditGrant>,
+
+    /// The file covered (required for a single-file scope)
+    pub file_record: Option<Box<Account<'info, FileRecord>>>,
+
+    /// The owner granting access (payer)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the create_audit_grant instruction
+/// 
+/// Lets an auditor view the owner's files in `scope` until `expires_at`.
+/// Grants authorize metadata-level access through the read-only views only;
+/// they never authorize downloads. Grants may overlap.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `grant_id` - Owner-chosen ID of the grant
+/// * `auditor` - The auditor's wallet
+/// * `scope` - The files covered
+/// * `expires_at` - Unix timestamp when the grant expires
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_audit_grant_handler(
+    ctx: Context<CreateAuditGrant>,
+    grant_id: u64,
+    auditor: Pubkey,
+    scope: AuditScope,
+    expires_at: i64,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let grant_key = ctx.accounts.audit_grant.key();
+    let clock = Clock::get()?;
+
+    // Validate the expiry and a single-file scope
+    require!(expires_at > clock.unix_timestamp, HelixError::ExpirationInPast);
+    if let AuditScope::File(file) = scope {
+        let file_record = ctx
+            .accounts
+            .file_record
+            .as_ref()
+            .ok_or(HelixError::AuditScopeMismatch)?;
+        require!(
+            file_record.key() == file && file_record.owner == owner,
+            HelixError::AuditScopeMismatch
+        );
+    }
+
+    // Initialize audit grant
+    let audit_grant = &mut ctx.accounts.audit_grant;
+    audit_grant.owner = owner;
+    audit_grant.auditor = auditor;
+    audit_grant.grant_id = grant_id;
+    audit_grant.scope = scope;
+    audit_grant.created_at = clock.unix_timestamp;
+    audit_grant.expires_at = expires_at;
+    audit_grant.revoked_at = 0;
+    audit_grant.bump = ctx.bumps.audit_grant;
+    audit_grant._reserved = [0u8; 16];
+
+    emit_event!(ctx, AuditGrantCreated {
+        version: EVENT_VERSION,
+        grant: grant_key,
+        owner,
+        auditor,
+        scope,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Audit grant created";
+        "Audit grant {} created by {} for {}",
+        grant_id,
+        owner,
+        auditor
+    );
+
+    Ok(())
+}
+
+/// Accounts required for revoking an audit grant
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeAuditGrant<'info> {
+    /// The audit grant to revoke
+    #[account(
+        mut,
+        seeds = [AUDIT_GRANT_SEED, owner.key().as_ref(), &audit_grant.grant_id.to_le_bytes()],
+        bump = audit_grant.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner
+    )]
+    pub audit_grant: Account<'info, AuditGrant>,
+
+    /// The owner that created the grant
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the revoke_audit_grant instruction
+/// 
+/// Marks the grant revoked. The account is kept as a record that the grant
+/// existed and when it ended.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn revoke_audit_grant_handler(ctx: Context<RevokeAuditGrant>) -> Result<()> {
+    let grant_key = ctx.accounts.audit_grant.key();
+    let audit_grant = &mut ctx.accounts.audit_grant;
+    let clock = Clock::get()?;
+
+    require!(audit_grant.revoked_at == 0, HelixError::AuditGrantAlreadyRevoked);
+    audit_grant.revoked_at = clock.unix_timestamp;
+
+    emit_event!(ctx, AuditGrantRevoked {
+        version: EVENT_VERSION,
+        grant: grant_key,
+        owner: audit_grant.owner,
+        auditor: audit_grant.auditor,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Audit grant revoked";
+        "Audit grant {} of {} revoked",
+        audit_grant.grant_id,
+        audit_grant.owner
+    );
+
+    Ok(())
+}
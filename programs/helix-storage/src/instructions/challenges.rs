@@ -0,0 +1,345 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::error::HelixError;
+use crate::state::{
+    Challenge, ChallengeAccepted, ChallengeAnswered, ChallengeOpened, ChallengeSettled,
+    FileRecord, CHALLENGE_BOND_LAMPORTS, CHALLENGE_SEED, EVENT_VERSION, FILE_SEED,
+    MAX_CHALLENGE_WINDOW_SECS, MIN_CHALLENGE_WINDOW_SECS,
+};
+
+/// Accounts required for opening a proof-of-access challenge
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct OpenChallenge<'info> {
+    /// The file record being challenged
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The challenge account to create (one open challenge per file and challenger)
+    #[account(
+        init,
+        payer = challenger,
+        space = Challenge::LEN,
+        seeds = [CHALLENGE_SEED, file_record.key().as_ref(), challenger.key().as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// The wallet opening the challenge (pays the bond and rent)
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    /// System program for account creation and the bond transfer
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the open_challenge instruction
+/// 
+/// Asks the file owner for the SHA-256 of a byte range of the file by a
+/// deadline. The challenger locks `CHALLENGE_BOND_LAMPORTS` in the challenge
+/// account on top of its rent.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `offset` - First byte of the challenged range
+/// * `length` - Length of the challenged range in bytes
+/// * `deadline` - Unix timestamp by which the owner must answer
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn open_challenge_handler(
+    ctx: Context<OpenChallenge>,
+    offset: u64,
+    length: u64,
+    deadline: i64,
+) -> Result<()> {
+    let challenger = ctx.accounts.challenger.key();
+    let file_key = ctx.accounts.file_record.key();
+    let challenge_key = ctx.accounts.challenge.key();
+    let file_record = &ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate the file is live and the range lies within it
+    require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+    let end = offset.checked_add(length).ok_or(HelixError::InvalidChallengeRange)?;
+    require!(length > 0 && end <= file_record.size, HelixError::InvalidChallengeRange);
+
+    // Validate the response window
+    let window = deadline.saturating_sub(clock.unix_timestamp);
+    require!(
+        (MIN_CHALLENGE_WINDOW_SECS..=MAX_CHALLENGE_WINDOW_SECS).contains(&window),
+        HelixError::InvalidChallengeDeadline
+    );
+
+    // Lock the bond
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.challenger.to_account_info(),
+                to: ctx.accounts.challenge.to_account_info(),
+            },
+        ),
+        CHALLENGE_BOND_LAMPORTS,
+    )?;
+
+    // Initialize challenge
+    let owner = file_record.owner;
+    let challenge = &mut ctx.accounts.challenge;
+    challenge.file = file_key;
+    challenge.challenger = challenger;
+    challenge.owner = owner;
+    challenge.offset = offset;
+    challenge.length = length;
+    challenge.bond = CHALLENGE_BOND_LAMPORTS;
+    challenge.created_at = clock.unix_timestamp;
+    challenge.deadline = deadline;
+    challenge.answer = None;
+    challenge.answered_at = 0;
+    challenge.bump = ctx.bumps.challenge;
+    challenge._reserved = [0u8; 16];
+
+    emit_event!(ctx, ChallengeOpened {
+        version: EVENT_VERSION,
+        challenge: challenge_key,
+        file: file_key,
+        challenger,
+        offset,
+        length,
+        bond: CHALLENGE_BOND_LAMPORTS,
+        deadline,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Challenge opened";
+        "Challenge on {} bytes {}..{} opened by {}",
+        file_key,
+        offset,
+        end,
+        challenger
+    );
+
+    Ok(())
+}
+
+/// Accounts required for answering a challenge
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AnswerChallenge<'info> {
+    /// The challenge being answered
+    #[account(
+        mut,
+        seeds = [CHALLENGE_SEED, challenge.file.as_ref(), challenge.challenger.as_ref()],
+        bump = challenge.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// The file owner answering
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the answer_challenge instruction
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `hash` - SHA-256 of the challenged byte range
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn answer_challenge_handler(ctx: Context<AnswerChallenge>, hash: [u8; 32]) -> Result<()> {
+    let challenge_key = ctx.accounts.challenge.key();
+    let challenge = &mut ctx.accounts.challenge;
+    let clock = Clock::get()?;
+
+    // Validate the challenge is unanswered and still open
+    require!(!challenge.is_answered(), HelixError::ChallengeAlreadyAnswered);
+    require!(
+        clock.unix_timestamp <= challenge.deadline,
+        HelixError::ChallengeDeadlinePassed
+    );
+
+    challenge.answer = Some(hash);
+    challenge.answered_at = clock.unix_timestamp;
+
+    emit_event!(ctx, ChallengeAnswered {
+        version: EVENT_VERSION,
+        challenge: challenge_key,
+        file: challenge.file,
+        owner: challenge.owner,
+        answer: hash,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Challenge answered";
+        "Challenge {} answered by {}",
+        challenge_key,
+        challenge.owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for accepting a challenge answer
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AcceptAnswer<'info> {
+    /// The answered challenge to close
+    #[account(
+        mut,
+        seeds = [CHALLENGE_SEED, challenge.file.as_ref(), challenger.key().as_ref()],
+        bump = challenge.bump,
+        has_one = challenger @ HelixError::UnauthorizedOwner,
+        close = challenger
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// The challenger accepting (receives the bond and rent)
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+}
+
+/// Handler for the accept_answer instruction
+/// 
+/// Closes an answered challenge, returning the bond and rent to the
+/// challenger.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn accept_answer_handler(ctx: Context<AcceptAnswer>) -> Result<()> {
+    let challenge_key = ctx.accounts.challenge.key();
+    let challenge = &ctx.accounts.challenge;
+    let clock = Clock::get()?;
+
+    require!(challenge.is_answered(), HelixError::ChallengeNotAnswered);
+
+    emit_event!(ctx, ChallengeAccepted {
+        version: EVENT_VERSION,
+        challenge: challenge_key,
+        file: challenge.file,
+        challenger: challenge.challenger,
+        bond_returned: challenge.bond,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Challenge answer accepted";
+        "Challenge {} answer accepted by {}",
+        challenge_key,
+        challenge.challenger
+    );
+
+    Ok(())
+}
+
+/// Accounts required for settling a challenge past its deadline
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SettleChallenge<'info> {
+    /// The challenge to close
+    #[account(
+        mut,
+        seeds = [CHALLENGE_SEED, challenge.file.as_ref(), challenger.key().as_ref()],
+        bump = challenge.bump,
+        has_one = challenger,
+        has_one = owner,
+        close = challenger
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    /// The challenged file record
+    /// CHECK: May have been purged; its counter is updated in the handler
+    #[account(mut, address = challenge.file)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The challenger (receives the rent and any returned bond)
+    /// CHECK: Must match the challenger recorded on the challenge
+    #[account(mut)]
+    pub challenger: UncheckedAccount<'info>,
+
+    /// The file owner (receives the bond for an unaccepted answer)
+    /// CHECK: Must match the owner recorded on the challenge
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+}
+
+/// Handler for the settle_challenge instruction
+/// 
+/// Closes a challenge once it can no longer progress. Callable by anyone:
+/// - Unanswered past the deadline: the file's `failed_challenges` counter is
+///   incremented and the bond returns to the challenger.
+/// - Answered but not accepted within `CHALLENGE_REVIEW_SECS` after the
+///   deadline: the answer stands and the bond is paid to the owner.
+/// 
+/// The rent always returns to the challenger.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn settle_challenge_handler(ctx: Context<SettleChallenge>) -> Result<()> {
+    let challenge_key = ctx.accounts.challenge.key();
+    let challenge_info = ctx.accounts.challenge.to_account_info();
+    let file_info = ctx.accounts.file_record.to_account_info();
+    let challenge = &ctx.accounts.challenge;
+    let clock = Clock::get()?;
+
+    require!(challenge.is_settleable(clock.unix_timestamp), HelixError::ChallengeStillOpen);
+
+    let answered = challenge.is_answered();
+    let (to_challenger, to_owner) = challenge.settlement();
+
+    // Pay the owner's share of the bond; the rest leaves with the close
+    if to_owner > 0 {
+        let owner_info = ctx.accounts.owner.to_account_info();
+        **challenge_info.try_borrow_mut_lamports()? = challenge_info
+            .lamports()
+            .checked_sub(to_owner)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        **owner_info.try_borrow_mut_lamports()? = owner_info
+            .lamports()
+            .checked_add(to_owner)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    // Count the failure against the file unless it has been purged
+    let mut failed_challenges = 0;
+    if !answered && file_info.owner == &crate::ID {
+        let mut data = file_info.try_borrow_mut_data()?;
+        if let Ok(mut file_record) = FileRecord::try_deserialize(&mut &data[..]) {
+            file_record.failed_challenges = file_record.failed_challenges.saturating_add(1);
+            failed_challenges = file_record.failed_challenges;
+            file_record.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit_event!(ctx, ChallengeSettled {
+        version: EVENT_VERSION,
+        challenge: challenge_key,
+        file: challenge.file,
+        answered,
+        bond_to_challenger: to_challenger,
+        bond_to_owner: to_owner,
+        failed_challenges,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Challenge settled";
+        "Challenge {} settled (answered: {})",
+        challenge_key,
+        answered
+    );
+
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    ChunkRootSet, FileRecord, verify_chunk_proof, EVENT_VERSION, FILE_SEED, MAX_CHUNK_PROOF_DEPTH,
+};
+use crate::types::TxId;
+
+/// Accounts required for setting a chunked file's Merkle root
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetChunkRoot<'info> {
+    /// The parent record of the chunked file
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner
+    pub owner: Signer<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the set_chunk_root instruction
+/// 
+/// Records the Merkle root over the file's chunk transaction IDs, as built by
+/// `client::chunk_merkle_root`. Passing `None` marks the file as not chunked.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `chunk_root` - The new Merkle root, or None to clear it
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn set_chunk_root_handler(
+    ctx: Context<SetChunkRoot>,
+    chunk_root: Option<[u8; 32]>,
+) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    file_record.chunk_root = chunk_root;
+    file_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, ChunkRootSet {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        chunk_root,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Chunk root set";
+        "Chunk root of {} set: {}",
+        file_record.transaction_id,
+        chunk_root.is_some()
+    );
+
+    Ok(())
+}
+
+/// Accounts required for verifying a chunk against its parent record
+#[derive(Accounts)]
+pub struct VerifyChunk<'info> {
+    /// The parent record holding the chunk root
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+}
+
+/// Handler for the verify_chunk instruction
+/// 
+/// Recomputes the leaf of `chunk_tx_id` at `index` and folds `proof` up to
+/// the record's chunk root. A well-formed proof that does not match returns
+/// false; a missing root or a proof that cannot be folded fails.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing the parent record
+/// * `index` - Position of the chunk in the file
+/// * `chunk_tx_id` - Arweave transaction ID of the chunk
+/// * `proof` - Sibling hashes from the leaf level upwards
+/// 
+/// # Returns
+/// * `Result<bool>` - Whether the chunk belongs to the file
+pub fn verify_chunk_handler(
+    ctx: Context<VerifyChunk>,
+    index: u16,
+    chunk_tx_id: TxId,
+    proof: Vec<[u8; 32]>,
+) -> Result<bool> {
+    let file_record = &ctx.accounts.file_record;
+
+    let root = file_record.chunk_root.ok_or(HelixError::ChunkRootMissing)?;
+    require!(proof.len() <= MAX_CHUNK_PROOF_DEPTH, HelixError::ChunkProofTooLong);
+    require!(u32::from(index) >> proof.len() == 0, HelixError::ChunkIndexOutOfRange);
+
+    Ok(verify_chunk_proof(&root, index, &chunk_tx_id, &proof))
+}
@@ -0,0 +1,246 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::profile::require_not_frozen;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    FileRecord, ShareClaimed, ShareCreated, ShareLink, StorageRegistry, EVENT_VERSION, FILE_SEED,
+    REGISTRY_SEED, SHARE_SEED, USER_PROFILE_SEED,
+};
+use crate::types::EncryptedKey;
+
+/// Accounts required for creating a claimable share link
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CreateClaimableShare<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file being shared
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The share link to create (PDA)
+    #[account(
+        init,
+        payer = owner,
+        space = ShareLink::LEN,
+        seeds = [
+            SHARE_SEED,
+            file_record.key().as_ref(),
+            &file_record.share_seed()
+        ],
+        bump
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The file owner (payer)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's profile, checked for a freeze
+    /// CHECK: May not exist; deserialized in the handler when it does
+    #[account(
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub owner_profile: UncheckedAccount<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the create_claimable_share instruction
+///
+/// Creates a share link with no recipient yet, for someone who may not have
+/// a wallet. Instead of a recipient the share stores `claim_hash`, the
+/// `share_claim_hash` of a secret the owner hands over off-chain. Nobody can
+/// use the share until a wallet claims it with `claim_share`. Until then
+/// the owner can revoke it as usual, and `expires_at` closes the claim
+/// window as well as limiting downloads after the claim.
+///
+/// # Arguments
+/// * `ctx` - The CreateClaimableShare context
+/// * `claim_hash` - `share_claim_hash` of the claim secret
+/// * `expires_at` - Optional Unix timestamp for expiration
+/// * `max_downloads` - Optional maximum download count
+/// * `encrypted_key` - Decryption key, wrapped so the claimant can open it
+///
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_claimable_share_handler(
+    ctx: Context<CreateClaimableShare>,
+    claim_hash: [u8; 32],
+    expires_at: Option<i64>,
+    max_downloads: Option<u32>,
+    encrypted_key: Option<EncryptedKey>,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate file is not deleted and below its share limit
+    require!(!file_record.is_deleted(), HelixError::CannotShareDeletedFile);
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+    registry.require_share_capacity(file_record.share_count)?;
+
+    // Validate the owner's account is not frozen
+    require_not_frozen(&ctx.accounts.owner_profile)?;
+
+    // Validate expiration if provided
+    if let Some(exp) = expires_at {
+        require!(exp > clock.unix_timestamp, HelixError::ExpirationInPast);
+    }
+
+    // Validate max downloads if provided
+    if let Some(max) = max_downloads {
+        require!(max > 0, HelixError::InvalidMaxDownloads);
+    }
+
+    // Initialize share link, waiting for its claim
+    share_link.file = file_record.key();
+    share_link.owner = ctx.accounts.owner.key();
+    share_link.recipient = None;
+    share_link.encrypted_key = encrypted_key.map(String::from);
+    share_link.expires_at = expires_at;
+    share_link.max_downloads = max_downloads;
+    share_link.download_count = 0;
+    share_link.flags = 0;
+    share_link.created_at = clock.unix_timestamp;
+    share_link.bump = ctx.bumps.share_link;
+    share_link.recipient_key_generation = 0;
+    share_link.bytes_served = 0;
+    share_link.recipient_commitment = None;
+    share_link.encrypted_message = None;
+    share_link.claim_hash = Some(claim_hash);
+    share_link.rent_payer = ctx.accounts.owner.key();
+    share_link.last_downloader = Pubkey::default();
+    share_link.last_download_at = 0;
+    share_link._reserved = [0u8; 4];
+
+    // Update file record share count
+    file_record.share_count = file_record
+        .share_count
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    file_record.advance_share_nonce()?;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Update registry stats
+    registry.total_shares = registry
+        .total_shares
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.current_epoch_stats.shares = registry
+        .current_epoch_stats
+        .shares
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, ShareCreated {
+        version: EVENT_VERSION,
+        file: file_record.key(),
+        owner: share_link.owner,
+        recipient: None,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+        has_message: false,
+        template_id: None,
+    });
+
+    private_msg!(
+        "Claimable share link created";
+        "Claimable share link created for file {} by {}",
+        file_record.transaction_id,
+        share_link.owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for claiming a claimable share link
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimShare<'info> {
+    /// The file record (checked for deletion)
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The share link to claim
+    #[account(
+        mut,
+        constraint = share_link.file == file_record.key() @ HelixError::ShareFileMismatch
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The wallet becoming the share's recipient
+    pub claimant: Signer<'info>,
+}
+
+/// Handler for the claim_share instruction
+///
+/// Binds the signing wallet as the recipient of a claimable share whose
+/// claim hash matches `preimage`. The hash is cleared in the same step, so
+/// the share can be claimed once; afterwards it behaves like any share
+/// locked to a recipient, and replaying the preimage fails with
+/// `ShareNotClaimable` without changing anything. Revoked, expired and
+/// deleted-file shares cannot be claimed.
+///
+/// The preimage is visible in the claim transaction, so the first wallet to
+/// land a claim wins; the secret should only be handed to the recipient.
+///
+/// # Arguments
+/// * `ctx` - The ClaimShare context
+/// * `preimage` - The claim secret
+///
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn claim_share_handler(ctx: Context<ClaimShare>, preimage: [u8; 32]) -> Result<()> {
+    let share_key = ctx.accounts.share_link.key();
+    let claimant = ctx.accounts.claimant.key();
+    let file_record = &ctx.accounts.file_record;
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate the share is still waiting for its claim and usable
+    require!(share_link.is_claimable(), HelixError::ShareNotClaimable);
+    share_link
+        .status(clock.unix_timestamp, file_record.is_deleted())
+        .require_active()?;
+
+    share_link.claim(claimant, &preimage)?;
+
+    emit_event!(ctx, ShareClaimed {
+        version: EVENT_VERSION,
+        share: share_key,
+        file: file_record.key(),
+        recipient: claimant,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Share link claimed"; "Share link {} claimed by {}", share_key, claimant);
+
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    Contact, ContactAdded, ContactRemoved, UserProfile, CONTACT_SEED, EVENT_VERSION,
+    USER_PROFILE_SEED,
+};
+use crate::types::EncryptedAlias;
+
+/// Accounts required for adding a contact to a wallet's address book
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(contact_wallet: Pubkey)]
+pub struct AddContact<'info> {
+    /// The owner's profile holding the contact count
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The contact account to create (PDA derived from owner and contact)
+    #[account(
+        init,
+        payer = owner,
+        space = Contact::LEN,
+        seeds = [CONTACT_SEED, owner.key().as_ref(), contact_wallet.as_ref()],
+        bump
+    )]
+    pub contact: Account<'info, Contact>,
+
+    /// The address book owner (pays for the contact)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the add_contact instruction
+/// 
+/// Adds a wallet to the owner's address book under a client-encrypted alias.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `contact_wallet` - The wallet to add
+/// * `encrypted_alias` - Client-encrypted display name for the contact
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn add_contact_handler(
+    ctx: Context<AddContact>,
+    contact_wallet: Pubkey,
+    encrypted_alias: EncryptedAlias,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let contact = &mut ctx.accounts.contact;
+    let clock = Clock::get()?;
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = owner;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    // Initialize contact
+    contact.owner = owner;
+    contact.contact = contact_wallet;
+    contact.encrypted_alias = encrypted_alias.into_inner();
+    contact.created_at = clock.unix_timestamp;
+    contact.bump = ctx.bumps.contact;
+    contact._reserved = [0u8; 16];
+
+    user_profile.contact_count = user_profile
+        .contact_count
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, ContactAdded {
+        version: EVENT_VERSION,
+        owner,
+        contact: contact_wallet,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Contact added"; "Contact {} added by {}", contact_wallet, owner);
+
+    Ok(())
+}
+
+/// Accounts required for removing a contact from a wallet's address book
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RemoveContact<'info> {
+    /// The owner's profile holding the contact count
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The contact account to close
+    #[account(
+        mut,
+        seeds = [CONTACT_SEED, owner.key().as_ref(), contact.contact.as_ref()],
+        bump = contact.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        close = owner
+    )]
+    pub contact: Account<'info, Contact>,
+
+    /// The address book owner (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the remove_contact instruction
+/// 
+/// Closes the contact account, returning its rent to the owner.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn remove_contact_handler(ctx: Context<RemoveContact>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let contact_wallet = ctx.accounts.contact.contact;
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    user_profile.contact_count = user_profile.contact_count.saturating_sub(1);
+
+    emit_event!(ctx, ContactRemoved {
+        version: EVENT_VERSION,
+        owner,
+        contact: contact_wallet,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Contact removed"; "Contact {} removed by {}", contact_wallet, owner);
+
+    Ok(())
+}
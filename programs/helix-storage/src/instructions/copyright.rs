@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::permit::{copyright_message, verify_ed25519_signature_bytes};
+use crate::state::{CopyrightAttested, FileRecord, EVENT_VERSION, FILE_SEED};
+
+/// Accounts required for attesting a copyright statement on a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AttestCopyright<'info> {
+    /// The file record the statement is bound to
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner
+    pub owner: Signer<'info>,
+
+    /// Instructions sysvar, used to find the rights holder's Ed25519 signature
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the attest_copyright instruction
+/// 
+/// Binds a rights holder's signed copyright statement to the file. The
+/// signature must cover `copyright_message(file, content_hash)` and be
+/// verified by an Ed25519 program instruction placed immediately before this
+/// one. A newer statement overwrites the previous one.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `rights_holder` - The key that signed the statement
+/// * `content_hash` - Hash of the content the statement covers
+/// * `signature` - The rights holder's Ed25519 signature
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn attest_copyright_handler(
+    ctx: Context<AttestCopyright>,
+    rights_holder: Pubkey,
+    content_hash: [u8; 32],
+    signature: [u8; 64],
+) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let clock = Clock::get()?;
+
+    // Validate the statement before touching the record
+    require!(ctx.accounts.file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+    verify_ed25519_signature_bytes(
+        &ctx.accounts.instructions.to_account_info(),
+        &rights_holder,
+        &copyright_message(&file_key, &content_hash),
+        &signature,
+        HelixError::InvalidCopyrightSignature,
+    )?;
+
+    let file_record = &mut ctx.accounts.file_record;
+    file_record.copyright_holder = rights_holder;
+    file_record.copyright_attested_at = clock.unix_timestamp;
+
+    emit_event!(ctx, CopyrightAttested {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        rights_holder,
+        content_hash,
+        signature,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Copyright attested";
+        "Copyright on {} attested by {}",
+        file_record.transaction_id,
+        rights_holder
+    );
+
+    Ok(())
+}
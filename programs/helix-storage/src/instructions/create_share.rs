@@ -1,13 +1,30 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::TokenAccount;
 
-use crate::error::{validate_optional_string_length, HelixError};
+use crate::cpi_guard::require_cpi_allowed;
+use crate::error::HelixError;
+use crate::instructions::inbox::push_notification;
+use crate::instructions::profile::load_profile;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::instructions::treasury::charge_fee;
+use crate::memo::process_memo;
+use crate::permit::{verify_ed25519_signature, SharePermit};
 use crate::state::{
-    FileRecord, ShareCreated, ShareLink, ShareRevoked, StorageRegistry,
-    FILE_SEED, MAX_ENCRYPTED_KEY_LEN, REGISTRY_SEED, SHARE_SEED,
+    AccessKind, AccessLogEntry, AuditGrant, Contact, DownloadRecorded, DownloadThrottle,
+    FileAccessLog, FileRecord, InboxEntry, NotificationKind, SessionKey, ShareCreated, ShareLink,
+    ShareMessageUpdated, SharePermitUsed, ShareRevoked, ShareStatus, ShareTemplate, StorageRegistry,
+    Treasury, UserProfile, EVENT_VERSION, FILE_SEED, INBOX_SEED, REGISTRY_SEED,
+    SESSION_SCOPE_RECORD_DOWNLOAD, SHARE_SEED, TREASURY_SEED, USER_PROFILE_SEED,
 };
+use crate::types::{EncryptedKey, EncryptedShareMessage};
+use crate::views::{ShareStatusView, VIEW_VERSION};
 
 /// Accounts required for creating a share link
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
+#[instruction(recipient: Option<Pubkey>)]
 pub struct CreateShare<'info> {
     /// The storage registry (for stats)
     #[account(
@@ -20,11 +37,12 @@ pub struct CreateShare<'info> {
     /// The file being shared
     #[account(
         mut,
-        seeds = [FILE_SEED, file_record.transaction_id.as_bytes()],
+        seeds = [FILE_SEED, &file_record.file_seed()],
         bump = file_record.bump,
-        has_one = owner
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
 
     /// The share link to create (PDA)
     #[account(
@@ -34,11 +52,11 @@ pub struct CreateShare<'info> {
         seeds = [
             SHARE_SEED,
             file_record.key().as_ref(),
-            &registry.total_shares.to_le_bytes()
+            &file_record.share_seed()
         ],
         bump
     )]
-    pub share_link: Account<'info, ShareLink>,
+    pub share_link: Box<Account<'info, ShareLink>>,
 
     /// The file owner (payer)
     #[account(mut)]
@@ -46,39 +64,544 @@ pub struct CreateShare<'info> {
 
     /// System program for account creation
     pub system_program: Program<'info, System>,
+
+    /// SPL Memo program (required only when a memo is supplied)
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// The recipient's profile (created on first use), holding the inbox
+    /// counter and the key generation the encrypted key was wrapped to.
+    /// Required when the share has a recipient.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, recipient.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub recipient_profile: Option<Box<Account<'info, UserProfile>>>,
+
+    /// The recipient's inbox entry for this share, at the next inbox sequence
+    /// number. Required when the share has a recipient.
+    #[account(
+        init,
+        payer = owner,
+        space = InboxEntry::LEN,
+        seeds = [
+            INBOX_SEED,
+            recipient.unwrap_or_default().as_ref(),
+            &recipient_profile
+                .as_ref()
+                .map_or(0, |profile| profile.inbox_count)
+                .to_le_bytes()
+        ],
+        bump
+    )]
+    pub inbox_entry: Option<Account<'info, InboxEntry>>,
+
+    /// The owner's contact entry for the recipient, checked against it
+    pub contact: Option<Account<'info, Contact>>,
+
+    /// The owner's profile, checked for a freeze and holding their share
+    /// defaults
+    /// CHECK: May not exist; deserialized in the handler when it does
+    #[account(
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub owner_profile: UncheckedAccount<'info>,
+
+    /// The file's access log, appended to when supplied (optional)
+    #[account(mut)]
+    pub access_log: Option<Box<Account<'info, FileAccessLog>>>,
+
+    /// The owner's share template. Required when `use_template` is set.
+    #[account(has_one = owner @ HelixError::UnauthorizedOwner)]
+    pub template: Option<Account<'info, ShareTemplate>>,
+
+    /// Instructions sysvar, identifying the calling program under CPI
+    /// (required only when the registry's CPI policy is an allowlist)
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 /// Handler for the create_share instruction
 /// 
 /// Creates a new share link for a file, allowing access to specified recipients.
+/// Shares with a recipient are also added to the recipient's inbox, creating
+/// their profile if needed; the owner pays the rent. If the recipient has an
+/// encryption key published, its generation is recorded so clients can
+/// detect shares wrapped to a key that has since been rotated.
 /// 
 /// # Arguments
 /// * `ctx` - The CreateShare context
-/// * `recipient` - Optional specific wallet that can access (None = public)
-/// * `expires_at` - Optional Unix timestamp for expiration
-/// * `max_downloads` - Optional maximum download count
-/// * `encrypted_key` - Encrypted decryption key for the recipient
+/// * `args` - The share's recipient, limits and options
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_share_handler(ctx: Context<CreateShare>, mut args: CreateShareArgs) -> Result<()> {
+    let clock = Clock::get()?;
+    let owner = ctx.accounts.owner.key();
+    let owner_profile = load_profile(&ctx.accounts.owner_profile)?;
+    require_cpi_allowed(&ctx.accounts.registry, ctx.accounts.instructions.as_deref())?;
+
+    // Attach the memo, if any, to the transaction
+    process_memo(&ctx.accounts.memo_program, args.memo.take())?;
+
+    let created = init_share(
+        NewShare {
+            registry: &mut ctx.accounts.registry,
+            file: ctx.accounts.file_record.key(),
+            file_record: &mut ctx.accounts.file_record,
+            share: ctx.accounts.share_link.key(),
+            share_link: &mut ctx.accounts.share_link,
+            share_bump: ctx.bumps.share_link,
+            owner,
+            rent_payer: owner,
+            owner_profile: owner_profile.as_ref(),
+            recipient_profile: ctx
+                .accounts
+                .recipient_profile
+                .as_deref_mut()
+                .map(|profile| &mut **profile),
+            recipient_profile_bump: ctx.bumps.recipient_profile,
+            inbox_entry: ctx.accounts.inbox_entry.as_deref_mut(),
+            inbox_entry_bump: ctx.bumps.inbox_entry,
+            contact: ctx.accounts.contact.as_deref(),
+            template: ctx.accounts.template.as_deref(),
+            access_log: ctx.accounts.access_log.as_deref_mut().map(|log| &mut **log),
+        },
+        args,
+        clock.unix_timestamp,
+        clock.epoch,
+    )?;
+
+    let registry = &ctx.accounts.registry;
+    let share_link = &ctx.accounts.share_link;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock, created.rolled_over);
+
+    // Emit event
+    emit_event!(ctx, ShareCreated {
+        version: EVENT_VERSION,
+        file: share_link.file,
+        owner,
+        recipient: share_link.recipient,
+        expires_at: share_link.expires_at,
+        timestamp: clock.unix_timestamp,
+        has_message: share_link.encrypted_message.is_some(),
+        template_id: created.template_id,
+    });
+
+    private_msg!(
+        "Share link created";
+        "Share link created for file {} by {}",
+        ctx.accounts.file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for creating a share link from an owner-signed permit
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(permit: SharePermit)]
+pub struct CreateShareWithPermit<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file being shared
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner's profile holding the permit nonce
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, file_record.owner.as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The share link to create (PDA)
+    #[account(
+        init,
+        payer = relayer,
+        space = ShareLink::LEN,
+        seeds = [
+            SHARE_SEED,
+            file_record.key().as_ref(),
+            &file_record.share_seed()
+        ],
+        bump
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The relayer submitting the transaction (payer)
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instructions sysvar, used to find the owner's Ed25519 signature
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The recipient's profile (created on first use), as for `create_share`.
+    /// Required when the permit has a recipient.
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, permit.recipient.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub recipient_profile: Option<Box<Account<'info, UserProfile>>>,
+
+    /// The recipient's inbox entry for this share, at the next inbox sequence
+    /// number. Required when the permit has a recipient.
+    #[account(
+        init,
+        payer = relayer,
+        space = InboxEntry::LEN,
+        seeds = [
+            INBOX_SEED,
+            permit.recipient.unwrap_or_default().as_ref(),
+            &recipient_profile
+                .as_ref()
+                .map_or(0, |profile| profile.inbox_count)
+                .to_le_bytes()
+        ],
+        bump
+    )]
+    pub inbox_entry: Option<Account<'info, InboxEntry>>,
+
+    /// The file's access log, appended to when supplied (optional)
+    #[account(mut)]
+    pub access_log: Option<Box<Account<'info, FileAccessLog>>>,
+}
+
+/// Handler for the create_share_with_permit instruction
+/// 
+/// Creates the same share link as `create_share` would with the permit's
+/// terms, authorized by the file owner's Ed25519 signature over the permit
+/// instead of a transaction signature. The signature must be verified by an
+/// Ed25519 program instruction placed immediately before this one. Options
+/// the permit does not carry (memo, contact check, recipient commitment,
+/// profile defaults, template, message, single use) are left off.
+/// 
+/// The relayer pays the rent of the share link and of the recipient's inbox
+/// entry, so it is recorded as their `rent_payer` and gets the lamports back
+/// when they are closed; recording the owner instead would let the owner
+/// reclaim rent it never paid.
+/// 
+/// # Arguments
+/// * `ctx` - The CreateShareWithPermit context
+/// * `permit` - The share terms signed by the owner
 /// 
 /// # Returns
 /// * `Result<()>` - Success or error
-pub fn handler(
-    ctx: Context<CreateShare>,
-    recipient: Option<Pubkey>,
-    expires_at: Option<i64>,
-    max_downloads: Option<u32>,
-    encrypted_key: Option<String>,
+pub fn create_with_permit_handler(
+    ctx: Context<CreateShareWithPermit>,
+    permit: SharePermit,
 ) -> Result<()> {
-    let registry = &mut ctx.accounts.registry;
-    let file_record = &mut ctx.accounts.file_record;
-    let share_link = &mut ctx.accounts.share_link;
     let clock = Clock::get()?;
+    let owner = ctx.accounts.file_record.owner;
+    let relayer = ctx.accounts.relayer.key();
+    require_cpi_allowed(
+        &ctx.accounts.registry,
+        Some(&ctx.accounts.instructions.to_account_info()),
+    )?;
+
+    // Validate the permit against the owner's signature, nonce and deadline
+    require_keys_eq!(
+        permit.file,
+        ctx.accounts.file_record.key(),
+        HelixError::PermitFileMismatch
+    );
+    require!(clock.unix_timestamp <= permit.deadline, HelixError::PermitExpired);
+    verify_ed25519_signature(
+        &ctx.accounts.instructions.to_account_info(),
+        &owner,
+        &permit.message()?,
+    )?;
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = owner;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+    require!(permit.nonce == user_profile.permit_nonce, HelixError::InvalidPermitNonce);
+    user_profile.permit_nonce = user_profile
+        .permit_nonce
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    let created = init_share(
+        NewShare {
+            registry: &mut ctx.accounts.registry,
+            file: ctx.accounts.file_record.key(),
+            file_record: &mut ctx.accounts.file_record,
+            share: ctx.accounts.share_link.key(),
+            share_link: &mut ctx.accounts.share_link,
+            share_bump: ctx.bumps.share_link,
+            owner,
+            rent_payer: relayer,
+            owner_profile: Some(&ctx.accounts.user_profile),
+            recipient_profile: ctx
+                .accounts
+                .recipient_profile
+                .as_deref_mut()
+                .map(|profile| &mut **profile),
+            recipient_profile_bump: ctx.bumps.recipient_profile,
+            inbox_entry: ctx.accounts.inbox_entry.as_deref_mut(),
+            inbox_entry_bump: ctx.bumps.inbox_entry,
+            contact: None,
+            template: None,
+            access_log: ctx.accounts.access_log.as_deref_mut().map(|log| &mut **log),
+        },
+        CreateShareArgs::from_permit(&permit),
+        clock.unix_timestamp,
+        clock.epoch,
+    )?;
+
+    let registry = &ctx.accounts.registry;
+    let share_key = ctx.accounts.share_link.key();
+    let share_link = &ctx.accounts.share_link;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock, created.rolled_over);
+
+    // Emit events
+    emit_event!(ctx, ShareCreated {
+        version: EVENT_VERSION,
+        file: share_link.file,
+        owner,
+        recipient: share_link.recipient,
+        expires_at: share_link.expires_at,
+        timestamp: clock.unix_timestamp,
+        has_message: false,
+        template_id: created.template_id,
+    });
+    emit_event!(ctx, SharePermitUsed {
+        version: EVENT_VERSION,
+        share: share_key,
+        owner,
+        relayer,
+        nonce: permit.nonce,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Share link created via permit";
+        "Share link created for file {} by {} via permit",
+        ctx.accounts.file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Share options of `create_share`, before defaults are applied
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CreateShareArgs {
+    /// Specific wallet that can access (None = public)
+    pub recipient: Option<Pubkey>,
+    /// Unix timestamp after which the share expires
+    pub expires_at: Option<i64>,
+    /// Maximum download count
+    pub max_downloads: Option<u32>,
+    /// Decryption key encrypted for the recipient
+    pub encrypted_key: Option<EncryptedKey>,
+    /// Note attached to the transaction via SPL Memo
+    pub memo: Option<String>,
+    /// Fail unless a contact matching the recipient is supplied
+    pub require_contact: bool,
+    /// Commitment to the recipient, used instead of `recipient` to keep the
+    /// recipient off-chain; such shares skip the inbox
+    pub recipient_commitment: Option<[u8; 32]>,
+    /// Take unset limits from the owner's profile defaults. Explicit limits
+    /// win; `SHARE_NO_EXPIRY` and `SHARE_UNLIMITED_DOWNLOADS` opt out of a
+    /// default limit.
+    pub use_profile_defaults: bool,
+    /// Message for the recipient, encrypted to them like `encrypted_key`
+    pub encrypted_message: Option<EncryptedShareMessage>,
+    /// Revoke the share with its first download. Implies
+    /// `max_downloads = 1`; any other explicit limit is rejected.
+    pub single_use: bool,
+    /// Take unset limits from the supplied share template, like
+    /// `use_profile_defaults`. The template's `single_use` applies unless
+    /// `max_downloads` is given. Cannot be combined with
+    /// `use_profile_defaults`.
+    pub use_template: bool,
+}
+
+impl CreateShareArgs {
+    /// The terms of a share permit, with every option it does not carry off
+    pub(crate) fn from_permit(permit: &SharePermit) -> Self {
+        Self {
+            recipient: permit.recipient,
+            expires_at: permit.expires_at,
+            max_downloads: permit.max_downloads,
+            encrypted_key: permit.encrypted_key.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Accounts a new share link is written to, borrowed from the context of
+/// either share creation instruction
+pub(crate) struct NewShare<'a> {
+    pub registry: &'a mut StorageRegistry,
+    pub file: Pubkey,
+    pub file_record: &'a mut FileRecord,
+    pub share: Pubkey,
+    pub share_link: &'a mut ShareLink,
+    pub share_bump: u8,
+    pub owner: Pubkey,
+    /// Wallet funding the share link and inbox entry, refunded on close
+    pub rent_payer: Pubkey,
+    pub owner_profile: Option<&'a UserProfile>,
+    pub recipient_profile: Option<&'a mut UserProfile>,
+    pub recipient_profile_bump: u8,
+    pub inbox_entry: Option<&'a mut InboxEntry>,
+    pub inbox_entry_bump: u8,
+    pub contact: Option<&'a Contact>,
+    pub template: Option<&'a ShareTemplate>,
+    pub access_log: Option<&'a mut FileAccessLog>,
+}
+
+/// What `init_share` leaves for the caller to report
+pub(crate) struct CreatedShare {
+    pub template_id: Option<u32>,
+    /// Whether the registry rolled over to a new epoch
+    pub rolled_over: bool,
+}
+
+/// Validate `terms` and initialize a share link from them, updating the
+/// file, registry, recipient inbox and access log. Both `create_share` and
+/// `create_share_with_permit` create their links here, so the two differ
+/// only in how the owner authorizes the share and who pays for it.
+pub(crate) fn init_share(
+    accounts: NewShare,
+    terms: CreateShareArgs,
+    now: i64,
+    epoch: u64,
+) -> Result<CreatedShare> {
+    let NewShare {
+        registry,
+        file,
+        file_record,
+        share,
+        share_link,
+        share_bump,
+        owner,
+        rent_payer,
+        owner_profile,
+        recipient_profile,
+        recipient_profile_bump,
+        inbox_entry,
+        inbox_entry_bump,
+        contact,
+        template,
+        access_log,
+    } = accounts;
+    let CreateShareArgs {
+        recipient,
+        expires_at,
+        max_downloads,
+        encrypted_key,
+        memo: _,
+        require_contact,
+        recipient_commitment,
+        use_profile_defaults,
+        encrypted_message,
+        single_use,
+        use_template,
+    } = terms;
 
-    // Validate file is not deleted
-    require!(!file_record.is_deleted, HelixError::CannotShareDeletedFile);
+    // Validate file is not deleted and below its share limit
+    require!(!file_record.is_deleted(), HelixError::CannotShareDeletedFile);
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+    registry.require_share_capacity(file_record.share_count)?;
+
+    // Validate the owner's account is not frozen
+    if let Some(profile) = owner_profile {
+        require!(!profile.is_frozen, HelixError::OwnerFrozen);
+    }
+
+    // Validate the share is locked to a recipient or a commitment, not both
+    require!(
+        recipient.is_none() || recipient_commitment.is_none(),
+        HelixError::RecipientModeConflict
+    );
+
+    // Validate an explicit download limit agrees with a single-use share
+    require!(
+        !single_use || matches!(max_downloads, None | Some(1)),
+        HelixError::InvalidMaxDownloads
+    );
+
+    // Resolve the share template, if requested
+    require!(
+        !(use_template && use_profile_defaults),
+        HelixError::ShareTemplateConflict
+    );
+    let template = if use_template {
+        Some(template.ok_or(HelixError::ShareTemplateMissing)?)
+    } else {
+        None
+    };
+    let template_id = template.map(|template| template.template_id);
+
+    // An explicit download limit overrides the template's single use
+    let single_use = single_use
+        || (max_downloads.is_none() && template.is_some_and(|t| t.settings.single_use));
+
+    // Fill unset limits from the template or the owner's share defaults
+    let defaults = match template {
+        Some(template) => Some(template.settings.limits()),
+        None if use_profile_defaults => Some(
+            owner_profile
+                .ok_or(HelixError::ShareDefaultsMissing)?
+                .share_defaults,
+        ),
+        None => None,
+    };
+    let (expires_at, max_downloads) = if let Some(defaults) = defaults {
+        require!(
+            !defaults.require_recipient || recipient.is_some() || recipient_commitment.is_some(),
+            HelixError::ShareRecipientRequired
+        );
+        (defaults.expires_at(expires_at, now), defaults.max_downloads(max_downloads))
+    } else {
+        (expires_at, max_downloads)
+    };
+
+    // A single-use share allows exactly one download, whatever the defaults
+    let max_downloads = if single_use { Some(1) } else { max_downloads };
 
     // Validate expiration if provided
     if let Some(exp) = expires_at {
-        require!(exp > clock.unix_timestamp, HelixError::ExpirationInPast);
+        require!(exp > now, HelixError::ExpirationInPast);
     }
 
     // Validate max downloads if provided
@@ -86,57 +609,103 @@ pub fn handler(
         require!(max > 0, HelixError::InvalidMaxDownloads);
     }
 
-    // Validate encrypted key length
-    validate_optional_string_length(
-        &encrypted_key,
-        MAX_ENCRYPTED_KEY_LEN,
-        HelixError::EncryptedKeyTooLong,
-    )?;
+    // Check the recipient against the owner's contact, if supplied or required
+    match contact {
+        Some(contact) => require!(
+            contact.owner == owner && recipient == Some(contact.contact),
+            HelixError::RecipientNotInContacts
+        ),
+        None => require!(!require_contact, HelixError::RecipientNotInContacts),
+    }
+
+    // Add the share to the recipient's inbox and record the generation of
+    // the key the encrypted key was wrapped to
+    let recipient_key_generation = match (recipient, recipient_profile, inbox_entry) {
+        (Some(recipient), Some(profile), Some(inbox_entry)) => {
+            if profile.owner == Pubkey::default() {
+                profile.owner = recipient;
+                profile.bump = recipient_profile_bump;
+            }
+
+            push_notification(
+                profile,
+                inbox_entry,
+                inbox_entry_bump,
+                recipient,
+                share,
+                rent_payer,
+                NotificationKind::ShareReceived,
+            )?;
+
+            if profile.encryption_pubkey.is_some() {
+                profile.key_generation
+            } else {
+                0
+            }
+        }
+        (Some(_), _, _) => return err!(HelixError::InboxAccountsMissing),
+        (None, None, None) => 0,
+        (None, _, _) => return err!(HelixError::RecipientProfileMismatch),
+    };
 
     // Initialize share link
-    share_link.file = file_record.key();
-    share_link.owner = ctx.accounts.owner.key();
+    share_link.file = file;
+    share_link.owner = owner;
     share_link.recipient = recipient;
-    share_link.encrypted_key = encrypted_key;
+    share_link.encrypted_key = encrypted_key.map(String::from);
     share_link.expires_at = expires_at;
     share_link.max_downloads = max_downloads;
     share_link.download_count = 0;
-    share_link.is_revoked = false;
-    share_link.created_at = clock.unix_timestamp;
-    share_link.bump = ctx.bumps.share_link;
-    share_link._reserved = [0u8; 16];
+    share_link.flags = 0;
+    share_link.created_at = now;
+    share_link.bump = share_bump;
+    share_link.recipient_key_generation = recipient_key_generation;
+    share_link.bytes_served = 0;
+    share_link.recipient_commitment = recipient_commitment;
+    share_link.encrypted_message = encrypted_message.map(String::from);
+    share_link.claim_hash = None;
+    share_link.set_single_use(single_use);
+    share_link.rent_payer = rent_payer;
+    share_link.last_downloader = Pubkey::default();
+    share_link.last_download_at = 0;
+    share_link._reserved = [0u8; 4];
 
     // Update file record share count
     file_record.share_count = file_record
         .share_count
         .checked_add(1)
         .ok_or(HelixError::ArithmeticOverflow)?;
+    file_record.advance_share_nonce()?;
+
+    // Roll epoch stats forward if a new epoch has started
+    let rolled_over = registry.roll_epoch(epoch);
 
     // Update registry stats
     registry.total_shares = registry
         .total_shares
         .checked_add(1)
         .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.current_epoch_stats.shares = registry
+        .current_epoch_stats
+        .shares
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
 
-    // Emit event
-    emit!(ShareCreated {
-        file: file_record.key(),
-        owner: share_link.owner,
-        recipient,
-        expires_at,
-        timestamp: clock.unix_timestamp,
-    });
-
-    msg!(
-        "Share link created for file {} by {}",
-        file_record.transaction_id,
-        share_link.owner
-    );
+    // Log the share creation, if the file keeps an access log
+    if let Some(access_log) = access_log {
+        access_log.record(&file, AccessLogEntry {
+            actor: owner,
+            share,
+            timestamp: now,
+            kind: AccessKind::ShareCreated,
+        });
+    }
 
-    Ok(())
+    Ok(CreatedShare { template_id, rolled_over })
 }
 
 /// Accounts required for revoking a share link
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct RevokeShare<'info> {
     /// The storage registry (for stats)
@@ -150,89 +719,410 @@ pub struct RevokeShare<'info> {
     /// The file record
     #[account(
         mut,
-        seeds = [FILE_SEED, file_record.transaction_id.as_bytes()],
+        seeds = [FILE_SEED, &file_record.file_seed()],
         bump = file_record.bump
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
 
     /// The share link to revoke
     #[account(
         mut,
         constraint = share_link.owner == owner.key() @ HelixError::UnauthorizedOwner,
-        constraint = share_link.file == file_record.key() @ HelixError::InvalidShareLink
+        constraint = share_link.file == file_record.key() @ HelixError::ShareFileMismatch
     )]
-    pub share_link: Account<'info, ShareLink>,
+    pub share_link: Box<Account<'info, ShareLink>>,
 
-    /// The share owner
+    /// The share owner (pays for the recipient's notification)
+    #[account(mut)]
     pub owner: Signer<'info>,
+
+    /// The recipient's profile (required only to notify the recipient)
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, share_link.recipient.unwrap_or_default().as_ref()],
+        bump = recipient_profile.bump
+    )]
+    pub recipient_profile: Option<Box<Account<'info, UserProfile>>>,
+
+    /// The recipient's revocation notice, at the next inbox sequence number
+    /// (required only to notify the recipient)
+    #[account(
+        init,
+        payer = owner,
+        space = InboxEntry::LEN,
+        seeds = [
+            INBOX_SEED,
+            share_link.recipient.unwrap_or_default().as_ref(),
+            &recipient_profile
+                .as_ref()
+                .map_or(0, |profile| profile.inbox_count)
+                .to_le_bytes()
+        ],
+        bump
+    )]
+    pub notification: Option<Account<'info, InboxEntry>>,
+
+    /// System program for creating the notification
+    pub system_program: Option<Program<'info, System>>,
 }
 
-/// Handler for revoking a share link
+/// Handler for revoking a share link. When the recipient's profile and a
+/// notification account are passed, a share-revoked notice is added to the
+/// recipient's inbox.
 pub fn revoke_handler(ctx: Context<RevokeShare>) -> Result<()> {
+    let share_key = ctx.accounts.share_link.key();
+    let owner = ctx.accounts.owner.key();
     let registry = &mut ctx.accounts.registry;
     let file_record = &mut ctx.accounts.file_record;
     let share_link = &mut ctx.accounts.share_link;
     let clock = Clock::get()?;
 
     // Validate share is not already revoked
-    require!(!share_link.is_revoked, HelixError::ShareRevoked);
+    require!(!share_link.is_revoked(), HelixError::ShareRevoked);
 
-    // Mark as revoked
-    share_link.is_revoked = true;
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
 
-    // Update file record share count
-    file_record.share_count = file_record.share_count.saturating_sub(1);
+    // Mark as revoked and drop the wrapped key
+    revoke_and_clear_key(share_link)?;
 
-    // Update registry stats
-    registry.total_shares = registry.total_shares.saturating_sub(1);
+    // Update file record share count and registry stats
+    release_share_counters(share_link, file_record, registry);
+
+    // Notify the recipient, if requested
+    match (
+        share_link.recipient,
+        ctx.accounts.recipient_profile.as_mut(),
+        ctx.accounts.notification.as_mut(),
+    ) {
+        (Some(recipient), Some(profile), Some(notification)) => {
+            push_notification(
+                profile,
+                notification,
+                ctx.bumps.notification,
+                recipient,
+                share_key,
+                owner,
+                NotificationKind::ShareRevoked,
+            )?;
+        }
+        (_, None, None) => {}
+        _ => return err!(HelixError::RecipientProfileMismatch),
+    }
 
     // Emit event
-    emit!(ShareRevoked {
-        share: ctx.accounts.share_link.key(),
+    emit_event!(ctx, ShareRevoked {
+        version: EVENT_VERSION,
+        share: share_key,
         owner: share_link.owner,
         timestamp: clock.unix_timestamp,
     });
 
-    msg!(
+    private_msg!(
+        "Share link revoked";
         "Share link revoked: {} by {}",
-        ctx.accounts.share_link.key(),
+        share_key,
         share_link.owner
     );
 
     Ok(())
 }
 
+/// Accounts required for replacing a share's encrypted message
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateShareMessage<'info> {
+    /// The share link to update
+    #[account(
+        mut,
+        constraint = share_link.owner == owner.key() @ HelixError::UnauthorizedOwner
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The share owner
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the update_share_message instruction
+/// 
+/// Replaces or, with `None`, removes the encrypted message of a share that
+/// has not been revoked. The stored data is zeroed first so a shorter
+/// message leaves none of the old ciphertext behind.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `encrypted_message` - The new message, encrypted to the recipient
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn update_share_message_handler(
+    ctx: Context<UpdateShareMessage>,
+    encrypted_message: Option<EncryptedShareMessage>,
+) -> Result<()> {
+    let share_key = ctx.accounts.share_link.key();
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate share is not revoked
+    require!(!share_link.is_revoked(), HelixError::ShareRevoked);
+
+    share_link.encrypted_message = encrypted_message.map(String::from);
+    share_link.to_account_info().try_borrow_mut_data()?.fill(0);
+
+    emit_event!(ctx, ShareMessageUpdated {
+        version: EVENT_VERSION,
+        share: share_key,
+        owner: share_link.owner,
+        has_message: share_link.encrypted_message.is_some(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Share message updated"; "Share message updated: {}", share_key);
+
+    Ok(())
+}
+
+/// Revoke a share link and drop its encrypted key and message. Borsh writes the shorter
+/// record over the old bytes without clearing the tail, so the stored data is
+/// zeroed first; the revoked link is written back on exit.
+pub(crate) fn revoke_and_clear_key(share_link: &mut Account<ShareLink>) -> Result<()> {
+    share_link.set_revoked(true);
+    if share_link.clear_key() {
+        share_link.to_account_info().try_borrow_mut_data()?.fill(0);
+    }
+
+    Ok(())
+}
+
+/// Release a revoked share link's hold on its file's `share_count` and the
+/// registry's `total_shares`. Each link releases at most once, whichever
+/// revocation path reaches it first.
+pub(crate) fn release_share_counters(
+    share_link: &mut ShareLink,
+    file_record: &mut FileRecord,
+    registry: &mut StorageRegistry,
+) {
+    if share_link.release_counters() {
+        file_record.share_count = file_record.share_count.saturating_sub(1);
+        registry.total_shares = registry.total_shares.saturating_sub(1);
+    }
+}
+
 /// Accounts required for recording a download
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct RecordDownload<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
     /// The share link being used
     #[account(mut)]
-    pub share_link: Account<'info, ShareLink>,
+    pub share_link: Box<Account<'info, ShareLink>>,
 
-    /// The wallet downloading (must match recipient if specified)
+    /// The shared file record (for its size and deleted state)
+    #[account(
+        mut,
+        address = share_link.file @ HelixError::ShareFileMismatch
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The wallet downloading (must match recipient if specified),
+    /// or a session key acting for it when `session` is supplied. Pays the
+    /// download fee when the registry's throttle charges one.
+    #[account(mut)]
     pub downloader: Signer<'info>,
+
+    /// Session delegating download rights from the real wallet
+    pub session: Option<Account<'info, SessionKey>>,
+
+    /// The downloading wallet's profile, to add the file to its recent files
+    /// when the wallet owns it (optional)
+    #[account(mut)]
+    pub downloader_profile: Option<Box<Account<'info, UserProfile>>>,
+
+    /// The file owner's profile, checked for a freeze (optional)
+    #[account(
+        seeds = [USER_PROFILE_SEED, file_record.owner.as_ref()],
+        bump = owner_profile.bump
+    )]
+    pub owner_profile: Option<Box<Account<'info, UserProfile>>>,
+
+    /// The file's access log, appended to when supplied (optional)
+    #[account(mut)]
+    pub access_log: Option<Box<Account<'info, FileAccessLog>>>,
+
+    /// The treasury receiving the download fee (required only when the
+    /// registry's throttle charges one)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// System program for profile creation and the fee transfer
+    pub system_program: Program<'info, System>,
+
+    /// The downloader's profile (created on first use), recording the fee
+    /// paid. Required only when a fee is charged. Declared last so its
+    /// write wins should it alias `downloader_profile`, which is only
+    /// changed for owner downloads, and those are never charged.
+    #[account(
+        init_if_needed,
+        payer = downloader,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, downloader.key().as_ref()],
+        bump
+    )]
+    pub fee_profile: Option<Box<Account<'info, UserProfile>>>,
 }
 
 /// Handler for recording a download
-pub fn record_download_handler(ctx: Context<RecordDownload>) -> Result<()> {
+/// 
+/// Each counted download adds the file's size to the bytes served on the
+/// share, the file and the registry. The counters saturate at `u64::MAX`.
+/// 
+/// Shares locked to a recipient commitment need `recipient_salt`; the
+/// commitment is recomputed from the acting wallet. The salt becomes public
+/// with the transaction, but is useless to anyone who cannot sign for the
+/// committed wallet.
+/// 
+/// Owners downloading their own file can pass their profile to move the
+/// file to the front of their recent files. Downloads that pass the file
+/// owner's profile are refused while the owner's account is frozen.
+/// 
+/// A single-use share is revoked by the download that counts it: its
+/// counters are released and its encrypted key cleared in the same
+/// instruction, so any later download fails with `ShareRevoked`.
+/// 
+/// Downloads of public shares by wallets other than the file owner are
+/// subject to the registry's `DownloadThrottle`: either a cooldown on
+/// repeat downloads by the share's latest downloader, or a fee paid by the
+/// downloader signer to the treasury.
+pub fn record_download_handler(
+    ctx: Context<RecordDownload>,
+    recipient_salt: Option<[u8; 32]>,
+) -> Result<()> {
+    let share_key = ctx.accounts.share_link.key();
+    let registry = &mut ctx.accounts.registry;
     let share_link = &mut ctx.accounts.share_link;
+    let file_record = &mut ctx.accounts.file_record;
     let clock = Clock::get()?;
 
-    // Validate share link is valid
-    require!(
-        share_link.is_valid(clock.unix_timestamp),
-        HelixError::InvalidShareLink
-    );
+    // Resolve the wallet acting, either directly or through a session key
+    let downloader = ctx.accounts.downloader.key();
+    let (wallet, session) = match &ctx.accounts.session {
+        Some(session) => {
+            require!(
+                session.authorizes(
+                    &downloader,
+                    SESSION_SCOPE_RECORD_DOWNLOAD,
+                    clock.unix_timestamp
+                ),
+                HelixError::SessionNotAuthorized
+            );
+            (session.wallet, Some(session.key()))
+        }
+        None => (downloader, None),
+    };
 
-    // Validate access if recipient is specified
-    require!(
-        share_link.can_access(&ctx.accounts.downloader.key(), clock.unix_timestamp),
-        HelixError::ShareAccessDenied
-    );
+    // Validate the owner's account is not frozen, if its profile is supplied
+    if let Some(profile) = &ctx.accounts.owner_profile {
+        require!(!profile.is_frozen, HelixError::OwnerFrozen);
+    }
 
-    // Record the download
-    let still_valid = share_link.record_download();
-    require!(still_valid, HelixError::MaxDownloadsReached);
+    // Validate the share is usable, then that the wallet is its recipient
+    share_link.require_download_access(
+        &wallet,
+        recipient_salt.as_ref(),
+        clock.unix_timestamp,
+        file_record.is_deleted(),
+    )?;
+
+    // Throttle public downloads by anyone but the file owner
+    if share_link.is_public() && wallet != file_record.owner {
+        match registry.download_throttle {
+            DownloadThrottle::None => {}
+            DownloadThrottle::Cooldown { cooldown_secs } => {
+                share_link.require_download_cooldown(&wallet, cooldown_secs, clock.unix_timestamp)?
+            }
+            DownloadThrottle::Fee { fee_lamports } => charge_fee(
+                fee_lamports,
+                registry,
+                &ctx.accounts.downloader,
+                ctx.accounts.fee_profile.as_deref_mut(),
+                ctx.bumps.fee_profile,
+                ctx.accounts.treasury.as_ref(),
+                &ctx.accounts.system_program,
+            )?,
+        }
+    }
+
+    // Record the download, failing unless the share is usable
+    share_link.record_download(
+        wallet,
+        clock.unix_timestamp,
+        file_record.is_deleted(),
+        file_record.size,
+    )?;
+    file_record.bytes_served = file_record.bytes_served.saturating_add(file_record.size);
+
+    // Track the file in the owner's recent files
+    if let Some(profile) = ctx.accounts.downloader_profile.as_mut() {
+        require_keys_eq!(profile.owner, wallet, HelixError::ProfileOwnerMismatch);
+        if file_record.owner == wallet {
+            profile.touch_recent_file(file_record.key(), clock.unix_timestamp);
+        }
+    }
+
+    // Log the download, if the file keeps an access log
+    if let Some(access_log) = ctx.accounts.access_log.as_mut() {
+        access_log.record(&file_record.key(), AccessLogEntry {
+            actor: wallet,
+            share: share_key,
+            timestamp: clock.unix_timestamp,
+            kind: AccessKind::Download,
+        });
+    }
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Update registry stats
+    registry.current_epoch_stats.downloads = registry
+        .current_epoch_stats
+        .downloads
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.total_bytes_served = registry.total_bytes_served.saturating_add(file_record.size);
+
+    emit_event!(ctx, DownloadRecorded {
+        version: EVENT_VERSION,
+        share: share_key,
+        wallet,
+        session,
+        download_count: share_link.download_count,
+        timestamp: clock.unix_timestamp,
+        bytes_served: share_link.bytes_served,
+    });
+
+    // Revoke a single-use share with its download
+    if share_link.is_single_use() {
+        revoke_and_clear_key(share_link)?;
+        release_share_counters(share_link, file_record, registry);
+
+        emit_event!(ctx, ShareRevoked {
+            version: EVENT_VERSION,
+            share: share_key,
+            owner: share_link.owner,
+            timestamp: clock.unix_timestamp,
+        });
+    }
 
     msg!(
         "Download recorded for share link. Count: {}",
@@ -246,14 +1136,17 @@ pub fn record_download_handler(ctx: Context<RecordDownload>) -> Result<()> {
 #[derive(Accounts)]
 pub struct ValidateAccess<'info> {
     /// The share link to validate
-    pub share_link: Account<'info, ShareLink>,
+    pub share_link: Box<Account<'info, ShareLink>>,
 
     /// The file record
     #[account(
-        seeds = [FILE_SEED, file_record.transaction_id.as_bytes()],
+        seeds = [FILE_SEED, &file_record.file_seed()],
         bump = file_record.bump
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// An audit grant to check as an alternative, view-only authorization
+    pub audit_grant: Option<Account<'info, AuditGrant>>,
 }
 
 /// Check if a wallet can access a shared file
@@ -263,10 +1156,432 @@ pub fn validate_access(ctx: &Context<ValidateAccess>, wallet: &Pubkey) -> Result
     let clock = Clock::get()?;
 
     // Check file is not deleted
-    if file_record.is_deleted {
+    if file_record.is_deleted() {
         return Ok(false);
     }
 
     // Check share link validity
     Ok(share_link.can_access(wallet, clock.unix_timestamp))
 }
+
+/// Check if a wallet can view the shared file's metadata through the
+/// supplied audit grant. This never implies download access.
+pub fn validate_audit_access(ctx: &Context<ValidateAccess>, wallet: &Pubkey) -> Result<bool> {
+    let file_record = &ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    Ok(ctx.accounts.audit_grant.as_ref().is_some_and(|grant| {
+        grant.auditor == *wallet
+            && grant.grants_view(&file_record.key(), file_record, clock.unix_timestamp)
+    }))
+}
+
+/// Accounts for querying share link status (read-only)
+#[derive(Accounts)]
+pub struct GetShareStatus<'info> {
+    /// The share link to query
+    #[account(
+        constraint = share_link.file == file_record.key() @ HelixError::ShareFileMismatch
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The file record being shared
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// An audit grant to check for view access to the file (optional)
+    pub audit_grant: Option<Account<'info, AuditGrant>>,
+}
+
+/// Return the current status of a share link
+/// 
+/// With an audit grant supplied, `audit_view` reports whether it currently
+/// lets its auditor view the file's metadata.
+pub fn get_share_status_handler(ctx: Context<GetShareStatus>) -> Result<ShareStatusView> {
+    let share_link = &ctx.accounts.share_link;
+    let file_record = &ctx.accounts.file_record;
+    let clock = Clock::get()?;
+    let status = share_link.status(clock.unix_timestamp, file_record.is_deleted());
+    let audit_view = ctx.accounts.audit_grant.as_ref().is_some_and(|grant| {
+        grant.grants_view(&file_record.key(), file_record, clock.unix_timestamp)
+    });
+
+    Ok(ShareStatusView {
+        version: VIEW_VERSION,
+        file: share_link.file,
+        recipient: share_link.recipient,
+        status,
+        is_valid: status == ShareStatus::Active,
+        is_revoked: share_link.is_revoked(),
+        file_deleted: file_record.is_deleted(),
+        expires_at: share_link.expires_at,
+        max_downloads: share_link.max_downloads,
+        download_count: share_link.download_count,
+        audit_view,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{ShareDefaults, SHARE_NO_EXPIRY, SHARE_UNLIMITED_DOWNLOADS};
+    use crate::test_utils::{
+        sample_file, sample_profile, sample_registry, zeroed, DAY, NOW, SAMPLE_TX_ID,
+    };
+
+    /// Run `init_share` for `terms` against fresh fixtures, returning the link
+    /// and the recipient's profile and inbox entry
+    fn create(terms: CreateShareArgs, rent_payer: Pubkey) -> (ShareLink, UserProfile, InboxEntry) {
+        let owner = Pubkey::new_unique();
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let mut share_link = zeroed::<ShareLink>(ShareLink::LEN);
+        let (_, owner_profile) = sample_profile(owner);
+        let mut recipient_profile = zeroed::<UserProfile>(UserProfile::LEN);
+        let mut inbox_entry = zeroed::<InboxEntry>(InboxEntry::LEN);
+        let has_recipient = terms.recipient.is_some();
+
+        init_share(
+            NewShare {
+                registry: &mut registry,
+                file: Pubkey::new_unique(),
+                file_record: &mut file_record,
+                share: Pubkey::new_unique(),
+                share_link: &mut share_link,
+                share_bump: 254,
+                owner,
+                rent_payer,
+                owner_profile: Some(&owner_profile),
+                recipient_profile: has_recipient.then_some(&mut recipient_profile),
+                recipient_profile_bump: 253,
+                inbox_entry: has_recipient.then_some(&mut inbox_entry),
+                inbox_entry_bump: 252,
+                contact: None,
+                template: None,
+                access_log: None,
+            },
+            terms,
+            NOW,
+            0,
+        )
+        .unwrap();
+        assert_eq!(file_record.share_count, 1);
+        assert_eq!(registry.total_shares, 1);
+
+        (share_link, recipient_profile, inbox_entry)
+    }
+
+    /// Run `init_share` for public `terms` by an owner whose profile holds
+    /// `share_defaults`, returning the link's expiry and download limit
+    fn limits_with_defaults(
+        terms: CreateShareArgs,
+        share_defaults: ShareDefaults,
+    ) -> (Option<i64>, Option<u32>) {
+        let owner = Pubkey::new_unique();
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let mut share_link = zeroed::<ShareLink>(ShareLink::LEN);
+        let (_, mut owner_profile) = sample_profile(owner);
+        owner_profile.share_defaults = share_defaults;
+
+        init_share(
+            NewShare {
+                registry: &mut registry,
+                file: Pubkey::new_unique(),
+                file_record: &mut file_record,
+                share: Pubkey::new_unique(),
+                share_link: &mut share_link,
+                share_bump: 254,
+                owner,
+                rent_payer: owner,
+                owner_profile: Some(&owner_profile),
+                recipient_profile: None,
+                recipient_profile_bump: 0,
+                inbox_entry: None,
+                inbox_entry_bump: 0,
+                contact: None,
+                template: None,
+                access_log: None,
+            },
+            terms,
+            NOW,
+            0,
+        )
+        .unwrap();
+
+        (share_link.expires_at, share_link.max_downloads)
+    }
+
+    fn permit(recipient: Option<Pubkey>) -> SharePermit {
+        SharePermit {
+            file: Pubkey::new_unique(),
+            recipient,
+            expires_at: Some(NOW + 60),
+            max_downloads: Some(3),
+            encrypted_key: None,
+            nonce: 0,
+            deadline: NOW,
+        }
+    }
+
+    #[test]
+    fn permit_terms_create_the_same_link_as_direct_terms() {
+        let recipient = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let permit = permit(Some(recipient));
+        let direct = CreateShareArgs {
+            recipient: Some(recipient),
+            expires_at: permit.expires_at,
+            max_downloads: permit.max_downloads,
+            ..CreateShareArgs::default()
+        };
+
+        let (via_permit, permit_profile, permit_entry) =
+            create(CreateShareArgs::from_permit(&permit), payer);
+        let (direct, direct_profile, direct_entry) = create(direct, payer);
+
+        let comparable = |link: &ShareLink| {
+            (link.recipient, link.expires_at, link.max_downloads, link.flags, link.rent_payer)
+        };
+        assert_eq!(comparable(&via_permit), comparable(&direct));
+        assert_eq!(permit_profile.owner, recipient);
+        assert_eq!(permit_profile.inbox_count, direct_profile.inbox_count);
+        assert_eq!(permit_entry.payer, direct_entry.payer);
+        assert_eq!(permit_entry.kind, NotificationKind::ShareReceived);
+    }
+
+    #[test]
+    fn rent_payer_is_whoever_funded_the_link() {
+        let relayer = Pubkey::new_unique();
+        let (share_link, _, inbox_entry) =
+            create(CreateShareArgs::from_permit(&permit(Some(Pubkey::new_unique()))), relayer);
+
+        assert_eq!(share_link.rent_payer, relayer);
+        assert_eq!(inbox_entry.payer, relayer);
+    }
+
+    #[test]
+    fn permit_to_a_recipient_needs_inbox_accounts() {
+        let owner = Pubkey::new_unique();
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let mut share_link = zeroed::<ShareLink>(ShareLink::LEN);
+
+        let result = init_share(
+            NewShare {
+                registry: &mut registry,
+                file: Pubkey::new_unique(),
+                file_record: &mut file_record,
+                share: Pubkey::new_unique(),
+                share_link: &mut share_link,
+                share_bump: 254,
+                owner,
+                rent_payer: owner,
+                owner_profile: None,
+                recipient_profile: None,
+                recipient_profile_bump: 0,
+                inbox_entry: None,
+                inbox_entry_bump: 0,
+                contact: None,
+                template: None,
+                access_log: None,
+            },
+            CreateShareArgs::from_permit(&permit(Some(Pubkey::new_unique()))),
+            NOW,
+            0,
+        );
+
+        assert_eq!(result.err(), Some(HelixError::InboxAccountsMissing.into()));
+        assert_eq!(registry.total_shares, 0);
+        assert_eq!(file_record.share_nonce, 0);
+    }
+
+    #[test]
+    fn share_nonce_keeps_advancing_after_links_are_released() {
+        let owner = Pubkey::new_unique();
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+
+        for expected_nonce in 0..3 {
+            assert_eq!(file_record.share_seed(), u64::to_le_bytes(expected_nonce));
+            let mut share_link = zeroed::<ShareLink>(ShareLink::LEN);
+            init_share(
+                NewShare {
+                    registry: &mut registry,
+                    file: Pubkey::new_unique(),
+                    file_record: &mut file_record,
+                    share: Pubkey::new_unique(),
+                    share_link: &mut share_link,
+                    share_bump: 254,
+                    owner,
+                    rent_payer: owner,
+                    owner_profile: None,
+                    recipient_profile: None,
+                    recipient_profile_bump: 0,
+                    inbox_entry: None,
+                    inbox_entry_bump: 0,
+                    contact: None,
+                    template: None,
+                    access_log: None,
+                },
+                CreateShareArgs::default(),
+                NOW,
+                0,
+            )
+            .unwrap();
+
+            share_link.set_revoked(true);
+            release_share_counters(&mut share_link, &mut file_record, &mut registry);
+            registry.total_shares = 0;
+        }
+
+        assert_eq!(file_record.share_nonce, 3);
+        assert_eq!(file_record.share_count, 0);
+    }
+
+    /// The paths that revoke a share link and release its counters
+    #[derive(Clone, Copy, Debug)]
+    enum ReleasePath {
+        Expire,
+        Revoke,
+        Close,
+        ForceRevoke,
+        SingleUse,
+    }
+
+    /// Take `path` against `share_link` as its handler would, skipping links
+    /// the handler rejects or passes over
+    fn release_by(
+        path: ReleasePath,
+        share_link: &mut ShareLink,
+        file_record: &mut FileRecord,
+        registry: &mut StorageRegistry,
+    ) {
+        let expired = NOW + 2 * DAY;
+        let admitted = match path {
+            ReleasePath::Expire => share_link.status(expired, false) == ShareStatus::Expired,
+            ReleasePath::Revoke | ReleasePath::Close | ReleasePath::ForceRevoke => {
+                !share_link.is_revoked()
+            }
+            ReleasePath::SingleUse => {
+                share_link.status(NOW, false) == ShareStatus::Active
+                    && share_link.is_single_use()
+            }
+        };
+        if admitted {
+            share_link.set_revoked(true);
+            share_link.clear_key();
+        }
+        // Release unconditionally to show a second release is a no-op
+        release_share_counters(share_link, file_record, registry);
+    }
+
+    #[test]
+    fn every_pair_of_release_paths_releases_a_link_once() {
+        use ReleasePath::*;
+        let paths = [Expire, Revoke, Close, ForceRevoke, SingleUse];
+
+        for first in paths {
+            for second in paths {
+                let owner = Pubkey::new_unique();
+                let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+                let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+                let mut share_link = zeroed::<ShareLink>(ShareLink::LEN);
+                init_share(
+                    NewShare {
+                        registry: &mut registry,
+                        file: Pubkey::new_unique(),
+                        file_record: &mut file_record,
+                        share: Pubkey::new_unique(),
+                        share_link: &mut share_link,
+                        share_bump: 254,
+                        owner,
+                        rent_payer: owner,
+                        owner_profile: None,
+                        recipient_profile: None,
+                        recipient_profile_bump: 0,
+                        inbox_entry: None,
+                        inbox_entry_bump: 0,
+                        contact: None,
+                        template: None,
+                        access_log: None,
+                    },
+                    CreateShareArgs {
+                        expires_at: Some(NOW + DAY),
+                        single_use: true,
+                        ..CreateShareArgs::default()
+                    },
+                    NOW,
+                    0,
+                )
+                .unwrap();
+                // Another live link on the file, which neither path may release
+                file_record.share_count += 1;
+                registry.total_shares += 1;
+
+                release_by(first, &mut share_link, &mut file_record, &mut registry);
+                assert!(share_link.is_revoked(), "{first:?} revokes a live link");
+                release_by(second, &mut share_link, &mut file_record, &mut registry);
+
+                assert!(share_link.counters_released(), "{first:?} then {second:?}");
+                assert_eq!(file_record.share_count, 1, "{first:?} then {second:?}");
+                assert_eq!(registry.total_shares, 1, "{first:?} then {second:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn explicit_terms_take_precedence_over_profile_defaults() {
+        let defaults = ShareDefaults {
+            expiry_duration: Some(7 * DAY),
+            max_downloads: Some(5),
+            require_recipient: false,
+        };
+        let with_defaults = |expires_at, max_downloads| CreateShareArgs {
+            expires_at,
+            max_downloads,
+            use_profile_defaults: true,
+            ..CreateShareArgs::default()
+        };
+
+        // Explicit limits win over the defaults
+        let explicit = with_defaults(Some(NOW + 60), Some(2));
+        assert_eq!(limits_with_defaults(explicit, defaults), (Some(NOW + 60), Some(2)));
+
+        // So does explicitly asking for no limit
+        let unlimited = with_defaults(Some(SHARE_NO_EXPIRY), Some(SHARE_UNLIMITED_DOWNLOADS));
+        assert_eq!(limits_with_defaults(unlimited, defaults), (None, None));
+
+        // Unset limits fall back to the defaults, one at a time
+        let unset = with_defaults(None, None);
+        assert_eq!(limits_with_defaults(unset, defaults), (Some(NOW + 7 * DAY), Some(5)));
+        let expiry_only = with_defaults(Some(NOW + 60), None);
+        assert_eq!(limits_with_defaults(expiry_only, defaults), (Some(NOW + 60), Some(5)));
+    }
+
+    #[test]
+    fn profile_defaults_take_precedence_over_built_in_limits() {
+        let defaults = ShareDefaults {
+            expiry_duration: Some(DAY),
+            max_downloads: Some(3),
+            require_recipient: false,
+        };
+
+        // Without `use_profile_defaults` an unset limit is no limit
+        assert_eq!(limits_with_defaults(CreateShareArgs::default(), defaults), (None, None));
+
+        // With it, defaults that leave a limit unset keep it unlimited
+        let opted_in = CreateShareArgs {
+            use_profile_defaults: true,
+            ..CreateShareArgs::default()
+        };
+        assert_eq!(limits_with_defaults(opted_in.clone(), defaults), (Some(NOW + DAY), Some(3)));
+        assert_eq!(
+            limits_with_defaults(opted_in, ShareDefaults::default()),
+            (None, None)
+        );
+    }
+}
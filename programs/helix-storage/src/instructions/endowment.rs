@@ -0,0 +1,322 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::memo::Memo;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::memo::process_memo;
+use crate::state::{
+    Endowment, EndowmentContributed, EndowmentWithdrawn, FileRecord, ENDOWMENT_SEED,
+    EVENT_VERSION, FILE_SEED,
+};
+use crate::views::{EndowmentView, VIEW_VERSION};
+
+/// Accounts required for contributing to a file's endowment
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct Contribute<'info> {
+    /// The file being endowed
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file's endowment (created by the first contribution)
+    #[account(
+        init_if_needed,
+        payer = contributor,
+        space = Endowment::LEN,
+        seeds = [ENDOWMENT_SEED, file_record.key().as_ref()],
+        bump
+    )]
+    pub endowment: Account<'info, Endowment>,
+
+    /// The wallet contributing (pays the amount and, first, the rent)
+    #[account(mut)]
+    pub contributor: Signer<'info>,
+
+    /// System program for account creation and the transfer
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the contribute instruction
+/// 
+/// Transfers `amount` lamports from any wallet into the file's endowment.
+/// The first contributor also pays the endowment's rent, which is not
+/// counted as raised.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `amount` - Lamports to contribute
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn contribute_handler(ctx: Context<Contribute>, amount: u64) -> Result<()> {
+    let contributor = ctx.accounts.contributor.key();
+    let file_key = ctx.accounts.file_record.key();
+    let endowment_key = ctx.accounts.endowment.key();
+    let clock = Clock::get()?;
+
+    // Validate the amount and that the file is live
+    require!(amount > 0, HelixError::InvalidEndowmentAmount);
+    require!(ctx.accounts.file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.contributor.to_account_info(),
+                to: ctx.accounts.endowment.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let file_record = &mut ctx.accounts.file_record;
+    let endowment = &mut ctx.accounts.endowment;
+    if !file_record.is_endowed() {
+        endowment.file = file_key;
+        endowment.created_at = clock.unix_timestamp;
+        endowment.bump = ctx.bumps.endowment;
+        endowment._reserved = [0u8; 16];
+        file_record.set_endowed(true);
+    }
+
+    endowment.total_raised = endowment
+        .total_raised
+        .checked_add(amount)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, EndowmentContributed {
+        version: EVENT_VERSION,
+        endowment: endowment_key,
+        file: file_key,
+        contributor,
+        amount,
+        total_raised: endowment.total_raised,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Endowment contribution received";
+        "{} lamports contributed to {} by {}",
+        amount,
+        file_record.transaction_id,
+        contributor
+    );
+
+    Ok(())
+}
+
+/// Accounts required for withdrawing from a file's endowment
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawEndowment<'info> {
+    /// The endowed file record (may be deleted)
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file's endowment
+    #[account(
+        mut,
+        seeds = [ENDOWMENT_SEED, file_record.key().as_ref()],
+        bump = endowment.bump
+    )]
+    pub endowment: Account<'info, Endowment>,
+
+    /// The file owner (receives the lamports)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// SPL Memo program (required only when a memo is supplied)
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the withdraw_endowment instruction
+/// 
+/// Moves `amount` lamports from the endowment to the file's current owner,
+/// also after the file was deleted. The endowment's rent reserve cannot be
+/// withdrawn; it is released by `close_endowment`.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `amount` - Lamports to withdraw
+/// * `memo` - Optional note on the purpose, logged and attached via SPL Memo
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn withdraw_endowment_handler(
+    ctx: Context<WithdrawEndowment>,
+    amount: u64,
+    memo: Option<String>,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let endowment_key = ctx.accounts.endowment.key();
+    let clock = Clock::get()?;
+
+    // Validate the amount against the balance above the rent reserve
+    require!(amount > 0, HelixError::InvalidEndowmentAmount);
+    let endowment_info = ctx.accounts.endowment.to_account_info();
+    require!(
+        amount <= withdrawable(&endowment_info)?,
+        HelixError::InsufficientEndowment
+    );
+
+    // Attach the memo, if any, to the transaction
+    process_memo(&ctx.accounts.memo_program, memo.clone())?;
+
+    **endowment_info.try_borrow_mut_lamports()? -= amount;
+    let owner_info = ctx.accounts.owner.to_account_info();
+    **owner_info.try_borrow_mut_lamports()? = owner_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    let endowment = &mut ctx.accounts.endowment;
+    endowment.total_withdrawn = endowment
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, EndowmentWithdrawn {
+        version: EVENT_VERSION,
+        endowment: endowment_key,
+        file: file_key,
+        owner,
+        amount,
+        total_withdrawn: endowment.total_withdrawn,
+        memo,
+        closed: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Endowment withdrawal";
+        "{} lamports withdrawn from the endowment of {} by {}",
+        amount,
+        file_key,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for closing a file's endowment
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CloseEndowment<'info> {
+    /// The endowed file record (may be deleted)
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The endowment to close
+    #[account(
+        mut,
+        seeds = [ENDOWMENT_SEED, file_record.key().as_ref()],
+        bump = endowment.bump,
+        close = owner
+    )]
+    pub endowment: Account<'info, Endowment>,
+
+    /// The file owner (receives the balance and the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the close_endowment instruction
+/// 
+/// Withdraws the remaining balance and the rent reserve to the owner and
+/// closes the endowment, which a file must not have when it is purged. A
+/// later contribution opens a new endowment with fresh totals.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn close_endowment_handler(ctx: Context<CloseEndowment>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let endowment_key = ctx.accounts.endowment.key();
+    let amount = withdrawable(&ctx.accounts.endowment.to_account_info())?;
+    let file_record = &mut ctx.accounts.file_record;
+    let endowment = & ctx.accounts.endowment;
+    let clock = Clock::get()?;
+
+    file_record.set_endowed(false);
+    let total_withdrawn = endowment.total_withdrawn.saturating_add(amount);
+
+    emit_event!(ctx, EndowmentWithdrawn {
+        version: EVENT_VERSION,
+        endowment: endowment_key,
+        file: file_key,
+        owner,
+        amount,
+        total_withdrawn,
+        memo: None,
+        closed: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Endowment closed";
+        "Endowment of {} closed by {}",
+        file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Accounts for reading a file's endowment (read-only)
+#[derive(Accounts)]
+pub struct GetEndowment<'info> {
+    /// The endowment to read
+    #[account(
+        seeds = [ENDOWMENT_SEED, endowment.file.as_ref()],
+        bump = endowment.bump
+    )]
+    pub endowment: Account<'info, Endowment>,
+}
+
+/// Return a file's endowment totals and withdrawable balance
+pub fn get_endowment_handler(ctx: Context<GetEndowment>) -> Result<EndowmentView> {
+    let endowment = &ctx.accounts.endowment;
+
+    Ok(EndowmentView {
+        version: VIEW_VERSION,
+        file: endowment.file,
+        balance: withdrawable(&endowment.to_account_info())?,
+        total_raised: endowment.total_raised,
+        total_withdrawn: endowment.total_withdrawn,
+    })
+}
+
+/// Lamports held by an endowment above its rent-exempt reserve
+fn withdrawable(endowment: &AccountInfo) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(endowment.data_len());
+    Ok(endowment.lamports().saturating_sub(reserve))
+}
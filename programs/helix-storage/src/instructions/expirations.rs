@@ -0,0 +1,241 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::instructions::create_share::{release_share_counters, revoke_and_clear_key};
+use crate::state::{
+    FileRecord, ShareExpired, ShareExpiringSoon, ShareLink, ShareStatus, StorageRegistry, Treasury,
+    EVENT_VERSION, MAX_EXPIRATIONS_PER_CALL, REGISTRY_SEED, TREASURY_SEED,
+};
+
+/// Accounts required for processing expired share links.
+/// 
+/// Share links and their file records are passed as `(share_link, file_record)`
+/// pairs in `remaining_accounts`, both writable.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ProcessExpirations<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The treasury paying the bounty (optional; no bounty without it)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// The keeper submitting the transaction (receives the bounty)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+/// Handler for the process_expirations instruction
+/// 
+/// Marks every supplied share link that is past its expiration as revoked and
+/// updates the share counters, exactly like an owner revocation. Pairs that are
+/// not program accounts, not writable, mismatched, not yet expired or already
+/// processed are skipped without failing, so the call is idempotent and safe
+/// with an arbitrary account mix.
+/// 
+/// When the registry sets a keeper bounty and the treasury is supplied, the
+/// keeper is paid it for each processed share the treasury can cover. Shares
+/// that were already warned earn nothing here, so no share pays more than one
+/// bounty.
+/// 
+/// # Arguments
+/// * `ctx` - The context, with `(share_link, file_record)` pairs as remaining accounts
+/// 
+/// # Returns
+/// * `Result<u32>` - Number of share links processed
+pub fn process_expirations_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ProcessExpirations<'info>>,
+) -> Result<u32> {
+    let keeper = ctx.accounts.keeper.key();
+    let registry = &mut ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    let mut processed: u32 = 0;
+    let mut bounty: u64 = 0;
+
+    for pair in ctx.remaining_accounts.chunks_exact(2).take(MAX_EXPIRATIONS_PER_CALL) {
+        let (share_info, file_info) = (&pair[0], &pair[1]);
+        if !share_info.is_writable || !file_info.is_writable {
+            continue;
+        }
+
+        let Ok(mut share_link) = Account::<ShareLink>::try_from(share_info) else {
+            continue;
+        };
+        let Ok(mut file_record) = Account::<FileRecord>::try_from(file_info) else {
+            continue;
+        };
+
+        if share_link.file != file_record.key()
+            || share_link.status(clock.unix_timestamp, false) != ShareStatus::Expired
+        {
+            continue;
+        }
+
+        // Pay the bounty unless the share was warned, which may have earned one
+        if !share_link.is_expiry_warned() {
+            if let Some(treasury) = &ctx.accounts.treasury {
+                bounty = bounty.saturating_add(pay_bounty(
+                    &treasury.to_account_info(),
+                    &ctx.accounts.keeper.to_account_info(),
+                    registry.expiry_warning_bounty_lamports,
+                )?);
+            }
+        }
+
+        // Expire the share like an owner revocation
+        revoke_and_clear_key(&mut share_link)?;
+        release_share_counters(&mut share_link, &mut file_record, registry);
+
+        share_link.exit(&crate::ID)?;
+        file_record.exit(&crate::ID)?;
+
+        emit_event!(ctx, ShareExpired {
+            version: EVENT_VERSION,
+            share: share_link.key(),
+            file: file_record.key(),
+            keeper,
+            timestamp: clock.unix_timestamp,
+        });
+
+        processed = processed
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    msg!("Processed {} expired share links for a {} lamport bounty", processed, bounty);
+
+    Ok(processed)
+}
+
+/// Accounts required for warning a share's recipient of its upcoming expiry
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct NotifyExpiringShare<'info> {
+    /// The storage registry (for the warning window and bounty)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The share link to warn about
+    #[account(mut)]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The shared file record (for its deleted state)
+    #[account(address = share_link.file @ HelixError::ShareFileMismatch)]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The treasury paying the bounty (optional; no bounty without it)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// The keeper submitting the transaction (receives the bounty)
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+}
+
+/// Handler for the notify_expiring_share instruction
+/// 
+/// Emits `ShareExpiringSoon` for a usable share whose `expires_at` falls
+/// within the registry's warning window, so clients can tell the recipient
+/// before downloads start failing. Each share is warned once; shares that
+/// never expire, are outside the window, already warned or no longer usable
+/// fail before any state is touched.
+/// 
+/// When the registry sets a bounty and the treasury is supplied, the keeper
+/// is paid from the treasury's balance above its rent reserve; a treasury
+/// that cannot cover it pays nothing. Shares created inside the window earn
+/// no bounty, and the bounty is capped below a share link's rent, so
+/// warning one's own shares cannot be farmed.
+/// 
+/// # Arguments
+/// * `ctx` - The NotifyExpiringShare context
+/// 
+/// # Returns
+/// * `Result<u64>` - Bounty paid in lamports
+pub fn notify_expiring_share_handler(ctx: Context<NotifyExpiringShare>) -> Result<u64> {
+    let share_key = ctx.accounts.share_link.key();
+    let keeper = ctx.accounts.keeper.key();
+    let registry = &ctx.accounts.registry;
+    let file_record = &ctx.accounts.file_record;
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate the share is usable, expiring within the window and unwarned
+    let window = registry.expiry_warning_secs;
+    require!(window > 0, HelixError::ExpiryWarningsDisabled);
+    share_link
+        .status(clock.unix_timestamp, file_record.is_deleted())
+        .require_active()?;
+    let expires_at = share_link.expires_at.ok_or(HelixError::ShareNeverExpires)?;
+    require!(
+        expires_at.saturating_sub(clock.unix_timestamp) <= window,
+        HelixError::ShareNotExpiringSoon
+    );
+    require!(!share_link.is_expiry_warned(), HelixError::ShareAlreadyWarned);
+
+    share_link.set_expiry_warned();
+
+    // Pay the bounty for shares that were created before the window opened
+    let mut bounty = 0;
+    if let Some(treasury) = &ctx.accounts.treasury {
+        if expires_at.saturating_sub(share_link.created_at) > window {
+            bounty = pay_bounty(
+                &treasury.to_account_info(),
+                &ctx.accounts.keeper.to_account_info(),
+                registry.expiry_warning_bounty_lamports,
+            )?;
+        }
+    }
+
+    emit_event!(ctx, ShareExpiringSoon {
+        version: EVENT_VERSION,
+        share: share_key,
+        file: share_link.file,
+        recipient: share_link.recipient,
+        expires_at,
+        keeper,
+        bounty_lamports: bounty,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Share {} expires at {}", share_key, expires_at);
+
+    Ok(bounty)
+}
+
+/// Pay `offered` lamports from the treasury to the keeper if the treasury's
+/// balance above its rent reserve covers it, returning the amount paid.
+fn pay_bounty(treasury_info: &AccountInfo, keeper_info: &AccountInfo, offered: u64) -> Result<u64> {
+    let reserve = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(reserve);
+    if offered == 0 || offered > available {
+        return Ok(0);
+    }
+
+    **treasury_info.try_borrow_mut_lamports()? -= offered;
+    **keeper_info.try_borrow_mut_lamports()? = keeper_info
+        .lamports()
+        .checked_add(offered)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    Ok(offered)
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::error::HelixError;
+use crate::state::{
+    FileRecord, ShareLink, SnapshotEntry, SnapshotRecord, EVENT_VERSION,
+    MAX_SNAPSHOT_ENTRIES_PER_CALL,
+};
+
+/// Accounts required for exporting part of a wallet's catalog.
+/// 
+/// The file records and share links to export are passed in
+/// `remaining_accounts`, read-only, in any mix.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ExportSnapshot<'info> {
+    /// The wallet whose records are exported
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the export_snapshot instruction
+/// 
+/// Emits a `SnapshotEntry` for each of up to `MAX_SNAPSHOT_ENTRIES_PER_CALL`
+/// file records and share links owned by the caller, numbered from
+/// `start_seq`. Replaying the events of every call with the same
+/// `snapshot_id` rebuilds the catalog without an account scan. No account
+/// is modified; any account that is not the caller's record or share fails
+/// the whole call, so a snapshot never silently skips an entry.
+/// 
+/// # Arguments
+/// * `ctx` - The context, with the records to export as remaining accounts
+/// * `snapshot_id` - Client-chosen ID tying the calls of one snapshot together
+/// * `start_seq` - Sequence number of the first entry in this call
+/// 
+/// # Returns
+/// * `Result<u64>` - Sequence number to start the next call from
+pub fn export_snapshot_handler(
+    ctx: Context<ExportSnapshot>,
+    snapshot_id: u64,
+    start_seq: u64,
+) -> Result<u64> {
+    let owner = ctx.accounts.owner.key();
+    let clock = Clock::get()?;
+
+    require!(
+        ctx.remaining_accounts.len() <= MAX_SNAPSHOT_ENTRIES_PER_CALL,
+        HelixError::TooManyBatchEntries
+    );
+
+    let mut seq = start_seq;
+
+    for info in ctx.remaining_accounts {
+        let record = snapshot_record(info, &owner)?;
+
+        emit_event!(ctx, SnapshotEntry {
+            version: EVENT_VERSION,
+            owner,
+            snapshot_id,
+            seq,
+            account: info.key(),
+            record,
+            timestamp: clock.unix_timestamp,
+        });
+
+        seq = seq.checked_add(1).ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    msg!("Exported {} snapshot entries", seq - start_seq);
+
+    Ok(seq)
+}
+
+/// Read the snapshot fields of a file record or share link owned by `owner`
+fn snapshot_record(info: &AccountInfo, owner: &Pubkey) -> Result<SnapshotRecord> {
+    require!(info.owner == &crate::ID, HelixError::UnauthorizedOwner);
+
+    let data = info.try_borrow_data()?;
+    if data.starts_with(&FileRecord::DISCRIMINATOR) {
+        let file_record = FileRecord::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(file_record.owner, *owner, HelixError::UnauthorizedOwner);
+
+        let status = file_record.file_status();
+
+        Ok(SnapshotRecord::File {
+            transaction_id: file_record.transaction_id,
+            encrypted_name: file_record.encrypted_name,
+            mime_type: file_record.mime_type,
+            mime_code: file_record.mime_code,
+            size: file_record.size,
+            flags: file_record.flags,
+            status,
+            created_at: file_record.created_at,
+            updated_at: file_record.updated_at,
+        })
+    } else {
+        let share_link = ShareLink::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(share_link.owner, *owner, HelixError::UnauthorizedOwner);
+
+        Ok(SnapshotRecord::Share {
+            file: share_link.file,
+            recipient: share_link.recipient,
+            recipient_commitment: share_link.recipient_commitment,
+            claim_hash: share_link.claim_hash,
+            encrypted_key: share_link.encrypted_key,
+            expires_at: share_link.expires_at,
+            max_downloads: share_link.max_downloads,
+            download_count: share_link.download_count,
+            flags: share_link.flags,
+            created_at: share_link.created_at,
+        })
+    }
+}
@@ -0,0 +1,285 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::instructions::treasury::charge_fee;
+use crate::state::{
+    FileRecord, GalleryCategory, GalleryEntry, GalleryEntryAdded, GalleryEntryRemoved,
+    RegistryExt, StorageRegistry, Treasury, UserProfile, EVENT_VERSION, FILE_SEED, GALLERY_SEED,
+    REGISTRY_EXT_SEED, REGISTRY_SEED, TREASURY_SEED, USER_PROFILE_SEED,
+};
+
+/// Accounts required for listing a file in the gallery
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(category: GalleryCategory)]
+pub struct AddGalleryEntry<'info> {
+    /// The storage registry (for the listing fee and fee totals)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The registry extension holding the per-category sequence counters
+    #[account(
+        mut,
+        seeds = [REGISTRY_EXT_SEED],
+        bump
+    )]
+    pub registry_ext: AccountLoader<'info, RegistryExt>,
+
+    /// The file to list
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The gallery entry to create at the category's next sequence number
+    #[account(
+        init,
+        payer = owner,
+        space = GalleryEntry::LEN,
+        seeds = [
+            GALLERY_SEED,
+            &[category as u8],
+            &registry_ext.load()?.category_counters[category.index()].to_le_bytes()
+        ],
+        bump
+    )]
+    pub gallery_entry: Account<'info, GalleryEntry>,
+
+    /// The treasury receiving the listing fee (required only when a fee is set)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// The file owner (pays the fee and rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation and the fee transfer
+    pub system_program: Program<'info, System>,
+
+    /// The owner's profile (created on first use), recording the fee paid.
+    /// Required only when a fee is set.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub owner_profile: Option<Account<'info, UserProfile>>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the add_gallery_entry instruction
+/// 
+/// Lists one of the owner's live, unencrypted files in a gallery category
+/// at the category's next sequence number, paying the registry's gallery
+/// fee to the treasury.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `category` - The category to list the file in
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn add_gallery_entry_handler(
+    ctx: Context<AddGalleryEntry>,
+    category: GalleryCategory,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let entry_key = ctx.accounts.gallery_entry.key();
+    let fee_lamports = ctx.accounts.registry.gallery_fee_lamports;
+    let file_record = &ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate the file is live and public
+    require!(!file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+    require!(!file_record.is_encrypted(), HelixError::FileNotPublic);
+
+    // Pay the listing fee
+    charge_fee(
+        fee_lamports,
+        &mut ctx.accounts.registry,
+        &ctx.accounts.owner,
+        ctx.accounts.owner_profile.as_mut(),
+        ctx.bumps.owner_profile,
+        ctx.accounts.treasury.as_ref(),
+        &ctx.accounts.system_program,
+    )?;
+
+    // Take the category's next sequence number
+    let seq = {
+        let mut registry_ext = ctx.accounts.registry_ext.load_mut()?;
+        let counter = &mut registry_ext.category_counters[category.index()];
+        let seq = *counter;
+        *counter = seq.checked_add(1).ok_or(HelixError::ArithmeticOverflow)?;
+        seq
+    };
+
+    // Initialize the entry
+    let gallery_entry = &mut ctx.accounts.gallery_entry;
+    gallery_entry.category = category;
+    gallery_entry.seq = seq;
+    gallery_entry.file = file_key;
+    gallery_entry.owner = owner;
+    gallery_entry.created_at = clock.unix_timestamp;
+    gallery_entry.bump = ctx.bumps.gallery_entry;
+    gallery_entry._reserved = [0u8; 16];
+
+    emit_event!(ctx, GalleryEntryAdded {
+        version: EVENT_VERSION,
+        entry: entry_key,
+        file: file_key,
+        owner,
+        category,
+        seq,
+        fee_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Gallery entry added";
+        "File {} listed in {:?} at {} by {}",
+        file_key,
+        category,
+        seq,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for removing one's own gallery entry
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RemoveGalleryEntry<'info> {
+    /// The gallery entry to close
+    #[account(
+        mut,
+        seeds = [
+            GALLERY_SEED,
+            &[gallery_entry.category as u8],
+            &gallery_entry.seq.to_le_bytes()
+        ],
+        bump = gallery_entry.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        close = owner
+    )]
+    pub gallery_entry: Account<'info, GalleryEntry>,
+
+    /// The wallet that listed the file (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the remove_gallery_entry instruction
+/// 
+/// Closes the entry, returning its rent to the owner. The listing fee is not
+/// refunded and the sequence number is not reused.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn remove_gallery_entry_handler(ctx: Context<RemoveGalleryEntry>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let entry_key = ctx.accounts.gallery_entry.key();
+    let gallery_entry = &ctx.accounts.gallery_entry;
+    let clock = Clock::get()?;
+
+    emit_event!(ctx, GalleryEntryRemoved {
+        version: EVENT_VERSION,
+        entry: entry_key,
+        file: gallery_entry.file,
+        owner,
+        removed_by: owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Gallery entry removed"; "Gallery entry {} removed by {}", entry_key, owner);
+
+    Ok(())
+}
+
+/// Accounts required for the authority to take down a gallery entry
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ForceRemoveGalleryEntry<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The gallery entry to close
+    #[account(
+        mut,
+        seeds = [
+            GALLERY_SEED,
+            &[gallery_entry.category as u8],
+            &gallery_entry.seq.to_le_bytes()
+        ],
+        bump = gallery_entry.bump,
+        has_one = owner,
+        close = owner
+    )]
+    pub gallery_entry: Account<'info, GalleryEntry>,
+
+    /// The wallet that listed the file (receives the rent)
+    /// CHECK: Must match the owner recorded on the entry
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// The registry authority
+    pub authority: Signer<'info>,
+}
+
+/// Handler for the force_remove_gallery_entry instruction
+/// 
+/// Moderation takedown of a gallery entry by the registry authority. The
+/// rent returns to the wallet that listed the file.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn force_remove_gallery_entry_handler(ctx: Context<ForceRemoveGalleryEntry>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let entry_key = ctx.accounts.gallery_entry.key();
+    let gallery_entry = &ctx.accounts.gallery_entry;
+    let clock = Clock::get()?;
+
+    emit_event!(ctx, GalleryEntryRemoved {
+        version: EVENT_VERSION,
+        entry: entry_key,
+        file: gallery_entry.file,
+        owner: gallery_entry.owner,
+        removed_by: authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Gallery entry {} removed by the authority", entry_key);
+
+    Ok(())
+}
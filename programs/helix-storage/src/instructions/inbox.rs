@@ -0,0 +1,241 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    InboxEntry, InboxEntryClosed, NotificationKind, ShareLink, UserProfile, EVENT_VERSION,
+    INBOX_SEED, MAX_INBOX_ENTRIES_PER_PAGE, USER_PROFILE_SEED,
+};
+use crate::views::{InboxEntrySummary, InboxPage, VIEW_VERSION};
+
+/// Accounts required for closing an entry of the recipient's inbox
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CloseInboxEntry<'info> {
+    /// The inbox entry to close
+    #[account(
+        mut,
+        seeds = [INBOX_SEED, recipient.key().as_ref(), &inbox_entry.seq.to_le_bytes()],
+        bump = inbox_entry.bump,
+        has_one = recipient @ HelixError::UnauthorizedOwner,
+        has_one = payer,
+        close = payer
+    )]
+    pub inbox_entry: Account<'info, InboxEntry>,
+
+    /// The share the entry points to
+    /// CHECK: May already be closed; its state is checked in the handler
+    #[account(address = inbox_entry.share)]
+    pub share_link: UncheckedAccount<'info>,
+
+    /// The wallet that paid for the entry (receives the rent)
+    /// CHECK: Must match the payer recorded on the entry
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The recipient the share was addressed to
+    pub recipient: Signer<'info>,
+
+    /// The recipient's profile, to update its unread counter (optional)
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, recipient.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+}
+
+/// Handler for the close_inbox_entry instruction
+/// 
+/// Closes an inbox entry whose share has been revoked or closed, returning
+/// the rent to the wallet that paid for it.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn close_inbox_entry_handler(ctx: Context<CloseInboxEntry>) -> Result<()> {
+    let share_info = ctx.accounts.share_link.to_account_info();
+    let inbox_entry = &ctx.accounts.inbox_entry;
+    let clock = Clock::get()?;
+
+    // Validate the share is revoked or no longer a share account
+    if share_info.owner == &crate::ID {
+        let data = share_info.try_borrow_data()?;
+        if let Ok(share_link) = ShareLink::try_deserialize(&mut &data[..]) {
+            require!(share_link.is_revoked(), HelixError::ShareStillActive);
+        }
+    }
+
+    if let Some(user_profile) = ctx.accounts.user_profile.as_mut() {
+        user_profile.unread_notifications = user_profile.unread_notifications.saturating_sub(1);
+    }
+
+    emit_event!(ctx, InboxEntryClosed {
+        version: EVENT_VERSION,
+        recipient: inbox_entry.recipient,
+        share: inbox_entry.share,
+        seq: inbox_entry.seq,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Inbox entry closed";
+        "Inbox entry {} closed by {}",
+        inbox_entry.seq,
+        inbox_entry.recipient
+    );
+
+    Ok(())
+}
+
+/// Accounts required for acknowledging a notification
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AckNotification<'info> {
+    /// The notification to close
+    #[account(
+        mut,
+        seeds = [INBOX_SEED, recipient.key().as_ref(), &inbox_entry.seq.to_le_bytes()],
+        bump = inbox_entry.bump,
+        has_one = recipient @ HelixError::UnauthorizedOwner,
+        has_one = payer,
+        close = payer
+    )]
+    pub inbox_entry: Account<'info, InboxEntry>,
+
+    /// The recipient's profile holding the unread counter
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, recipient.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The wallet that paid for the notification (receives the rent)
+    /// CHECK: Must match the payer recorded on the entry
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+
+    /// The recipient of the notification
+    pub recipient: Signer<'info>,
+}
+
+/// Handler for the ack_notification instruction
+/// 
+/// Closes any of the recipient's notifications once read, returning the
+/// rent to the wallet that paid for it and decrementing the unread counter.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn ack_notification_handler(ctx: Context<AckNotification>) -> Result<()> {
+    let inbox_entry = &ctx.accounts.inbox_entry;
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    user_profile.unread_notifications = user_profile.unread_notifications.saturating_sub(1);
+
+    emit_event!(ctx, InboxEntryClosed {
+        version: EVENT_VERSION,
+        recipient: inbox_entry.recipient,
+        share: inbox_entry.share,
+        seq: inbox_entry.seq,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Notification acknowledged";
+        "Notification {} acknowledged by {}",
+        inbox_entry.seq,
+        inbox_entry.recipient
+    );
+
+    Ok(())
+}
+
+/// Write a notification at the recipient's next inbox sequence number
+pub(crate) fn push_notification(
+    profile: &mut UserProfile,
+    entry: &mut InboxEntry,
+    entry_bump: u8,
+    recipient: Pubkey,
+    share: Pubkey,
+    payer: Pubkey,
+    kind: NotificationKind,
+) -> Result<()> {
+    let seq = profile.inbox_count;
+    entry.recipient = recipient;
+    entry.seq = seq;
+    entry.share = share;
+    entry.payer = payer;
+    entry.bump = entry_bump;
+    entry.kind = kind;
+    entry._reserved = [0u8; 7];
+    profile.inbox_count = seq
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    profile.unread_notifications = profile.unread_notifications.saturating_add(1);
+
+    Ok(())
+}
+
+/// Accounts for listing a page of a wallet's inbox (read-only).
+/// 
+/// Inbox entries are passed in `remaining_accounts`, in sequence order from
+/// `start_seq`.
+#[derive(Accounts)]
+pub struct ListInbox<'info> {
+    /// The wallet's profile holding the inbox counter
+    #[account(
+        seeds = [USER_PROFILE_SEED, user_profile.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+/// Return a page of a wallet's incoming shares via return data
+/// 
+/// Reads up to `limit` (capped at `MAX_INBOX_ENTRIES_PER_PAGE`) sequence
+/// numbers from `start_seq`. A page starting past the end of the inbox is
+/// empty. Accounts that are no longer inbox entries (closed entries) are
+/// skipped; every open entry must be the one at the expected sequence number.
+pub fn list_inbox_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ListInbox<'info>>,
+    start_seq: u64,
+    limit: u8,
+) -> Result<InboxPage> {
+    let user_profile = &ctx.accounts.user_profile;
+
+    let available = user_profile.inbox_count.saturating_sub(start_seq);
+    let count = available.min(u64::from(limit.min(MAX_INBOX_ENTRIES_PER_PAGE))) as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    for (offset, entry_info) in ctx.remaining_accounts.iter().take(count).enumerate() {
+        let Ok(entry) = Account::<InboxEntry>::try_from(entry_info) else {
+            continue;
+        };
+
+        let seq = start_seq + offset as u64;
+        require!(
+            entry.recipient == user_profile.owner && entry.seq == seq,
+            HelixError::InvalidIndexEntry
+        );
+
+        entries.push(InboxEntrySummary {
+            seq,
+            share: entry.share,
+            kind: entry.kind,
+        });
+    }
+
+    Ok(InboxPage {
+        version: VIEW_VERSION,
+        start_seq,
+        inbox_count: user_profile.inbox_count,
+        entries,
+        unread_notifications: user_profile.unread_notifications,
+    })
+}
@@ -1,6 +1,14 @@
 use anchor_lang::prelude::*;
 
-use crate::state::{StorageRegistry, REGISTRY_SEED};
+use crate::error::HelixError;
+use crate::state::{
+    AuthorityTransferAccepted, AuthorityTransferCancelled, AuthorityTransferProposed, CpiPolicy,
+    DownloadThrottle, EpochStats, RegistryExt, ShareLink, StorageRegistry,
+    DEFAULT_MAX_ALIASES_PER_FILE, DEFAULT_MAX_MIRRORS_PER_FILE, DEFAULT_MAX_SESSION_LIFETIME,
+    DEFAULT_TRASH_RETENTION_SECS, ENCRYPTED_NAME_LEN_CEILING, EVENT_VERSION, MAX_CPI_ALLOWLIST,
+    MAX_ENCRYPTED_NAME_LEN, REGISTRY_EXT_SEED, REGISTRY_SEED,
+};
+use crate::views::{FeeQuote, RegistryStats, VIEW_VERSION};
 
 /// Accounts required for initializing the storage registry
 #[derive(Accounts)]
@@ -34,7 +42,7 @@ pub struct Initialize<'info> {
 /// 
 /// # Returns
 /// * `Result<()>` - Success or error
-pub fn handler(ctx: Context<Initialize>, base_fee_lamports: u64) -> Result<()> {
+pub fn initialize_handler(ctx: Context<Initialize>, base_fee_lamports: u64) -> Result<()> {
     let registry = &mut ctx.accounts.registry;
     let clock = Clock::get()?;
 
@@ -42,9 +50,34 @@ pub fn handler(ctx: Context<Initialize>, base_fee_lamports: u64) -> Result<()> {
     registry.base_fee_lamports = base_fee_lamports;
     registry.total_files = 0;
     registry.total_shares = 0;
+    registry.total_bytes = 0;
     registry.is_paused = false;
     registry.bump = ctx.bumps.registry;
-    registry._reserved = [0u8; 64];
+    registry.current_epoch = clock.epoch;
+    registry.current_epoch_stats = EpochStats::default();
+    registry.previous_epoch_stats = EpochStats::default();
+    registry.operators = Vec::new();
+    registry.cpi_policy = CpiPolicy::Open;
+    registry.cpi_allowlist = Vec::new();
+    registry.max_encrypted_name_len = MAX_ENCRYPTED_NAME_LEN as u16;
+    registry.free_below_bytes = 0;
+    registry.max_session_lifetime = DEFAULT_MAX_SESSION_LIFETIME;
+    registry.trash_retention_secs = DEFAULT_TRASH_RETENTION_SECS;
+    registry.max_aliases_per_file = DEFAULT_MAX_ALIASES_PER_FILE;
+    registry.short_code_fee_lamports = 0;
+    registry.total_bytes_served = 0;
+    registry.scrub_on_delete = false;
+    registry.archived_files = 0;
+    registry.gallery_fee_lamports = 0;
+    registry.likes_require_public = false;
+    registry.expiry_warning_secs = 0;
+    registry.expiry_warning_bounty_lamports = 0;
+    registry.max_mirrors_per_file = DEFAULT_MAX_MIRRORS_PER_FILE;
+    registry.download_throttle = DownloadThrottle::None;
+    registry.total_fees_collected = 0;
+    registry.pending_authority = None;
+    registry.max_shares_per_file = 0;
+    registry._reserved = [0u8; 2];
 
     msg!(
         "Helix Storage Registry initialized at {} by {}",
@@ -55,7 +88,99 @@ pub fn handler(ctx: Context<Initialize>, base_fee_lamports: u64) -> Result<()> {
     Ok(())
 }
 
+/// Accounts required for creating the zero-copy registry extension
+#[derive(Accounts)]
+pub struct InitializeRegistryExt<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The registry extension account to initialize (PDA)
+    #[account(
+        init,
+        payer = authority,
+        space = RegistryExt::LEN,
+        seeds = [REGISTRY_EXT_SEED],
+        bump
+    )]
+    pub registry_ext: AccountLoader<'info, RegistryExt>,
+
+    /// The registry authority (payer)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the initialize_registry_ext instruction
+/// 
+/// Creates the zero-copy registry extension. Can only be called once,
+/// by the registry authority.
+pub fn initialize_ext_handler(ctx: Context<InitializeRegistryExt>) -> Result<()> {
+    let mut registry_ext = ctx.accounts.registry_ext.load_init()?;
+
+    registry_ext.registry = ctx.accounts.registry.key();
+    registry_ext.version = RegistryExt::VERSION;
+    registry_ext.bump = ctx.bumps.registry_ext;
+
+    msg!(
+        "Registry extension initialized for {}",
+        registry_ext.registry
+    );
+
+    Ok(())
+}
+
+/// Accounts required for updating registry extension configuration
+#[derive(Accounts)]
+pub struct UpdateRegistryExt<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The registry extension account
+    #[account(
+        mut,
+        seeds = [REGISTRY_EXT_SEED],
+        bump
+    )]
+    pub registry_ext: AccountLoader<'info, RegistryExt>,
+
+    /// The authority updating the extension
+    pub authority: Signer<'info>,
+}
+
+/// Configure the merkle tree and collection used for registration receipts.
+/// Passing the default pubkey as the tree disables receipts.
+pub fn set_receipt_config_handler(
+    ctx: Context<UpdateRegistryExt>,
+    receipt_tree: Pubkey,
+    receipt_collection: Pubkey,
+) -> Result<()> {
+    let mut registry_ext = ctx.accounts.registry_ext.load_mut()?;
+    registry_ext.receipt_tree = receipt_tree;
+    registry_ext.receipt_collection = receipt_collection;
+
+    msg!(
+        "Receipt config updated: tree {} collection {}",
+        receipt_tree,
+        receipt_collection
+    );
+
+    Ok(())
+}
+
 /// Accounts required for updating registry configuration
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct UpdateRegistry<'info> {
     /// The storage registry account
@@ -63,7 +188,7 @@ pub struct UpdateRegistry<'info> {
         mut,
         seeds = [REGISTRY_SEED],
         bump = registry.bump,
-        has_one = authority
+        has_one = authority @ HelixError::UnauthorizedAuthority
     )]
     pub registry: Account<'info, StorageRegistry>,
 
@@ -86,6 +211,304 @@ pub fn update_fee_handler(ctx: Context<UpdateRegistry>, new_fee: u64) -> Result<
     Ok(())
 }
 
+/// Update the size threshold below which registration is free
+pub fn set_free_below_bytes_handler(ctx: Context<UpdateRegistry>, free_below_bytes: u64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let old_threshold = registry.free_below_bytes;
+    registry.free_below_bytes = free_below_bytes;
+
+    msg!(
+        "Free registration threshold updated from {} to {} bytes",
+        old_threshold,
+        free_below_bytes
+    );
+
+    Ok(())
+}
+
+/// Set which programs may register files and create shares through CPI.
+/// 
+/// The allowlist is stored whatever the policy, so switching back to
+/// `Allowlist` later restores it.
+pub fn set_cpi_policy_handler(
+    ctx: Context<UpdateRegistry>,
+    policy: CpiPolicy,
+    allowlist: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        allowlist.len() <= MAX_CPI_ALLOWLIST
+            && allowlist
+                .iter()
+                .enumerate()
+                .all(|(i, program)| !allowlist[..i].contains(program)),
+        HelixError::InvalidCpiAllowlist
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    let old_policy = registry.cpi_policy;
+    registry.cpi_policy = policy;
+    registry.cpi_allowlist = allowlist;
+
+    msg!(
+        "CPI policy updated from {:?} to {:?} with {} allowed programs",
+        old_policy,
+        policy,
+        registry.cpi_allowlist.len()
+    );
+
+    Ok(())
+}
+
+/// Update the maximum encrypted filename length
+/// 
+/// Lowering the limit only affects future writes; names already stored
+/// under a higher limit remain valid.
+pub fn set_max_encrypted_name_len_handler(
+    ctx: Context<UpdateRegistry>,
+    max_len: u16,
+) -> Result<()> {
+    require!(
+        max_len > 0 && max_len as usize <= ENCRYPTED_NAME_LEN_CEILING,
+        HelixError::InvalidEncryptedNameLimit
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    let old_len = registry.encrypted_name_limit();
+    registry.max_encrypted_name_len = max_len;
+
+    msg!(
+        "Encrypted name limit updated from {} to {} bytes",
+        old_len,
+        max_len
+    );
+
+    Ok(())
+}
+
+/// Update the maximum lifetime of newly created session keys.
+/// 
+/// Existing sessions keep their expiry.
+pub fn set_max_session_lifetime_handler(
+    ctx: Context<UpdateRegistry>,
+    max_lifetime: i64,
+) -> Result<()> {
+    require!(max_lifetime > 0, HelixError::InvalidSessionLifetime);
+
+    let registry = &mut ctx.accounts.registry;
+    let old_lifetime = registry.session_lifetime_limit();
+    registry.max_session_lifetime = max_lifetime;
+
+    msg!(
+        "Session lifetime limit updated from {} to {} seconds",
+        old_lifetime,
+        max_lifetime
+    );
+
+    Ok(())
+}
+
+/// Update the window during which deleted files can be restored.
+/// 
+/// Applies to files already in the trash as well as future deletions.
+pub fn set_trash_retention_handler(
+    ctx: Context<UpdateRegistry>,
+    retention_secs: i64,
+) -> Result<()> {
+    require!(retention_secs > 0, HelixError::InvalidTrashRetention);
+
+    let registry = &mut ctx.accounts.registry;
+    let old_retention = registry.trash_retention();
+    registry.trash_retention_secs = retention_secs;
+
+    msg!(
+        "Trash retention updated from {} to {} seconds",
+        old_retention,
+        retention_secs
+    );
+
+    Ok(())
+}
+
+/// Update the maximum number of aliases per file.
+/// 
+/// Files already over a lowered limit keep their aliases but cannot add more.
+pub fn set_max_aliases_per_file_handler(
+    ctx: Context<UpdateRegistry>,
+    max_aliases: u16,
+) -> Result<()> {
+    require!(max_aliases > 0, HelixError::InvalidAliasLimit);
+
+    let registry = &mut ctx.accounts.registry;
+    let old_limit = registry.alias_limit();
+    registry.max_aliases_per_file = max_aliases;
+
+    msg!(
+        "Alias limit updated from {} to {} per file",
+        old_limit,
+        max_aliases
+    );
+
+    Ok(())
+}
+
+/// Update the maximum number of mirrors per file.
+/// 
+/// Files already over a lowered limit keep their mirrors but cannot add more.
+pub fn set_max_mirrors_per_file_handler(
+    ctx: Context<UpdateRegistry>,
+    max_mirrors: u16,
+) -> Result<()> {
+    require!(max_mirrors > 0, HelixError::InvalidMirrorLimit);
+
+    let registry = &mut ctx.accounts.registry;
+    let old_limit = registry.mirror_limit();
+    registry.max_mirrors_per_file = max_mirrors;
+
+    msg!(
+        "Mirror limit updated from {} to {} per file",
+        old_limit,
+        max_mirrors
+    );
+
+    Ok(())
+}
+
+/// Update the maximum number of live share links per file (0 = unlimited).
+/// 
+/// Files already over a lowered limit keep their shares but cannot add more.
+pub fn set_max_shares_per_file_handler(
+    ctx: Context<UpdateRegistry>,
+    max_shares: u32,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let old_limit = registry.max_shares_per_file;
+    registry.max_shares_per_file = max_shares;
+
+    msg!(
+        "Share limit updated from {} to {} per file (0 = unlimited)",
+        old_limit,
+        max_shares
+    );
+
+    Ok(())
+}
+
+/// Set the throttle on counted downloads of public shares.
+/// 
+/// A cooldown rejects repeat downloads by the same wallet within the
+/// window; a fee charges every counted download. Recipient-locked shares
+/// and the file owner's downloads are never throttled.
+pub fn set_download_throttle_handler(
+    ctx: Context<UpdateRegistry>,
+    throttle: DownloadThrottle,
+) -> Result<()> {
+    require!(throttle.is_valid(), HelixError::InvalidDownloadThrottle);
+
+    let registry = &mut ctx.accounts.registry;
+    let old_throttle = registry.download_throttle;
+    registry.download_throttle = throttle;
+
+    msg!(
+        "Download throttle updated from {:?} to {:?}",
+        old_throttle,
+        throttle
+    );
+
+    Ok(())
+}
+
+/// Update the fee for claiming a short code
+pub fn set_short_code_fee_handler(ctx: Context<UpdateRegistry>, fee_lamports: u64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let old_fee = registry.short_code_fee_lamports;
+    registry.short_code_fee_lamports = fee_lamports;
+
+    msg!(
+        "Short code fee updated from {} to {} lamports",
+        old_fee,
+        fee_lamports
+    );
+
+    Ok(())
+}
+
+/// Update the fee for listing a file in the gallery
+pub fn set_gallery_fee_handler(ctx: Context<UpdateRegistry>, fee_lamports: u64) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let old_fee = registry.gallery_fee_lamports;
+    registry.gallery_fee_lamports = fee_lamports;
+
+    msg!(
+        "Gallery fee updated from {} to {} lamports",
+        old_fee,
+        fee_lamports
+    );
+
+    Ok(())
+}
+
+/// Set whether deleting a file wipes its metadata and share keys
+pub fn set_scrub_on_delete_handler(ctx: Context<UpdateRegistry>, enabled: bool) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let old_value = registry.scrub_on_delete;
+    registry.scrub_on_delete = enabled;
+
+    msg!(
+        "Scrub on delete updated from {} to {}",
+        old_value,
+        enabled
+    );
+
+    Ok(())
+}
+
+/// Set whether only live, unencrypted files can be liked
+pub fn set_likes_require_public_handler(
+    ctx: Context<UpdateRegistry>,
+    enabled: bool,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let old_value = registry.likes_require_public;
+    registry.likes_require_public = enabled;
+
+    msg!(
+        "Likes require public updated from {} to {}",
+        old_value,
+        enabled
+    );
+
+    Ok(())
+}
+
+/// Configure pre-expiry warnings: the window before expiry in which a share
+/// can be warned (0 = disabled) and the bounty paid for each warning or
+/// expiration. A share earns at most one bounty, and the bounty must stay
+/// below the rent of a share link, which cannot be reclaimed, so creating
+/// shares only to warn or expire them never pays.
+pub fn set_expiry_warning_handler(
+    ctx: Context<UpdateRegistry>,
+    window_secs: i64,
+    bounty_lamports: u64,
+) -> Result<()> {
+    require!(window_secs >= 0, HelixError::InvalidExpiryWarningWindow);
+    require!(
+        bounty_lamports < Rent::get()?.minimum_balance(ShareLink::LEN),
+        HelixError::ExpiryWarningBountyTooHigh
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    registry.expiry_warning_secs = window_secs;
+    registry.expiry_warning_bounty_lamports = bounty_lamports;
+
+    msg!(
+        "Expiry warning window set to {} seconds with a {} lamport bounty",
+        window_secs,
+        bounty_lamports
+    );
+
+    Ok(())
+}
+
 /// Pause or unpause the registry
 pub fn set_paused_handler(ctx: Context<UpdateRegistry>, paused: bool) -> Result<()> {
     let registry = &mut ctx.accounts.registry;
@@ -99,20 +522,237 @@ pub fn set_paused_handler(ctx: Context<UpdateRegistry>, paused: bool) -> Result<
     Ok(())
 }
 
-/// Transfer authority to a new wallet
-pub fn transfer_authority_handler(
+/// Propose a new authority. Nothing changes until that wallet calls
+/// `accept_authority`, so a mistyped key only costs a cancellation.
+/// Proposing again replaces the pending authority.
+pub fn propose_authority_handler(
     ctx: Context<UpdateRegistry>,
     new_authority: Pubkey,
 ) -> Result<()> {
     let registry = &mut ctx.accounts.registry;
-    let old_authority = registry.authority;
+    let clock = Clock::get()?;
+
+    require!(
+        new_authority != registry.authority && new_authority != Pubkey::default(),
+        HelixError::InvalidNewAuthority
+    );
+
+    registry.pending_authority = Some(new_authority);
+
+    emit_event!(ctx, AuthorityTransferProposed {
+        version: EVENT_VERSION,
+        authority: registry.authority,
+        pending_authority: new_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Authority transfer from {} to {} proposed",
+        registry.authority,
+        new_authority
+    );
+
+    Ok(())
+}
+
+/// Withdraw the pending authority proposal
+pub fn cancel_authority_transfer_handler(ctx: Context<UpdateRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    let pending_authority = registry
+        .pending_authority
+        .take()
+        .ok_or(HelixError::NoPendingAuthority)?;
+
+    emit_event!(ctx, AuthorityTransferCancelled {
+        version: EVENT_VERSION,
+        authority: registry.authority,
+        pending_authority,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Authority transfer to {} cancelled", pending_authority);
+
+    Ok(())
+}
+
+/// Accounts required for accepting a proposed authority transfer
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// The storage registry account
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        constraint = registry.pending_authority == Some(new_authority.key())
+            @ HelixError::NoPendingAuthority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The proposed authority
+    pub new_authority: Signer<'info>,
+}
+
+/// Make the signing wallet the authority if it is the pending authority
+pub fn accept_authority_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let new_authority = ctx.accounts.new_authority.key();
+    let registry = &mut ctx.accounts.registry;
+    let clock = Clock::get()?;
+
+    let previous_authority = registry.authority;
     registry.authority = new_authority;
+    registry.pending_authority = None;
+
+    emit_event!(ctx, AuthorityTransferAccepted {
+        version: EVENT_VERSION,
+        previous_authority,
+        new_authority,
+        timestamp: clock.unix_timestamp,
+    });
 
     msg!(
         "Authority transferred from {} to {}",
-        old_authority,
+        previous_authority,
         new_authority
     );
 
     Ok(())
 }
+
+/// Accounts for querying the registry (read-only)
+#[derive(Accounts)]
+pub struct ReadRegistry<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+}
+
+/// Return registry statistics, with epoch buckets normalized to the current epoch
+pub fn get_stats_handler(ctx: Context<ReadRegistry>) -> Result<RegistryStats> {
+    let mut registry = (*ctx.accounts.registry).clone();
+    let clock = Clock::get()?;
+
+    // Apply any pending rollover to the copy so stale buckets are not reported
+    registry.roll_epoch(clock.epoch);
+
+    Ok(RegistryStats {
+        version: VIEW_VERSION,
+        total_files: registry.total_files,
+        total_shares: registry.total_shares,
+        total_bytes: registry.total_bytes,
+        current_epoch: registry.current_epoch,
+        current_epoch_stats: registry.current_epoch_stats,
+        previous_epoch_stats: registry.previous_epoch_stats,
+        archived_files: registry.archived_files,
+    })
+}
+
+/// Quote the registration fee for a file of the given size
+pub fn estimate_fee_handler(ctx: Context<ReadRegistry>, size: u64) -> Result<FeeQuote> {
+    let registry = &ctx.accounts.registry;
+    let fee_lamports = registry.registration_fee(size);
+
+    Ok(FeeQuote {
+        version: VIEW_VERSION,
+        size,
+        base_fee_lamports: registry.base_fee_lamports,
+        fee_lamports,
+        waived: fee_lamports == 0 && registry.base_fee_lamports > 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    use crate::test_utils::{sample_registry, TestAccount};
+
+    /// Run the account constraints of `$accounts` over the registry with
+    /// `$signer` as its signer, followed by the event-CPI accounts
+    macro_rules! run_constraints {
+        ($accounts:ident, $bumps:ident, $registry:expr, $signer:expr) => {{
+            let (registry_key, _) = crate::client::find_registry_address();
+            let (event_authority, _) =
+                Pubkey::find_program_address(&[b"__event_authority"], &crate::ID);
+            let mut registry = TestAccount::new(registry_key, $registry, StorageRegistry::LEN);
+            let empty = |key, owner| TestAccount { key, owner, lamports: 0, data: vec![] };
+            let system = anchor_lang::system_program::ID;
+            let loader = anchor_lang::solana_program::bpf_loader_upgradeable::ID;
+            let mut signer = empty($signer, system);
+            let mut event_authority = empty(event_authority, system);
+            let mut program = empty(crate::ID, loader);
+
+            let mut signer_info = signer.info();
+            signer_info.is_signer = true;
+            let mut program_info = program.info();
+            program_info.executable = true;
+            let infos = [registry.info(), signer_info, event_authority.info(), program_info];
+            $accounts::try_accounts(
+                &crate::ID,
+                &mut &infos[..],
+                &[],
+                &mut $bumps::default(),
+                &mut BTreeSet::new(),
+            )
+            .map(|_| ())
+        }};
+    }
+
+    #[test]
+    fn only_the_authority_can_update_the_registry() {
+        let authority = Pubkey::new_unique();
+        let (_, registry) = sample_registry(authority, 0);
+
+        assert_eq!(
+            run_constraints!(UpdateRegistry, UpdateRegistryBumps, &registry, authority),
+            Ok(())
+        );
+        for stranger in [Pubkey::new_unique(), Pubkey::default()] {
+            assert_eq!(
+                run_constraints!(UpdateRegistry, UpdateRegistryBumps, &registry, stranger),
+                Err(HelixError::UnauthorizedAuthority.into())
+            );
+        }
+    }
+
+    #[test]
+    fn only_the_pending_authority_can_accept() {
+        let authority = Pubkey::new_unique();
+        let pending_authority = Pubkey::new_unique();
+        let (_, mut registry) = sample_registry(authority, 0);
+        let no_pending = Err(HelixError::NoPendingAuthority.into());
+
+        // Without a proposal nobody can accept, not even the authority
+        for signer in [authority, pending_authority] {
+            assert_eq!(
+                run_constraints!(AcceptAuthority, AcceptAuthorityBumps, &registry, signer),
+                no_pending
+            );
+        }
+
+        registry.pending_authority = Some(pending_authority);
+        assert_eq!(
+            run_constraints!(AcceptAuthority, AcceptAuthorityBumps, &registry, pending_authority),
+            Ok(())
+        );
+        for signer in [authority, Pubkey::new_unique()] {
+            assert_eq!(
+                run_constraints!(AcceptAuthority, AcceptAuthorityBumps, &registry, signer),
+                no_pending
+            );
+        }
+
+        // A cancelled or accepted proposal cannot be accepted again
+        registry.pending_authority = None;
+        assert_eq!(
+            run_constraints!(AcceptAuthority, AcceptAuthorityBumps, &registry, pending_authority),
+            no_pending
+        );
+    }
+}
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    FileRecord, FileStatus, FileStatusChanged, OwnerFileEntry, StorageRegistry, UserProfile,
+    EVENT_VERSION, FILE_SEED, REGISTRY_SEED, USER_PROFILE_SEED,
+};
+
+/// Accounts required for archiving or unarchiving a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ArchiveFile<'info> {
+    /// The storage registry (for the active and archived file counters)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to move
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The owner's profile holding the archived file counter
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The file's entry in its owner's index (required only for indexed files)
+    #[account(
+        mut,
+        constraint = index_entry.file == file_record.key() @ HelixError::InvalidIndexEntry
+    )]
+    pub index_entry: Option<Account<'info, OwnerFileEntry>>,
+
+    /// The file owner (pays for the profile if it does not exist yet)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for profile creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the archive_file instruction
+/// 
+/// Moves an active file to `Archived` and from the registry's active file
+/// count to the archived counters. Its content, shares, downloads and
+/// metadata updates stay fully usable. Indexed files also flag their index
+/// entry so listings can filter them out.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn archive_file_handler(ctx: Context<ArchiveFile>) -> Result<()> {
+    change_status(ctx, FileStatus::Archived)
+}
+
+/// Handler for the unarchive_file instruction
+/// 
+/// Moves an archived file back to `Active`, reversing `archive_file`.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn unarchive_file_handler(ctx: Context<ArchiveFile>) -> Result<()> {
+    let from = ctx.accounts.file_record.file_status();
+    require!(from == FileStatus::Archived, HelixError::InvalidStatusTransition);
+
+    change_status(ctx, FileStatus::Active)
+}
+
+/// Apply an archive transition, move the file between the counters and
+/// emit `FileStatusChanged`
+fn change_status(ctx: Context<ArchiveFile>, to: FileStatus) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    let from = file_record.file_status();
+    file_record.set_status(to)?;
+    file_record.updated_at = clock.unix_timestamp;
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = owner;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    // Move the file between the active and archived counters
+    let archived = to == FileStatus::Archived;
+    if archived {
+        registry.total_files = registry.total_files.saturating_sub(1);
+        registry.archived_files = registry
+            .archived_files
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        user_profile.archived_files = user_profile.archived_files.saturating_add(1);
+    } else {
+        registry.archived_files = registry.archived_files.saturating_sub(1);
+        registry.total_files = registry
+            .total_files
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        user_profile.archived_files = user_profile.archived_files.saturating_sub(1);
+    }
+
+    // Keep the index entry's filterable flag in sync
+    match ctx.accounts.index_entry.as_mut() {
+        Some(index_entry) => index_entry.archived = archived,
+        None => require!(!file_record.is_indexed(), HelixError::IndexEntryMissing),
+    }
+
+    emit_event!(ctx, FileStatusChanged {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner,
+        from,
+        to,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File status changed";
+        "File status of {} changed from {:?} to {:?}",
+        file_record.transaction_id,
+        from,
+        to
+    );
+
+    Ok(())
+}
+
+/// Take a file being deleted out of the file counter it is held in. Archived
+/// files are counted separately, also on the owner's profile, which must be
+/// supplied for them.
+pub(crate) fn release_file_count(
+    registry: &mut StorageRegistry,
+    status: FileStatus,
+    user_profile: Option<&mut Account<UserProfile>>,
+) -> Result<()> {
+    if status == FileStatus::Archived {
+        let user_profile = user_profile.ok_or(HelixError::ProfileMissing)?;
+        registry.archived_files = registry.archived_files.saturating_sub(1);
+        user_profile.archived_files = user_profile.archived_files.saturating_sub(1);
+    } else {
+        registry.total_files = registry.total_files.saturating_sub(1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    FileLiked, FileRecord, FileUnliked, LikeReceipt, StorageRegistry, EVENT_VERSION, FILE_SEED,
+    LIKE_SEED, REGISTRY_SEED,
+};
+
+/// Accounts required for liking a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct LikeFile<'info> {
+    /// The storage registry (for the like policy)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to like
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The like receipt to create (PDA derived from file and wallet)
+    #[account(
+        init,
+        payer = wallet,
+        space = LikeReceipt::LEN,
+        seeds = [LIKE_SEED, file_record.key().as_ref(), wallet.key().as_ref()],
+        bump
+    )]
+    pub like_receipt: Account<'info, LikeReceipt>,
+
+    /// The wallet liking the file (pays for the receipt)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the like_file instruction
+/// 
+/// Records the wallet's like with a receipt, so each wallet counts once,
+/// and increments the file's like count. Under the registry's
+/// `likes_require_public` policy only live, unencrypted files can be liked.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn like_file_handler(ctx: Context<LikeFile>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let like_receipt = &mut ctx.accounts.like_receipt;
+    let clock = Clock::get()?;
+
+    // Validate the file is likeable under the registry policy
+    if ctx.accounts.registry.likes_require_public {
+        require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+        require!(!file_record.is_encrypted(), HelixError::FileNotPublic);
+    }
+
+    // Initialize receipt
+    like_receipt.file = file_key;
+    like_receipt.wallet = wallet;
+    like_receipt.created_at = clock.unix_timestamp;
+    like_receipt.bump = ctx.bumps.like_receipt;
+    like_receipt._reserved = [0u8; 8];
+
+    file_record.like_count = file_record.like_count.saturating_add(1);
+
+    emit_event!(ctx, FileLiked {
+        version: EVENT_VERSION,
+        file: file_key,
+        wallet,
+        like_count: file_record.like_count,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File liked";
+        "File {} liked by {}",
+        file_record.transaction_id,
+        wallet
+    );
+
+    Ok(())
+}
+
+/// Accounts required for withdrawing a like
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UnlikeFile<'info> {
+    /// The like receipt to close
+    #[account(
+        mut,
+        seeds = [LIKE_SEED, like_receipt.file.as_ref(), wallet.key().as_ref()],
+        bump = like_receipt.bump,
+        has_one = wallet @ HelixError::UnauthorizedOwner,
+        close = wallet
+    )]
+    pub like_receipt: Account<'info, LikeReceipt>,
+
+    /// The liked file record
+    /// CHECK: May have been purged; its counter is updated in the handler
+    #[account(mut, address = like_receipt.file)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The wallet that liked the file (receives the rent)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+}
+
+/// Handler for the unlike_file instruction
+/// 
+/// Closes the receipt, returning its rent to the wallet, and decrements the
+/// file's like count. Works whether or not the file still exists.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn unlike_file_handler(ctx: Context<UnlikeFile>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let file_info = ctx.accounts.file_record.to_account_info();
+    let clock = Clock::get()?;
+
+    // Release the file's counter unless the file has been purged
+    let mut like_count = None;
+    if file_info.owner == &crate::ID {
+        let mut data = file_info.try_borrow_mut_data()?;
+        if let Ok(mut file_record) = FileRecord::try_deserialize(&mut &data[..]) {
+            file_record.like_count = file_record.like_count.saturating_sub(1);
+            like_count = Some(file_record.like_count);
+            file_record.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit_event!(ctx, FileUnliked {
+        version: EVENT_VERSION,
+        file: file_info.key(),
+        wallet,
+        like_count,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("File unliked"; "File {} unliked by {}", file_info.key(), wallet);
+
+    Ok(())
+}
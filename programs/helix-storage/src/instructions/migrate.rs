@@ -0,0 +1,374 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+use crate::error::HelixError;
+use crate::offsets::{FILE_RECORD_OWNER, REGISTRY_AUTHORITY, SHARE_LINK_OWNER};
+use crate::state::{
+    AccountMigrated, FileRecord, ShareLink, StorageRegistry, EVENT_VERSION, REGISTRY_SEED,
+};
+
+// Accounts created before a struct gained fields hold the original layout:
+// the original fields at their original offsets, followed by zeroed reserved
+// bytes. Every field added since is appended after the original `bump` and
+// decodes from zero bytes as its default (0, `false`, `None`, the first enum
+// variant), so an old account only needs to grow to the current size to
+// read correctly. Until it does, it fails to deserialize wherever the
+// appended fields run past its end.
+
+/// Accounts required for migrating the registry to the current layout
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MigrateRegistry<'info> {
+    /// The registry to grow. Read as raw bytes because an outdated registry
+    /// does not deserialize.
+    /// CHECK: Program owner, discriminator and authority are checked in the handler
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump
+    )]
+    pub registry: UncheckedAccount<'info>,
+
+    /// The registry authority (pays for the added space)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program for the rent transfer
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the migrate_registry instruction
+/// 
+/// Grows a registry created under an older layout to `StorageRegistry::LEN`,
+/// zero-filling the added bytes so every newer setting reads as its default.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn migrate_registry_handler(ctx: Context<MigrateRegistry>) -> Result<()> {
+    let info = ctx.accounts.registry.to_account_info();
+    let authority = ctx.accounts.authority.key();
+
+    require_stored_key(&info, REGISTRY_AUTHORITY, &authority, HelixError::UnauthorizedAuthority)?;
+    let (old_len, new_len) = grow_account::<StorageRegistry>(
+        &info,
+        StorageRegistry::LEN,
+        &ctx.accounts.authority,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit_event!(ctx, AccountMigrated {
+        version: EVENT_VERSION,
+        account: info.key(),
+        payer: authority,
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Registry migrated from {} to {} bytes", old_len, new_len);
+
+    Ok(())
+}
+
+/// Accounts required for migrating a file record to the current layout
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MigrateFileRecord<'info> {
+    /// The file record to grow. Read as raw bytes because an outdated
+    /// record may not deserialize.
+    /// CHECK: Program owner, discriminator and owner are checked in the handler
+    #[account(mut)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The file owner (pays for the added space)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for the rent transfer
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the migrate_file_record instruction
+/// 
+/// Grows a file record created under an older layout to `FileRecord::LEN`.
+/// Only records that no longer deserialize need this: older records whose
+/// strings leave enough zeroed space already read correctly.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn migrate_file_record_handler(ctx: Context<MigrateFileRecord>) -> Result<()> {
+    let info = ctx.accounts.file_record.to_account_info();
+    let owner = ctx.accounts.owner.key();
+
+    require_stored_key(&info, FILE_RECORD_OWNER, &owner, HelixError::UnauthorizedOwner)?;
+    let (old_len, new_len) = grow_account::<FileRecord>(
+        &info,
+        FileRecord::LEN,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit_event!(ctx, AccountMigrated {
+        version: EVENT_VERSION,
+        account: info.key(),
+        payer: owner,
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    private_msg!(
+        "File record migrated";
+        "File record {} migrated from {} to {} bytes",
+        info.key(),
+        old_len,
+        new_len
+    );
+
+    Ok(())
+}
+
+/// Accounts required for migrating a share link to the current layout
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct MigrateShareLink<'info> {
+    /// The share link to grow. Read as raw bytes because an outdated link
+    /// may not deserialize.
+    /// CHECK: Program owner, discriminator and owner are checked in the handler
+    #[account(mut)]
+    pub share_link: UncheckedAccount<'info>,
+
+    /// The share owner (pays for the added space)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for the rent transfer
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the migrate_share_link instruction
+/// 
+/// Grows a share link created under an older layout to `ShareLink::LEN`.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn migrate_share_link_handler(ctx: Context<MigrateShareLink>) -> Result<()> {
+    let info = ctx.accounts.share_link.to_account_info();
+    let owner = ctx.accounts.owner.key();
+
+    require_stored_key(&info, SHARE_LINK_OWNER, &owner, HelixError::UnauthorizedOwner)?;
+    let (old_len, new_len) = grow_account::<ShareLink>(
+        &info,
+        ShareLink::LEN,
+        &ctx.accounts.owner,
+        &ctx.accounts.system_program,
+    )?;
+
+    emit_event!(ctx, AccountMigrated {
+        version: EVENT_VERSION,
+        account: info.key(),
+        payer: owner,
+        old_len: old_len as u32,
+        new_len: new_len as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    private_msg!(
+        "Share link migrated";
+        "Share link {} migrated from {} to {} bytes",
+        info.key(),
+        old_len,
+        new_len
+    );
+
+    Ok(())
+}
+
+/// Check that `info` is owned by the Helix program and holds `expected` at
+/// `offset`, the position of a pubkey field from the original layout
+fn require_stored_key(
+    info: &AccountInfo,
+    offset: usize,
+    expected: &Pubkey,
+    error: HelixError,
+) -> Result<()> {
+    if info.owner != &crate::ID {
+        return Err(error!(ErrorCode::AccountOwnedByWrongProgram)
+            .with_pubkeys((*info.owner, crate::ID)));
+    }
+
+    let data = info.try_borrow_data()?;
+    let stored = data
+        .get(offset..offset + 32)
+        .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+    if stored != expected.as_ref() {
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Grow an outdated account of type `T` to `new_len`, zero-filling the added
+/// bytes and charging `payer` the extra rent. Fails with
+/// `AccountAlreadyMigrated` when the account already deserializes as `T`.
+/// 
+/// Returns the old and new lengths.
+fn grow_account<'info, T: AccountDeserialize + Discriminator>(
+    info: &AccountInfo<'info>,
+    new_len: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<(usize, usize)> {
+    let old_len = info.data_len();
+    {
+        let data = info.try_borrow_data()?;
+        require!(
+            data.starts_with(&T::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(
+            T::try_deserialize(&mut &data[..]).is_err() && old_len < new_len,
+            HelixError::AccountAlreadyMigrated
+        );
+    }
+
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    info.realloc(new_len, true)?;
+
+    // The grown account must now decode under the current layout
+    T::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+    Ok((old_len, new_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DownloadThrottle, FileStatus};
+    use crate::test_utils::{NOW, SAMPLE_TX_ID};
+
+    /// A registry as the original layout stored it: authority, base fee,
+    /// file and share counts, paused flag, bump and 64 reserved bytes
+    fn original_registry(authority: &Pubkey) -> Vec<u8> {
+        let mut data = StorageRegistry::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data.extend_from_slice(&12u64.to_le_bytes());
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.push(1);
+        data.push(254);
+        data.extend_from_slice(&[0u8; 64]);
+        data
+    }
+
+    /// A file record as the original layout stored it, allocated at the
+    /// original fixed 543 bytes, with a name and MIME type that fill their
+    /// slots
+    fn original_file_record(owner: &Pubkey) -> Vec<u8> {
+        let mut data = FileRecord::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(owner.as_ref());
+        data.extend_from_slice(&43u32.to_le_bytes());
+        data.extend_from_slice(SAMPLE_TX_ID.as_bytes());
+        data.push(1);
+        data.extend_from_slice(&256u32.to_le_bytes());
+        data.extend_from_slice(&[b'n'; 256]);
+        data.extend_from_slice(&128u32.to_le_bytes());
+        data.extend_from_slice(&[b'm'; 128]);
+        data.extend_from_slice(&4096u64.to_le_bytes());
+        data.push(1); // is_encrypted
+        data.push(1); // is_deleted
+        data.extend_from_slice(&NOW.to_le_bytes());
+        data.extend_from_slice(&NOW.to_le_bytes());
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.push(253);
+        data.resize(543, 0);
+        data
+    }
+
+    #[test]
+    fn original_registry_needs_migration() {
+        let data = original_registry(&Pubkey::new_unique());
+        assert!(StorageRegistry::try_deserialize(&mut &data[..]).is_err());
+    }
+
+    #[test]
+    fn grown_registry_keeps_original_fields_and_zeroes_new_ones() {
+        let authority = Pubkey::new_unique();
+        let mut data = original_registry(&authority);
+        data.resize(StorageRegistry::LEN, 0);
+
+        let registry = StorageRegistry::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(registry.authority, authority);
+        assert_eq!(registry.base_fee_lamports, 5_000);
+        assert_eq!(registry.total_files, 12);
+        assert_eq!(registry.total_shares, 3);
+        assert!(registry.is_paused);
+        assert_eq!(registry.bump, 254);
+        assert_eq!(registry.total_bytes, 0);
+        assert_eq!(registry.current_epoch, 0);
+        assert!(registry.operators.is_empty());
+        assert_eq!(registry.download_throttle, DownloadThrottle::None);
+        assert_eq!(registry.pending_authority, None);
+        assert_eq!(registry.max_shares_per_file, 0);
+    }
+
+    #[test]
+    fn grown_file_record_keeps_original_fields_and_zeroes_new_ones() {
+        let owner = Pubkey::new_unique();
+        let mut data = original_file_record(&owner);
+        assert!(FileRecord::try_deserialize(&mut &data[..]).is_err());
+        data.resize(FileRecord::LEN, 0);
+
+        let file_record = FileRecord::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(file_record.owner, owner);
+        assert_eq!(file_record.transaction_id, SAMPLE_TX_ID);
+        assert_eq!(file_record.encrypted_name.as_deref().map(str::len), Some(256));
+        assert_eq!(file_record.mime_type.len(), 128);
+        assert_eq!(file_record.size, 4096);
+        assert!(file_record.is_encrypted());
+        assert!(file_record.is_deleted());
+        assert_eq!(file_record.file_status(), FileStatus::Deleted);
+        assert_eq!(file_record.share_count, 2);
+        assert_eq!(file_record.bump, 253);
+        assert_eq!(file_record.rent_recipient(), owner);
+        assert_eq!(file_record.pending_owner, None);
+        assert_eq!(file_record.owner_seq, 0);
+        assert_eq!(file_record.share_nonce, 0);
+    }
+
+    #[test]
+    fn stored_keys_sit_at_their_original_offsets() {
+        let authority = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let registry = original_registry(&authority);
+        let file_record = original_file_record(&owner);
+
+        assert_eq!(&registry[REGISTRY_AUTHORITY..REGISTRY_AUTHORITY + 32], authority.as_ref());
+        assert_eq!(&file_record[FILE_RECORD_OWNER..FILE_RECORD_OWNER + 32], owner.as_ref());
+    }
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    MimeTable, MimeTypeAdded, StorageRegistry, EVENT_VERSION, MIME_TABLE_SEED, REGISTRY_SEED,
+};
+use crate::types::MimeType;
+
+/// Accounts required for creating the MIME table
+#[derive(Accounts)]
+pub struct InitializeMimeTable<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The MIME table to initialize (PDA)
+    #[account(
+        init,
+        payer = authority,
+        space = MimeTable::LEN,
+        seeds = [MIME_TABLE_SEED],
+        bump
+    )]
+    pub mime_table: Account<'info, MimeTable>,
+
+    /// The registry authority (payer)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the initialize_mime_table instruction
+/// 
+/// Creates the empty MIME table. Can only be called once, by the registry
+/// authority.
+pub fn initialize_mime_table_handler(ctx: Context<InitializeMimeTable>) -> Result<()> {
+    let mime_table = &mut ctx.accounts.mime_table;
+
+    mime_table.registry = ctx.accounts.registry.key();
+    mime_table.entries = Vec::new();
+    mime_table.bump = ctx.bumps.mime_table;
+    mime_table._reserved = [0u8; 32];
+
+    msg!("MIME table initialized for {}", mime_table.registry);
+
+    Ok(())
+}
+
+/// Accounts required for interning a MIME type
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AddMimeType<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The MIME table to append to
+    #[account(
+        mut,
+        seeds = [MIME_TABLE_SEED],
+        bump = mime_table.bump
+    )]
+    pub mime_table: Account<'info, MimeTable>,
+
+    /// The registry authority
+    pub authority: Signer<'info>,
+}
+
+/// Handler for the add_mime_type instruction
+/// 
+/// Appends a MIME type to the table. Entries are never removed or
+/// reordered, so a code keeps its meaning for the life of the table.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `mime_type` - The canonical MIME type to intern
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn add_mime_type_handler(ctx: Context<AddMimeType>, mime_type: MimeType) -> Result<()> {
+    let mime_table = &mut ctx.accounts.mime_table;
+    let mime_type = mime_type.into_inner();
+    let clock = Clock::get()?;
+
+    // The MIME type format was validated when the argument was deserialized
+    let code = mime_table.intern(mime_type.clone())?;
+
+    emit_event!(ctx, MimeTypeAdded {
+        version: EVENT_VERSION,
+        code,
+        mime_type,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("MIME type interned with code {}", code);
+
+    Ok(())
+}
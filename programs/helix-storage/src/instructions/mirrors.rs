@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    ContentLocator, FileRecord, Mirror, MirrorAdded, MirrorRemoved, StorageRegistry,
+    EVENT_VERSION, FILE_SEED, MIRROR_SEED, REGISTRY_SEED,
+};
+
+/// Accounts required for attaching a mirror to a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(mirror_key: [u8; 32])]
+pub struct AddMirror<'info> {
+    /// The storage registry (for the mirror limit)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record the mirror belongs to
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The mirror account to create (PDA derived from file and mirror key)
+    #[account(
+        init,
+        payer = owner,
+        space = Mirror::LEN,
+        seeds = [MIRROR_SEED, file_record.key().as_ref(), mirror_key.as_ref()],
+        bump
+    )]
+    pub mirror: Account<'info, Mirror>,
+
+    /// The file owner (pays for the mirror)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the add_mirror instruction
+/// 
+/// Attaches another copy of the file's content, on Arweave or IPFS, to the
+/// record. The locator goes through the same validation as a registration,
+/// and `mirror_key` must be its `record_key`, so one piece of content can
+/// be attached once per file. The primary content cannot be its own mirror.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `mirror_key` - `record_key` of `content`
+/// * `content` - Where the mirrored content lives
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn add_mirror_handler(
+    ctx: Context<AddMirror>,
+    mirror_key: [u8; 32],
+    content: ContentLocator,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let mirror_address = ctx.accounts.mirror.key();
+    let mirror_limit = ctx.accounts.registry.mirror_limit();
+    let file_record = &mut ctx.accounts.file_record;
+    let mirror = &mut ctx.accounts.mirror;
+    let clock = Clock::get()?;
+
+    // Validate the file is live and below its mirror limit
+    require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+    require!(file_record.mirror_count < mirror_limit, HelixError::MirrorLimitReached);
+
+    // Validate the locator matches its key and differs from the primary
+    require!(content.record_key() == mirror_key, HelixError::ContentLocatorMismatch);
+    require!(
+        content.record_id().as_str() != file_record.transaction_id,
+        HelixError::MirrorIsPrimary
+    );
+
+    // Initialize mirror
+    mirror.file = file_key;
+    mirror.mirror_key = mirror_key;
+    mirror.locator = content.into_mirror();
+    mirror.added_at = clock.unix_timestamp;
+    mirror.bump = ctx.bumps.mirror;
+    mirror._reserved = [0u8; 16];
+
+    file_record.mirror_count = file_record
+        .mirror_count
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, MirrorAdded {
+        version: EVENT_VERSION,
+        mirror: mirror_address,
+        file: file_key,
+        owner,
+        locator: mirror.locator.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Mirror added";
+        "Mirror added to {} by {}",
+        file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for removing a mirror from a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RemoveMirror<'info> {
+    /// The file record the mirror belongs to
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The mirror account to close
+    #[account(
+        mut,
+        seeds = [MIRROR_SEED, file_record.key().as_ref(), mirror.mirror_key.as_ref()],
+        bump = mirror.bump,
+        close = owner
+    )]
+    pub mirror: Account<'info, Mirror>,
+
+    /// The file owner (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the remove_mirror instruction
+/// 
+/// Closes the mirror account, returning its rent to the file's owner.
+/// Works on deleted files too, so their mirrors can always be reclaimed.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn remove_mirror_handler(ctx: Context<RemoveMirror>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let mirror_key = ctx.accounts.mirror.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    file_record.mirror_count = file_record.mirror_count.saturating_sub(1);
+
+    emit_event!(ctx, MirrorRemoved {
+        version: EVENT_VERSION,
+        mirror: mirror_key,
+        file: file_key,
+        owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Mirror removed";
+        "Mirror removed from {} by {}",
+        file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
@@ -1,7 +1,230 @@
+// `FileRecord` and `ShareLink` each take several hundred bytes once
+// deserialized, so every context holds them as `Box<Account<...>>` to keep
+// them off the 4KB SBF stack frame. New contexts should do the same, and
+// box `UserProfile` too once a context holds more than one. The largest
+// context, `CreateShare`, takes about 1.6KB; `contexts_fit_the_stack_budget`
+// fails any context that grows past half the frame.
+
+pub mod access_log;
+pub mod admin;
+pub mod aliases;
+pub mod attestations;
+pub mod audit;
+pub mod challenges;
+pub mod chunks;
+pub mod claims;
+pub mod contacts;
+pub mod copyright;
 pub mod create_share;
+pub mod endowment;
+pub mod expirations;
+pub mod export;
+pub mod gallery;
+pub mod inbox;
 pub mod initialize;
+pub mod lifecycle;
+pub mod likes;
+pub mod migrate;
+pub mod mime_table;
+pub mod mirrors;
+pub mod names;
+pub mod owner_index;
+pub mod ownership;
+pub mod profile;
+pub mod references;
+pub mod register_batch;
 pub mod register_file;
+pub mod rename_batch;
+pub mod restore_batch;
+pub mod session;
+pub mod share_batch;
+pub mod short_codes;
+pub mod stealth;
+pub mod templates;
+pub mod timed_release;
+pub mod tokenize;
+pub mod treasury;
 
+pub use access_log::*;
+pub use admin::*;
+pub use aliases::*;
+pub use attestations::*;
+pub use audit::*;
+pub use challenges::*;
+pub use chunks::*;
+pub use claims::*;
+pub use contacts::*;
+pub use copyright::*;
 pub use create_share::*;
+pub use endowment::*;
+pub use expirations::*;
+pub use export::*;
+pub use gallery::*;
+pub use inbox::*;
 pub use initialize::*;
+pub use lifecycle::*;
+pub use likes::*;
+pub use migrate::*;
+pub use mime_table::*;
+pub use mirrors::*;
+pub use names::*;
+pub use owner_index::*;
+pub use ownership::*;
+pub use profile::*;
+pub use references::*;
+pub use register_batch::*;
 pub use register_file::*;
+pub use rename_batch::*;
+pub use restore_batch::*;
+pub use session::*;
+pub use share_batch::*;
+pub use short_codes::*;
+pub use stealth::*;
+pub use templates::*;
+pub use timed_release::*;
+pub use tokenize::*;
+pub use treasury::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes a deserialized context may take: half the 4KB SBF stack frame,
+    /// leaving the rest to the handler
+    const CONTEXT_STACK_BUDGET: usize = 2048;
+
+    macro_rules! assert_within_budget {
+        ($($context:ident),* $(,)?) => {
+            $(
+                let size = std::mem::size_of::<$context<'static>>();
+                assert!(
+                    size <= CONTEXT_STACK_BUDGET,
+                    "{} takes {} bytes",
+                    stringify!($context),
+                    size
+                );
+            )*
+        };
+    }
+
+    #[test]
+    fn contexts_fit_the_stack_budget() {
+        assert_within_budget!(
+            CreateAccessLog,
+            CloseAccessLog,
+            GetAccessLog,
+            AdminDeleteFile,
+            AdminCorrectSize,
+            AttestSize,
+            ForceRevokeShare,
+            CreateAlias,
+            RemoveAlias,
+            UpdateAttestors,
+            AttestFile,
+            RevokeAttestation,
+            CreateAuditGrant,
+            RevokeAuditGrant,
+            OpenChallenge,
+            AnswerChallenge,
+            AcceptAnswer,
+            SettleChallenge,
+            SetChunkRoot,
+            VerifyChunk,
+            CreateClaimableShare,
+            ClaimShare,
+            AddContact,
+            RemoveContact,
+            AttestCopyright,
+            CreateShare,
+            CreateShareWithPermit,
+            RevokeShare,
+            UpdateShareMessage,
+            RecordDownload,
+            ValidateAccess,
+            GetShareStatus,
+            Contribute,
+            WithdrawEndowment,
+            CloseEndowment,
+            GetEndowment,
+            ProcessExpirations,
+            NotifyExpiringShare,
+            ExportSnapshot,
+            AddGalleryEntry,
+            RemoveGalleryEntry,
+            ForceRemoveGalleryEntry,
+            CloseInboxEntry,
+            AckNotification,
+            ListInbox,
+            Initialize,
+            InitializeRegistryExt,
+            UpdateRegistryExt,
+            UpdateRegistry,
+            AcceptAuthority,
+            ReadRegistry,
+            ArchiveFile,
+            LikeFile,
+            UnlikeFile,
+            MigrateRegistry,
+            MigrateFileRecord,
+            MigrateShareLink,
+            InitializeMimeTable,
+            AddMimeType,
+            AddMirror,
+            RemoveMirror,
+            RegisterName,
+            UpdateNameTarget,
+            ReleaseName,
+            ResolveName,
+            IndexFile,
+            SetSortKey,
+            ListOwnerFiles,
+            GetStorageStatement,
+            ManageOwnershipTransfer,
+            AcceptOwnership,
+            SetEncryptionKey,
+            UpdateProfile,
+            UpdateShareDefaults,
+            SetGuardian,
+            FreezeAccount,
+            UnfreezeAccount,
+            TouchFile,
+            GetProfile,
+            GetRecentFiles,
+            CreateReference,
+            CloseReference,
+            RegisterFilesCompact,
+            RegisterFile,
+            RegisterFileV2,
+            UpdateFile,
+            DeleteFile,
+            RestoreFile,
+            PurgeFile,
+            CloseFile,
+            GetFile,
+            UpdateNamesBatch,
+            RestoreFilesBatch,
+            CreateSession,
+            RevokeSession,
+            CreateSharesBatch,
+            ClaimShortCode,
+            ReleaseShortCode,
+            ReapShortCode,
+            RegisterFileStealth,
+            DeleteFileStealth,
+            CreateShareStealth,
+            RevokeShareStealth,
+            CreateTemplate,
+            UpdateTemplate,
+            DeleteTemplate,
+            CreateTimedRelease,
+            CancelTimedRelease,
+            TriggerRelease,
+            RevealKey,
+            TokenizeFile,
+            ClaimTokenizedFile,
+            DetokenizeFile,
+            InitializeTreasury,
+            WithdrawFees,
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    FileRecord, NameRecord, NameRegistered, NameReleased, NameTargetUpdated, EVENT_VERSION,
+    FILE_SEED, NAME_SEED,
+};
+use crate::types::Name;
+use crate::views::{NameResolution, VIEW_VERSION};
+
+/// Accounts required for registering a name in the owner's namespace
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(name: Name)]
+pub struct RegisterName<'info> {
+    /// The file record the name points to
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The name record to create (PDA derived from owner and name)
+    #[account(
+        init,
+        payer = owner,
+        space = NameRecord::LEN,
+        seeds = [NAME_SEED, owner.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub name_record: Account<'info, NameRecord>,
+
+    /// The namespace owner (pays for the name record)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the register_name instruction
+/// 
+/// Registers `name` in the owner's namespace, pointing at one of the owner's
+/// live files. Names are scoped per owner, so `owner/name` is unique.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `name` - The name to register
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn register_name_handler(ctx: Context<RegisterName>, name: Name) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let name_key = ctx.accounts.name_record.key();
+    let name_record = &mut ctx.accounts.name_record;
+    let clock = Clock::get()?;
+
+    // Validate the file is live
+    require!(ctx.accounts.file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    // Initialize name record
+    name_record.owner = owner;
+    name_record.name = name.into_inner();
+    name_record.file = file_key;
+    name_record.created_at = clock.unix_timestamp;
+    name_record.updated_at = clock.unix_timestamp;
+    name_record.bump = ctx.bumps.name_record;
+    name_record._reserved = [0u8; 16];
+
+    emit_event!(ctx, NameRegistered {
+        version: EVENT_VERSION,
+        name_record: name_key,
+        owner,
+        name: name_record.name.clone(),
+        file: file_key,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Name registered"; "Name {}/{} registered", owner, name_record.name);
+
+    Ok(())
+}
+
+/// Accounts required for pointing a name at a different file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateNameTarget<'info> {
+    /// The name record to update
+    #[account(
+        mut,
+        seeds = [NAME_SEED, owner.key().as_ref(), name_record.name.as_bytes()],
+        bump = name_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner
+    )]
+    pub name_record: Account<'info, NameRecord>,
+
+    /// The new target file record
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The namespace owner
+    pub owner: Signer<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the update_name_target instruction
+/// 
+/// Points the name at another of the owner's live files.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn update_name_target_handler(ctx: Context<UpdateNameTarget>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let name_key = ctx.accounts.name_record.key();
+    let name_record = &mut ctx.accounts.name_record;
+    let clock = Clock::get()?;
+
+    // Validate the new target is live
+    require!(ctx.accounts.file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    let old_file = name_record.file;
+    name_record.file = file_key;
+    name_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, NameTargetUpdated {
+        version: EVENT_VERSION,
+        name_record: name_key,
+        owner,
+        old_file,
+        new_file: file_key,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Name target updated";
+        "Name {}/{} now points at {}",
+        owner,
+        name_record.name,
+        file_key
+    );
+
+    Ok(())
+}
+
+/// Accounts required for releasing a name
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReleaseName<'info> {
+    /// The name record to close
+    #[account(
+        mut,
+        seeds = [NAME_SEED, owner.key().as_ref(), name_record.name.as_bytes()],
+        bump = name_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        close = owner
+    )]
+    pub name_record: Account<'info, NameRecord>,
+
+    /// The namespace owner (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the release_name instruction
+/// 
+/// Closes the name record, returning its rent to the owner and freeing the
+/// name for reuse.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn release_name_handler(ctx: Context<ReleaseName>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let name_key = ctx.accounts.name_record.key();
+    let name_record = &ctx.accounts.name_record;
+    let clock = Clock::get()?;
+
+    emit_event!(ctx, NameReleased {
+        version: EVENT_VERSION,
+        name_record: name_key,
+        owner,
+        name: name_record.name.clone(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Name released"; "Name {}/{} released", owner, name_record.name);
+
+    Ok(())
+}
+
+/// Accounts for resolving a name (read-only)
+#[derive(Accounts)]
+pub struct ResolveName<'info> {
+    /// The name record to resolve
+    #[account(
+        seeds = [NAME_SEED, name_record.owner.as_ref(), name_record.name.as_bytes()],
+        bump = name_record.bump
+    )]
+    pub name_record: Account<'info, NameRecord>,
+
+    /// The file record the name points to
+    #[account(address = name_record.file)]
+    pub file_record: Box<Account<'info, FileRecord>>,
+}
+
+/// Return the file a name points to, with its transaction ID
+/// 
+/// Names whose target was deleted still resolve, with `is_deleted` set.
+pub fn resolve_name_handler(ctx: Context<ResolveName>) -> Result<NameResolution> {
+    let name_record = &ctx.accounts.name_record;
+    let file_record = &ctx.accounts.file_record;
+
+    Ok(NameResolution {
+        version: VIEW_VERSION,
+        owner: name_record.owner,
+        name: name_record.name.clone(),
+        file: name_record.file,
+        transaction_id: file_record.transaction_id.clone(),
+        is_deleted: file_record.is_deleted(),
+    })
+}
@@ -0,0 +1,356 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    FileIndexed, FileRecord, FileSortKeySet, FileStatus, OwnerFileEntry, UserProfile,
+    EVENT_VERSION, FILE_SEED, MAX_OWNER_FILES_PER_PAGE, MAX_STATEMENT_FILES_PER_PAGE,
+    OWNER_FILE_SEED, USER_PROFILE_SEED,
+};
+use crate::views::{OwnerFileSummary, OwnerFilesPage, StorageStatement, VIEW_VERSION};
+
+/// Accounts required for appending a file to its owner's file index
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct IndexFile<'info> {
+    /// The file record to index
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The owner's profile holding the index length
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The index entry to create at the next sequence number
+    #[account(
+        init,
+        payer = owner,
+        space = OwnerFileEntry::LEN,
+        seeds = [
+            OWNER_FILE_SEED,
+            owner.key().as_ref(),
+            &user_profile.file_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub entry: Account<'info, OwnerFileEntry>,
+
+    /// The file owner (pays for the profile and entry)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the index_file instruction
+/// 
+/// Appends the file to its owner's index at the next sequence number, so
+/// the owner's files can be listed by address without scanning accounts.
+/// Each file can be indexed once; deleted files can be indexed too.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn index_file_handler(ctx: Context<IndexFile>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let user_profile = &mut ctx.accounts.user_profile;
+    let entry = &mut ctx.accounts.entry;
+    let clock = Clock::get()?;
+
+    // Validate the file is not indexed yet
+    require!(!file_record.is_indexed(), HelixError::FileAlreadyIndexed);
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = owner;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    // Append the entry
+    let seq = user_profile.file_count;
+    entry.owner = owner;
+    entry.seq = seq;
+    entry.file = file_key;
+    entry.bump = ctx.bumps.entry;
+    entry.archived = file_record.file_status() == FileStatus::Archived;
+    entry.sort_key = u32::try_from(seq).unwrap_or(u32::MAX);
+    entry.next_live_seq = seq.saturating_add(1);
+    entry._reserved = [0u8; 3];
+
+    user_profile.file_count = seq
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    file_record.set_indexed(true);
+
+    emit_event!(ctx, FileIndexed {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner,
+        seq,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File indexed";
+        "File indexed: {} for {} at {}",
+        file_record.transaction_id,
+        owner,
+        seq
+    );
+
+    Ok(())
+}
+
+/// Accounts required for reordering a file in its owner's file index
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetSortKey<'info> {
+    /// The indexed file record
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file's index entry
+    #[account(
+        mut,
+        seeds = [OWNER_FILE_SEED, owner.key().as_ref(), &entry.seq.to_le_bytes()],
+        bump = entry.bump,
+        constraint = entry.file == file_record.key() @ HelixError::InvalidIndexEntry
+    )]
+    pub entry: Account<'info, OwnerFileEntry>,
+
+    /// The file owner
+    pub owner: Signer<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the set_sort_key instruction
+/// 
+/// Stores a user-defined ordering key on the file's index entry, e.g. after
+/// a drag-and-drop reorder. The program does not keep keys unique; clients
+/// order files with equal keys themselves.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `sort_key` - The new ordering key
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn set_sort_key_handler(ctx: Context<SetSortKey>, sort_key: u32) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let entry = &mut ctx.accounts.entry;
+    let clock = Clock::get()?;
+
+    let old_sort_key = entry.sort_key;
+    entry.sort_key = sort_key;
+    file_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, FileSortKeySet {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner,
+        seq: entry.seq,
+        old_sort_key,
+        sort_key,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Sort key updated";
+        "Sort key of {} updated from {} to {}",
+        file_record.transaction_id,
+        old_sort_key,
+        sort_key
+    );
+
+    Ok(())
+}
+
+/// Accounts for listing a page of a wallet's file index (read-only).
+/// 
+/// Index entries and their file records are passed as `(entry, file_record)`
+/// pairs in `remaining_accounts`, in sequence order from `start_seq`.
+#[derive(Accounts)]
+pub struct ListOwnerFiles<'info> {
+    /// The wallet's profile holding the index length
+    #[account(
+        seeds = [USER_PROFILE_SEED, user_profile.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+/// Return a page of a wallet's files via return data
+/// 
+/// Follows the index's skip pointers from `cursor` (0 for the first page)
+/// and returns up to `limit` (capped at `MAX_OWNER_FILES_PER_PAGE`) files
+/// plus the cursor of the next page. Deleted files are included with their
+/// deleted flag set. Purged files are skipped without using up the page, so
+/// a page is full unless fewer pairs are supplied or the index ends; a
+/// cursor at or past `file_count` means there are no more files. Every pair
+/// must be the entry the chain reaches next and the file it points to.
+pub fn list_owner_files_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ListOwnerFiles<'info>>,
+    cursor: u64,
+    limit: u8,
+) -> Result<OwnerFilesPage> {
+    let user_profile = &ctx.accounts.user_profile;
+    let limit = usize::from(limit.min(MAX_OWNER_FILES_PER_PAGE));
+
+    let mut next_cursor = cursor;
+    let mut entries = Vec::with_capacity(limit);
+    for pair in ctx.remaining_accounts.chunks_exact(2) {
+        if entries.len() == limit || next_cursor >= user_profile.file_count {
+            break;
+        }
+
+        let entry = Account::<OwnerFileEntry>::try_from(&pair[0])?;
+        require!(
+            entry.owner == user_profile.owner
+                && entry.seq == next_cursor
+                && entry.file == pair[1].key(),
+            HelixError::InvalidIndexEntry
+        );
+        next_cursor = entry.next_live();
+
+        // Purged files no longer have a record
+        let Ok(file_record) = Account::<FileRecord>::try_from(&pair[1]) else {
+            continue;
+        };
+
+        entries.push(OwnerFileSummary {
+            transaction_id: file_record.transaction_id.clone(),
+            size: file_record.size,
+            flags: file_record.flags,
+            updated_at: file_record.updated_at,
+            sort_key: entry.sort_key,
+            owner_seq: file_record.owner_seq,
+        });
+    }
+
+    Ok(OwnerFilesPage {
+        version: VIEW_VERSION,
+        start_seq: cursor,
+        file_count: user_profile.file_count,
+        entries,
+        next_cursor,
+    })
+}
+
+/// Accounts for reading a page of a wallet's storage statement (read-only).
+/// 
+/// Index entries and their file records are passed as `(entry, file_record)`
+/// pairs in `remaining_accounts`, in sequence order from `cursor`.
+#[derive(Accounts)]
+pub struct GetStorageStatement<'info> {
+    /// The wallet's profile holding the index length and fees paid
+    #[account(
+        seeds = [USER_PROFILE_SEED, user_profile.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+/// Return a page of a wallet's storage statement via return data
+/// 
+/// Walks the file index like `list_owner_files`, reading up to `limit`
+/// (capped at `MAX_STATEMENT_FILES_PER_PAGE`) entries from `cursor`, and
+/// totals the live files, their bytes and active shares, and the bytes
+/// served. Every pair must be the entry the chain reaches next and the file
+/// it points to, so no file is counted twice or left out of a full walk.
+/// Purged files count towards the limit but add nothing.
+pub fn get_storage_statement_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GetStorageStatement<'info>>,
+    cursor: u64,
+    limit: u8,
+) -> Result<StorageStatement> {
+    let user_profile = &ctx.accounts.user_profile;
+    let limit = usize::from(limit.min(MAX_STATEMENT_FILES_PER_PAGE));
+    let clock = Clock::get()?;
+
+    let mut statement = StorageStatement {
+        version: VIEW_VERSION,
+        owner: user_profile.owner,
+        start_seq: cursor,
+        next_cursor: cursor,
+        file_count: user_profile.file_count,
+        active_files: 0,
+        total_bytes: 0,
+        active_shares: 0,
+        bytes_served: 0,
+        fees_paid_lamports: user_profile.fees_paid_lamports,
+        as_of: clock.unix_timestamp,
+    };
+
+    for pair in ctx.remaining_accounts.chunks_exact(2).take(limit) {
+        if statement.next_cursor >= user_profile.file_count {
+            break;
+        }
+
+        let entry = Account::<OwnerFileEntry>::try_from(&pair[0])?;
+        require!(
+            entry.owner == user_profile.owner
+                && entry.seq == statement.next_cursor
+                && entry.file == pair[1].key(),
+            HelixError::InvalidIndexEntry
+        );
+        statement.next_cursor = entry.next_live();
+
+        // Purged files no longer have a record
+        let Ok(file_record) = Account::<FileRecord>::try_from(&pair[1]) else {
+            continue;
+        };
+
+        statement.bytes_served = statement
+            .bytes_served
+            .saturating_add(file_record.bytes_served);
+
+        if !file_record.is_deleted() {
+            statement.active_files = statement
+                .active_files
+                .checked_add(1)
+                .ok_or(HelixError::ArithmeticOverflow)?;
+            statement.total_bytes = statement
+                .total_bytes
+                .checked_add(file_record.size)
+                .ok_or(HelixError::ArithmeticOverflow)?;
+            statement.active_shares = statement
+                .active_shares
+                .checked_add(u64::from(file_record.share_count))
+                .ok_or(HelixError::ArithmeticOverflow)?;
+        }
+    }
+
+    Ok(statement)
+}
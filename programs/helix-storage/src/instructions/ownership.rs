@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    FileRecord, OwnershipTransferAccepted, OwnershipTransferCancelled, OwnershipTransferProposed,
+    EVENT_VERSION, FILE_SEED, OWNERSHIP_TRANSFER_WINDOW_SECS,
+};
+
+/// Accounts required for proposing or cancelling an ownership transfer
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ManageOwnershipTransfer<'info> {
+    /// The file record being transferred
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The current file owner
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the propose_ownership_transfer instruction
+/// 
+/// Records `new_owner` as the file's pending owner. Nothing changes hands
+/// until that wallet calls `accept_ownership`, so a mistyped address only
+/// costs a cancellation. The proposal lapses `OWNERSHIP_TRANSFER_WINDOW_SECS`
+/// after it is made; proposing again replaces it and restarts the window.
+/// Tokenized files change hands through their token instead.
+/// 
+/// # Arguments
+/// * `ctx` - The ManageOwnershipTransfer context
+/// * `new_owner` - The wallet that may accept the file
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn propose_ownership_transfer_handler(
+    ctx: Context<ManageOwnershipTransfer>,
+    new_owner: Pubkey,
+) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate the file can change hands
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+    require!(!file_record.is_tokenized(), HelixError::FileAlreadyTokenized);
+    require!(new_owner != file_record.owner, HelixError::InvalidNewOwner);
+
+    let expires_at = clock
+        .unix_timestamp
+        .checked_add(OWNERSHIP_TRANSFER_WINDOW_SECS)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    file_record.pending_owner = Some(new_owner);
+    file_record.transfer_expires_at = expires_at;
+
+    emit_event!(ctx, OwnershipTransferProposed {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        pending_owner: new_owner,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Ownership transfer proposed";
+        "Ownership transfer of {} proposed to {}",
+        file_key,
+        new_owner
+    );
+
+    Ok(())
+}
+
+/// Handler for the cancel_ownership_transfer instruction
+/// 
+/// Clears the file's pending owner. Lapsed proposals can be cleared too.
+/// 
+/// # Arguments
+/// * `ctx` - The ManageOwnershipTransfer context
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn cancel_ownership_transfer_handler(ctx: Context<ManageOwnershipTransfer>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    let pending_owner = file_record
+        .pending_owner
+        .take()
+        .ok_or(HelixError::NoPendingTransfer)?;
+    file_record.transfer_expires_at = 0;
+
+    emit_event!(ctx, OwnershipTransferCancelled {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        pending_owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Ownership transfer cancelled";
+        "Ownership transfer of {} to {} cancelled",
+        file_key,
+        pending_owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for accepting a proposed ownership transfer
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    /// The file record being transferred
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The proposed owner
+    pub new_owner: Signer<'info>,
+}
+
+/// Handler for the accept_ownership instruction
+/// 
+/// Makes the signing wallet the file's owner if it is the pending owner and
+/// the proposal has not lapsed. As with tokenized claims, only the record's
+/// owner changes; existing share links keep the previous owner recorded.
+/// 
+/// # Arguments
+/// * `ctx` - The AcceptOwnership context
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn accept_ownership_handler(ctx: Context<AcceptOwnership>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let new_owner = ctx.accounts.new_owner.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate the signer holds a live proposal on a live file
+    require!(
+        file_record.pending_transfer(clock.unix_timestamp) == Some(new_owner),
+        HelixError::NoPendingTransfer
+    );
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+
+    let previous_owner = file_record.owner;
+    file_record.owner = new_owner;
+    file_record.pending_owner = None;
+    file_record.transfer_expires_at = 0;
+    file_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, OwnershipTransferAccepted {
+        version: EVENT_VERSION,
+        file: file_key,
+        previous_owner,
+        new_owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Ownership transfer accepted";
+        "Ownership of {} accepted by {}",
+        file_key,
+        new_owner
+    );
+
+    Ok(())
+}
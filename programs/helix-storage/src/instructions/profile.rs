@@ -0,0 +1,559 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    AccountFrozen, AccountUnfrozen, EncryptionKeyUpdated, FileRecord, GuardianUpdated, MimeTable,
+    ProfileUpdated, ShareDefaults, ShareDefaultsUpdated, UserProfile, EVENT_VERSION, FILE_SEED,
+    MIME_TABLE_SEED, USER_PROFILE_SEED,
+};
+use crate::types::DisplayName;
+use crate::views::{ProfileView, RecentFilesView, VIEW_VERSION};
+
+/// Accounts required for publishing a wallet's encryption key
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetEncryptionKey<'info> {
+    /// The wallet's profile (created on first use)
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, wallet.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The wallet publishing the key (pays for the profile)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the set_encryption_key instruction
+/// 
+/// Stores the wallet's X25519 public key on its profile so share creators
+/// can wrap content keys for it, or clears it when `None` is passed. The
+/// replaced key moves into the profile's key history under its generation.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `encryption_pubkey` - The new X25519 public key, or `None` to clear it
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn set_encryption_key_handler(
+    ctx: Context<SetEncryptionKey>,
+    encryption_pubkey: Option<[u8; 32]>,
+) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    // Validate the key is not the all-zero point
+    if let Some(key) = encryption_pubkey {
+        require!(key != [0u8; 32], HelixError::InvalidEncryptionKey);
+    }
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = wallet;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    // Retire the current key and bump the generation
+    user_profile.rotate_encryption_key(encryption_pubkey, clock.unix_timestamp)?;
+
+    emit_event!(ctx, EncryptionKeyUpdated {
+        version: EVENT_VERSION,
+        wallet,
+        encryption_pubkey,
+        key_generation: user_profile.key_generation,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Encryption key updated";
+        "Encryption key {} for {}",
+        if encryption_pubkey.is_some() { "set" } else { "cleared" },
+        wallet
+    );
+
+    Ok(())
+}
+
+/// Accounts required for updating a wallet's public profile metadata
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateProfile<'info> {
+    /// The wallet's profile (created on first use)
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, wallet.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The avatar's file record (required only when setting an avatar)
+    pub avatar_file_record: Option<Box<Account<'info, FileRecord>>>,
+
+    /// The MIME table (required only for avatars with a MIME code)
+    #[account(
+        seeds = [MIME_TABLE_SEED],
+        bump = mime_table.bump
+    )]
+    pub mime_table: Option<Account<'info, MimeTable>>,
+
+    /// The wallet updating its profile (pays for the profile)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the update_profile instruction
+/// 
+/// Replaces the wallet's public display name and avatar. The avatar must be
+/// a live, unencrypted image file owned by the wallet; `None` clears either
+/// field.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `display_name` - The new display name, or `None` to clear it
+/// * `avatar_file` - The avatar's file record, or `None` to clear it
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn update_profile_handler(
+    ctx: Context<UpdateProfile>,
+    display_name: Option<DisplayName>,
+    avatar_file: Option<Pubkey>,
+) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let clock = Clock::get()?;
+
+    // Validate the avatar file
+    if let Some(avatar_file) = avatar_file {
+        let file_record = ctx
+            .accounts
+            .avatar_file_record
+            .as_ref()
+            .ok_or(HelixError::InvalidAvatarFile)?;
+        require_keys_eq!(file_record.key(), avatar_file, HelixError::InvalidAvatarFile);
+        require!(
+            file_record.is_usable_avatar(&wallet, ctx.accounts.mime_table.as_deref()),
+            HelixError::InvalidAvatarFile
+        );
+    }
+
+    let user_profile = &mut ctx.accounts.user_profile;
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = wallet;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    user_profile.display_name = display_name.map(String::from);
+    user_profile.avatar_file = avatar_file;
+
+    emit_event!(ctx, ProfileUpdated {
+        version: EVENT_VERSION,
+        wallet,
+        display_name: user_profile.display_name.clone(),
+        avatar_file,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Profile updated"; "Profile updated for {}", wallet);
+
+    Ok(())
+}
+
+/// Accounts required for updating a wallet's default share limits
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateShareDefaults<'info> {
+    /// The wallet's profile (created on first use)
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, wallet.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The wallet updating its defaults (pays for the profile)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the update_share_defaults instruction
+/// 
+/// Replaces the limits `create_share` applies when the owner asks for their
+/// profile defaults, so every client shares with the same terms.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `share_defaults` - The new default share limits
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn update_share_defaults_handler(
+    ctx: Context<UpdateShareDefaults>,
+    share_defaults: ShareDefaults,
+) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    // Validate the limits; unlimited is expressed as None
+    require!(
+        share_defaults.expiry_duration.is_none_or(|duration| duration > 0)
+            && share_defaults.max_downloads.is_none_or(|max| max > 0),
+        HelixError::InvalidShareDefaults
+    );
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = wallet;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    user_profile.share_defaults = share_defaults;
+
+    emit_event!(ctx, ShareDefaultsUpdated {
+        version: EVENT_VERSION,
+        wallet,
+        share_defaults,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Share defaults updated"; "Share defaults updated for {}", wallet);
+
+    Ok(())
+}
+
+/// Accounts required for setting a wallet's guardian
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    /// The wallet's profile (created on first use)
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, wallet.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The wallet setting its guardian (pays for the profile)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the set_guardian instruction
+/// 
+/// Designates a backup key that can freeze the wallet's account, e.g. from
+/// another device after the wallet's key is compromised, or clears it when
+/// `None` is passed. The guardian cannot be changed while frozen.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `guardian` - The guardian key, or `None` to clear it
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn set_guardian_handler(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = wallet;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    require!(!user_profile.is_frozen, HelixError::OwnerFrozen);
+
+    user_profile.guardian = guardian;
+
+    emit_event!(ctx, GuardianUpdated {
+        version: EVENT_VERSION,
+        wallet,
+        guardian,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Guardian updated"; "Guardian updated for {}", wallet);
+
+    Ok(())
+}
+
+/// Accounts required for freezing a wallet's account
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct FreezeAccount<'info> {
+    /// The profile of the wallet to freeze
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, user_profile.owner.as_ref()],
+        bump = user_profile.bump,
+        constraint = user_profile.can_freeze(&signer.key()) @ HelixError::UnauthorizedGuardian
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The wallet or its guardian
+    pub signer: Signer<'info>,
+}
+
+/// Handler for the freeze_account instruction
+/// 
+/// Freezes the wallet's account until the wallet itself unfreezes it. While
+/// frozen, the wallet cannot create shares or change file metadata, and
+/// downloads of its shares that pass its profile are refused. The wallet
+/// needs a profile to be frozen.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn freeze_account_handler(ctx: Context<FreezeAccount>) -> Result<()> {
+    let frozen_by = ctx.accounts.signer.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    require!(!user_profile.is_frozen, HelixError::OwnerFrozen);
+
+    user_profile.is_frozen = true;
+
+    emit_event!(ctx, AccountFrozen {
+        version: EVENT_VERSION,
+        wallet: user_profile.owner,
+        frozen_by,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Account frozen";
+        "Account {} frozen by {}",
+        user_profile.owner,
+        frozen_by
+    );
+
+    Ok(())
+}
+
+/// Accounts required for unfreezing a wallet's account
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UnfreezeAccount<'info> {
+    /// The wallet's profile
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, wallet.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The wallet; its guardian cannot unfreeze
+    pub wallet: Signer<'info>,
+}
+
+/// Handler for the unfreeze_account instruction
+/// 
+/// Lifts a freeze set by the wallet or its guardian. Only the wallet can
+/// unfreeze, so a guardian freeze holds until the wallet's owner acts.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn unfreeze_account_handler(ctx: Context<UnfreezeAccount>) -> Result<()> {
+    let wallet = ctx.accounts.wallet.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    require!(user_profile.is_frozen, HelixError::AccountNotFrozen);
+
+    user_profile.is_frozen = false;
+
+    emit_event!(ctx, AccountUnfrozen {
+        version: EVENT_VERSION,
+        wallet,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Account unfrozen"; "Account {} unfrozen", wallet);
+
+    Ok(())
+}
+
+/// Fail with `OwnerFrozen` if the owner profile account `info` is frozen.
+/// A profile that was never created cannot be frozen and passes.
+pub(crate) fn require_not_frozen(info: &AccountInfo) -> Result<()> {
+    if let Some(profile) = load_profile(info)? {
+        require!(!profile.is_frozen, HelixError::OwnerFrozen);
+    }
+
+    Ok(())
+}
+
+/// Deserialize the profile account `info`, or `None` if it was never created
+pub(crate) fn load_profile(info: &AccountInfo) -> Result<Option<UserProfile>> {
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(None);
+    }
+
+    let data = info.try_borrow_data()?;
+    UserProfile::try_deserialize(&mut &data[..]).map(Some)
+}
+
+/// Accounts required for recording that the owner opened a file
+#[derive(Accounts)]
+pub struct TouchFile<'info> {
+    /// The opened file record
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The owner's profile holding the recent files (created on first use)
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The file owner (pays for the profile)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the touch_file instruction
+/// 
+/// Moves the file to the front of the owner's recent files, so every
+/// device lists the same recents. Called by clients when a file is opened.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn touch_file_handler(ctx: Context<TouchFile>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    let clock = Clock::get()?;
+
+    require!(ctx.accounts.file_record.is_accessible(), HelixError::FileNotActive);
+
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = owner;
+        user_profile.bump = ctx.bumps.user_profile;
+    }
+
+    user_profile.touch_recent_file(file_key, clock.unix_timestamp);
+
+    private_msg!("File touched"; "File {} touched by {}", file_key, owner);
+
+    Ok(())
+}
+
+/// Accounts for reading a wallet's public profile (read-only)
+#[derive(Accounts)]
+pub struct GetProfile<'info> {
+    /// The wallet's profile
+    #[account(
+        seeds = [USER_PROFILE_SEED, user_profile.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    /// The avatar's file record (omit to report the avatar as unset)
+    pub avatar_file_record: Option<Box<Account<'info, FileRecord>>>,
+
+    /// The MIME table (required only for avatars with a MIME code)
+    #[account(
+        seeds = [MIME_TABLE_SEED],
+        bump = mime_table.bump
+    )]
+    pub mime_table: Option<Account<'info, MimeTable>>,
+}
+
+/// Return a wallet's public profile metadata
+/// 
+/// The avatar is reported only if its file record is supplied and is still
+/// a usable avatar; a deleted, encrypted or transferred file reads as unset.
+pub fn get_profile_handler(ctx: Context<GetProfile>) -> Result<ProfileView> {
+    let user_profile = &ctx.accounts.user_profile;
+
+    let avatar_file = user_profile.avatar_file.filter(|avatar_file| {
+        ctx.accounts.avatar_file_record.as_ref().is_some_and(|file_record| {
+            file_record.key() == *avatar_file
+                && file_record.is_usable_avatar(
+                    &user_profile.owner,
+                    ctx.accounts.mime_table.as_deref(),
+                )
+        })
+    });
+
+    Ok(ProfileView {
+        version: VIEW_VERSION,
+        owner: user_profile.owner,
+        display_name: user_profile.display_name.clone(),
+        avatar_file,
+        encryption_pubkey: user_profile.encryption_pubkey,
+        key_generation: user_profile.key_generation,
+        archived_files: user_profile.archived_files,
+        is_frozen: user_profile.is_frozen,
+    })
+}
+
+/// Accounts for reading a wallet's recently accessed files (read-only)
+#[derive(Accounts)]
+pub struct GetRecentFiles<'info> {
+    /// The wallet's profile
+    #[account(
+        seeds = [USER_PROFILE_SEED, user_profile.owner.as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+}
+
+/// Return a wallet's recently accessed files, newest first
+/// 
+/// Files deleted since they were accessed are still listed; clients drop
+/// them when they fail to load the record.
+pub fn get_recent_files_handler(ctx: Context<GetRecentFiles>) -> Result<RecentFilesView> {
+    let user_profile = &ctx.accounts.user_profile;
+
+    Ok(RecentFilesView {
+        version: VIEW_VERSION,
+        owner: user_profile.owner,
+        entries: user_profile
+            .recent_files
+            .iter()
+            .copied()
+            .filter(|recent| recent.file != Pubkey::default())
+            .collect(),
+    })
+}
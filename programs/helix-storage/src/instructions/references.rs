@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    FileRecord, FileReference, ReferenceClosed, ReferenceCreated, EVENT_VERSION, FILE_SEED,
+    REFERENCE_SEED,
+};
+use crate::types::EncryptedNote;
+
+/// Accounts required for saving a reference to a file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CreateReference<'info> {
+    /// The file record to reference
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The reference account to create (PDA derived from referrer and file)
+    #[account(
+        init,
+        payer = referrer,
+        space = FileReference::LEN,
+        seeds = [REFERENCE_SEED, referrer.key().as_ref(), file_record.key().as_ref()],
+        bump
+    )]
+    pub reference: Account<'info, FileReference>,
+
+    /// The wallet saving the reference (pays for it)
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the create_reference instruction
+/// 
+/// Saves a reference to a live file in the referrer's drive. Any wallet can
+/// reference any file; the reference grants no access to its content.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `encrypted_note` - Client-encrypted note (optional)
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_reference_handler(
+    ctx: Context<CreateReference>,
+    encrypted_note: Option<EncryptedNote>,
+) -> Result<()> {
+    let referrer = ctx.accounts.referrer.key();
+    let file_key = ctx.accounts.file_record.key();
+    let reference_key = ctx.accounts.reference.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let reference = &mut ctx.accounts.reference;
+    let clock = Clock::get()?;
+
+    // Validate the file is live
+    require!(file_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    // Initialize reference
+    reference.referrer = referrer;
+    reference.file = file_key;
+    reference.encrypted_note = encrypted_note.map(String::from);
+    reference.created_at = clock.unix_timestamp;
+    reference.bump = ctx.bumps.reference;
+    reference._reserved = [0u8; 16];
+
+    file_record.reference_count = file_record.reference_count.saturating_add(1);
+
+    emit_event!(ctx, ReferenceCreated {
+        version: EVENT_VERSION,
+        reference: reference_key,
+        file: file_key,
+        referrer,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Reference created";
+        "Reference to {} created by {}",
+        file_record.transaction_id,
+        referrer
+    );
+
+    Ok(())
+}
+
+/// Accounts required for closing a file reference
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CloseReference<'info> {
+    /// The reference account to close
+    #[account(
+        mut,
+        seeds = [REFERENCE_SEED, referrer.key().as_ref(), reference.file.as_ref()],
+        bump = reference.bump,
+        has_one = referrer @ HelixError::UnauthorizedOwner,
+        close = referrer
+    )]
+    pub reference: Account<'info, FileReference>,
+
+    /// The referenced file record
+    /// CHECK: May have been purged; its counter is updated in the handler
+    #[account(mut, address = reference.file)]
+    pub file_record: UncheckedAccount<'info>,
+
+    /// The wallet that saved the reference (receives the rent)
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+}
+
+/// Handler for the close_reference instruction
+/// 
+/// Closes the reference, returning its rent to the referrer. Works whether
+/// or not the referenced file still exists.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn close_reference_handler(ctx: Context<CloseReference>) -> Result<()> {
+    let referrer = ctx.accounts.referrer.key();
+    let reference_key = ctx.accounts.reference.key();
+    let file_info = ctx.accounts.file_record.to_account_info();
+    let clock = Clock::get()?;
+
+    // Release the file's counter unless the file has been purged
+    if file_info.owner == &crate::ID {
+        let mut data = file_info.try_borrow_mut_data()?;
+        if let Ok(mut file_record) = FileRecord::try_deserialize(&mut &data[..]) {
+            file_record.reference_count = file_record.reference_count.saturating_sub(1);
+            file_record.try_serialize(&mut &mut data[..])?;
+        }
+    }
+
+    emit_event!(ctx, ReferenceClosed {
+        version: EVENT_VERSION,
+        reference: reference_key,
+        file: file_info.key(),
+        referrer,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Reference closed"; "Reference to {} closed by {}", file_info.key(), referrer);
+
+    Ok(())
+}
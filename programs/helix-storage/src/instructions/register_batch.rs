@@ -0,0 +1,261 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::system_program::{self, CreateAccount};
+
+use crate::compact::{decode_compact_batch, CompactFile};
+use crate::cpi_guard::require_cpi_allowed;
+use crate::error::HelixError;
+use crate::instructions::register_file::{assign_owner_seq, record_registration};
+use crate::instructions::treasury::charge_fee;
+use crate::state::{
+    ContentLocator, FileRecord, FileRegistered, FileStatus, MimeTable, MimeTypeArg, StorageRegistry,
+    Treasury, UserProfile, EVENT_VERSION, FILE_FLAG_COMPACT_MIME, FILE_FLAG_ENCRYPTED, FILE_SEED,
+    MIME_TABLE_SEED, REGISTRY_SEED, TREASURY_SEED, USER_PROFILE_SEED,
+};
+use crate::types::EncryptedName;
+
+/// Accounts required for registering a compact batch of files.
+///
+/// Each file's record PDA is passed in `remaining_accounts`, writable and
+/// uninitialized, in the same order as the payload's entries.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RegisterFilesCompact<'info> {
+    /// The storage registry (for validation and stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The owner of the files (payer)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The MIME table (required only when an entry uses a MIME code)
+    #[account(
+        seeds = [MIME_TABLE_SEED],
+        bump = mime_table.bump
+    )]
+    pub mime_table: Option<Account<'info, MimeTable>>,
+
+    /// The owner's profile (created on first use), assigning each file's
+    /// `owner_seq`. Required only when a registration fee is due.
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Option<Box<Account<'info, UserProfile>>>,
+
+    /// The treasury receiving the registration fees (required only when a
+    /// fee is due)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// Instructions sysvar, identifying the calling program under CPI
+    /// (required only when the registry's CPI policy is an allowlist)
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+}
+
+/// Handler for the register_files_compact instruction
+///
+/// Registers every file of a compact batch like `register_file_v2` would,
+/// without encrypted names, memos or provenance links. The payload is
+/// decoded in full before any account is created, and any malformed entry,
+/// duplicate, unknown MIME code or mismatched address fails the whole
+/// batch. The registration fees of all files are paid in one transfer.
+///
+/// # Arguments
+/// * `ctx` - The context, with each file's record PDA as a remaining account
+/// * `payload` - The compact batch, see `crate::compact`
+///
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn register_files_compact_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RegisterFilesCompact<'info>>,
+    payload: Vec<u8>,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let clock = Clock::get()?;
+
+    // Validate the batch before creating anything
+    let files = decode_compact_batch(&payload)?;
+    require!(
+        ctx.remaining_accounts.len() == files.len(),
+        HelixError::BatchLengthMismatch
+    );
+
+    let registry = &mut ctx.accounts.registry;
+    require!(!registry.is_paused, HelixError::RegistryPaused);
+    require_cpi_allowed(registry, ctx.accounts.instructions.as_deref())?;
+
+    let mut fee_lamports = 0u64;
+    for file in &files {
+        require!(file.size > 0, HelixError::InvalidFileSize);
+        if let MimeTypeArg::Code(code) = file.mime_type {
+            let mime_table = ctx
+                .accounts
+                .mime_table
+                .as_ref()
+                .ok_or(HelixError::MimeTableMissing)?;
+            mime_table.resolve_code(code)?;
+        }
+        fee_lamports = fee_lamports
+            .checked_add(registry.registration_fee(file.size))
+            .ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    // Pay the registration fees
+    charge_fee(
+        fee_lamports,
+        registry,
+        &ctx.accounts.owner,
+        ctx.accounts.user_profile.as_deref_mut(),
+        ctx.bumps.user_profile,
+        ctx.accounts.treasury.as_ref(),
+        &ctx.accounts.system_program,
+    )?;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    let rent = Rent::get()?;
+
+    for (file, file_info) in files.into_iter().zip(ctx.remaining_accounts) {
+        require!(file_info.is_writable, ErrorCode::AccountNotMutable);
+
+        // Create the file record at its transaction ID's address
+        let transaction_id = file.tx_id();
+        let file_seed = transaction_id.file_seed();
+        let (file_key, bump) = Pubkey::try_find_program_address(
+            &[FILE_SEED, &file_seed],
+            &crate::ID,
+        )
+        .ok_or(HelixError::FileAddressMismatch)?;
+        require_keys_eq!(file_info.key(), file_key, HelixError::FileAddressMismatch);
+        if !file_info.data_is_empty() {
+            msg!("transaction_id: {} is already registered", transaction_id);
+            return Err(HelixError::DuplicateTransactionId.into());
+        }
+
+        let space = FileRecord::compact_space(
+            &None::<EncryptedName>,
+            &file.mime_type,
+            &ContentLocator::Arweave(file.transaction_id),
+        );
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: file_info.clone(),
+                },
+                &[&[FILE_SEED, &file_seed, &[bump]]],
+            ),
+            rent.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        // Take the owner's next registration sequence number
+        let owner_seq = assign_owner_seq(
+            ctx.accounts.user_profile.as_deref_mut(),
+            owner,
+            ctx.bumps.user_profile,
+        )?;
+
+        let file_record = compact_record(&file, transaction_id.into_inner(), owner, bump, owner_seq, &clock);
+        file_record.try_serialize(&mut &mut file_info.try_borrow_mut_data()?[..])?;
+
+        // Update registry stats
+        record_registration(registry, file.size)?;
+
+        emit_event!(ctx, FileRegistered {
+            version: EVENT_VERSION,
+            owner,
+            transaction_id: file_record.transaction_id,
+            size: file.size,
+            is_encrypted: file.is_encrypted(),
+            timestamp: clock.unix_timestamp,
+            storage_location: file_record.storage_location,
+            owner_seq,
+        });
+    }
+
+    log_msg!(
+        "Compact batch registered";
+        "Registered {} files for {}",
+        ctx.remaining_accounts.len(),
+        owner
+    );
+
+    Ok(())
+}
+
+/// A new file record for a compact batch entry
+fn compact_record(
+    file: &CompactFile,
+    transaction_id: String,
+    owner: Pubkey,
+    bump: u8,
+    owner_seq: u64,
+    clock: &Clock,
+) -> FileRecord {
+    let (mime_type, mime_code) = match &file.mime_type {
+        MimeTypeArg::Code(code) => (String::new(), *code),
+        MimeTypeArg::Literal(mime_type) => (mime_type.to_string(), 0),
+    };
+    let mut flags = FILE_FLAG_COMPACT_MIME;
+    if file.is_encrypted() {
+        flags |= FILE_FLAG_ENCRYPTED;
+    }
+
+    FileRecord {
+        owner,
+        transaction_id,
+        encrypted_name: None,
+        mime_type,
+        size: file.size,
+        flags,
+        created_at: clock.unix_timestamp,
+        updated_at: clock.unix_timestamp,
+        share_count: 0,
+        bump,
+        mime_code,
+        deleted_at: 0,
+        alias_count: 0,
+        reference_count: 0,
+        bytes_served: 0,
+        attestation_count: 0,
+        failed_challenges: 0,
+        copyright_holder: Pubkey::default(),
+        copyright_attested_at: 0,
+        derived_from: None,
+        derivative_count: 0,
+        chunk_root: None,
+        status: FileStatus::Active,
+        like_count: 0,
+        storage_location: file.storage_location(),
+        ipfs_cid: None,
+        mirror_count: 0,
+        rent_payer: owner,
+        pending_owner: None,
+        transfer_expires_at: 0,
+        owner_seq,
+        share_nonce: 0,
+        _reserved: [0u8; 1],
+    }
+}
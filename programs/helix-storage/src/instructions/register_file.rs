@@ -1,17 +1,31 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_spl::memo::Memo;
+use anchor_spl::token::TokenAccount;
 
-use crate::error::{
-    validate_mime_type, validate_optional_string_length, validate_string_length,
-    validate_transaction_id, HelixError,
-};
+use crate::cpi_guard::require_cpi_allowed;
+use crate::error::{validate_optional_byte_length, HelixError};
+use crate::instructions::lifecycle::release_file_count;
+use crate::instructions::profile::require_not_frozen;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::instructions::treasury::charge_fee;
+use crate::memo::process_memo;
+use crate::receipt::{mint_receipt, ReceiptAccounts};
 use crate::state::{
-    FileDeleted, FileRecord, FileRegistered, StorageRegistry, FILE_SEED,
-    MAX_ENCRYPTED_NAME_LEN, MAX_MIME_TYPE_LEN, MAX_TRANSACTION_ID_LEN, REGISTRY_SEED,
+    ContentLocator, FileClosed, FileDeleted, FileDerived, FilePurged, FileRecord, FileRegistered,
+    FileRestored, FileScrubbed, FileStatus, IpfsContentRegistered, MimeTable, MimeTypeArg, Mirror,
+    OwnerFileEntry, RegistryExt, ShareLink, ShareRevoked, StorageLocation, StorageLocationArg,
+    StorageRegistry, Treasury, UserProfile, EVENT_VERSION, FILE_FLAG_COMPACT_MIME, FILE_SEED,
+    MIME_TABLE_SEED, REGISTRY_EXT_SEED, REGISTRY_SEED, SCRUBBED_MIME_TYPE, TREASURY_SEED,
+    USER_PROFILE_SEED,
 };
+use crate::types::{EncryptedName, MimeType, TxId};
+use crate::views::{FileInfo, MirrorInfo, VIEW_VERSION};
 
 /// Accounts required for registering a new file
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
-#[instruction(transaction_id: String)]
+#[instruction(transaction_id: TxId, encrypted_name: Option<EncryptedName>)]
 pub struct RegisterFile<'info> {
     /// The storage registry (for validation and stats)
     #[account(
@@ -23,13 +37,13 @@ pub struct RegisterFile<'info> {
 
     /// The file record to create (PDA derived from tx_id)
     #[account(
-        init,
+        init_if_needed,
         payer = owner,
-        space = FileRecord::LEN,
-        seeds = [FILE_SEED, transaction_id.as_bytes()],
+        space = FileRecord::init_space(file_record, FileRecord::space(&encrypted_name)),
+        seeds = [FILE_SEED, &transaction_id.file_seed()],
         bump
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
 
     /// The file owner (payer)
     #[account(mut)]
@@ -37,13 +51,83 @@ pub struct RegisterFile<'info> {
 
     /// System program for account creation
     pub system_program: Program<'info, System>,
+
+    /// SPL Memo program (required only when a memo is supplied)
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// Registry extension holding the receipt config (required only for receipts)
+    #[account(
+        seeds = [REGISTRY_EXT_SEED],
+        bump
+    )]
+    pub registry_ext: Option<AccountLoader<'info, RegistryExt>>,
+
+    /// Merkle tree receiving the receipt; supplying it requests a receipt
+    /// CHECK: Must match the configured receipt tree; validated by Bubblegum
+    #[account(mut)]
+    pub receipt_merkle_tree: Option<UncheckedAccount<'info>>,
+
+    /// Bubblegum tree config PDA of the receipt tree
+    /// CHECK: Validated by Bubblegum
+    #[account(mut)]
+    pub receipt_tree_authority: Option<UncheckedAccount<'info>>,
+
+    /// Metaplex Bubblegum program
+    /// CHECK: Checked against the Bubblegum program ID before the CPI
+    pub bubblegum_program: Option<UncheckedAccount<'info>>,
+
+    /// SPL Noop log wrapper program
+    /// CHECK: Validated by Bubblegum
+    pub log_wrapper: Option<UncheckedAccount<'info>>,
+
+    /// SPL Account Compression program
+    /// CHECK: Validated by Bubblegum
+    pub compression_program: Option<UncheckedAccount<'info>>,
+
+    /// The record this file is derived from (optional provenance link)
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &source_record.file_seed()],
+        bump = source_record.bump
+    )]
+    pub source_record: Option<Box<Account<'info, FileRecord>>>,
+    /// The owner's profile (created on first use), assigning the file's
+    /// `owner_seq`. Required only when a registration fee is due; files
+    /// registered without it get `owner_seq` 0 (not assigned).
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Option<Box<Account<'info, UserProfile>>>,
+    /// The treasury receiving the registration fee (required only when a
+    /// fee is due)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// Instructions sysvar, identifying the calling program under CPI
+    /// (required only when the registry's CPI policy is an allowlist)
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
 }
 
 /// Handler for the register_file instruction
-/// 
+///
 /// Creates a new file record linking the owner's wallet to an Arweave transaction.
 /// This should be called after successfully uploading a file to Arweave via Irys.
-/// 
+/// Supplying `source_record` marks the file as derived from that live record.
+/// The owner's profile, when supplied, is created on first use and gives the
+/// file the next `owner_seq`, which orders files registered within the same
+/// second. It is required only when a registration fee is due.
+/// The registry's registration fee, if due, is paid to the treasury.
+///
 /// # Arguments
 /// * `ctx` - The RegisterFile context
 /// * `transaction_id` - The Arweave transaction ID (43 chars)
@@ -51,62 +135,531 @@ pub struct RegisterFile<'info> {
 /// * `mime_type` - The file's MIME type
 /// * `size` - File size in bytes
 /// * `is_encrypted` - Whether the file content is encrypted
-/// 
+/// * `memo` - Optional note attached to the transaction via SPL Memo
+/// * `storage_location` - Whether the ID is an L1 transaction or an Irys
+///   data item
+///
 /// # Returns
 /// * `Result<()>` - Success or error
-pub fn handler(
+pub fn register_file_handler(
     ctx: Context<RegisterFile>,
-    transaction_id: String,
-    encrypted_name: Option<String>,
-    mime_type: String,
+    transaction_id: TxId,
+    encrypted_name: Option<EncryptedName>,
+    mime_type: MimeType,
     size: u64,
     is_encrypted: bool,
+    memo: Option<String>,
+    storage_location: StorageLocationArg,
 ) -> Result<()> {
     let registry = &mut ctx.accounts.registry;
     let file_record = &mut ctx.accounts.file_record;
     let clock = Clock::get()?;
 
-    // Validate registry is not paused
-    require!(!registry.is_paused, HelixError::RegistryPaused);
-
     // Validate inputs
-    validate_string_length(&transaction_id, MAX_TRANSACTION_ID_LEN, HelixError::TransactionIdTooLong)?;
-    validate_transaction_id(&transaction_id)?;
-    validate_optional_string_length(&encrypted_name, MAX_ENCRYPTED_NAME_LEN, HelixError::EncryptedNameTooLong)?;
-    validate_string_length(&mime_type, MAX_MIME_TYPE_LEN, HelixError::MimeTypeTooLong)?;
-    validate_mime_type(&mime_type)?;
-    require!(size > 0, HelixError::InvalidFileSize);
+    validate_registration(registry, file_record, &encrypted_name, size)?;
+    require_cpi_allowed(registry, ctx.accounts.instructions.as_deref())?;
+    let storage_location = storage_location.into_location()?;
+
+    // Attach the memo, if any, to the transaction
+    process_memo(&ctx.accounts.memo_program, memo)?;
+
+    // Take the owner's next registration sequence number
+    let owner_seq = assign_owner_seq(
+        ctx.accounts.user_profile.as_deref_mut(),
+        ctx.accounts.owner.key(),
+        ctx.bumps.user_profile,
+    )?;
+
+    // Pay the registration fee
+    charge_fee(
+        registry.registration_fee(size),
+        registry,
+        &ctx.accounts.owner,
+        ctx.accounts.user_profile.as_deref_mut(),
+        ctx.bumps.user_profile,
+        ctx.accounts.treasury.as_ref(),
+        &ctx.accounts.system_program,
+    )?;
 
     // Initialize file record
     file_record.owner = ctx.accounts.owner.key();
-    file_record.transaction_id = transaction_id.clone();
-    file_record.encrypted_name = encrypted_name;
-    file_record.mime_type = mime_type;
+    file_record.encrypted_name = encrypted_name.map(String::from);
+    file_record.mime_type = mime_type.into_inner();
     file_record.size = size;
-    file_record.is_encrypted = is_encrypted;
-    file_record.is_deleted = false;
+    file_record.flags = 0;
+    file_record.set_encrypted(is_encrypted);
     file_record.created_at = clock.unix_timestamp;
     file_record.updated_at = clock.unix_timestamp;
     file_record.share_count = 0;
     file_record.bump = ctx.bumps.file_record;
-    file_record._reserved = [0u8; 32];
+    file_record.mime_code = 0;
+    file_record.deleted_at = 0;
+    file_record.alias_count = 0;
+    file_record.reference_count = 0;
+    file_record.bytes_served = 0;
+    file_record.attestation_count = 0;
+    file_record.failed_challenges = 0;
+    file_record.copyright_holder = Pubkey::default();
+    file_record.copyright_attested_at = 0;
+    file_record.derived_from = None;
+    file_record.derivative_count = 0;
+    file_record.chunk_root = None;
+    file_record.status = FileStatus::Active;
+    file_record.like_count = 0;
+    file_record.storage_location = storage_location;
+    file_record.ipfs_cid = None;
+    file_record.mirror_count = 0;
+    file_record.rent_payer = ctx.accounts.owner.key();
+    file_record.pending_owner = None;
+    file_record.transfer_expires_at = 0;
+    file_record.owner_seq = owner_seq;
+    file_record.share_nonce = 0;
+    file_record._reserved = [0u8; 1];
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
 
     // Update registry stats
+    record_registration(registry, size)?;
+
+    // Emit the event, then move the transaction ID into the record
+    let event = FileRegistered {
+        version: EVENT_VERSION,
+        owner: file_record.owner,
+        transaction_id: transaction_id.into_inner(),
+        size,
+        is_encrypted,
+        timestamp: clock.unix_timestamp,
+        storage_location,
+        owner_seq,
+    };
+    emit_event!(ctx, event);
+    file_record.transaction_id = event.transaction_id;
+
+    // Link the file to its source, if any
+    if let Some(source_record) = ctx.accounts.source_record.as_mut() {
+        let source = source_record.key();
+        link_source(file_record, source_record, source)?;
+    }
+    if let Some(source) = file_record.derived_from {
+        emit_event!(ctx, FileDerived {
+            version: EVENT_VERSION,
+            file: file_record.key(),
+            source,
+            owner: file_record.owner,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    // Mint a compressed NFT receipt when one is requested
+    if let Some(merkle_tree) = &ctx.accounts.receipt_merkle_tree {
+        let registry_ext = ctx
+            .accounts
+            .registry_ext
+            .as_ref()
+            .ok_or(HelixError::ReceiptAccountsMissing)?
+            .load()?;
+        require!(registry_ext.receipts_enabled(), HelixError::ReceiptNotConfigured);
+
+        let (
+            Some(tree_authority),
+            Some(bubblegum_program),
+            Some(log_wrapper),
+            Some(compression_program),
+        ) = (
+            &ctx.accounts.receipt_tree_authority,
+            &ctx.accounts.bubblegum_program,
+            &ctx.accounts.log_wrapper,
+            &ctx.accounts.compression_program,
+        ) else {
+            return err!(HelixError::ReceiptAccountsMissing);
+        };
+
+        mint_receipt(
+            ReceiptAccounts {
+                registry: &registry.to_account_info(),
+                registry_bump: registry.bump,
+                tree_authority,
+                merkle_tree,
+                owner: &ctx.accounts.owner.to_account_info(),
+                bubblegum_program,
+                log_wrapper,
+                compression_program,
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            },
+            &registry_ext,
+            &file_record.transaction_id,
+            file_record.content_url(file_record.default_gateway()),
+        )?;
+    }
+
+    log_msg!(
+        "File registered";
+        "File registered: {} by {}",
+        file_record.transaction_id,
+        file_record.owner
+    );
+
+    Ok(())
+}
+
+/// Validate the inputs shared by the registration paths. The transaction
+/// ID and MIME type formats are already checked by their argument types.
+///
+/// The record account is created with `init_if_needed`, so an existing
+/// record for the transaction ID arrives here intact and is rejected with
+/// `DuplicateTransactionId`, logging its owner.
+pub(crate) fn validate_registration(
+    registry: &StorageRegistry,
+    file_record: &FileRecord,
+    encrypted_name: &Option<EncryptedName>,
+    size: u64,
+) -> Result<()> {
+    // Validate registry is not paused
+    require!(!registry.is_paused, HelixError::RegistryPaused);
+
+    // Validate the transaction ID is not already registered
+    if !file_record.transaction_id.is_empty() {
+        msg!(
+            "transaction_id: {} is already registered by {}",
+            file_record.transaction_id,
+            file_record.owner
+        );
+        return Err(HelixError::DuplicateTransactionId.into());
+    }
+
+    validate_optional_byte_length(
+        "encrypted_name",
+        encrypted_name,
+        registry.encrypted_name_limit(),
+        HelixError::EncryptedNameTooLong,
+    )?;
+    require!(size > 0, HelixError::InvalidFileSize);
+
+    Ok(())
+}
+
+/// Record `file_record` as derived from the live `source_record`
+fn link_source(
+    file_record: &mut FileRecord,
+    source_record: &mut FileRecord,
+    source: Pubkey,
+) -> Result<()> {
+    require!(source_record.is_accessible(), HelixError::FileAlreadyDeleted);
+
+    file_record.derived_from = Some(source);
+    source_record.derivative_count = source_record
+        .derivative_count
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Take the next registration sequence number from the owner's profile,
+/// initializing the profile if it was just created. Without a profile the
+/// file gets 0, meaning no sequence number was assigned.
+pub(crate) fn assign_owner_seq(
+    user_profile: Option<&mut Account<UserProfile>>,
+    owner: Pubkey,
+    bump: u8,
+) -> Result<u64> {
+    let Some(user_profile) = user_profile else {
+        return Ok(0);
+    };
+    if user_profile.owner == Pubkey::default() {
+        user_profile.owner = owner;
+        user_profile.bump = bump;
+    }
+
+    user_profile.next_file_seq()
+}
+
+/// Add a newly registered file to the registry totals and epoch stats
+pub(crate) fn record_registration(registry: &mut StorageRegistry, size: u64) -> Result<()> {
     registry.total_files = registry
         .total_files
         .checked_add(1)
         .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.total_bytes = registry
+        .total_bytes
+        .checked_add(size)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.current_epoch_stats.files = registry
+        .current_epoch_stats
+        .files
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.current_epoch_stats.bytes = registry
+        .current_epoch_stats
+        .bytes
+        .checked_add(size)
+        .ok_or(HelixError::ArithmeticOverflow)?;
 
-    // Emit event
-    emit!(FileRegistered {
+    Ok(())
+}
+
+/// Accounts required for registering a file with a compact MIME type
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(
+    transaction_id: TxId,
+    encrypted_name: Option<EncryptedName>,
+    mime_type: MimeTypeArg,
+    size: u64,
+    is_encrypted: bool,
+    memo: Option<String>,
+    storage_location: StorageLocationArg,
+    content: ContentLocator
+)]
+pub struct RegisterFileV2<'info> {
+    /// The storage registry (for validation and stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to create (PDA derived from tx_id)
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = FileRecord::init_space(
+            file_record,
+            FileRecord::compact_space(&encrypted_name, &mime_type, &content)
+        ),
+        seeds = [FILE_SEED, &transaction_id.file_seed()],
+        bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The MIME table (required only when registering with a MIME code)
+    #[account(
+        seeds = [MIME_TABLE_SEED],
+        bump = mime_table.bump
+    )]
+    pub mime_table: Option<Account<'info, MimeTable>>,
+
+    /// The file owner (payer)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// SPL Memo program (required only when a memo is supplied)
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// The record this file is derived from (optional provenance link)
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &source_record.file_seed()],
+        bump = source_record.bump
+    )]
+    pub source_record: Option<Box<Account<'info, FileRecord>>>,
+    /// The owner's profile (created on first use), assigning the file's
+    /// `owner_seq`. Required only when a registration fee is due; files
+    /// registered without it get `owner_seq` 0 (not assigned).
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub user_profile: Option<Box<Account<'info, UserProfile>>>,
+    /// The treasury receiving the registration fee (required only when a
+    /// fee is due)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// Instructions sysvar, identifying the calling program under CPI
+    /// (required only when the registry's CPI policy is an allowlist)
+    /// CHECK: Address is checked against the instructions sysvar ID
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+}
+
+/// Handler for the register_file_v2 instruction
+///
+/// Same as `register_file`, but the MIME type is either a code from the
+/// MIME table (stored as 2 bytes) or a literal string, and the record is
+/// allocated without the fixed `MAX_MIME_TYPE_LEN` slot.
+/// Accepts the same optional `source_record` provenance link.
+///
+/// `content` locates the file on Arweave or IPFS, and `transaction_id` must
+/// be its `record_id`, which seeds the record's PDA. IPFS CIDs arrive
+/// normalized, so a CID registered as v0 or v1 maps to the same record and
+/// the duplicate check covers both. IPFS content must use `ArweaveL1`, the
+/// neutral location.
+///
+/// # Arguments
+/// * `ctx` - The RegisterFileV2 context
+/// * `transaction_id` - The record ID of `content` (43 chars)
+/// * `encrypted_name` - Optional client-encrypted filename
+/// * `mime_type` - MIME table code or literal MIME type
+/// * `size` - File size in bytes
+/// * `is_encrypted` - Whether the file content is encrypted
+/// * `memo` - Optional note attached to the transaction via SPL Memo
+/// * `storage_location` - Whether the ID is an L1 transaction or an Irys
+///   data item
+/// * `content` - The Arweave transaction or IPFS CID holding the content
+///
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn register_file_v2_handler(
+    ctx: Context<RegisterFileV2>,
+    transaction_id: TxId,
+    encrypted_name: Option<EncryptedName>,
+    mime_type: MimeTypeArg,
+    size: u64,
+    is_encrypted: bool,
+    memo: Option<String>,
+    storage_location: StorageLocationArg,
+    content: ContentLocator,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate inputs
+    validate_registration(registry, file_record, &encrypted_name, size)?;
+    require_cpi_allowed(registry, ctx.accounts.instructions.as_deref())?;
+    let storage_location = storage_location.into_location()?;
+    require!(
+        content.record_id() == transaction_id,
+        HelixError::ContentLocatorMismatch
+    );
+    let ipfs_cid = match content {
+        ContentLocator::Arweave(_) => None,
+        ContentLocator::Ipfs { cid } => {
+            require!(
+                storage_location == StorageLocation::ArweaveL1,
+                HelixError::IpfsStorageLocation
+            );
+            Some(cid.into_inner())
+        }
+    };
+    let (mime_type, mime_code) = match mime_type {
+        MimeTypeArg::Code(code) => {
+            let mime_table = ctx
+                .accounts
+                .mime_table
+                .as_ref()
+                .ok_or(HelixError::MimeTableMissing)?;
+            mime_table.resolve_code(code)?;
+            (String::new(), code)
+        }
+        MimeTypeArg::Literal(mime_type) => (mime_type.into_inner(), 0),
+    };
+
+    // Attach the memo, if any, to the transaction
+    process_memo(&ctx.accounts.memo_program, memo)?;
+
+    // Take the owner's next registration sequence number
+    let owner_seq = assign_owner_seq(
+        ctx.accounts.user_profile.as_deref_mut(),
+        ctx.accounts.owner.key(),
+        ctx.bumps.user_profile,
+    )?;
+
+    // Pay the registration fee
+    charge_fee(
+        registry.registration_fee(size),
+        registry,
+        &ctx.accounts.owner,
+        ctx.accounts.user_profile.as_deref_mut(),
+        ctx.bumps.user_profile,
+        ctx.accounts.treasury.as_ref(),
+        &ctx.accounts.system_program,
+    )?;
+
+    // Initialize file record
+    file_record.owner = ctx.accounts.owner.key();
+    file_record.encrypted_name = encrypted_name.map(String::from);
+    file_record.mime_type = mime_type;
+    file_record.size = size;
+    file_record.flags = FILE_FLAG_COMPACT_MIME;
+    file_record.set_encrypted(is_encrypted);
+    file_record.created_at = clock.unix_timestamp;
+    file_record.updated_at = clock.unix_timestamp;
+    file_record.share_count = 0;
+    file_record.bump = ctx.bumps.file_record;
+    file_record.mime_code = mime_code;
+    file_record.deleted_at = 0;
+    file_record.alias_count = 0;
+    file_record.reference_count = 0;
+    file_record.bytes_served = 0;
+    file_record.attestation_count = 0;
+    file_record.failed_challenges = 0;
+    file_record.copyright_holder = Pubkey::default();
+    file_record.copyright_attested_at = 0;
+    file_record.derived_from = None;
+    file_record.derivative_count = 0;
+    file_record.chunk_root = None;
+    file_record.status = FileStatus::Active;
+    file_record.like_count = 0;
+    file_record.storage_location = storage_location;
+    file_record.ipfs_cid = ipfs_cid;
+    file_record.mirror_count = 0;
+    file_record.rent_payer = ctx.accounts.owner.key();
+    file_record.pending_owner = None;
+    file_record.transfer_expires_at = 0;
+    file_record.owner_seq = owner_seq;
+    file_record.share_nonce = 0;
+    file_record._reserved = [0u8; 1];
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Update registry stats
+    record_registration(registry, size)?;
+
+    // Emit the event, then move the transaction ID into the record
+    let event = FileRegistered {
+        version: EVENT_VERSION,
         owner: file_record.owner,
-        transaction_id,
+        transaction_id: transaction_id.into_inner(),
         size,
         is_encrypted,
         timestamp: clock.unix_timestamp,
-    });
+        storage_location,
+        owner_seq,
+    };
+    emit_event!(ctx, event);
+    file_record.transaction_id = event.transaction_id;
+    if let Some(cid) = &file_record.ipfs_cid {
+        emit_event!(ctx, IpfsContentRegistered {
+            version: EVENT_VERSION,
+            file: file_record.key(),
+            owner: file_record.owner,
+            cid: cid.clone(),
+            timestamp: clock.unix_timestamp,
+        });
+    }
 
-    msg!(
+    // Link the file to its source, if any
+    if let Some(source_record) = ctx.accounts.source_record.as_mut() {
+        let source = source_record.key();
+        link_source(file_record, source_record, source)?;
+    }
+    if let Some(source) = file_record.derived_from {
+        emit_event!(ctx, FileDerived {
+            version: EVENT_VERSION,
+            file: file_record.key(),
+            source,
+            owner: file_record.owner,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    log_msg!(
+        "File registered";
         "File registered: {} by {}",
         file_record.transaction_id,
         file_record.owner
@@ -117,40 +670,77 @@ pub fn handler(
 
 /// Accounts required for updating a file record
 #[derive(Accounts)]
+#[instruction(encrypted_name: Option<EncryptedName>)]
 pub struct UpdateFile<'info> {
-    /// The file record to update
+    /// The storage registry (for the encrypted name limit)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to update (resized to fit the new name)
     #[account(
         mut,
-        seeds = [FILE_SEED, file_record.transaction_id.as_bytes()],
+        seeds = [FILE_SEED, &file_record.file_seed()],
         bump = file_record.bump,
-        has_one = owner
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired,
+        realloc = file_record.resized_space(&encrypted_name),
+        realloc::payer = owner,
+        realloc::zero = false
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
 
-    /// The file owner
+    /// The file owner (pays for any extra space)
+    #[account(mut)]
     pub owner: Signer<'info>,
+
+    /// System program for account resizing
+    pub system_program: Program<'info, System>,
+
+    /// The owner's profile, checked for a freeze
+    /// CHECK: May not exist; deserialized in the handler when it does
+    #[account(
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub owner_profile: UncheckedAccount<'info>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 /// Handler for updating file metadata
-pub fn update_handler(ctx: Context<UpdateFile>, encrypted_name: Option<String>) -> Result<()> {
+pub fn update_handler(
+    ctx: Context<UpdateFile>,
+    encrypted_name: Option<EncryptedName>,
+) -> Result<()> {
     let file_record = &mut ctx.accounts.file_record;
     let clock = Clock::get()?;
 
     // Validate file is not deleted
-    require!(!file_record.is_deleted, HelixError::FileAlreadyDeleted);
+    require!(!file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+
+    // Validate the owner's account is not frozen
+    require_not_frozen(&ctx.accounts.owner_profile)?;
 
     // Validate encrypted name length
-    validate_optional_string_length(
+    validate_optional_byte_length(
+        "encrypted_name",
         &encrypted_name,
-        MAX_ENCRYPTED_NAME_LEN,
+        ctx.accounts.registry.encrypted_name_limit(),
         HelixError::EncryptedNameTooLong,
     )?;
 
     // Update fields
-    file_record.encrypted_name = encrypted_name;
+    file_record.encrypted_name = encrypted_name.map(String::from);
     file_record.updated_at = clock.unix_timestamp;
 
-    msg!(
+    private_msg!(
+        "File updated";
         "File updated: {} at {}",
         file_record.transaction_id,
         clock.unix_timestamp
@@ -160,6 +750,7 @@ pub fn update_handler(ctx: Context<UpdateFile>, encrypted_name: Option<String>)
 }
 
 /// Accounts required for deleting a file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
 #[derive(Accounts)]
 pub struct DeleteFile<'info> {
     /// The storage registry (for stats)
@@ -173,43 +764,115 @@ pub struct DeleteFile<'info> {
     /// The file record to mark as deleted
     #[account(
         mut,
-        seeds = [FILE_SEED, file_record.transaction_id.as_bytes()],
+        seeds = [FILE_SEED, &file_record.file_seed()],
         bump = file_record.bump,
-        has_one = owner
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
 
     /// The file owner
     pub owner: Signer<'info>,
+
+    /// SPL Memo program (required only when a memo is supplied)
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    /// The owner's profile (required only for archived files)
+    #[account(
+        mut,
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump = user_profile.bump
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
 }
 
 /// Handler for marking a file as deleted
-/// 
+///
 /// Note: This only marks the file as deleted in the on-chain registry.
 /// The actual file content on Arweave remains permanent by design.
-pub fn delete_handler(ctx: Context<DeleteFile>) -> Result<()> {
+/// The file can be restored until the registry's trash retention window
+/// ends, after which it can be purged.
+///
+/// Every share link of the file must be passed in `remaining_accounts`,
+/// writable: they are revoked with their keys cleared, and the deletion fails
+/// with `FileHasActiveShares` if any unrevoked link is left out. A restored
+/// file therefore comes back with no shares.
+///
+/// When the registry's `scrub_on_delete` policy is set, the encrypted name
+/// and MIME type are wiped as well. A restored scrubbed file comes back
+/// without its old metadata.
+///
+/// Deletion fails while an ownership transfer is pending, so the proposed
+/// owner never accepts a deleted file; cancel the transfer first.
+pub fn delete_handler(ctx: Context<DeleteFile>, memo: Option<String>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
     let registry = &mut ctx.accounts.registry;
     let file_record = &mut ctx.accounts.file_record;
     let clock = Clock::get()?;
 
-    // Validate file is not already deleted
-    require!(!file_record.is_deleted, HelixError::FileAlreadyDeleted);
+    // Validate file is not already deleted or being handed over
+    require!(!file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+    file_record.require_no_pending_transfer(clock.unix_timestamp)?;
+
+    // Revoke the supplied share links; none may be left live
+    let (revoked_shares, released, keys_cleared) =
+        revoke_shares(&file_key, ctx.remaining_accounts)?;
+    file_record.share_count = file_record.share_count.saturating_sub(released);
+    registry.total_shares = registry.total_shares.saturating_sub(u64::from(released));
+    require!(file_record.share_count == 0, HelixError::FileHasActiveShares);
+
+    // Attach the memo, if any, to the transaction
+    process_memo(&ctx.accounts.memo_program, memo)?;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
 
     // Mark as deleted
-    file_record.is_deleted = true;
+    let previous_status = file_record.file_status();
+    file_record.set_status(FileStatus::Deleted)?;
     file_record.updated_at = clock.unix_timestamp;
+    file_record.deleted_at = clock.unix_timestamp;
 
     // Update registry stats (decrement if tracking active files)
-    registry.total_files = registry.total_files.saturating_sub(1);
+    release_file_count(registry, previous_status, ctx.accounts.user_profile.as_mut())?;
+    registry.total_bytes = registry.total_bytes.saturating_sub(file_record.size);
 
     // Emit event
-    emit!(FileDeleted {
-        file: ctx.accounts.file_record.key(),
+    emit_event!(ctx, FileDeleted {
+        version: EVENT_VERSION,
+        file: file_key,
         owner: file_record.owner,
         timestamp: clock.unix_timestamp,
     });
 
-    msg!(
+    for share in revoked_shares {
+        emit_event!(ctx, ShareRevoked {
+            version: EVENT_VERSION,
+            share,
+            owner: file_record.owner,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    // Wipe metadata under the scrub policy; share keys went with revocation
+    if registry.scrub_on_delete {
+        scrub_record(file_record)?;
+
+        emit_event!(ctx, FileScrubbed {
+            version: EVENT_VERSION,
+            file: file_key,
+            owner: file_record.owner,
+            shares_scrubbed: keys_cleared,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    private_msg!(
+        "File marked as deleted";
         "File marked as deleted: {} by {}",
         file_record.transaction_id,
         file_record.owner
@@ -218,14 +881,645 @@ pub fn delete_handler(ctx: Context<DeleteFile>) -> Result<()> {
     Ok(())
 }
 
+/// Scrub a file record's metadata. The stored bytes are zeroed first so the
+/// old name cannot linger past the shorter serialization written on exit.
+pub(crate) fn scrub_record(file_record: &mut Account<FileRecord>) -> Result<()> {
+    file_record.scrub();
+    file_record.to_account_info().try_borrow_mut_data()?.fill(0);
+
+    Ok(())
+}
+
+/// Clear the encrypted keys and messages of `file`'s share links in
+/// `share_accounts`, returning how many held either
+pub(crate) fn scrub_shares(file: &Pubkey, share_accounts: &[AccountInfo]) -> Result<u32> {
+    let mut scrubbed = 0u32;
+    for share_info in share_accounts {
+        require!(
+            share_info.owner == &crate::ID && share_info.is_writable,
+            HelixError::ShareFileMismatch
+        );
+
+        let mut data = share_info.try_borrow_mut_data()?;
+        let mut share_link = ShareLink::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(share_link.file, *file, HelixError::ShareFileMismatch);
+
+        if share_link.clear_key() {
+            data.fill(0);
+            share_link.try_serialize(&mut &mut data[..])?;
+            scrubbed = scrubbed.saturating_add(1);
+        }
+    }
+
+    Ok(scrubbed)
+}
+
+/// Revoke `file`'s share links in `share_accounts` as part of its deletion,
+/// clearing every encrypted key. Returns the links that were still unrevoked,
+/// the number of them whose share counters are released here, and the number
+/// of keys cleared.
+pub(crate) fn revoke_shares(
+    file: &Pubkey,
+    share_accounts: &[AccountInfo],
+) -> Result<(Vec<Pubkey>, u32, u32)> {
+    let mut revoked = Vec::new();
+    let mut released = 0u32;
+    let mut keys_cleared = 0u32;
+    for share_info in share_accounts {
+        require!(
+            share_info.owner == &crate::ID && share_info.is_writable,
+            HelixError::ShareFileMismatch
+        );
+
+        let mut data = share_info.try_borrow_mut_data()?;
+        let mut share_link = ShareLink::try_deserialize(&mut &data[..])?;
+        require_keys_eq!(share_link.file, *file, HelixError::ShareFileMismatch);
+
+        if share_link.is_revoked()
+            && share_link.encrypted_key.is_none()
+            && share_link.encrypted_message.is_none()
+        {
+            continue;
+        }
+        if !share_link.is_revoked() {
+            share_link.set_revoked(true);
+            revoked.push(share_info.key());
+            if share_link.release_counters() {
+                released = released.saturating_add(1);
+            }
+        }
+        if share_link.clear_key() {
+            keys_cleared = keys_cleared.saturating_add(1);
+        }
+
+        data.fill(0);
+        share_link.try_serialize(&mut &mut data[..])?;
+    }
+
+    Ok((revoked, released, keys_cleared))
+}
+
+/// Accounts required for restoring a deleted file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RestoreFile<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to restore
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner
+    pub owner: Signer<'info>,
+
+    /// The program authority (required for files deleted by the authority)
+    #[account(
+        constraint = authority.key() == registry.authority @ HelixError::UnauthorizedAuthority
+    )]
+    pub authority: Option<Signer<'info>>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for restoring a deleted file
+///
+/// Files force-deleted by the program authority can only be restored
+/// when the authority co-signs the transaction. Restoring is only possible
+/// within the registry's trash retention window.
+pub fn restore_handler(ctx: Context<RestoreFile>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let authority_signed = ctx.accounts.authority.is_some();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate file is deleted and still in the trash
+    require!(file_record.is_deleted(), HelixError::FileNotDeleted);
+    require!(
+        !file_record.retention_expired(registry.trash_retention(), clock.unix_timestamp),
+        HelixError::RetentionExpired
+    );
+
+    // Authority deletions need the authority to sign off on the restore
+    if file_record.is_deleted_by_authority() {
+        require!(authority_signed, HelixError::UnauthorizedAuthority);
+    }
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Clear deletion state
+    file_record.set_status(FileStatus::Active)?;
+    file_record.set_deleted_by_authority(false);
+    file_record.updated_at = clock.unix_timestamp;
+    file_record.deleted_at = 0;
+
+    // Update registry stats
+    registry.total_files = registry
+        .total_files
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.total_bytes = registry
+        .total_bytes
+        .checked_add(file_record.size)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    // Emit event
+    emit_event!(ctx, FileRestored {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File restored";
+        "File restored: {} by {}",
+        file_record.transaction_id,
+        file_record.owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for purging a deleted file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct PurgeFile<'info> {
+    /// The storage registry (for the retention window)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The deleted file record to close
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        close = rent_payer
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner
+    /// CHECK: Must match the owner recorded on the file
+    pub owner: UncheckedAccount<'info>,
+
+    /// The wallet that paid the record's rent (receives it back); the owner
+    /// for records that store no payer
+    /// CHECK: Must match the file's rent recipient
+    #[account(
+        mut,
+        address = file_record.rent_recipient() @ HelixError::RentPayerMismatch
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// The wallet purging the file (the owner or any crank)
+    pub caller: Signer<'info>,
+
+    /// The file's entry in its owner's index (optional, with `previous_entry`)
+    #[account(
+        constraint = index_entry.file == file_record.key() @ HelixError::InvalidIndexEntry
+    )]
+    pub index_entry: Option<Account<'info, OwnerFileEntry>>,
+
+    /// The entry whose skip pointer reaches `index_entry`, re-pointed past it
+    #[account(mut)]
+    pub previous_entry: Option<Account<'info, OwnerFileEntry>>,
+}
+
+/// Handler for purging a deleted file
+///
+/// Closes the record of a file whose trash retention window has ended,
+/// returning its rent to the wallet that paid it. Anyone can purge an expired file; the
+/// owner can purge earlier by passing `force`. Files with active share
+/// links, aliases, mirrors, a mint or an endowment must have them revoked,
+/// removed, detokenized or closed first. Once
+/// purged, the transaction ID can be registered again.
+///
+/// When the file's index entry and the entry pointing at it are supplied,
+/// the purged entry is spliced out of the index's skip chain so listings
+/// no longer visit it.
+///
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `force` - Purge before the retention window ends (owner only)
+///
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn purge_handler(ctx: Context<PurgeFile>, force: bool) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let caller = ctx.accounts.caller.key();
+    let registry = &ctx.accounts.registry;
+    let file_record = &ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate the file can be purged, then splice its index entry out
+    let forced = purge_record(
+        registry,
+        file_record,
+        caller,
+        force,
+        clock.unix_timestamp,
+        ctx.accounts.index_entry.as_ref(),
+        ctx.accounts.previous_entry.as_mut(),
+    )?;
+
+    emit_event!(ctx, FilePurged {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        purged_by: caller,
+        forced,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File purged";
+        "File purged: {} by {}",
+        file_record.transaction_id,
+        caller
+    );
+
+    Ok(())
+}
+
+/// The checks and index bookkeeping shared by `purge_file` and
+/// `close_file`, run before the record is closed. Returns whether the purge
+/// was forced ahead of the retention window, which only the owner can do.
+///
+/// Registry counters are left alone: `delete_file` already released the
+/// file from them, so closing the record must not release it again.
+pub(crate) fn purge_record(
+    registry: &StorageRegistry,
+    file_record: &FileRecord,
+    caller: Pubkey,
+    force: bool,
+    now: i64,
+    index_entry: Option<&Account<OwnerFileEntry>>,
+    previous_entry: Option<&mut Account<OwnerFileEntry>>,
+) -> Result<bool> {
+    let forced = require_purgeable(registry, file_record, caller, force, now)?;
+    unlink_index_entry(index_entry, previous_entry)?;
+    Ok(forced)
+}
+
+/// Check that `caller` can purge the record now, returning whether the purge
+/// is forced ahead of the retention window
+pub(crate) fn require_purgeable(
+    registry: &StorageRegistry,
+    file_record: &FileRecord,
+    caller: Pubkey,
+    force: bool,
+    now: i64,
+) -> Result<bool> {
+    // Validate file is deleted and nothing still points at it
+    require_closable(file_record)?;
+
+    // Only the owner can purge before the retention window ends
+    let forced = !file_record.retention_expired(registry.trash_retention(), now);
+    if forced {
+        require!(force, HelixError::RetentionActive);
+        require_keys_eq!(caller, file_record.owner, HelixError::UnauthorizedOwner);
+    }
+
+    Ok(forced)
+}
+
+/// Check that a record can be closed: the file is deleted and no share
+/// link, alias, mirror, mint or endowment still points at it
+pub(crate) fn require_closable(file_record: &FileRecord) -> Result<()> {
+    require!(file_record.is_deleted(), HelixError::FileNotDeleted);
+    require!(file_record.share_count == 0, HelixError::FileHasActiveShares);
+    require!(file_record.alias_count == 0, HelixError::FileHasAliases);
+    require!(file_record.mirror_count == 0, HelixError::FileHasMirrors);
+    require!(!file_record.is_tokenized(), HelixError::FileAlreadyTokenized);
+    require!(!file_record.is_endowed(), HelixError::FileHasEndowment);
+    Ok(())
+}
+
+/// Splice a closed file's index entry out of the skip chain, when both the
+/// entry and the entry pointing at it are supplied
+fn unlink_index_entry(
+    index_entry: Option<&Account<OwnerFileEntry>>,
+    previous_entry: Option<&mut Account<OwnerFileEntry>>,
+) -> Result<()> {
+    if let (Some(index_entry), Some(previous_entry)) = (index_entry, previous_entry) {
+        require!(
+            previous_entry.owner == index_entry.owner
+                && previous_entry.next_live() == index_entry.seq,
+            HelixError::InvalidIndexEntry
+        );
+        previous_entry.next_live_seq = index_entry.next_live();
+    }
+
+    Ok(())
+}
+
+/// Accounts required for an owner closing a deleted file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CloseFile<'info> {
+    /// The storage registry (for the retention window)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The deleted file record to close
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner,
+        close = rent_payer
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner
+    pub owner: Signer<'info>,
+
+    /// The wallet that paid the record's rent (receives it back); the owner
+    /// for records that store no payer
+    /// CHECK: Must match the file's rent recipient
+    #[account(
+        mut,
+        address = file_record.rent_recipient() @ HelixError::RentPayerMismatch
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// The file's entry in its owner's index (optional, with `previous_entry`)
+    #[account(
+        constraint = index_entry.file == file_record.key() @ HelixError::InvalidIndexEntry
+    )]
+    pub index_entry: Option<Account<'info, OwnerFileEntry>>,
+
+    /// The entry whose skip pointer reaches `index_entry`, re-pointed past it
+    #[account(mut)]
+    pub previous_entry: Option<Account<'info, OwnerFileEntry>>,
+}
+
+/// Handler for closing a deleted file
+///
+/// An owner's forced purge: the record is closed without waiting for the
+/// trash retention window, and its rent goes back to the wallet that paid
+/// it, as with `purge_file`. The same conditions apply: the file must be
+/// deleted, its shares revoked and aliases, mirrors, a mint or an endowment
+/// removed first, or closing fails with the matching error. Registry
+/// counters were already released when the file was deleted, so closing
+/// changes none of them.
+///
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+///
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn close_handler(ctx: Context<CloseFile>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let owner = ctx.accounts.owner.key();
+    let registry = &ctx.accounts.registry;
+    let file_record = &ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate the file can be purged, then splice its index entry out
+    purge_record(
+        registry,
+        file_record,
+        owner,
+        true,
+        clock.unix_timestamp,
+        ctx.accounts.index_entry.as_ref(),
+        ctx.accounts.previous_entry.as_mut(),
+    )?;
+
+    emit_event!(ctx, FileClosed {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner,
+        transaction_id: file_record.transaction_id.clone(),
+        rent_recipient: ctx.accounts.rent_payer.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File closed";
+        "File closed: {} by {}",
+        file_record.transaction_id,
+        owner
+    );
+
+    Ok(())
+}
+
 /// Accounts for querying file info (read-only)
 #[derive(Accounts)]
-#[instruction(transaction_id: String)]
+#[instruction(transaction_id: TxId)]
 pub struct GetFile<'info> {
     /// The file record to query
     #[account(
-        seeds = [FILE_SEED, transaction_id.as_bytes()],
+        seeds = [FILE_SEED, &transaction_id.file_seed()],
         bump = file_record.bump
     )]
-    pub file_record: Account<'info, FileRecord>,
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The MIME table (required only for records with a MIME code)
+    #[account(
+        seeds = [MIME_TABLE_SEED],
+        bump = mime_table.bump
+    )]
+    pub mime_table: Option<Account<'info, MimeTable>>,
+}
+
+/// Return file metadata for a transaction ID
+///
+/// Interned MIME codes are resolved back to strings through the MIME table.
+/// Every mirror of the file must be passed once in `remaining_accounts`, so
+/// the returned list is complete.
+pub fn get_file_handler(ctx: Context<GetFile>, transaction_id: TxId) -> Result<FileInfo> {
+    let file_record = &ctx.accounts.file_record;
+
+    let mut mirrors: Vec<MirrorInfo> = Vec::with_capacity(ctx.remaining_accounts.len());
+    for info in ctx.remaining_accounts {
+        require!(info.owner == &crate::ID, HelixError::MirrorFileMismatch);
+        let mirror = Mirror::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(mirror.file, file_record.key(), HelixError::MirrorFileMismatch);
+        require!(
+            mirrors.iter().all(|m| m.mirror != info.key()),
+            HelixError::MirrorsIncomplete
+        );
+        mirrors.push(MirrorInfo {
+            mirror: info.key(),
+            url: mirror.locator.content_url(mirror.locator.default_gateway()),
+            locator: mirror.locator,
+            added_at: mirror.added_at,
+        });
+    }
+    require!(
+        mirrors.len() == usize::from(file_record.mirror_count),
+        HelixError::MirrorsIncomplete
+    );
+
+    let mime_type = match file_record.mime_code {
+        0 if file_record.is_scrubbed() => SCRUBBED_MIME_TYPE.to_string(),
+        0 => file_record.mime_type.clone(),
+        code => ctx
+            .accounts
+            .mime_table
+            .as_ref()
+            .ok_or(HelixError::MimeTableMissing)?
+            .resolve_code(code)?
+            .to_string(),
+    };
+
+    Ok(FileInfo {
+        version: VIEW_VERSION,
+        owner: file_record.owner,
+        transaction_id: transaction_id.into_inner(),
+        mime_type,
+        size: file_record.size,
+        is_encrypted: file_record.is_encrypted(),
+        is_deleted: file_record.is_deleted(),
+        created_at: file_record.created_at,
+        updated_at: file_record.updated_at,
+        share_count: file_record.share_count,
+        status: file_record.file_status(),
+        like_count: file_record.like_count,
+        size_attested: file_record.is_size_attested(),
+        storage_location: file_record.storage_location,
+        ipfs_cid: file_record.ipfs_cid.clone(),
+        url: file_record.content_url(file_record.default_gateway()),
+        mirrors,
+        owner_seq: file_record.owner_seq,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        sample_file, sample_profile, sample_registry, TestAccount, DAY, NOW, SAMPLE_TX_ID,
+    };
+
+    /// A registry with a one-day trash retention that has counted one file
+    /// of `size` bytes
+    fn registry(size: u64) -> StorageRegistry {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        registry.trash_retention_secs = DAY;
+        record_registration(&mut registry, size).unwrap();
+        registry
+    }
+
+    /// A file deleted at `NOW`, released from `registry` as `delete_file` does
+    fn deleted_file(registry: &mut StorageRegistry, owner: Pubkey) -> FileRecord {
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let previous_status = file_record.file_status();
+        file_record.set_status(FileStatus::Deleted).unwrap();
+        file_record.deleted_at = NOW;
+        release_file_count(registry, previous_status, None).unwrap();
+        registry.total_bytes = registry.total_bytes.saturating_sub(file_record.size);
+        file_record
+    }
+
+    #[test]
+    fn close_is_an_owner_forced_purge() {
+        let owner = Pubkey::new_unique();
+        let mut registry = registry(1024);
+        let file_record = deleted_file(&mut registry, owner);
+
+        // close_file passes the owner and force, so it works inside the window
+        assert_eq!(require_purgeable(&registry, &file_record, owner, true, NOW), Ok(true));
+        assert_eq!(
+            require_purgeable(&registry, &file_record, owner, false, NOW),
+            Err(HelixError::RetentionActive.into())
+        );
+        assert_eq!(
+            require_purgeable(&registry, &file_record, Pubkey::new_unique(), true, NOW),
+            Err(HelixError::UnauthorizedOwner.into())
+        );
+        // After the window anyone can purge, and the purge is not forced
+        assert_eq!(
+            require_purgeable(&registry, &file_record, Pubkey::new_unique(), false, NOW + DAY),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn close_fails_for_live_or_shared_files() {
+        let owner = Pubkey::new_unique();
+        let mut registry = registry(1024);
+        let live = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let mut shared = deleted_file(&mut registry, owner);
+        shared.share_count = 1;
+
+        assert_eq!(
+            require_purgeable(&registry, &live, owner, true, NOW),
+            Err(HelixError::FileNotDeleted.into())
+        );
+        assert_eq!(
+            require_purgeable(&registry, &shared, owner, true, NOW),
+            Err(HelixError::FileHasActiveShares.into())
+        );
+    }
+
+    #[test]
+    fn owner_purge_of_a_sponsored_file_refunds_the_sponsor() {
+        let owner = Pubkey::new_unique();
+        let sponsor = Pubkey::new_unique();
+        let mut registry = registry(1024);
+        let mut file_record = deleted_file(&mut registry, owner);
+        file_record.rent_payer = sponsor;
+
+        // The owner can force the purge, but `PurgeFile` pays the rent to
+        // the recorded sponsor rather than to the owner
+        assert_eq!(require_purgeable(&registry, &file_record, owner, true, NOW), Ok(true));
+        assert_eq!(file_record.rent_recipient(), sponsor);
+    }
+
+    #[test]
+    fn delete_then_close_releases_counters_once() {
+        let owner = Pubkey::new_unique();
+        let mut registry = registry(1024);
+        let file_record = deleted_file(&mut registry, owner);
+        let after_delete = registry.counters();
+
+        purge_record(&registry, &file_record, owner, true, NOW, None, None).unwrap();
+
+        assert_eq!(registry.counters(), after_delete);
+        assert_eq!(after_delete.total_files, 0);
+        assert_eq!(after_delete.total_bytes, 0);
+    }
+
+    #[test]
+    fn owner_seq_is_consecutive_with_a_profile_and_unassigned_without() {
+        let owner = Pubkey::new_unique();
+        let (address, profile) = sample_profile(owner);
+        let mut account = TestAccount::new(address, &profile, UserProfile::LEN);
+        let info = account.info();
+        let mut user_profile = Account::<UserProfile>::try_from(&info).unwrap();
+
+        // Two registrations in one transaction take consecutive numbers
+        let first = assign_owner_seq(Some(&mut user_profile), owner, 0).unwrap();
+        let second = assign_owner_seq(Some(&mut user_profile), owner, 0).unwrap();
+        assert_eq!((first, second), (1, 2));
+
+        assert_eq!(assign_owner_seq(None, owner, 0), Ok(0));
+        assert_eq!(user_profile.last_file_seq, 2);
+    }
 }
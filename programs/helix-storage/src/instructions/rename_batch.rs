@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::error::{validate_optional_byte_length, HelixError};
+use crate::instructions::profile::require_not_frozen;
+use crate::state::{
+    FileRecord, FilesUpdated, StorageRegistry, EVENT_VERSION, MAX_NAME_UPDATES_PER_CALL,
+    REGISTRY_SEED, USER_PROFILE_SEED,
+};
+use crate::types::EncryptedName;
+
+/// Accounts required for rewriting the encrypted names of several files.
+/// 
+/// The file records are passed in `remaining_accounts`, writable, in the
+/// same order as the new names.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateNamesBatch<'info> {
+    /// The storage registry (for the encrypted name limit)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The owner of the files (pays for any extra space)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account resizing
+    pub system_program: Program<'info, System>,
+
+    /// The owner's profile, checked for a freeze
+    /// CHECK: May not exist; deserialized in the handler when it does
+    #[account(
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub owner_profile: UncheckedAccount<'info>,
+}
+
+/// Handler for the update_names_batch instruction
+/// 
+/// Replaces the encrypted name of up to `MAX_NAME_UPDATES_PER_CALL` file
+/// records, exactly like `update_file`. Unlike the other batch instructions,
+/// any invalid entry fails the whole call: after a key rotation a partial
+/// rename would leave names encrypted under two different keys.
+/// 
+/// # Arguments
+/// * `ctx` - The context, with the file records as remaining accounts
+/// * `encrypted_names` - The new encrypted name of each record, in order
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn update_names_batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateNamesBatch<'info>>,
+    encrypted_names: Vec<Option<EncryptedName>>,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let name_limit = ctx.accounts.registry.encrypted_name_limit();
+    let clock = Clock::get()?;
+
+    // Validate the owner's account is not frozen
+    require_not_frozen(&ctx.accounts.owner_profile)?;
+
+    // Validate the batch shape before touching any record
+    require!(
+        encrypted_names.len() == ctx.remaining_accounts.len(),
+        HelixError::BatchLengthMismatch
+    );
+    require!(
+        encrypted_names.len() <= MAX_NAME_UPDATES_PER_CALL,
+        HelixError::TooManyBatchEntries
+    );
+
+    let mut files = Vec::with_capacity(encrypted_names.len());
+
+    for (file_info, encrypted_name) in ctx.remaining_accounts.iter().zip(encrypted_names) {
+        require!(file_info.is_writable, ErrorCode::AccountNotMutable);
+
+        let mut file_record = Account::<FileRecord>::try_from(file_info)?;
+        require_keys_eq!(file_record.owner, owner, HelixError::UnauthorizedOwner);
+        require!(!file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+        require!(file_record.is_accessible(), HelixError::FileNotActive);
+        validate_optional_byte_length(
+            "encrypted_name",
+            &encrypted_name,
+            name_limit,
+            HelixError::EncryptedNameTooLong,
+        )?;
+
+        // Resize to fit the new name before writing it back
+        let space = file_record.resized_space(&encrypted_name);
+        resize_record(
+            file_info,
+            space,
+            &ctx.accounts.owner.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        file_record.encrypted_name = encrypted_name.map(String::from);
+        file_record.updated_at = clock.unix_timestamp;
+        file_record.exit(&crate::ID)?;
+
+        files.push(file_info.key());
+    }
+
+    private_msg!("Batch rename processed"; "Renamed {} files for {}", files.len(), owner);
+
+    emit_event!(ctx, FilesUpdated {
+        version: EVENT_VERSION,
+        owner,
+        files,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// Resize a file record, topping up rent from the owner when it grows and
+/// refunding the excess to the owner when it shrinks, like Anchor's
+/// `realloc` constraint on `UpdateFile`.
+fn resize_record<'info>(
+    record: &AccountInfo<'info>,
+    space: usize,
+    owner: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if space == record.data_len() {
+        return Ok(());
+    }
+
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    let balance = record.lamports();
+    if rent_exempt > balance {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: owner.clone(),
+                    to: record.clone(),
+                },
+            ),
+            rent_exempt - balance,
+        )?;
+    } else if space < record.data_len() {
+        let refund = balance - rent_exempt;
+        **record.try_borrow_mut_lamports()? -= refund;
+        **owner.try_borrow_mut_lamports()? += refund;
+    }
+
+    record.realloc(space, false)?;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    FileRecord, FileRestored, FileStatus, FilesRestoredBatch, StorageRegistry, EVENT_VERSION,
+    MAX_RESTORES_PER_CALL, REGISTRY_SEED,
+};
+
+/// Accounts required for restoring several deleted files at once.
+/// 
+/// The deleted file records are passed in `remaining_accounts`, writable.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RestoreFilesBatch<'info> {
+    /// The storage registry (for stats and the retention window)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The owner of the files
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the restore_files_batch instruction
+/// 
+/// Restores up to `MAX_RESTORES_PER_CALL` deleted file records owned by the
+/// signer, exactly like `restore_file`. Records that are not writable file
+/// records, belong to another wallet, are not deleted, were deleted by the
+/// program authority or are past the trash retention window are skipped
+/// without failing and listed in the `FilesRestoredBatch` summary.
+/// 
+/// # Arguments
+/// * `ctx` - The context, with deleted file records as remaining accounts
+/// 
+/// # Returns
+/// * `Result<u32>` - Number of files restored
+pub fn restore_files_batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RestoreFilesBatch<'info>>,
+) -> Result<u32> {
+    let owner = ctx.accounts.owner.key();
+    let registry = &mut ctx.accounts.registry;
+    let retention_secs = registry.trash_retention();
+    let clock = Clock::get()?;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    let mut restored: u32 = 0;
+    let mut skipped = Vec::new();
+
+    for file_info in ctx.remaining_accounts.iter().take(MAX_RESTORES_PER_CALL) {
+        if !file_info.is_writable {
+            skipped.push(file_info.key());
+            continue;
+        }
+
+        let Ok(mut file_record) = Account::<FileRecord>::try_from(file_info) else {
+            skipped.push(file_info.key());
+            continue;
+        };
+
+        if file_record.owner != owner
+            || !file_record.is_deleted()
+            || file_record.is_deleted_by_authority()
+            || file_record.retention_expired(retention_secs, clock.unix_timestamp)
+        {
+            skipped.push(file_info.key());
+            continue;
+        }
+
+        // Clear deletion state
+        file_record.set_status(FileStatus::Active)?;
+        file_record.updated_at = clock.unix_timestamp;
+        file_record.deleted_at = 0;
+
+        // Update registry stats
+        registry.total_files = registry
+            .total_files
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        registry.total_bytes = registry
+            .total_bytes
+            .checked_add(file_record.size)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+
+        file_record.exit(&crate::ID)?;
+
+        emit_event!(ctx, FileRestored {
+            version: EVENT_VERSION,
+            file: file_record.key(),
+            owner,
+            timestamp: clock.unix_timestamp,
+        });
+
+        restored = restored
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+    }
+
+    emit_event!(ctx, FilesRestoredBatch {
+        version: EVENT_VERSION,
+        owner,
+        restored,
+        skipped,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Batch restore processed"; "Restored {} files for {}", restored, owner);
+
+    Ok(restored)
+}
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    SessionCreated, SessionKey, SessionRevoked, StorageRegistry, EVENT_VERSION, REGISTRY_SEED,
+    SESSION_SCOPE_ALL, SESSION_SEED,
+};
+
+/// Accounts required for creating a session key
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(session_pubkey: Pubkey)]
+pub struct CreateSession<'info> {
+    /// The storage registry (for the session lifetime cap)
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The session account to create (PDA derived from wallet and session key)
+    #[account(
+        init,
+        payer = wallet,
+        space = SessionKey::LEN,
+        seeds = [SESSION_SEED, wallet.key().as_ref(), session_pubkey.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+
+    /// The wallet delegating to the session key (payer)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the create_session instruction
+/// 
+/// Authorizes an ephemeral keypair to act for the wallet within `scope`
+/// until `expires_at`, which is capped by the registry's session lifetime.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `session_pubkey` - The ephemeral public key
+/// * `expires_at` - Unix timestamp when the session expires
+/// * `scope` - Bitmask of `SESSION_SCOPE_*` actions
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_session_handler(
+    ctx: Context<CreateSession>,
+    session_pubkey: Pubkey,
+    expires_at: i64,
+    scope: u8,
+) -> Result<()> {
+    let session_account = ctx.accounts.session.key();
+    let registry = &ctx.accounts.registry;
+    let session = &mut ctx.accounts.session;
+    let clock = Clock::get()?;
+
+    // Validate scope and lifetime
+    require!(
+        scope != 0 && scope & !SESSION_SCOPE_ALL == 0,
+        HelixError::InvalidSessionScope
+    );
+    let max_expires_at = clock
+        .unix_timestamp
+        .checked_add(registry.session_lifetime_limit())
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    require!(
+        expires_at > clock.unix_timestamp && expires_at <= max_expires_at,
+        HelixError::InvalidSessionExpiry
+    );
+
+    session.wallet = ctx.accounts.wallet.key();
+    session.session_key = session_pubkey;
+    session.expires_at = expires_at;
+    session.scope = scope;
+    session.created_at = clock.unix_timestamp;
+    session.bump = ctx.bumps.session;
+    session._reserved = [0u8; 16];
+
+    emit_event!(ctx, SessionCreated {
+        version: EVENT_VERSION,
+        session: session_account,
+        wallet: session.wallet,
+        session_key: session_pubkey,
+        expires_at,
+        scope,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Session created";
+        "Session created for {} until {}",
+        session.wallet,
+        expires_at
+    );
+
+    Ok(())
+}
+
+/// Accounts required for revoking a session key
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct RevokeSession<'info> {
+    /// The session account to close
+    #[account(
+        mut,
+        seeds = [SESSION_SEED, wallet.key().as_ref(), session.session_key.as_ref()],
+        bump = session.bump,
+        has_one = wallet @ HelixError::UnauthorizedOwner,
+        close = wallet
+    )]
+    pub session: Account<'info, SessionKey>,
+
+    /// The wallet that created the session (receives the rent)
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+}
+
+/// Handler for the revoke_session instruction
+/// 
+/// Closes the session account, returning its rent to the wallet.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn revoke_session_handler(ctx: Context<RevokeSession>) -> Result<()> {
+    let session_account = ctx.accounts.session.key();
+    let wallet = ctx.accounts.wallet.key();
+    let clock = Clock::get()?;
+
+    emit_event!(ctx, SessionRevoked {
+        version: EVENT_VERSION,
+        session: session_account,
+        wallet,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Session revoked"; "Session {} revoked by {}", session_account, wallet);
+
+    Ok(())
+}
@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, CreateAccount};
+
+use crate::error::HelixError;
+use crate::instructions::profile::{load_profile, require_not_frozen};
+use crate::state::{
+    FileRecord, ShareBatchSettings, ShareLink, SharesBatchCreated, StorageRegistry, EVENT_VERSION,
+    MAX_SHARES_PER_BATCH, REGISTRY_SEED, SHARE_SEED, USER_PROFILE_SEED,
+};
+use crate::types::EncryptedKey;
+
+/// Accounts required for sharing several files with one recipient.
+/// 
+/// `(file_record, share_link)` pairs are passed in `remaining_accounts`,
+/// both writable, in the same order as the encrypted keys. Each share link
+/// is the uninitialized share PDA the file would get from `create_share`.
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(recipient: Pubkey)]
+pub struct CreateSharesBatch<'info> {
+    /// The storage registry (for stats and share addresses)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The owner of the files (payer)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's profile, checked for a freeze
+    /// CHECK: May not exist; deserialized in the handler when it does
+    #[account(
+        seeds = [USER_PROFILE_SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub owner_profile: UncheckedAccount<'info>,
+
+    /// The recipient's profile, for the key generation the keys were wrapped to
+    /// CHECK: May not exist; deserialized in the handler when it does
+    #[account(
+        seeds = [USER_PROFILE_SEED, recipient.as_ref()],
+        bump
+    )]
+    pub recipient_profile: UncheckedAccount<'info>,
+}
+
+/// Handler for the create_shares_batch instruction
+/// 
+/// Shares up to `MAX_SHARES_PER_BATCH` files with one recipient under the
+/// same limits, validating and counting each share like `create_share`.
+/// Share addresses follow the usual scheme, the file key and the registry's
+/// running share count, so each pair's share link must be the PDA for the
+/// count at its position in the batch. Any deleted or unshareable file,
+/// mismatched address or frozen owner fails the whole batch, so a recipient
+/// never ends up with part of a set.
+/// 
+/// Batch shares skip the recipient's inbox; `SharesBatchCreated` lists them
+/// instead of one `ShareCreated` per share.
+/// 
+/// # Arguments
+/// * `ctx` - The context, with `(file_record, share_link)` pairs as remaining accounts
+/// * `recipient` - The wallet every share is locked to
+/// * `settings` - Expiration and download limit of every share
+/// * `encrypted_keys` - Each file's decryption key wrapped for the recipient, in order
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_shares_batch_handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateSharesBatch<'info>>,
+    recipient: Pubkey,
+    settings: ShareBatchSettings,
+    encrypted_keys: Vec<EncryptedKey>,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let clock = Clock::get()?;
+
+    // Validate the owner's account is not frozen
+    require_not_frozen(&ctx.accounts.owner_profile)?;
+
+    // Validate the batch shape before creating anything
+    require!(
+        ctx.remaining_accounts.len() == encrypted_keys.len() * 2,
+        HelixError::BatchLengthMismatch
+    );
+    require!(
+        encrypted_keys.len() <= MAX_SHARES_PER_BATCH,
+        HelixError::TooManyBatchEntries
+    );
+
+    // Validate the shared limits
+    if let Some(exp) = settings.expires_at {
+        require!(exp > clock.unix_timestamp, HelixError::ExpirationInPast);
+    }
+    if let Some(max) = settings.max_downloads {
+        require!(max > 0, HelixError::InvalidMaxDownloads);
+    }
+
+    // Record the generation of the key the encrypted keys were wrapped to
+    let recipient_key_generation = match load_profile(&ctx.accounts.recipient_profile)? {
+        Some(profile) if profile.encryption_pubkey.is_some() => profile.key_generation,
+        _ => 0,
+    };
+
+    let registry = &mut ctx.accounts.registry;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    let rent = Rent::get()?.minimum_balance(ShareLink::LEN);
+    let mut shares = Vec::with_capacity(encrypted_keys.len());
+
+    for (pair, encrypted_key) in ctx.remaining_accounts.chunks_exact(2).zip(encrypted_keys) {
+        let (file_info, share_info) = (&pair[0], &pair[1]);
+        require!(file_info.is_writable, ErrorCode::AccountNotMutable);
+
+        // Validate the file like create_share
+        let mut file_record = Account::<FileRecord>::try_from(file_info)?;
+        require_keys_eq!(file_record.owner, owner, HelixError::UnauthorizedOwner);
+        require!(!file_record.is_deleted(), HelixError::CannotShareDeletedFile);
+        require!(file_record.is_accessible(), HelixError::FileNotActive);
+        registry.require_share_capacity(file_record.share_count)?;
+
+        // Create the share link at the PDA of the file's share nonce
+        let file_key = file_record.key();
+        let nonce = file_record.share_seed();
+        let (share_key, bump) = Pubkey::find_program_address(
+            &[SHARE_SEED, file_key.as_ref(), &nonce],
+            &crate::ID,
+        );
+        require_keys_eq!(share_info.key(), share_key, HelixError::ShareAddressMismatch);
+
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                CreateAccount {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: share_info.clone(),
+                },
+                &[&[SHARE_SEED, file_key.as_ref(), &nonce, &[bump]]],
+            ),
+            rent,
+            ShareLink::LEN as u64,
+            &crate::ID,
+        )?;
+
+        let share_link = ShareLink {
+            file: file_key,
+            owner,
+            recipient: Some(recipient),
+            encrypted_key: Some(encrypted_key.into_inner()),
+            expires_at: settings.expires_at,
+            max_downloads: settings.max_downloads,
+            download_count: 0,
+            flags: 0,
+            created_at: clock.unix_timestamp,
+            bump,
+            recipient_key_generation,
+            bytes_served: 0,
+            recipient_commitment: None,
+            encrypted_message: None,
+            claim_hash: None,
+            rent_payer: owner,
+            last_downloader: Pubkey::default(),
+            last_download_at: 0,
+            _reserved: [0u8; 4],
+        };
+        share_link.try_serialize(&mut &mut share_info.try_borrow_mut_data()?[..])?;
+
+        // Update file record share count
+        file_record.share_count = file_record
+            .share_count
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        file_record.advance_share_nonce()?;
+        file_record.exit(&crate::ID)?;
+
+        // Update registry stats
+        registry.total_shares = registry
+            .total_shares
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        registry.current_epoch_stats.shares = registry
+            .current_epoch_stats
+            .shares
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+
+        shares.push(share_key);
+    }
+
+    private_msg!(
+        "Share batch created";
+        "Created {} shares for {} by {}",
+        shares.len(),
+        recipient,
+        owner
+    );
+
+    emit_event!(ctx, SharesBatchCreated {
+        version: EVENT_VERSION,
+        owner,
+        recipient,
+        shares,
+        expires_at: settings.expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,264 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::instructions::treasury::charge_fee;
+use crate::state::{
+    FileRecord, ShareLink, ShareStatus, ShortCode, ShortCodeClaimed, ShortCodeReleased,
+    StorageRegistry, Treasury, UserProfile, EVENT_VERSION, REGISTRY_SEED, SHORT_CODE_SEED,
+    TREASURY_SEED, USER_PROFILE_SEED,
+};
+use crate::types::Slug;
+
+/// Accounts required for claiming a short code
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(code: Slug)]
+pub struct ClaimShortCode<'info> {
+    /// The storage registry (for the claim fee and fee totals)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The public share link the code points to
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The shared file record (for the share's status)
+    #[account(address = share_link.file @ HelixError::ShareFileMismatch)]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The short code account to create (PDA derived from the code)
+    #[account(
+        init,
+        payer = claimer,
+        space = ShortCode::LEN,
+        seeds = [SHORT_CODE_SEED, code.as_bytes()],
+        bump
+    )]
+    pub short_code: Account<'info, ShortCode>,
+
+    /// The treasury receiving the claim fee (required only when a fee is set)
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Option<Account<'info, Treasury>>,
+
+    /// The share owner claiming the code (pays the fee and rent)
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+
+    /// System program for account creation and the fee transfer
+    pub system_program: Program<'info, System>,
+
+    /// The claimer's profile (created on first use), recording the fee
+    /// paid. Required only when a fee is set.
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        space = UserProfile::LEN,
+        seeds = [USER_PROFILE_SEED, claimer.key().as_ref()],
+        bump
+    )]
+    pub claimer_profile: Option<Account<'info, UserProfile>>,
+}
+
+/// Handler for the claim_short_code instruction
+/// 
+/// Claims a global short code for one of the claimer's active public share
+/// links, first come first served, paying the registry's short code fee to
+/// the treasury.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `code` - The code to claim
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn claim_short_code_handler(ctx: Context<ClaimShortCode>, code: Slug) -> Result<()> {
+    let claimer = ctx.accounts.claimer.key();
+    let share_key = ctx.accounts.share_link.key();
+    let short_code_key = ctx.accounts.short_code.key();
+    let fee_lamports = ctx.accounts.registry.short_code_fee_lamports;
+    let share_link = &ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate the share is the claimer's, public and usable
+    require_keys_eq!(share_link.owner, claimer, HelixError::UnauthorizedOwner);
+    require!(share_link.is_public(), HelixError::ShareNotPublic);
+    share_link
+        .status(clock.unix_timestamp, ctx.accounts.file_record.is_deleted())
+        .require_active()?;
+
+    // Pay the claim fee
+    charge_fee(
+        fee_lamports,
+        &mut ctx.accounts.registry,
+        &ctx.accounts.claimer,
+        ctx.accounts.claimer_profile.as_mut(),
+        ctx.bumps.claimer_profile,
+        ctx.accounts.treasury.as_ref(),
+        &ctx.accounts.system_program,
+    )?;
+
+    // Initialize short code
+    let short_code = &mut ctx.accounts.short_code;
+    short_code.code = code.into_inner();
+    short_code.share = share_key;
+    short_code.claimer = claimer;
+    short_code.created_at = clock.unix_timestamp;
+    short_code.bump = ctx.bumps.short_code;
+    short_code._reserved = [0u8; 16];
+
+    emit_event!(ctx, ShortCodeClaimed {
+        version: EVENT_VERSION,
+        short_code: short_code_key,
+        code: short_code.code.clone(),
+        share: share_key,
+        claimer,
+        fee_lamports,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Short code claimed";
+        "Short code {} claimed by {}",
+        short_code.code,
+        claimer
+    );
+
+    Ok(())
+}
+
+/// Accounts required for releasing a short code
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReleaseShortCode<'info> {
+    /// The short code account to close
+    #[account(
+        mut,
+        seeds = [SHORT_CODE_SEED, short_code.code.as_bytes()],
+        bump = short_code.bump,
+        has_one = claimer @ HelixError::UnauthorizedOwner,
+        close = claimer
+    )]
+    pub short_code: Account<'info, ShortCode>,
+
+    /// The wallet that claimed the code (receives the rent)
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+}
+
+/// Handler for the release_short_code instruction
+/// 
+/// Closes the short code, returning its rent to the claimer and freeing the
+/// code for anyone to claim. The claim fee is not refunded.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn release_short_code_handler(ctx: Context<ReleaseShortCode>) -> Result<()> {
+    let claimer = ctx.accounts.claimer.key();
+    let short_code_key = ctx.accounts.short_code.key();
+    let short_code = &ctx.accounts.short_code;
+    let clock = Clock::get()?;
+
+    emit_event!(ctx, ShortCodeReleased {
+        version: EVENT_VERSION,
+        short_code: short_code_key,
+        code: short_code.code.clone(),
+        share: short_code.share,
+        claimer,
+        released_by: claimer,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Short code released";
+        "Short code {} released by {}",
+        short_code.code,
+        claimer
+    );
+
+    Ok(())
+}
+
+/// Accounts required for reaping a short code whose share is no longer usable
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ReapShortCode<'info> {
+    /// The short code account to close
+    #[account(
+        mut,
+        seeds = [SHORT_CODE_SEED, short_code.code.as_bytes()],
+        bump = short_code.bump,
+        has_one = claimer,
+        close = claimer
+    )]
+    pub short_code: Account<'info, ShortCode>,
+
+    /// The share the code points to
+    /// CHECK: May already be closed; its state is checked in the handler
+    #[account(address = short_code.share)]
+    pub share_link: UncheckedAccount<'info>,
+
+    /// The wallet that claimed the code (receives the rent)
+    /// CHECK: Must match the claimer recorded on the code
+    #[account(mut)]
+    pub claimer: UncheckedAccount<'info>,
+
+    /// The wallet reaping the code (any crank)
+    pub caller: Signer<'info>,
+}
+
+/// Handler for the reap_short_code instruction
+/// 
+/// Permissionlessly closes a short code whose share has been revoked,
+/// expired, used up or closed, returning the rent to the claimer and freeing
+/// the code for anyone to claim.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn reap_short_code_handler(ctx: Context<ReapShortCode>) -> Result<()> {
+    let caller = ctx.accounts.caller.key();
+    let short_code_key = ctx.accounts.short_code.key();
+    let share_info = ctx.accounts.share_link.to_account_info();
+    let short_code = &ctx.accounts.short_code;
+    let clock = Clock::get()?;
+
+    // Validate the share is no longer usable or no longer a share account
+    if share_info.owner == &crate::ID {
+        let data = share_info.try_borrow_data()?;
+        if let Ok(share_link) = ShareLink::try_deserialize(&mut &data[..]) {
+            require!(
+                matches!(
+                    share_link.status(clock.unix_timestamp, false),
+                    ShareStatus::Revoked | ShareStatus::Expired | ShareStatus::Exhausted
+                ),
+                HelixError::ShareStillActive
+            );
+        }
+    }
+
+    emit_event!(ctx, ShortCodeReleased {
+        version: EVENT_VERSION,
+        short_code: short_code_key,
+        code: short_code.code.clone(),
+        share: short_code.share,
+        claimer: short_code.claimer,
+        released_by: caller,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Short code reaped"; "Short code {} reaped by {}", short_code.code, caller);
+
+    Ok(())
+}
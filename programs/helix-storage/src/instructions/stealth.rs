@@ -0,0 +1,501 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::instructions::create_share::{release_share_counters, revoke_and_clear_key};
+use crate::instructions::register_file::{
+    record_registration, revoke_shares, scrub_record, validate_registration,
+};
+use crate::state::{
+    owner_commitment, FileDeleted, FileRecord, FileRegistered, FileScrubbed, FileStatus,
+    ShareCreated, ShareLink, ShareRevoked, StorageLocation, StorageRegistry, EVENT_VERSION,
+    FILE_FLAG_STEALTH, FILE_SEED, REGISTRY_SEED, SHARE_SEED,
+};
+use crate::types::{EncryptedKey, EncryptedName, MimeType, TxId};
+
+/// Accounts required for registering a file under an owner commitment
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(transaction_id: TxId, encrypted_name: Option<EncryptedName>)]
+pub struct RegisterFileStealth<'info> {
+    /// The storage registry (for validation and stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to create (PDA derived from tx_id)
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = FileRecord::init_space(file_record, FileRecord::space(&encrypted_name)),
+        seeds = [FILE_SEED, &transaction_id.file_seed()],
+        bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file owner (payer); only its commitment is stored
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the register_file_stealth instruction
+/// 
+/// Same as `register_file`, but the record's `owner` is the commitment
+/// `owner_commitment(owner, salt)` rather than the owner's wallet, so the
+/// record cannot be found by filtering on the wallet. Owner-gated actions on
+/// the record go through the `*_stealth` instructions, which take the salt.
+/// 
+/// The wallet still signs and pays for these transactions; the commitment
+/// only keeps it out of account state and events.
+/// Stealth records take no `owner_seq` from the wallet's profile, which
+/// would link them to it.
+/// 
+/// # Arguments
+/// * `ctx` - The RegisterFileStealth context
+/// * `transaction_id` - The Arweave transaction ID (43 chars)
+/// * `encrypted_name` - Optional client-encrypted filename
+/// * `mime_type` - The file's MIME type
+/// * `size` - File size in bytes
+/// * `is_encrypted` - Whether the file content is encrypted
+/// * `salt` - Client-chosen salt of the owner commitment
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn register_stealth_handler(
+    ctx: Context<RegisterFileStealth>,
+    transaction_id: TxId,
+    encrypted_name: Option<EncryptedName>,
+    mime_type: MimeType,
+    size: u64,
+    is_encrypted: bool,
+    salt: [u8; 32],
+) -> Result<()> {
+    let commitment = owner_commitment(&ctx.accounts.owner.key(), &salt);
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate inputs
+    validate_registration(registry, file_record, &encrypted_name, size)?;
+
+    // Initialize file record
+    file_record.owner = commitment;
+    file_record.encrypted_name = encrypted_name.map(String::from);
+    file_record.mime_type = mime_type.into_inner();
+    file_record.size = size;
+    file_record.flags = FILE_FLAG_STEALTH;
+    file_record.set_encrypted(is_encrypted);
+    file_record.created_at = clock.unix_timestamp;
+    file_record.updated_at = clock.unix_timestamp;
+    file_record.share_count = 0;
+    file_record.bump = ctx.bumps.file_record;
+    file_record.mime_code = 0;
+    file_record.deleted_at = 0;
+    file_record.alias_count = 0;
+    file_record.reference_count = 0;
+    file_record.bytes_served = 0;
+    file_record.attestation_count = 0;
+    file_record.failed_challenges = 0;
+    file_record.copyright_holder = Pubkey::default();
+    file_record.copyright_attested_at = 0;
+    file_record.derived_from = None;
+    file_record.derivative_count = 0;
+    file_record.chunk_root = None;
+    file_record.status = FileStatus::Active;
+    file_record.like_count = 0;
+    file_record.storage_location = StorageLocation::ArweaveL1;
+    file_record.ipfs_cid = None;
+    file_record.mirror_count = 0;
+    file_record.rent_payer = Pubkey::default();
+    file_record.pending_owner = None;
+    file_record.transfer_expires_at = 0;
+    file_record.owner_seq = 0;
+    file_record.share_nonce = 0;
+    file_record._reserved = [0u8; 1];
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Update registry stats
+    record_registration(registry, size)?;
+
+    // Emit the event (naming the commitment), then move the transaction ID
+    // into the record
+    let event = FileRegistered {
+        version: EVENT_VERSION,
+        owner: commitment,
+        transaction_id: transaction_id.into_inner(),
+        size,
+        is_encrypted,
+        timestamp: clock.unix_timestamp,
+        storage_location: file_record.storage_location,
+        owner_seq: file_record.owner_seq,
+    };
+    emit_event!(ctx, event);
+    file_record.transaction_id = event.transaction_id;
+
+    log_msg!(
+        "File registered";
+        "File registered: {} by commitment {}",
+        file_record.transaction_id,
+        commitment
+    );
+
+    Ok(())
+}
+
+/// Accounts required for deleting a stealth file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(salt: [u8; 32])]
+pub struct DeleteFileStealth<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record to mark as deleted
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        constraint = file_record.owner == owner_commitment(&owner.key(), &salt)
+            @ HelixError::OwnerCommitmentMismatch
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The wallet behind the owner commitment
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the delete_file_stealth instruction
+/// 
+/// Same as `delete_file` for a record registered with
+/// `register_file_stealth`, including share revocation through
+/// `remaining_accounts` and the registry's scrub policy.
+/// 
+/// # Arguments
+/// * `ctx` - The DeleteFileStealth context
+/// * `salt` - Salt of the record's owner commitment
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn delete_stealth_handler(ctx: Context<DeleteFileStealth>, _salt: [u8; 32]) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    // Validate file is not already deleted
+    require!(!file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+
+    // Revoke the supplied share links; none may be left live
+    let (revoked_shares, released, keys_cleared) =
+        revoke_shares(&file_key, ctx.remaining_accounts)?;
+    file_record.share_count = file_record.share_count.saturating_sub(released);
+    registry.total_shares = registry.total_shares.saturating_sub(u64::from(released));
+    require!(file_record.share_count == 0, HelixError::FileHasActiveShares);
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Mark as deleted
+    file_record.set_status(FileStatus::Deleted)?;
+    file_record.updated_at = clock.unix_timestamp;
+    file_record.deleted_at = clock.unix_timestamp;
+
+    // Update registry stats
+    registry.total_files = registry.total_files.saturating_sub(1);
+    registry.total_bytes = registry.total_bytes.saturating_sub(file_record.size);
+
+    emit_event!(ctx, FileDeleted {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner: file_record.owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    for share in revoked_shares {
+        emit_event!(ctx, ShareRevoked {
+            version: EVENT_VERSION,
+            share,
+            owner: file_record.owner,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    // Wipe metadata under the scrub policy; share keys went with revocation
+    if registry.scrub_on_delete {
+        scrub_record(file_record)?;
+
+        emit_event!(ctx, FileScrubbed {
+            version: EVENT_VERSION,
+            file: file_key,
+            owner: file_record.owner,
+            shares_scrubbed: keys_cleared,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+
+    private_msg!(
+        "File marked as deleted";
+        "File marked as deleted: {} by commitment {}",
+        file_record.transaction_id,
+        file_record.owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for creating a share link of a stealth file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(salt: [u8; 32])]
+pub struct CreateShareStealth<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file being shared
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        constraint = file_record.owner == owner_commitment(&owner.key(), &salt)
+            @ HelixError::OwnerCommitmentMismatch
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The share link to create (PDA)
+    #[account(
+        init,
+        payer = owner,
+        space = ShareLink::LEN,
+        seeds = [
+            SHARE_SEED,
+            file_record.key().as_ref(),
+            &file_record.share_seed()
+        ],
+        bump
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The wallet behind the owner commitment (payer)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the create_share_stealth instruction
+/// 
+/// Same as `create_share` for a record registered with
+/// `register_file_stealth`; the share's `owner` is the same commitment.
+/// Stealth shares are not delivered to the recipient's inbox, whose entries
+/// record the paying wallet.
+/// 
+/// # Arguments
+/// * `ctx` - The CreateShareStealth context
+/// * `salt` - Salt of the record's owner commitment
+/// * `recipient` - Optional specific wallet that can access (None = public)
+/// * `expires_at` - Optional Unix timestamp for expiration
+/// * `max_downloads` - Optional maximum download count
+/// * `encrypted_key` - Encrypted decryption key for the recipient
+/// * `recipient_commitment` - Commitment to the recipient, used instead of
+///   `recipient` to keep the recipient off-chain
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_share_stealth_handler(
+    ctx: Context<CreateShareStealth>,
+    _salt: [u8; 32],
+    recipient: Option<Pubkey>,
+    expires_at: Option<i64>,
+    max_downloads: Option<u32>,
+    encrypted_key: Option<EncryptedKey>,
+    recipient_commitment: Option<[u8; 32]>,
+) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate file is not deleted and below its share limit
+    require!(!file_record.is_deleted(), HelixError::CannotShareDeletedFile);
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+    registry.require_share_capacity(file_record.share_count)?;
+
+    // Validate the share is locked to a recipient or a commitment, not both
+    require!(
+        recipient.is_none() || recipient_commitment.is_none(),
+        HelixError::RecipientModeConflict
+    );
+
+    // Validate expiration if provided
+    if let Some(exp) = expires_at {
+        require!(exp > clock.unix_timestamp, HelixError::ExpirationInPast);
+    }
+
+    // Validate max downloads if provided
+    if let Some(max) = max_downloads {
+        require!(max > 0, HelixError::InvalidMaxDownloads);
+    }
+
+    // Initialize share link under the record's commitment
+    share_link.file = file_record.key();
+    share_link.owner = file_record.owner;
+    share_link.recipient = recipient;
+    share_link.encrypted_key = encrypted_key.map(String::from);
+    share_link.expires_at = expires_at;
+    share_link.max_downloads = max_downloads;
+    share_link.download_count = 0;
+    share_link.flags = 0;
+    share_link.created_at = clock.unix_timestamp;
+    share_link.bump = ctx.bumps.share_link;
+    share_link.recipient_key_generation = 0;
+    share_link.bytes_served = 0;
+    share_link.recipient_commitment = recipient_commitment;
+    share_link.encrypted_message = None;
+    share_link.claim_hash = None;
+    share_link.rent_payer = Pubkey::default();
+    share_link.last_downloader = Pubkey::default();
+    share_link.last_download_at = 0;
+    share_link._reserved = [0u8; 4];
+
+    // Update file record share count
+    file_record.share_count = file_record
+        .share_count
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    file_record.advance_share_nonce()?;
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Update registry stats
+    registry.total_shares = registry
+        .total_shares
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.current_epoch_stats.shares = registry
+        .current_epoch_stats
+        .shares
+        .checked_add(1)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, ShareCreated {
+        version: EVENT_VERSION,
+        file: file_record.key(),
+        owner: share_link.owner,
+        recipient,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+        has_message: false,
+        template_id: None,
+    });
+
+    private_msg!(
+        "Share link created";
+        "Share link created for file {} by commitment {}",
+        file_record.transaction_id,
+        share_link.owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for revoking a share link of a stealth file record
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(salt: [u8; 32])]
+pub struct RevokeShareStealth<'info> {
+    /// The storage registry (for stats)
+    #[account(
+        mut,
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The file record
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The share link to revoke
+    #[account(
+        mut,
+        constraint = share_link.owner == owner_commitment(&owner.key(), &salt)
+            @ HelixError::OwnerCommitmentMismatch,
+        constraint = share_link.file == file_record.key() @ HelixError::ShareFileMismatch
+    )]
+    pub share_link: Box<Account<'info, ShareLink>>,
+
+    /// The wallet behind the owner commitment
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the revoke_share_stealth instruction
+/// 
+/// Same as `revoke_share` for a share created with `create_share_stealth`.
+/// 
+/// # Arguments
+/// * `ctx` - The RevokeShareStealth context
+/// * `salt` - Salt of the share's owner commitment
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn revoke_share_stealth_handler(
+    ctx: Context<RevokeShareStealth>,
+    _salt: [u8; 32],
+) -> Result<()> {
+    let share_key = ctx.accounts.share_link.key();
+    let registry = &mut ctx.accounts.registry;
+    let file_record = &mut ctx.accounts.file_record;
+    let share_link = &mut ctx.accounts.share_link;
+    let clock = Clock::get()?;
+
+    // Validate share is not already revoked
+    require!(!share_link.is_revoked(), HelixError::ShareRevoked);
+
+    // Roll epoch stats forward if a new epoch has started
+    roll_epoch!(ctx, registry, clock);
+
+    // Mark as revoked and drop the wrapped key
+    revoke_and_clear_key(share_link)?;
+
+    // Update file record share count and registry stats
+    release_share_counters(share_link, file_record, registry);
+
+    emit_event!(ctx, ShareRevoked {
+        version: EVENT_VERSION,
+        share: share_key,
+        owner: share_link.owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Share link revoked";
+        "Share link revoked: {} by commitment {}",
+        share_key,
+        share_link.owner
+    );
+
+    Ok(())
+}
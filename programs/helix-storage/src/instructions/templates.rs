@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{
+    ShareTemplate, ShareTemplateCreated, ShareTemplateDeleted, ShareTemplateSettings,
+    ShareTemplateUpdated, EVENT_VERSION, SHARE_TEMPLATE_SEED,
+};
+use crate::types::DisplayName;
+
+/// Accounts required for creating a share template
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+#[instruction(template_id: u32)]
+pub struct CreateTemplate<'info> {
+    /// The template account to create (PDA derived from owner and ID)
+    #[account(
+        init,
+        payer = owner,
+        space = ShareTemplate::LEN,
+        seeds = [SHARE_TEMPLATE_SEED, owner.key().as_ref(), &template_id.to_le_bytes()],
+        bump
+    )]
+    pub template: Account<'info, ShareTemplate>,
+
+    /// The template owner (pays for the template)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the create_template instruction
+/// 
+/// Stores a named bundle of share settings that `create_share` applies when
+/// the owner passes the template and `use_template`.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `template_id` - Client-chosen ID of the template
+/// * `name` - Public label shown by clients
+/// * `settings` - The settings shares created from the template receive
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_template_handler(
+    ctx: Context<CreateTemplate>,
+    template_id: u32,
+    name: DisplayName,
+    settings: ShareTemplateSettings,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let template = &mut ctx.accounts.template;
+    let clock = Clock::get()?;
+
+    require!(settings.is_valid(), HelixError::InvalidShareTemplate);
+
+    template.owner = owner;
+    template.template_id = template_id;
+    template.name = name.into_inner();
+    template.settings = settings;
+    template.created_at = clock.unix_timestamp;
+    template.updated_at = clock.unix_timestamp;
+    template.bump = ctx.bumps.template;
+    template._reserved = [0u8; 16];
+
+    emit_event!(ctx, ShareTemplateCreated {
+        version: EVENT_VERSION,
+        template: template.key(),
+        owner,
+        template_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Share template created"; "Share template {} created by {}", template_id, owner);
+
+    Ok(())
+}
+
+/// Accounts required for changing a share template
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct UpdateTemplate<'info> {
+    /// The template to change
+    #[account(
+        mut,
+        seeds = [
+            SHARE_TEMPLATE_SEED,
+            owner.key().as_ref(),
+            &template.template_id.to_le_bytes()
+        ],
+        bump = template.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner
+    )]
+    pub template: Account<'info, ShareTemplate>,
+
+    /// The template owner
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the update_template instruction
+/// 
+/// Replaces the template's name and settings. Shares already created from
+/// the template keep the limits they were created with.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `name` - New public label
+/// * `settings` - New settings
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn update_template_handler(
+    ctx: Context<UpdateTemplate>,
+    name: DisplayName,
+    settings: ShareTemplateSettings,
+) -> Result<()> {
+    let template = &mut ctx.accounts.template;
+    let clock = Clock::get()?;
+
+    require!(settings.is_valid(), HelixError::InvalidShareTemplate);
+
+    template.name = name.into_inner();
+    template.settings = settings;
+    template.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, ShareTemplateUpdated {
+        version: EVENT_VERSION,
+        template: template.key(),
+        owner: template.owner,
+        template_id: template.template_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Share template updated";
+        "Share template {} updated by {}",
+        template.template_id,
+        template.owner
+    );
+
+    Ok(())
+}
+
+/// Accounts required for deleting a share template
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct DeleteTemplate<'info> {
+    /// The template to close
+    #[account(
+        mut,
+        seeds = [
+            SHARE_TEMPLATE_SEED,
+            owner.key().as_ref(),
+            &template.template_id.to_le_bytes()
+        ],
+        bump = template.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        close = owner
+    )]
+    pub template: Account<'info, ShareTemplate>,
+
+    /// The template owner (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the delete_template instruction
+/// 
+/// Closes the template account, returning its rent to the owner.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn delete_template_handler(ctx: Context<DeleteTemplate>) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let template_id = ctx.accounts.template.template_id;
+    let clock = Clock::get()?;
+
+    emit_event!(ctx, ShareTemplateDeleted {
+        version: EVENT_VERSION,
+        template: ctx.accounts.template.key(),
+        owner,
+        template_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Share template deleted"; "Share template {} deleted by {}", template_id, owner);
+
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+use crate::error::HelixError;
+use crate::instructions::tokenize::owner_controls_file;
+use crate::state::{
+    FileRecord, KeyReleased, TimedRelease, TimedReleaseCancelled, TimedReleaseCreated,
+    EVENT_VERSION, FILE_SEED, TIMED_RELEASE_SEED,
+};
+use crate::types::EncryptedKey;
+use crate::views::{ReleasedKey, VIEW_VERSION};
+
+/// Accounts required for scheduling the release of a file's key
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CreateTimedRelease<'info> {
+    /// The encrypted file whose key is released
+    #[account(
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        constraint = owner_controls_file(&file_record, &owner.key(), owner_token_account.as_ref()) @ HelixError::FileTokenRequired
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The timed release to create (PDA derived from the file)
+    #[account(
+        init,
+        payer = owner,
+        space = TimedRelease::LEN,
+        seeds = [TIMED_RELEASE_SEED, file_record.key().as_ref()],
+        bump
+    )]
+    pub timed_release: Box<Account<'info, TimedRelease>>,
+
+    /// The file owner (pays for the release)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+
+    /// The owner's token account holding the file's ownership token
+    /// (required only when the file is tokenized)
+    pub owner_token_account: Option<Account<'info, TokenAccount>>,
+}
+
+/// Handler for the create_timed_release instruction
+/// 
+/// Stores the file's key blob to be published at `release_at`. From then
+/// on anyone can trigger the release, and the owner can no longer stop it.
+/// The blob sits in public account data until then, so it must be wrapped
+/// such that it is useless before the deadline.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `key_blob` - The wrapped or plaintext key to publish
+/// * `release_at` - Unix timestamp from which the release can be triggered
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn create_timed_release_handler(
+    ctx: Context<CreateTimedRelease>,
+    key_blob: EncryptedKey,
+    release_at: i64,
+) -> Result<()> {
+    let owner = ctx.accounts.owner.key();
+    let file_key = ctx.accounts.file_record.key();
+    let file_record = &ctx.accounts.file_record;
+    let timed_release = &mut ctx.accounts.timed_release;
+    let clock = Clock::get()?;
+
+    require!(file_record.is_accessible(), HelixError::FileNotActive);
+    require!(file_record.is_encrypted(), HelixError::FileNotEncrypted);
+    require!(release_at > clock.unix_timestamp, HelixError::ReleaseTimeInPast);
+
+    timed_release.file = file_key;
+    timed_release.owner = owner;
+    timed_release.release_at = release_at;
+    timed_release.released = false;
+    timed_release.key_blob = key_blob.into();
+    timed_release.bump = ctx.bumps.timed_release;
+    timed_release._reserved = [0u8; 8];
+
+    emit_event!(ctx, TimedReleaseCreated {
+        version: EVENT_VERSION,
+        file: file_key,
+        owner,
+        release_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "Timed release created";
+        "Key of {} scheduled for release at {}",
+        file_key,
+        release_at
+    );
+
+    Ok(())
+}
+
+/// Accounts required for cancelling a scheduled key release
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct CancelTimedRelease<'info> {
+    /// The timed release to close
+    #[account(
+        mut,
+        seeds = [TIMED_RELEASE_SEED, timed_release.file.as_ref()],
+        bump = timed_release.bump,
+        has_one = owner @ HelixError::UnauthorizedOwner,
+        close = owner
+    )]
+    pub timed_release: Box<Account<'info, TimedRelease>>,
+
+    /// The wallet that scheduled the release (receives the rent)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+/// Handler for the cancel_timed_release instruction
+/// 
+/// Closes the release and returns its rent, but only before the deadline.
+/// Once `release_at` has passed the release can only be triggered.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn cancel_timed_release_handler(ctx: Context<CancelTimedRelease>) -> Result<()> {
+    let timed_release = &ctx.accounts.timed_release;
+    let clock = Clock::get()?;
+
+    timed_release.require_cancellable(clock.unix_timestamp)?;
+
+    emit_event!(ctx, TimedReleaseCancelled {
+        version: EVENT_VERSION,
+        file: timed_release.file,
+        owner: timed_release.owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Timed release cancelled"; "Key release of {} cancelled", timed_release.file);
+
+    Ok(())
+}
+
+/// Accounts required for triggering a due key release
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct TriggerRelease<'info> {
+    /// The timed release to trigger
+    #[account(
+        mut,
+        seeds = [TIMED_RELEASE_SEED, timed_release.file.as_ref()],
+        bump = timed_release.bump
+    )]
+    pub timed_release: Box<Account<'info, TimedRelease>>,
+
+    /// Anyone triggering the release
+    pub triggerer: Signer<'info>,
+}
+
+/// Handler for the trigger_release instruction
+/// 
+/// Publishes the key blob in a `KeyReleased` event once `release_at` has
+/// passed. Permissionless; the release is one-way and cannot be triggered,
+/// cancelled or reset again afterwards.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn trigger_release_handler(ctx: Context<TriggerRelease>) -> Result<()> {
+    let triggered_by = ctx.accounts.triggerer.key();
+    let timed_release = &mut ctx.accounts.timed_release;
+    let clock = Clock::get()?;
+
+    timed_release.trigger(clock.unix_timestamp)?;
+
+    emit_event!(ctx, KeyReleased {
+        version: EVENT_VERSION,
+        file: timed_release.file,
+        key_blob: timed_release.key_blob.clone(),
+        triggered_by,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Key released"; "Key of {} released by {}", timed_release.file, triggered_by);
+
+    Ok(())
+}
+
+/// Accounts for reading a released key (read-only)
+#[derive(Accounts)]
+pub struct RevealKey<'info> {
+    /// The timed release to read
+    #[account(
+        seeds = [TIMED_RELEASE_SEED, timed_release.file.as_ref()],
+        bump = timed_release.bump
+    )]
+    pub timed_release: Box<Account<'info, TimedRelease>>,
+}
+
+/// Return a file's released key, failing with `KeyNotReleased` until the
+/// release has been triggered
+pub fn reveal_key_handler(ctx: Context<RevealKey>) -> Result<ReleasedKey> {
+    let timed_release = &ctx.accounts.timed_release;
+
+    require!(timed_release.released, HelixError::KeyNotReleased);
+
+    Ok(ReleasedKey {
+        version: VIEW_VERSION,
+        file: timed_release.file,
+        release_at: timed_release.release_at,
+        key_blob: timed_release.key_blob.clone(),
+    })
+}
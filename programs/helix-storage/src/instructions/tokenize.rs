@@ -0,0 +1,326 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount};
+
+use crate::client::find_file_mint_address;
+use crate::error::HelixError;
+use crate::state::{
+    FileDetokenized, FileRecord, FileTokenized, TokenizedFileClaimed, EVENT_VERSION, FILE_MINT_SEED,
+    FILE_SEED,
+};
+use crate::token_metadata::{
+    create_file_token_metadata, FileTokenMetadataAccounts, METADATA_SEED,
+    TOKEN_METADATA_PROGRAM_ID,
+};
+
+/// Whether `owner`, the recorded owner of `file_record`, may act on it.
+/// 
+/// A plain file is controlled by its recorded owner. Once tokenized, the
+/// recorded owner must also present an unfrozen token account of theirs
+/// holding the ownership token: a record that has not been reclaimed since
+/// the token moved on (e.g. into an escrow) no longer grants control.
+pub fn owner_controls_file(
+    file_record: &Account<FileRecord>,
+    owner: &Pubkey,
+    owner_token_account: Option<&Account<TokenAccount>>,
+) -> bool {
+    if !file_record.is_tokenized() {
+        return true;
+    }
+    let Some(token_account) = owner_token_account else {
+        return false;
+    };
+    token_account.mint == find_file_mint_address(&file_record.key()).0
+        && token_account.owner == *owner
+        && token_account.amount == 1
+        && !token_account.is_frozen()
+}
+
+/// Accounts required for minting a file's ownership token
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct TokenizeFile<'info> {
+    /// The file record to tokenize (also the mint authority)
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        has_one = owner
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file's ownership token mint (created on first tokenization)
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [FILE_MINT_SEED, file_record.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = file_record
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The owner's associated token account for the mint
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    /// The mint's Metaplex metadata (created on first tokenization)
+    /// CHECK: Metadata PDA of the mint; created and validated by Token Metadata
+    #[account(
+        mut,
+        seeds = [METADATA_SEED, TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.key().as_ref()],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// The file owner (pays for the mint, token account and metadata)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+
+    /// Associated Token Account program
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// Metaplex Token Metadata program
+    /// CHECK: Address is checked against the Token Metadata program ID
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the tokenize_file instruction
+/// 
+/// Mints the single ownership token for the file to the owner, with
+/// Metaplex metadata naming it after the transaction ID and pointing at the
+/// file's Arweave URL. From then on whoever holds the token can claim the
+/// file record, and the recorded owner needs the token to act on the file.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn tokenize_handler(ctx: Context<TokenizeFile>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let file_seed = ctx.accounts.file_record.file_seed();
+    let bump = ctx.accounts.file_record.bump;
+    let clock = Clock::get()?;
+
+    // Validate file state
+    require!(!ctx.accounts.file_record.is_deleted(), HelixError::FileAlreadyDeleted);
+    require!(!ctx.accounts.file_record.is_tokenized(), HelixError::FileAlreadyTokenized);
+    ctx.accounts
+        .file_record
+        .require_no_pending_transfer(clock.unix_timestamp)?;
+
+    // Mint the ownership token, signed by the file record PDA
+    let signer_seeds: &[&[&[u8]]] = &[&[FILE_SEED, &file_seed, &[bump]]];
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.owner_token_account.to_account_info(),
+                authority: ctx.accounts.file_record.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    // Describe the token, signed by the file record PDA as mint authority
+    let file_record = &ctx.accounts.file_record;
+    create_file_token_metadata(
+        FileTokenMetadataAccounts {
+            metadata: &ctx.accounts.metadata.to_account_info(),
+            mint: &ctx.accounts.mint.to_account_info(),
+            file_record: &file_record.to_account_info(),
+            payer: &ctx.accounts.owner.to_account_info(),
+            token_metadata_program: &ctx.accounts.token_metadata_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        },
+        signer_seeds[0],
+        &file_record.transaction_id,
+        file_record.content_url(file_record.default_gateway()),
+    )?;
+
+    let file_record = &mut ctx.accounts.file_record;
+    file_record.set_tokenized(true);
+    file_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, FileTokenized {
+        version: EVENT_VERSION,
+        file: file_key,
+        mint: ctx.accounts.mint.key(),
+        owner: ctx.accounts.owner.key(),
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!(
+        "File tokenized";
+        "File tokenized: {} mint {}",
+        ctx.accounts.file_record.transaction_id,
+        ctx.accounts.mint.key()
+    );
+
+    Ok(())
+}
+
+/// Accounts required for claiming a tokenized file as the token holder
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct ClaimTokenizedFile<'info> {
+    /// The tokenized file record
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        constraint = file_record.is_tokenized() @ HelixError::FileNotTokenized
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file's ownership token mint
+    #[account(
+        seeds = [FILE_MINT_SEED, file_record.key().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The holder's token account containing the ownership token
+    #[account(
+        token::mint = mint,
+        token::authority = holder,
+        constraint = holder_token_account.amount == 1 @ HelixError::InvalidFileToken
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    /// The current token holder
+    pub holder: Signer<'info>,
+}
+
+/// Handler for the claim_tokenized_file instruction
+/// 
+/// Makes the current token holder the owner of the file record, so that
+/// owner-gated instructions follow token transfers.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn claim_tokenized_handler(ctx: Context<ClaimTokenizedFile>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let holder = ctx.accounts.holder.key();
+    let file_record = &mut ctx.accounts.file_record;
+    let clock = Clock::get()?;
+
+    let previous_owner = file_record.owner;
+    file_record.owner = holder;
+    file_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, TokenizedFileClaimed {
+        version: EVENT_VERSION,
+        file: file_key,
+        mint: ctx.accounts.mint.key(),
+        previous_owner,
+        new_owner: holder,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("Tokenized file claimed"; "Tokenized file claimed: {} by {}", file_key, holder);
+
+    Ok(())
+}
+
+/// Accounts required for burning a file's ownership token
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct DetokenizeFile<'info> {
+    /// The tokenized file record
+    #[account(
+        mut,
+        seeds = [FILE_SEED, &file_record.file_seed()],
+        bump = file_record.bump,
+        constraint = file_record.is_tokenized() @ HelixError::FileNotTokenized
+    )]
+    pub file_record: Box<Account<'info, FileRecord>>,
+
+    /// The file's ownership token mint
+    #[account(
+        mut,
+        seeds = [FILE_MINT_SEED, file_record.key().as_ref()],
+        bump
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// The holder's token account containing the ownership token
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = holder,
+        constraint = holder_token_account.amount == 1 @ HelixError::InvalidFileToken
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    /// The current token holder, who becomes the plain owner
+    pub holder: Signer<'info>,
+
+    /// SPL Token program
+    pub token_program: Program<'info, Token>,
+}
+
+/// Handler for the detokenize_file instruction
+/// 
+/// Burns the ownership token and makes the holder the plain owner of the
+/// file record. The mint is kept so the file can be tokenized again.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn detokenize_handler(ctx: Context<DetokenizeFile>) -> Result<()> {
+    let file_key = ctx.accounts.file_record.key();
+    let holder = ctx.accounts.holder.key();
+    let clock = Clock::get()?;
+
+    // Burn the ownership token (fails if the token account is frozen)
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.holder_token_account.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    let file_record = &mut ctx.accounts.file_record;
+    file_record.owner = holder;
+    file_record.set_tokenized(false);
+    file_record.updated_at = clock.unix_timestamp;
+
+    emit_event!(ctx, FileDetokenized {
+        version: EVENT_VERSION,
+        file: file_key,
+        mint: ctx.accounts.mint.key(),
+        owner: holder,
+        timestamp: clock.unix_timestamp,
+    });
+
+    private_msg!("File detokenized"; "File detokenized: {} owner {}", file_key, holder);
+
+    Ok(())
+}
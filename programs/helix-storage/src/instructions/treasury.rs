@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+use crate::error::HelixError;
+use crate::state::{
+    FeesWithdrawn, StorageRegistry, Treasury, UserProfile, EVENT_VERSION, REGISTRY_SEED,
+    TREASURY_SEED,
+};
+
+/// Accounts required for creating the treasury
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The treasury to initialize (PDA)
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::LEN,
+        seeds = [TREASURY_SEED],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The registry authority (payer)
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// System program for account creation
+    pub system_program: Program<'info, System>,
+}
+
+/// Handler for the initialize_treasury instruction
+/// 
+/// Creates the program-owned account that collects protocol fees. Can only
+/// be called once, by the registry authority.
+pub fn initialize_treasury_handler(ctx: Context<InitializeTreasury>) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+
+    treasury.bump = ctx.bumps.treasury;
+    treasury._reserved = [0u8; 32];
+
+    msg!("Treasury initialized at {}", treasury.key());
+
+    Ok(())
+}
+
+/// Pay a protocol fee from `payer` to the treasury and add it to the
+/// payer's `fees_paid_lamports` and the registry's `total_fees_collected`.
+/// The treasury and the payer's profile (created on first use, with
+/// `profile_bump`) are required only when the fee is nonzero.
+pub(crate) fn charge_fee<'info>(
+    fee_lamports: u64,
+    registry: &mut StorageRegistry,
+    payer: &Signer<'info>,
+    payer_profile: Option<&mut Account<'info, UserProfile>>,
+    profile_bump: u8,
+    treasury: Option<&Account<'info, Treasury>>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if fee_lamports == 0 {
+        return Ok(());
+    }
+
+    let treasury = treasury.ok_or(HelixError::TreasuryMissing)?;
+    let profile = payer_profile.ok_or(HelixError::FeeProfileMissing)?;
+    if profile.owner == Pubkey::default() {
+        profile.owner = payer.key();
+        profile.bump = profile_bump;
+    }
+    profile.fees_paid_lamports = profile
+        .fees_paid_lamports
+        .checked_add(fee_lamports)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+    registry.total_fees_collected = registry
+        .total_fees_collected
+        .checked_add(fee_lamports)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: treasury.to_account_info(),
+            },
+        ),
+        fee_lamports,
+    )
+}
+
+/// Accounts required for withdrawing fees from the treasury
+#[cfg_attr(feature = "event-cpi", event_cpi)]
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    /// The storage registry account
+    #[account(
+        seeds = [REGISTRY_SEED],
+        bump = registry.bump,
+        has_one = authority
+    )]
+    pub registry: Account<'info, StorageRegistry>,
+
+    /// The treasury paying out
+    #[account(
+        mut,
+        seeds = [TREASURY_SEED],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// The account receiving the lamports
+    /// CHECK: Any writable account can receive lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    /// The registry authority
+    pub authority: Signer<'info>,
+}
+
+/// Handler for the withdraw_fees instruction
+/// 
+/// Moves `amount` lamports, or everything above the treasury's rent-exempt
+/// minimum when `None`, from the treasury to `destination`. The treasury
+/// always keeps its rent reserve.
+/// 
+/// # Arguments
+/// * `ctx` - The context containing all accounts
+/// * `amount` - Lamports to withdraw, or `None` for all excess
+/// 
+/// # Returns
+/// * `Result<()>` - Success or error
+pub fn withdraw_fees_handler(ctx: Context<WithdrawFees>, amount: Option<u64>) -> Result<()> {
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let destination_info = ctx.accounts.destination.to_account_info();
+    let clock = Clock::get()?;
+
+    let reserve = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(reserve);
+    let amount = amount.unwrap_or(available);
+
+    require!(amount > 0, HelixError::InvalidWithdrawalAmount);
+    require!(amount <= available, HelixError::WithdrawalBelowRentExempt);
+
+    **treasury_info.try_borrow_mut_lamports()? -= amount;
+    **destination_info.try_borrow_mut_lamports()? = destination_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(HelixError::ArithmeticOverflow)?;
+
+    emit_event!(ctx, FeesWithdrawn {
+        version: EVENT_VERSION,
+        authority: ctx.accounts.authority.key(),
+        destination: destination_info.key(),
+        amount,
+        remaining: available - amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Withdrew {} lamports from the treasury to {}", amount, destination_info.key());
+
+    Ok(())
+}
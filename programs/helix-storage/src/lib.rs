@@ -1,9 +1,122 @@
+// Instruction arguments are part of the program's ABI, and `#[program]`
+// generates client functions taking each of them
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 
+/// Emit an event as self-CPI instruction data when the `event-cpi` feature
+/// is enabled, falling back to log-based `emit!` otherwise.
+#[cfg(all(feature = "event-cpi", not(feature = "wasm-client")))]
+macro_rules! emit_event {
+    ($ctx:ident, $event:expr) => {
+        crate::events::emit_via_cpi(
+            &$ctx.accounts.event_authority,
+            $ctx.bumps.event_authority,
+            &$event,
+        )?
+    };
+}
+
+#[cfg(not(any(feature = "event-cpi", feature = "wasm-client")))]
+macro_rules! emit_event {
+    ($ctx:ident, $event:expr) => {
+        emit!($event)
+    };
+}
+
+/// Roll the registry's epoch stats forward if a new epoch has started and
+/// emit `EpochRolledOver` when it does. Pass `rolled_over` for a registry
+/// that was already rolled, e.g. by `init_share`.
+#[cfg(not(feature = "wasm-client"))]
+macro_rules! roll_epoch {
+    ($ctx:ident, $registry:expr, $clock:expr) => {
+        roll_epoch!($ctx, $registry, $clock, $registry.roll_epoch($clock.epoch))
+    };
+    ($ctx:ident, $registry:expr, $clock:expr, $rolled_over:expr) => {
+        if $rolled_over {
+            emit_event!($ctx, crate::state::EpochRolledOver {
+                version: crate::state::EVENT_VERSION,
+                epoch: $registry.current_epoch,
+                previous_epoch_stats: $registry.previous_epoch_stats,
+                timestamp: $clock.unix_timestamp,
+            });
+        }
+    };
+}
+
+/// Log a message that identifies files, wallets or shares. Takes a generic
+/// message followed by the detailed format, e.g.
+/// `private_msg!("File deleted"; "File deleted: {}", tx_id)`. With the
+/// `quiet-logs` feature only the generic message is logged; structured
+/// events are unaffected.
+#[cfg(all(feature = "quiet-logs", not(feature = "wasm-client")))]
+macro_rules! private_msg {
+    ($generic:literal; $($arg:tt)*) => {
+        msg!($generic)
+    };
+}
+
+#[cfg(all(not(feature = "quiet-logs"), not(feature = "wasm-client")))]
+macro_rules! private_msg {
+    ($generic:literal; $($arg:tt)*) => {
+        msg!($($arg)*)
+    };
+}
+
+/// Log an identifying message as `private_msg!` does when the `verbose-logs`
+/// feature is enabled. Without it the formatting is compiled out to save
+/// compute units.
+#[cfg(all(feature = "verbose-logs", not(feature = "wasm-client")))]
+macro_rules! log_msg {
+    ($($arg:tt)*) => {
+        private_msg!($($arg)*)
+    };
+}
+
+#[cfg(not(any(feature = "verbose-logs", feature = "wasm-client")))]
+macro_rules! log_msg {
+    ($($arg:tt)*) => {};
+}
+
+pub mod client;
+pub mod compact;
+#[cfg(not(feature = "wasm-client"))]
+pub mod cpi_guard;
 pub mod error;
+pub mod events;
+#[cfg(not(feature = "wasm-client"))]
 pub mod instructions;
+#[cfg(not(feature = "wasm-client"))]
+pub mod memo;
+pub mod offsets;
+pub mod permit;
+pub mod readers;
+#[cfg(not(feature = "wasm-client"))]
+pub mod receipt;
 pub mod state;
+#[cfg(all(feature = "program-test", not(feature = "wasm-client")))]
+pub mod test_context;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+#[cfg(not(feature = "wasm-client"))]
+pub mod token_metadata;
+pub mod types;
+pub mod views;
+
+pub use error::HelixError;
+pub use permit::SharePermit;
+pub use state::*;
+pub use types::{
+    Cid, DisplayName, EncryptedAlias, EncryptedBlob, EncryptedFileAlias, EncryptedKey,
+    EncryptedName, EncryptedNote, EncryptedShareMessage, MimeType, Name, Slug, TxId,
+};
+pub use views::{
+    AccessLogView, EndowmentView, FeeQuote, FileInfo, InboxEntrySummary, InboxPage, NameResolution,
+    MirrorInfo, OwnerFileSummary, OwnerFilesPage, ProfileView, RecentFilesView, RegistryStats,
+    ReleasedKey, ShareStatusView, StorageStatement,
+};
 
+#[cfg(not(feature = "wasm-client"))]
 use instructions::*;
 
 declare_id!("HeLiX1111111111111111111111111111111111111");
@@ -13,6 +126,60 @@ declare_id!("HeLiX1111111111111111111111111111111111111");
 /// A Solana program for managing permanent encrypted file storage metadata.
 /// Files are stored on Arweave via Irys, while this program maintains
 /// on-chain records for access control, sharing, and verification.
+/// 
+/// # CPI
+/// 
+/// Other programs can depend on this crate with `features = ["cpi"]` and
+/// call instructions through the generated `cpi` module. Owner accounts are
+/// `Signer`s, which a PDA satisfies when its program signs with
+/// `invoke_signed`. Instructions that create accounts (`register_file`,
+/// `create_share`) also use the owner as rent payer, so a PDA owner must be
+/// a system-owned account funded with enough lamports for rent.
+/// 
+/// Deployments can restrict which programs call `register_file`,
+/// `register_file_v2`, `create_share` and `create_share_with_permit` through
+/// CPI with `set_cpi_policy`. The default policy is open; a top-level call
+/// is always allowed. Callers under an allowlist policy must pass the
+/// instructions sysvar so the calling program can be identified.
+/// 
+/// Multisig vaults such as Squads are system-owned PDAs that their program
+/// signs for with `invoke_signed`, so a vault can own files directly: its
+/// transactions pass the vault as the owner account and every owner-gated
+/// instruction, including `create_share` and `revoke_share`, accepts it.
+/// 
+/// # Library use
+/// 
+/// Other workspace members that only need the account types, errors and
+/// instruction arguments can depend on this crate with
+/// `default-features = false, features = ["no-entrypoint"]`. The Anchor
+/// entrypoint is only generated without `no-entrypoint`, so it does not
+/// collide with the host program's own entrypoint.
+/// 
+/// Programs that take Helix accounts as plain `AccountInfo`s should read
+/// them through `readers`, which checks the owning program as well as the
+/// discriminator. `programs/helix-reader-example` shows the pattern.
+/// 
+/// # Testing
+/// 
+/// The `test-utils` feature exports `test_utils`, the fixtures this crate's
+/// own unit tests use: sample registry, file, share and profile accounts at
+/// their derived addresses, and `TestAccount` to pass them to code as
+/// `AccountInfo`s. They run without a validator.
+/// 
+/// The `program-test` feature adds `HelixTestContext`, which runs the
+/// program in a `solana-program-test` bank with its registry initialized.
+/// `register_test_file` and `create_test_share` set up files and shares in
+/// one call, and `warp_forward` moves the bank's clock, e.g. past a share's
+/// expiry. Crates that call Helix through CPI can load their own program
+/// next to it via `HelixTestContext::program_test`.
+/// 
+/// # Browser clients
+/// 
+/// The `wasm-client` feature (with default features disabled) compiles only
+/// the account state, validation helpers, views, offsets and the `client`
+/// module, leaving out the instruction handlers and this program module so
+/// the crate can be built for `wasm32-unknown-unknown`.
+#[cfg(not(feature = "wasm-client"))]
 #[program]
 pub mod helix_storage {
     use super::*;
@@ -24,7 +191,31 @@ pub mod helix_storage {
     /// * `ctx` - The context containing the registry account and authority
     /// * `base_fee_lamports` - Base fee in lamports for file registration
     pub fn initialize(ctx: Context<Initialize>, base_fee_lamports: u64) -> Result<()> {
-        instructions::initialize::handler(ctx, base_fee_lamports)
+        instructions::initialize::initialize_handler(ctx, base_fee_lamports)
+    }
+
+    /// Create the zero-copy registry extension holding large tables.
+    /// Can only be called once, by the registry authority.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, extension and authority
+    pub fn initialize_registry_ext(ctx: Context<InitializeRegistryExt>) -> Result<()> {
+        instructions::initialize::initialize_ext_handler(ctx)
+    }
+
+    /// Configure the merkle tree and collection for compressed NFT
+    /// registration receipts. A default tree disables receipts.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, extension and authority
+    /// * `receipt_tree` - Merkle tree delegated to the registry PDA
+    /// * `receipt_collection` - Collection recorded on receipts (default for none)
+    pub fn set_receipt_config(
+        ctx: Context<UpdateRegistryExt>,
+        receipt_tree: Pubkey,
+        receipt_collection: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize::set_receipt_config_handler(ctx, receipt_tree, receipt_collection)
     }
 
     /// Register a new file after successful Arweave upload.
@@ -37,50 +228,247 @@ pub mod helix_storage {
     /// * `mime_type` - MIME type of the file
     /// * `size` - File size in bytes
     /// * `is_encrypted` - Whether the file content is encrypted
+    /// * `memo` - Optional note attached to the transaction via SPL Memo
+    /// * `storage_location` - L1 transaction or Irys data item (with its bundle)
     pub fn register_file(
         ctx: Context<RegisterFile>,
-        transaction_id: String,
-        encrypted_name: Option<String>,
-        mime_type: String,
+        transaction_id: TxId,
+        encrypted_name: Option<EncryptedName>,
+        mime_type: MimeType,
         size: u64,
         is_encrypted: bool,
+        memo: Option<String>,
+        storage_location: StorageLocationArg,
     ) -> Result<()> {
-        instructions::register_file::handler(
+        instructions::register_file::register_file_handler(
             ctx,
             transaction_id,
             encrypted_name,
             mime_type,
             size,
             is_encrypted,
+            memo,
+            storage_location,
         )
     }
 
-    /// Create a share link for an existing file.
-    /// Allows the file owner to grant access to other wallets.
+    /// Register a new file under an owner commitment instead of the wallet.
+    /// Owner-gated actions on the record use the `*_stealth` instructions.
     /// 
     /// # Arguments
-    /// * `ctx` - The context containing share link and file accounts
-    /// * `recipient` - Optional specific wallet to grant access
+    /// * `ctx` - The context containing file record and owner accounts
+    /// * `transaction_id` - The Arweave transaction ID (43 chars)
+    /// * `encrypted_name` - Client-encrypted filename (optional)
+    /// * `mime_type` - The file's MIME type
+    /// * `size` - File size in bytes
+    /// * `is_encrypted` - Whether the file content is encrypted
+    /// * `salt` - Client-chosen salt of the owner commitment
+    pub fn register_file_stealth(
+        ctx: Context<RegisterFileStealth>,
+        transaction_id: TxId,
+        encrypted_name: Option<EncryptedName>,
+        mime_type: MimeType,
+        size: u64,
+        is_encrypted: bool,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        instructions::stealth::register_stealth_handler(
+            ctx,
+            transaction_id,
+            encrypted_name,
+            mime_type,
+            size,
+            is_encrypted,
+            salt,
+        )
+    }
+
+    /// Mark a stealth file record as deleted.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and owner
+    /// * `salt` - Salt of the record's owner commitment
+    pub fn delete_file_stealth(ctx: Context<DeleteFileStealth>, salt: [u8; 32]) -> Result<()> {
+        instructions::stealth::delete_stealth_handler(ctx, salt)
+    }
+
+    /// Create a share link for a stealth file record.
+    /// The share stores the record's owner commitment and skips the inbox.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file record, share link and owner
+    /// * `salt` - Salt of the record's owner commitment
+    /// * `recipient` - Optional specific wallet that can access (None = public)
     /// * `expires_at` - Optional Unix timestamp for expiration
     /// * `max_downloads` - Optional maximum download count
     /// * `encrypted_key` - Encrypted decryption key for the recipient
-    pub fn create_share(
-        ctx: Context<CreateShare>,
+    /// * `recipient_commitment` - Recipient commitment used instead of `recipient`
+    pub fn create_share_stealth(
+        ctx: Context<CreateShareStealth>,
+        salt: [u8; 32],
         recipient: Option<Pubkey>,
         expires_at: Option<i64>,
         max_downloads: Option<u32>,
-        encrypted_key: Option<String>,
+        encrypted_key: Option<EncryptedKey>,
+        recipient_commitment: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::stealth::create_share_stealth_handler(
+            ctx,
+            salt,
+            recipient,
+            expires_at,
+            max_downloads,
+            encrypted_key,
+            recipient_commitment,
+        )
+    }
+
+    /// Revoke a share link of a stealth file record and clear its key.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing share link and owner
+    /// * `salt` - Salt of the share's owner commitment
+    pub fn revoke_share_stealth(ctx: Context<RevokeShareStealth>, salt: [u8; 32]) -> Result<()> {
+        instructions::stealth::revoke_share_stealth_handler(ctx, salt)
+    }
+
+    /// Register a new file with an interned or literal MIME type.
+    /// Records registered this way are allocated without the fixed MIME slot.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file record, MIME table and owner accounts
+    /// * `transaction_id` - The Arweave transaction ID (43 chars)
+    /// * `encrypted_name` - Client-encrypted filename (optional)
+    /// * `mime_type` - MIME table code or literal MIME type
+    /// * `size` - File size in bytes
+    /// * `is_encrypted` - Whether the file content is encrypted
+    /// * `memo` - Optional note attached to the transaction via SPL Memo
+    /// * `storage_location` - L1 transaction or Irys data item (with its bundle)
+    /// * `content` - Arweave transaction or IPFS CID; `transaction_id` must be
+    ///   its record ID
+    pub fn register_file_v2(
+        ctx: Context<RegisterFileV2>,
+        transaction_id: TxId,
+        encrypted_name: Option<EncryptedName>,
+        mime_type: MimeTypeArg,
+        size: u64,
+        is_encrypted: bool,
+        memo: Option<String>,
+        storage_location: StorageLocationArg,
+        content: ContentLocator,
+    ) -> Result<()> {
+        instructions::register_file::register_file_v2_handler(
+            ctx,
+            transaction_id,
+            encrypted_name,
+            mime_type,
+            size,
+            is_encrypted,
+            memo,
+            storage_location,
+            content,
+        )
+    }
+
+    /// Register up to `MAX_FILES_PER_COMPACT_BATCH` Arweave files in one
+    /// call. The files arrive in the compact format of `crate::compact`,
+    /// built with `client::encode_compact_batch`; any invalid entry fails
+    /// the call.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context, with each file's record PDA as a remaining account
+    /// * `payload` - The compact batch
+    pub fn register_files_compact<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RegisterFilesCompact<'info>>,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        instructions::register_batch::register_files_compact_handler(ctx, payload)
+    }
+
+    /// Create a share link for an existing file.
+    /// Allows the file owner to grant access to other wallets.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing share link and file accounts
+    /// * `args` - The recipient, limits and options of the share
+    pub fn create_share(ctx: Context<CreateShare>, args: CreateShareArgs) -> Result<()> {
+        instructions::create_share::create_share_handler(ctx, args)
+    }
+
+    /// Create a share link authorized by the owner's off-chain signature.
+    /// A relayer submits and pays; the owner's Ed25519 signature over the
+    /// permit must be verified by the preceding instruction.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing share link, file, profile and relayer accounts
+    /// * `permit` - The share terms signed by the owner
+    pub fn create_share_with_permit(
+        ctx: Context<CreateShareWithPermit>,
+        permit: SharePermit,
+    ) -> Result<()> {
+        instructions::create_share::create_with_permit_handler(ctx, permit)
+    }
+
+    /// Share up to `MAX_SHARES_PER_BATCH` of the owner's files with one
+    /// recipient under the same limits. Any invalid entry fails the call.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with `(file_record, share_link)` pairs as remaining accounts
+    /// * `recipient` - The wallet every share is locked to
+    /// * `settings` - Expiration and download limit of every share
+    /// * `encrypted_keys` - Each file's decryption key wrapped for the recipient, in order
+    pub fn create_shares_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateSharesBatch<'info>>,
+        recipient: Pubkey,
+        settings: ShareBatchSettings,
+        encrypted_keys: Vec<EncryptedKey>,
     ) -> Result<()> {
-        instructions::create_share::handler(
+        instructions::share_batch::create_shares_batch_handler(
             ctx,
             recipient,
+            settings,
+            encrypted_keys,
+        )
+    }
+
+    /// Create a share link for a recipient without a wallet yet. The share
+    /// stores the hash of a claim secret instead of a recipient, and the
+    /// first wallet to call `claim_share` with the secret becomes the
+    /// recipient.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file, share link and owner accounts
+    /// * `claim_hash` - `share_claim_hash` of the claim secret
+    /// * `expires_at` - Optional expiration, closing the claim window too
+    /// * `max_downloads` - Optional download limit
+    /// * `encrypted_key` - Decryption key wrapped for the claimant
+    pub fn create_claimable_share(
+        ctx: Context<CreateClaimableShare>,
+        claim_hash: [u8; 32],
+        expires_at: Option<i64>,
+        max_downloads: Option<u32>,
+        encrypted_key: Option<EncryptedKey>,
+    ) -> Result<()> {
+        instructions::claims::create_claimable_share_handler(
+            ctx,
+            claim_hash,
             expires_at,
             max_downloads,
             encrypted_key,
         )
     }
 
-    /// Revoke an existing share link.
+    /// Claim a claimable share with its secret, binding the signer as the
+    /// recipient. Single-use: the claim hash is cleared on success.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file, share link and claimant accounts
+    /// * `preimage` - The claim secret
+    pub fn claim_share(ctx: Context<ClaimShare>, preimage: [u8; 32]) -> Result<()> {
+        instructions::claims::claim_share_handler(ctx, preimage)
+    }
+
+    /// Revoke an existing share link and clear its encrypted key.
     /// Only the original file owner can revoke shares.
     /// 
     /// # Arguments
@@ -89,13 +477,242 @@ pub mod helix_storage {
         instructions::create_share::revoke_handler(ctx)
     }
 
+    /// Replace or remove the encrypted message of a share link.
+    /// Only the share owner can update it, and not after revocation.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the share link
+    /// * `encrypted_message` - The new message, encrypted to the recipient
+    pub fn update_share_message(
+        ctx: Context<UpdateShareMessage>,
+        encrypted_message: Option<EncryptedShareMessage>,
+    ) -> Result<()> {
+        instructions::create_share::update_share_message_handler(ctx, encrypted_message)
+    }
+
     /// Increment download count for a share link.
-    /// Called when a recipient downloads the shared file.
+    /// Called when a recipient downloads the shared file, either signing
+    /// directly or through a session key with the download scope.
     /// 
     /// # Arguments
     /// * `ctx` - The context containing share link to update
-    pub fn record_download(ctx: Context<RecordDownload>) -> Result<()> {
-        instructions::create_share::record_download_handler(ctx)
+    /// * `recipient_salt` - Salt of the share's recipient commitment, if it has one
+    pub fn record_download(
+        ctx: Context<RecordDownload>,
+        recipient_salt: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::create_share::record_download_handler(ctx, recipient_salt)
+    }
+
+    /// Authorize an ephemeral keypair to act for the wallet within a scope.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, session and wallet
+    /// * `session_pubkey` - The ephemeral public key
+    /// * `expires_at` - Unix timestamp when the session expires
+    /// * `scope` - Bitmask of `SESSION_SCOPE_*` actions
+    pub fn create_session(
+        ctx: Context<CreateSession>,
+        session_pubkey: Pubkey,
+        expires_at: i64,
+        scope: u8,
+    ) -> Result<()> {
+        instructions::session::create_session_handler(ctx, session_pubkey, expires_at, scope)
+    }
+
+    /// Revoke a session key, closing its account.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the session and wallet
+    pub fn revoke_session(ctx: Context<RevokeSession>) -> Result<()> {
+        instructions::session::revoke_session_handler(ctx)
+    }
+
+    /// Close an inbox entry whose share was revoked or closed.
+    /// The rent returns to the wallet that paid for the entry.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the inbox entry and its share
+    pub fn close_inbox_entry(ctx: Context<CloseInboxEntry>) -> Result<()> {
+        instructions::inbox::close_inbox_entry_handler(ctx)
+    }
+
+    /// Acknowledge a notification, closing it and decrementing the unread
+    /// counter. The rent returns to the wallet that paid for the entry.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the notification and the profile
+    pub fn ack_notification(ctx: Context<AckNotification>) -> Result<()> {
+        instructions::inbox::ack_notification_handler(ctx)
+    }
+
+    /// Add a wallet to the caller's address book.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the profile and new contact account
+    /// * `contact_wallet` - The wallet to add
+    /// * `encrypted_alias` - Client-encrypted display name (max 64 bytes)
+    pub fn add_contact(
+        ctx: Context<AddContact>,
+        contact_wallet: Pubkey,
+        encrypted_alias: EncryptedAlias,
+    ) -> Result<()> {
+        instructions::contacts::add_contact_handler(ctx, contact_wallet, encrypted_alias)
+    }
+
+    /// Remove a contact from the caller's address book, refunding its rent.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the profile and contact account
+    pub fn remove_contact(ctx: Context<RemoveContact>) -> Result<()> {
+        instructions::contacts::remove_contact_handler(ctx)
+    }
+
+    /// Create a named bundle of share settings `create_share` can apply.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the new template account
+    /// * `template_id` - Client-chosen ID of the template
+    /// * `name` - Public label (max 48 bytes)
+    /// * `settings` - Expiry, download limit, recipient and single-use settings
+    pub fn create_template(
+        ctx: Context<CreateTemplate>,
+        template_id: u32,
+        name: DisplayName,
+        settings: ShareTemplateSettings,
+    ) -> Result<()> {
+        instructions::templates::create_template_handler(ctx, template_id, name, settings)
+    }
+
+    /// Replace a share template's name and settings.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the template account
+    /// * `name` - New public label (max 48 bytes)
+    /// * `settings` - New settings
+    pub fn update_template(
+        ctx: Context<UpdateTemplate>,
+        name: DisplayName,
+        settings: ShareTemplateSettings,
+    ) -> Result<()> {
+        instructions::templates::update_template_handler(ctx, name, settings)
+    }
+
+    /// Delete a share template, refunding its rent.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the template account
+    pub fn delete_template(ctx: Context<DeleteTemplate>) -> Result<()> {
+        instructions::templates::delete_template_handler(ctx)
+    }
+
+    /// Publish or clear the wallet's X25519 encryption public key.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile
+    /// * `encryption_pubkey` - The new public key, or `None` to clear it
+    pub fn set_encryption_key(
+        ctx: Context<SetEncryptionKey>,
+        encryption_pubkey: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::profile::set_encryption_key_handler(ctx, encryption_pubkey)
+    }
+
+    /// Set the wallet's public display name and avatar file.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile and avatar file record
+    /// * `display_name` - The new display name, or `None` to clear it
+    /// * `avatar_file` - The avatar's file record, or `None` to clear it
+    pub fn update_profile(
+        ctx: Context<UpdateProfile>,
+        display_name: Option<DisplayName>,
+        avatar_file: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::profile::update_profile_handler(ctx, display_name, avatar_file)
+    }
+
+    /// Set the limits `create_share` applies when asked to use profile defaults.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile
+    /// * `share_defaults` - The new default share limits
+    pub fn update_share_defaults(
+        ctx: Context<UpdateShareDefaults>,
+        share_defaults: ShareDefaults,
+    ) -> Result<()> {
+        instructions::profile::update_share_defaults_handler(ctx, share_defaults)
+    }
+
+    /// Set or clear the backup key allowed to freeze the wallet's account.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile
+    /// * `guardian` - The guardian key, or `None` to clear it
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Option<Pubkey>) -> Result<()> {
+        instructions::profile::set_guardian_handler(ctx, guardian)
+    }
+
+    /// Freeze a wallet's account, as the wallet or its guardian.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile and the signer
+    pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
+        instructions::profile::freeze_account_handler(ctx)
+    }
+
+    /// Unfreeze the wallet's account. Only the wallet itself can unfreeze.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile
+    pub fn unfreeze_account(ctx: Context<UnfreezeAccount>) -> Result<()> {
+        instructions::profile::unfreeze_account_handler(ctx)
+    }
+
+    /// Record that the owner opened one of their files, for the recent files list.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and the owner's profile
+    pub fn touch_file(ctx: Context<TouchFile>) -> Result<()> {
+        instructions::profile::touch_file_handler(ctx)
+    }
+
+    /// Revoke expired share links on behalf of their owners.
+    /// Intended for keeper networks, which earn the registry's keeper bounty
+    /// for each unwarned link; returns the number of links processed.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with `(share_link, file_record)` pairs as remaining accounts
+    pub fn process_expirations<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ProcessExpirations<'info>>,
+    ) -> Result<u32> {
+        instructions::expirations::process_expirations_handler(ctx)
+    }
+
+    /// Warn a share's recipient that it expires within the registry's
+    /// warning window. Anyone can call this once per share; returns the
+    /// bounty paid from the treasury, if any.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the share link, its file and the keeper
+    pub fn notify_expiring_share(ctx: Context<NotifyExpiringShare>) -> Result<u64> {
+        instructions::expirations::notify_expiring_share_handler(ctx)
+    }
+
+    /// Emit a snapshot entry event for each of the caller's file records and
+    /// share links passed as remaining accounts, without modifying them.
+    /// Returns the sequence number to continue the snapshot from.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with the records to export as remaining accounts
+    /// * `snapshot_id` - Client-chosen ID shared by every call of the snapshot
+    /// * `start_seq` - Sequence number of the first entry in this call
+    pub fn export_snapshot(
+        ctx: Context<ExportSnapshot>,
+        snapshot_id: u64,
+        start_seq: u64,
+    ) -> Result<u64> {
+        instructions::export::export_snapshot_handler(ctx, snapshot_id, start_seq)
     }
 
     /// Update file metadata.
@@ -104,16 +721,1039 @@ pub mod helix_storage {
     /// # Arguments
     /// * `ctx` - The context containing file record to update
     /// * `encrypted_name` - New encrypted filename (optional)
-    pub fn update_file(ctx: Context<UpdateFile>, encrypted_name: Option<String>) -> Result<()> {
+    pub fn update_file(
+        ctx: Context<UpdateFile>,
+        encrypted_name: Option<EncryptedName>,
+    ) -> Result<()> {
         instructions::register_file::update_handler(ctx, encrypted_name)
     }
 
     /// Mark a file as deleted in the registry.
     /// Note: This does not delete the file from Arweave (permanent by design).
+    /// All of the file's share links must be passed as remaining accounts and
+    /// are revoked with it.
     /// 
     /// # Arguments
     /// * `ctx` - The context containing file record to mark as deleted
-    pub fn delete_file(ctx: Context<DeleteFile>) -> Result<()> {
-        instructions::register_file::delete_handler(ctx)
+    /// * `memo` - Optional note attached to the transaction via SPL Memo
+    pub fn delete_file(ctx: Context<DeleteFile>, memo: Option<String>) -> Result<()> {
+        instructions::register_file::delete_handler(ctx, memo)
+    }
+
+    /// Restore a file previously marked as deleted, within the trash retention window.
+    /// Files deleted by the program authority also require the authority's signature.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file record to restore
+    pub fn restore_file(ctx: Context<RestoreFile>) -> Result<()> {
+        instructions::register_file::restore_handler(ctx)
+    }
+
+    /// Archive an active file, moving it out of the active file counters.
+    /// Sharing and downloads keep working.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, profile and index entry
+    pub fn archive_file(ctx: Context<ArchiveFile>) -> Result<()> {
+        instructions::lifecycle::archive_file_handler(ctx)
+    }
+
+    /// Return an archived file to active use.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, profile and index entry
+    pub fn unarchive_file(ctx: Context<ArchiveFile>) -> Result<()> {
+        instructions::lifecycle::unarchive_file_handler(ctx)
+    }
+
+    /// Close a deleted file's record once its trash retention window has ended.
+    /// Anyone can purge expired files; the owner can force an earlier purge.
+    /// The rent goes back to the wallet that paid it.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, its owner and rent payer
+    /// * `force` - Purge before the retention window ends (owner only)
+    pub fn purge_file(ctx: Context<PurgeFile>, force: bool) -> Result<()> {
+        instructions::register_file::purge_handler(ctx, force)
+    }
+
+    /// Close a deleted file's record as its owner, without waiting for the
+    /// trash retention window. The rent goes back to the wallet that paid it.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, file record, owner and
+    ///   rent payer
+    pub fn close_file(ctx: Context<CloseFile>) -> Result<()> {
+        instructions::register_file::close_handler(ctx)
+    }
+
+    /// Restore several of the owner's deleted files in one transaction.
+    /// Files that cannot be restored are skipped; returns the number restored.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with deleted file records as remaining accounts
+    pub fn restore_files_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RestoreFilesBatch<'info>>,
+    ) -> Result<u32> {
+        instructions::restore_batch::restore_files_batch_handler(ctx)
+    }
+
+    /// Replace the encrypted names of several of the owner's files in one
+    /// transaction, e.g. after a key rotation. Any invalid entry fails the call.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with the file records as remaining accounts
+    /// * `encrypted_names` - The new encrypted name of each record, in order
+    pub fn update_names_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateNamesBatch<'info>>,
+        encrypted_names: Vec<Option<EncryptedName>>,
+    ) -> Result<()> {
+        instructions::rename_batch::update_names_batch_handler(ctx, encrypted_names)
+    }
+
+    /// Append a file to its owner's file index at the next sequence number.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, profile and new entry
+    pub fn index_file(ctx: Context<IndexFile>) -> Result<()> {
+        instructions::owner_index::index_file_handler(ctx)
+    }
+
+    /// Set the manual ordering key of a file in its owner's file index.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and its index entry
+    /// * `sort_key` - The new ordering key (duplicates are allowed)
+    pub fn set_sort_key(ctx: Context<SetSortKey>, sort_key: u32) -> Result<()> {
+        instructions::owner_index::set_sort_key_handler(ctx, sort_key)
+    }
+
+    /// Add a lookup alias to a file, addressed by a hash of the alias.
+    /// Only the file owner can add aliases, up to the registry's per-file limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and alias account
+    /// * `alias_hash` - Client-computed hash of the plaintext alias
+    /// * `encrypted_alias` - Client-encrypted alias name
+    pub fn create_alias(
+        ctx: Context<CreateAlias>,
+        alias_hash: [u8; 32],
+        encrypted_alias: EncryptedFileAlias,
+    ) -> Result<()> {
+        instructions::aliases::create_alias_handler(ctx, alias_hash, encrypted_alias)
+    }
+
+    /// Remove a file alias, returning its rent to the wallet that created it.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and alias account
+    pub fn remove_alias(ctx: Context<RemoveAlias>) -> Result<()> {
+        instructions::aliases::remove_alias_handler(ctx)
+    }
+
+    /// Attach a copy of a file's content on Arweave or IPFS as a mirror.
+    /// Only the file owner can add mirrors, up to the registry's per-file limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and mirror account
+    /// * `mirror_key` - `record_key` of the content locator
+    /// * `content` - Where the mirrored content lives
+    pub fn add_mirror(
+        ctx: Context<AddMirror>,
+        mirror_key: [u8; 32],
+        content: ContentLocator,
+    ) -> Result<()> {
+        instructions::mirrors::add_mirror_handler(ctx, mirror_key, content)
+    }
+
+    /// Remove a mirror from a file, returning its rent to the file owner.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and mirror account
+    pub fn remove_mirror(ctx: Context<RemoveMirror>) -> Result<()> {
+        instructions::mirrors::remove_mirror_handler(ctx)
+    }
+
+    /// Propose handing a file to another wallet, which must accept it.
+    /// The proposal lapses after `OWNERSHIP_TRANSFER_WINDOW_SECS`.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and its owner
+    /// * `new_owner` - The wallet that may accept the file
+    pub fn propose_ownership_transfer(
+        ctx: Context<ManageOwnershipTransfer>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        instructions::ownership::propose_ownership_transfer_handler(ctx, new_owner)
+    }
+
+    /// Accept a proposed ownership transfer as the pending owner.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and the new owner
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        instructions::ownership::accept_ownership_handler(ctx)
+    }
+
+    /// Withdraw a pending ownership transfer proposal.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and its owner
+    pub fn cancel_ownership_transfer(ctx: Context<ManageOwnershipTransfer>) -> Result<()> {
+        instructions::ownership::cancel_ownership_transfer_handler(ctx)
+    }
+
+    /// Save a reference to another wallet's file in the caller's drive.
+    /// References grant no access; reading the file still requires a share.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and reference account
+    /// * `encrypted_note` - Client-encrypted note (optional)
+    pub fn create_reference(
+        ctx: Context<CreateReference>,
+        encrypted_note: Option<EncryptedNote>,
+    ) -> Result<()> {
+        instructions::references::create_reference_handler(ctx, encrypted_note)
+    }
+
+    /// Close a file reference, returning its rent to the referrer.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the reference and its file record
+    pub fn close_reference(ctx: Context<CloseReference>) -> Result<()> {
+        instructions::references::close_reference_handler(ctx)
+    }
+
+    /// Like a file, once per wallet. The wallet pays for its like receipt.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, file record and like receipt
+    pub fn like_file(ctx: Context<LikeFile>) -> Result<()> {
+        instructions::likes::like_file_handler(ctx)
+    }
+
+    /// Withdraw a like, returning the receipt's rent to the wallet.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the like receipt and its file record
+    pub fn unlike_file(ctx: Context<UnlikeFile>) -> Result<()> {
+        instructions::likes::unlike_file_handler(ctx)
+    }
+
+    /// Contribute lamports to a file's endowment. Anyone can contribute.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and its endowment
+    /// * `amount` - Lamports to contribute
+    pub fn contribute(ctx: Context<Contribute>, amount: u64) -> Result<()> {
+        instructions::endowment::contribute_handler(ctx, amount)
+    }
+
+    /// Withdraw lamports from a file's endowment to the file owner.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, endowment and owner
+    /// * `amount` - Lamports to withdraw
+    /// * `memo` - Optional note on the purpose of the withdrawal
+    pub fn withdraw_endowment(
+        ctx: Context<WithdrawEndowment>,
+        amount: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        instructions::endowment::withdraw_endowment_handler(ctx, amount, memo)
+    }
+
+    /// Close a file's endowment, paying out its balance and rent to the owner.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, endowment and owner
+    pub fn close_endowment(ctx: Context<CloseEndowment>) -> Result<()> {
+        instructions::endowment::close_endowment_handler(ctx)
+    }
+
+    /// Create a file's access log, which share creation and downloads append to.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and access log
+    pub fn create_access_log(ctx: Context<CreateAccessLog>) -> Result<()> {
+        instructions::access_log::create_access_log_handler(ctx)
+    }
+
+    /// Close a file's access log, returning its rent to the wallet that created it.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the access log
+    pub fn close_access_log(ctx: Context<CloseAccessLog>) -> Result<()> {
+        instructions::access_log::close_access_log_handler(ctx)
+    }
+
+    /// Schedule an encrypted file's key to be published at `release_at`.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and timed release
+    /// * `key_blob` - The wrapped or plaintext key to publish
+    /// * `release_at` - Unix timestamp from which the release can be triggered
+    pub fn create_timed_release(
+        ctx: Context<CreateTimedRelease>,
+        key_blob: EncryptedKey,
+        release_at: i64,
+    ) -> Result<()> {
+        instructions::timed_release::create_timed_release_handler(ctx, key_blob, release_at)
+    }
+
+    /// Cancel a scheduled key release before its deadline, returning the rent.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the timed release
+    pub fn cancel_timed_release(ctx: Context<CancelTimedRelease>) -> Result<()> {
+        instructions::timed_release::cancel_timed_release_handler(ctx)
+    }
+
+    /// Publish a file's key once its release time has passed. Anyone can call this.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the timed release
+    pub fn trigger_release(ctx: Context<TriggerRelease>) -> Result<()> {
+        instructions::timed_release::trigger_release_handler(ctx)
+    }
+
+    /// Attest, as a listed gateway operator, that it holds a replica of a file.
+    /// Re-attesting refreshes the check time and content hash.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry extension, file record and attestation
+    /// * `content_hash` - Hash of the content the attestor verified (optional)
+    pub fn attest_file(ctx: Context<AttestFile>, content_hash: Option<[u8; 32]>) -> Result<()> {
+        instructions::attestations::attest_file_handler(ctx, content_hash)
+    }
+
+    /// Revoke a replica attestation, as its attestor or the program authority.
+    /// The rent returns to the attestor.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the attestation and its file record
+    pub fn revoke_attestation(ctx: Context<RevokeAttestation>) -> Result<()> {
+        instructions::attestations::revoke_attestation_handler(ctx)
+    }
+
+    /// Grant an auditor view (never download) access to one or all of the
+    /// owner's files until an expiry. Grants may overlap.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the audit grant, owner and scoped file
+    /// * `grant_id` - Owner-chosen ID of the grant
+    /// * `auditor` - The auditor's wallet
+    /// * `scope` - The files covered
+    /// * `expires_at` - Unix timestamp when the grant expires
+    pub fn create_audit_grant(
+        ctx: Context<CreateAuditGrant>,
+        grant_id: u64,
+        auditor: Pubkey,
+        scope: AuditScope,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::audit::create_audit_grant_handler(ctx, grant_id, auditor, scope, expires_at)
+    }
+
+    /// Revoke an audit grant. The grant account is kept as a record.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the audit grant and owner
+    pub fn revoke_audit_grant(ctx: Context<RevokeAuditGrant>) -> Result<()> {
+        instructions::audit::revoke_audit_grant_handler(ctx)
+    }
+
+    /// Bind a rights holder's signed copyright statement to a file.
+    /// The rights holder's signature over the canonical statement must be
+    /// verified by the preceding Ed25519 program instruction; a newer
+    /// statement overwrites the previous one.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, owner and instructions sysvar
+    /// * `rights_holder` - The key that signed the statement
+    /// * `content_hash` - Hash of the content the statement covers
+    /// * `signature` - The rights holder's Ed25519 signature
+    pub fn attest_copyright(
+        ctx: Context<AttestCopyright>,
+        rights_holder: Pubkey,
+        content_hash: [u8; 32],
+        signature: [u8; 64],
+    ) -> Result<()> {
+        instructions::copyright::attest_copyright_handler(
+            ctx,
+            rights_holder,
+            content_hash,
+            signature,
+        )
+    }
+
+    /// Set or clear the Merkle root over a chunked file's chunk transaction IDs.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and owner
+    /// * `chunk_root` - The new Merkle root, or None to clear it
+    pub fn set_chunk_root(ctx: Context<SetChunkRoot>, chunk_root: Option<[u8; 32]>) -> Result<()> {
+        instructions::chunks::set_chunk_root_handler(ctx, chunk_root)
+    }
+
+    /// Verify a chunk's Merkle proof against its parent record via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the parent file record
+    /// * `index` - Position of the chunk in the file
+    /// * `chunk_tx_id` - Arweave transaction ID of the chunk
+    /// * `proof` - Sibling hashes from the leaf level upwards
+    pub fn verify_chunk(
+        ctx: Context<VerifyChunk>,
+        index: u16,
+        chunk_tx_id: TxId,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<bool> {
+        instructions::chunks::verify_chunk_handler(ctx, index, chunk_tx_id, proof)
+    }
+
+    /// Challenge a file's owner to prove access to a byte range by a deadline.
+    /// The challenger locks a bond until the challenge is accepted or settled.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and challenge
+    /// * `offset` - First byte of the challenged range
+    /// * `length` - Length of the challenged range in bytes
+    /// * `deadline` - Unix timestamp by which the owner must answer
+    pub fn open_challenge(
+        ctx: Context<OpenChallenge>,
+        offset: u64,
+        length: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::challenges::open_challenge_handler(ctx, offset, length, deadline)
+    }
+
+    /// Answer a challenge with the SHA-256 of the challenged range.
+    /// Only the file owner can answer, and only before the deadline.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the challenge
+    /// * `hash` - SHA-256 of the challenged byte range
+    pub fn answer_challenge(ctx: Context<AnswerChallenge>, hash: [u8; 32]) -> Result<()> {
+        instructions::challenges::answer_challenge_handler(ctx, hash)
+    }
+
+    /// Accept a challenge answer, closing the challenge and returning the bond.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the challenge
+    pub fn accept_answer(ctx: Context<AcceptAnswer>) -> Result<()> {
+        instructions::challenges::accept_answer_handler(ctx)
+    }
+
+    /// Settle a challenge past its deadline (callable by anyone).
+    /// Unanswered challenges count as failed against the file and return the
+    /// bond; answers left unaccepted pay the bond to the owner.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the challenge and file record
+    pub fn settle_challenge(ctx: Context<SettleChallenge>) -> Result<()> {
+        instructions::challenges::settle_challenge_handler(ctx)
+    }
+
+    /// Register a name in the owner's namespace pointing at one of their files.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and name record
+    /// * `name` - Lowercase name of at most `MAX_NAME_LEN` bytes
+    pub fn register_name(ctx: Context<RegisterName>, name: Name) -> Result<()> {
+        instructions::names::register_name_handler(ctx, name)
+    }
+
+    /// Point a registered name at another of the owner's files.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the name record and new file record
+    pub fn update_name_target(ctx: Context<UpdateNameTarget>) -> Result<()> {
+        instructions::names::update_name_target_handler(ctx)
+    }
+
+    /// Release a registered name, returning its rent to the owner.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the name record
+    pub fn release_name(ctx: Context<ReleaseName>) -> Result<()> {
+        instructions::names::release_name_handler(ctx)
+    }
+
+    /// Claim a global short code for one of the caller's public share links.
+    /// Codes are first come, first served; the registry's claim fee goes to the treasury.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the share link, short code and treasury
+    /// * `code` - 4-16 lowercase letters or digits
+    pub fn claim_short_code(ctx: Context<ClaimShortCode>, code: Slug) -> Result<()> {
+        instructions::short_codes::claim_short_code_handler(ctx, code)
+    }
+
+    /// Release a short code, returning its rent to the claimer.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the short code
+    pub fn release_short_code(ctx: Context<ReleaseShortCode>) -> Result<()> {
+        instructions::short_codes::release_short_code_handler(ctx)
+    }
+
+    /// Free a short code whose share was revoked, expired, used up or closed.
+    /// Anyone can call this; the rent returns to the claimer.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the short code and its share link
+    pub fn reap_short_code(ctx: Context<ReapShortCode>) -> Result<()> {
+        instructions::short_codes::reap_short_code_handler(ctx)
+    }
+
+    /// List one of the caller's unencrypted files in a gallery category.
+    /// The registry's gallery fee goes to the treasury.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, gallery entry and treasury
+    /// * `category` - The category to list the file in
+    pub fn add_gallery_entry(
+        ctx: Context<AddGalleryEntry>,
+        category: GalleryCategory,
+    ) -> Result<()> {
+        instructions::gallery::add_gallery_entry_handler(ctx, category)
+    }
+
+    /// Remove the caller's gallery entry, returning its rent.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the gallery entry
+    pub fn remove_gallery_entry(ctx: Context<RemoveGalleryEntry>) -> Result<()> {
+        instructions::gallery::remove_gallery_entry_handler(ctx)
+    }
+
+    /// Take down a gallery entry for moderation.
+    /// Only the program authority can do this; the rent returns to the lister.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, gallery entry and authority
+    pub fn force_remove_gallery_entry(ctx: Context<ForceRemoveGalleryEntry>) -> Result<()> {
+        instructions::gallery::force_remove_gallery_entry_handler(ctx)
+    }
+
+    /// Mint a token representing ownership of a file record, with Metaplex
+    /// metadata naming the file. While the file is tokenized, owner-gated
+    /// instructions also require the owner's token account holding it.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, mint, owner token account and metadata
+    pub fn tokenize_file(ctx: Context<TokenizeFile>) -> Result<()> {
+        instructions::tokenize::tokenize_handler(ctx)
+    }
+
+    /// Take ownership of a tokenized file record as the current token holder.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and holder token account
+    pub fn claim_tokenized_file(ctx: Context<ClaimTokenizedFile>) -> Result<()> {
+        instructions::tokenize::claim_tokenized_handler(ctx)
+    }
+
+    /// Burn a file's ownership token and restore plain ownership to the holder.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and holder token account
+    pub fn detokenize_file(ctx: Context<DetokenizeFile>) -> Result<()> {
+        instructions::tokenize::detokenize_handler(ctx)
+    }
+
+    /// Force-delete a file record as the program authority or an operator.
+    /// Used for moderation and legal takedowns where the owner cannot sign.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file record and moderator
+    /// * `reason` - Moderation reason code recorded in the event
+    pub fn admin_delete_file(ctx: Context<AdminDeleteFile>, reason: u8) -> Result<()> {
+        instructions::admin::admin_delete_handler(ctx, reason)
+    }
+
+    /// Correct the recorded size of a file as the program authority.
+    /// Adjusts the registry byte total by the difference.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file record and authority
+    /// * `new_size` - The corrected file size in bytes
+    pub fn admin_correct_size(ctx: Context<AdminCorrectSize>, new_size: u64) -> Result<()> {
+        instructions::admin::admin_correct_size_handler(ctx, new_size)
+    }
+
+    /// Attest a file's recorded size as the program authority, marking it
+    /// as confirmed rather than self-reported.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing file record and authority
+    /// * `size` - The confirmed file size in bytes; must match the record
+    pub fn attest_size(ctx: Context<AttestSize>, size: u64) -> Result<()> {
+        instructions::admin::attest_size_handler(ctx, size)
+    }
+
+    /// Revoke a share link as the program authority or an operator.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing share link and moderator
+    /// * `reason` - Moderation reason code recorded in the event
+    pub fn force_revoke_share(ctx: Context<ForceRevokeShare>, reason: u8) -> Result<()> {
+        instructions::admin::force_revoke_share_handler(ctx, reason)
+    }
+
+    /// Add an operator allowed to perform a limited set of moderation actions.
+    /// Only the program authority can manage operators.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `operator` - The operator wallet
+    /// * `permissions` - Bitmask of `OPERATOR_*` permissions
+    pub fn add_operator(
+        ctx: Context<UpdateRegistry>,
+        operator: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        instructions::admin::add_operator_handler(ctx, operator, permissions)
+    }
+
+    /// Add a gateway operator to the attestor set.
+    /// Only the program authority can manage attestors.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry extension and authority
+    /// * `attestor` - The gateway operator's wallet
+    pub fn add_attestor(ctx: Context<UpdateAttestors>, attestor: Pubkey) -> Result<()> {
+        instructions::attestations::add_attestor_handler(ctx, attestor)
+    }
+
+    /// Remove a gateway operator from the attestor set.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry extension and authority
+    /// * `attestor` - The gateway operator's wallet
+    pub fn remove_attestor(ctx: Context<UpdateAttestors>, attestor: Pubkey) -> Result<()> {
+        instructions::attestations::remove_attestor_handler(ctx, attestor)
+    }
+
+    /// Remove an operator from the registry.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `operator` - The operator wallet to remove
+    pub fn remove_operator(ctx: Context<UpdateRegistry>, operator: Pubkey) -> Result<()> {
+        instructions::admin::remove_operator_handler(ctx, operator)
+    }
+
+    /// Overwrite drifted registry counters with recounted values.
+    /// Only the program authority can reconcile the counters.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `counters` - The corrected lifetime counters
+    /// * `reason_hash` - Hash of the off-chain explanation of the correction
+    pub fn reconcile_counters(
+        ctx: Context<UpdateRegistry>,
+        counters: RegistryCounters,
+        reason_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::admin::reconcile_counters_handler(ctx, counters, reason_hash)
+    }
+
+    /// Set the maximum encrypted filename length accepted by the registry.
+    /// Only the program authority can change this limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `max_len` - New limit in bytes (at most 1024)
+    pub fn set_max_encrypted_name_len(ctx: Context<UpdateRegistry>, max_len: u16) -> Result<()> {
+        instructions::initialize::set_max_encrypted_name_len_handler(ctx, max_len)
+    }
+
+    /// Create the append-only MIME table used by `register_file_v2`.
+    /// Can only be called once, by the registry authority.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, MIME table and authority
+    pub fn initialize_mime_table(ctx: Context<InitializeMimeTable>) -> Result<()> {
+        instructions::mime_table::initialize_mime_table_handler(ctx)
+    }
+
+    /// Create the treasury that collects protocol fees.
+    /// Only the program authority can initialize it, once.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, treasury and authority
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::treasury::initialize_treasury_handler(ctx)
+    }
+
+    /// Withdraw collected fees from the treasury, keeping its rent reserve.
+    /// Only the program authority can withdraw.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, treasury and destination
+    /// * `amount` - Lamports to withdraw, or `None` for everything above rent
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: Option<u64>) -> Result<()> {
+        instructions::treasury::withdraw_fees_handler(ctx, amount)
+    }
+
+    /// Intern a MIME type, assigning it the next code.
+    /// Only the program authority can add entries; entries are never removed.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry, MIME table and authority
+    /// * `mime_type` - The canonical MIME type to intern
+    pub fn add_mime_type(ctx: Context<AddMimeType>, mime_type: MimeType) -> Result<()> {
+        instructions::mime_table::add_mime_type_handler(ctx, mime_type)
+    }
+
+    /// Set the maximum lifetime of newly created session keys.
+    /// Only the program authority can change this limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `max_lifetime` - New limit in seconds
+    pub fn set_max_session_lifetime(ctx: Context<UpdateRegistry>, max_lifetime: i64) -> Result<()> {
+        instructions::initialize::set_max_session_lifetime_handler(ctx, max_lifetime)
+    }
+
+    /// Set the window during which deleted files can be restored.
+    /// Only the program authority can change this window.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `retention_secs` - New window in seconds
+    pub fn set_trash_retention(ctx: Context<UpdateRegistry>, retention_secs: i64) -> Result<()> {
+        instructions::initialize::set_trash_retention_handler(ctx, retention_secs)
+    }
+
+    /// Set the maximum number of aliases per file.
+    /// Only the program authority can change this limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `max_aliases` - New per-file limit
+    pub fn set_max_aliases_per_file(ctx: Context<UpdateRegistry>, max_aliases: u16) -> Result<()> {
+        instructions::initialize::set_max_aliases_per_file_handler(ctx, max_aliases)
+    }
+
+    /// Set the maximum number of mirrors per file.
+    /// Only the program authority can change this limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `max_mirrors` - New per-file limit
+    pub fn set_max_mirrors_per_file(ctx: Context<UpdateRegistry>, max_mirrors: u16) -> Result<()> {
+        instructions::initialize::set_max_mirrors_per_file_handler(ctx, max_mirrors)
+    }
+
+    /// Set the maximum number of live share links per file (0 = unlimited).
+    /// Only the program authority can change this limit.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `max_shares` - New per-file limit
+    pub fn set_max_shares_per_file(ctx: Context<UpdateRegistry>, max_shares: u32) -> Result<()> {
+        instructions::initialize::set_max_shares_per_file_handler(ctx, max_shares)
+    }
+
+    /// Set the throttle on counted downloads of public shares.
+    /// Only the program authority can change the throttle.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `throttle` - None, a per-wallet cooldown or a per-download fee
+    pub fn set_download_throttle(
+        ctx: Context<UpdateRegistry>,
+        throttle: DownloadThrottle,
+    ) -> Result<()> {
+        instructions::initialize::set_download_throttle_handler(ctx, throttle)
+    }
+
+    /// Set the fee for claiming a short code (0 = free).
+    /// Only the program authority can change this fee.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `fee_lamports` - New fee in lamports
+    pub fn set_short_code_fee(ctx: Context<UpdateRegistry>, fee_lamports: u64) -> Result<()> {
+        instructions::initialize::set_short_code_fee_handler(ctx, fee_lamports)
+    }
+
+    /// Set the fee for listing a file in the gallery (0 = free).
+    /// Only the program authority can change this fee.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `fee_lamports` - New fee in lamports
+    pub fn set_gallery_fee(ctx: Context<UpdateRegistry>, fee_lamports: u64) -> Result<()> {
+        instructions::initialize::set_gallery_fee_handler(ctx, fee_lamports)
+    }
+
+    /// Set whether deleting a file wipes its encrypted name, MIME type and
+    /// the encrypted keys of the share links passed alongside it.
+    /// Only the program authority can change this policy.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `enabled` - Whether deletions scrub metadata
+    pub fn set_scrub_on_delete(ctx: Context<UpdateRegistry>, enabled: bool) -> Result<()> {
+        instructions::initialize::set_scrub_on_delete_handler(ctx, enabled)
+    }
+
+    /// Set whether only live, unencrypted files can be liked.
+    /// Only the program authority can change this policy.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `enabled` - Whether likes are restricted to public files
+    pub fn set_likes_require_public(ctx: Context<UpdateRegistry>, enabled: bool) -> Result<()> {
+        instructions::initialize::set_likes_require_public_handler(ctx, enabled)
+    }
+
+    /// Configure pre-expiry share warnings (window 0 = disabled) and the
+    /// bounty paid to keepers for each warning or expiration.
+    /// Only the program authority can change these settings.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `window_secs` - Seconds before expiry in which a share can be warned
+    /// * `bounty_lamports` - Bounty per share, below a share link's rent
+    pub fn set_expiry_warning(
+        ctx: Context<UpdateRegistry>,
+        window_secs: i64,
+        bounty_lamports: u64,
+    ) -> Result<()> {
+        instructions::initialize::set_expiry_warning_handler(ctx, window_secs, bounty_lamports)
+    }
+
+    /// Update the base fee for file registration.
+    /// Only the program authority can change this fee.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `new_fee` - New base fee in lamports
+    pub fn update_fee(ctx: Context<UpdateRegistry>, new_fee: u64) -> Result<()> {
+        instructions::initialize::update_fee_handler(ctx, new_fee)
+    }
+
+    /// Pause or unpause the registry.
+    /// Only the program authority can pause the registry.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `paused` - Whether the registry is paused
+    pub fn set_paused(ctx: Context<UpdateRegistry>, paused: bool) -> Result<()> {
+        instructions::initialize::set_paused_handler(ctx, paused)
+    }
+
+    /// Propose a new program authority, who takes over with `accept_authority`.
+    /// Only the current program authority can propose a successor.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `new_authority` - The wallet that may accept the authority
+    pub fn propose_authority(ctx: Context<UpdateRegistry>, new_authority: Pubkey) -> Result<()> {
+        instructions::initialize::propose_authority_handler(ctx, new_authority)
+    }
+
+    /// Withdraw a pending authority proposal.
+    /// Only the current program authority can cancel it.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    pub fn cancel_authority_transfer(ctx: Context<UpdateRegistry>) -> Result<()> {
+        instructions::initialize::cancel_authority_transfer_handler(ctx)
+    }
+
+    /// Accept a proposed authority transfer, signed by the pending authority.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and pending authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::initialize::accept_authority_handler(ctx)
+    }
+
+    /// Grow a registry created under an older layout to the current size.
+    /// Only the program authority can migrate the registry.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        instructions::migrate::migrate_registry_handler(ctx)
+    }
+
+    /// Grow a file record created under an older layout to the current size.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record and its owner
+    pub fn migrate_file_record(ctx: Context<MigrateFileRecord>) -> Result<()> {
+        instructions::migrate::migrate_file_record_handler(ctx)
+    }
+
+    /// Grow a share link created under an older layout to the current size.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the share link and its owner
+    pub fn migrate_share_link(ctx: Context<MigrateShareLink>) -> Result<()> {
+        instructions::migrate::migrate_share_link_handler(ctx)
+    }
+
+    /// Set the file size at or below which registration fees are waived.
+    /// Only the program authority can change this threshold.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `free_below_bytes` - Size threshold in bytes (0 disables the waiver)
+    pub fn set_free_below_bytes(ctx: Context<UpdateRegistry>, free_below_bytes: u64) -> Result<()> {
+        instructions::initialize::set_free_below_bytes_handler(ctx, free_below_bytes)
+    }
+
+    /// Set which programs may register files and create shares through
+    /// CPI. Only the program authority can change the policy.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry and authority
+    /// * `policy` - Open, deny or allowlist
+    /// * `allowlist` - Programs allowed under `CpiPolicy::Allowlist`
+    pub fn set_cpi_policy(
+        ctx: Context<UpdateRegistry>,
+        policy: CpiPolicy,
+        allowlist: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::initialize::set_cpi_policy_handler(ctx, policy, allowlist)
+    }
+
+    /// Return registry statistics, including the current and previous
+    /// epoch activity buckets, via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry account
+    pub fn get_registry_stats(ctx: Context<ReadRegistry>) -> Result<RegistryStats> {
+        instructions::initialize::get_stats_handler(ctx)
+    }
+
+    /// Return file metadata via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the file record, with every mirror
+    ///   of the file as remaining accounts
+    /// * `transaction_id` - The Arweave transaction ID of the file
+    pub fn get_file(ctx: Context<GetFile>, transaction_id: TxId) -> Result<FileInfo> {
+        instructions::register_file::get_file_handler(ctx, transaction_id)
+    }
+
+    /// Resolve an `owner/name` pair to its file and transaction ID via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the name record and its file record
+    pub fn resolve_name(ctx: Context<ResolveName>) -> Result<NameResolution> {
+        instructions::names::resolve_name_handler(ctx)
+    }
+
+    /// Return a wallet's public profile metadata via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the profile and, optionally, its avatar file record
+    pub fn get_profile(ctx: Context<GetProfile>) -> Result<ProfileView> {
+        instructions::profile::get_profile_handler(ctx)
+    }
+
+    /// Return a wallet's recently accessed files via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the wallet's profile
+    pub fn get_recent_files(ctx: Context<GetRecentFiles>) -> Result<RecentFilesView> {
+        instructions::profile::get_recent_files_handler(ctx)
+    }
+
+    /// Return a file's endowment totals and withdrawable balance via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the endowment
+    pub fn get_endowment(ctx: Context<GetEndowment>) -> Result<EndowmentView> {
+        instructions::endowment::get_endowment_handler(ctx)
+    }
+
+    /// Return a file's recent accesses, newest first, via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the access log
+    pub fn get_access_log(ctx: Context<GetAccessLog>) -> Result<AccessLogView> {
+        instructions::access_log::get_access_log_handler(ctx)
+    }
+
+    /// Return a file's released key via return data. Fails until the release
+    /// has been triggered.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the timed release
+    pub fn reveal_key(ctx: Context<RevealKey>) -> Result<ReleasedKey> {
+        instructions::timed_release::reveal_key_handler(ctx)
+    }
+
+    /// Return a page of a wallet's files from its file index via return data,
+    /// skipping purged files, with the cursor of the next page.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with `(entry, file_record)` pairs as remaining accounts
+    /// * `cursor` - Sequence number to start from (0, or a previous `next_cursor`)
+    /// * `limit` - Maximum entries to return (capped at `MAX_OWNER_FILES_PER_PAGE`)
+    pub fn list_owner_files<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListOwnerFiles<'info>>,
+        cursor: u64,
+        limit: u8,
+    ) -> Result<OwnerFilesPage> {
+        instructions::owner_index::list_owner_files_handler(ctx, cursor, limit)
+    }
+
+    /// Return a page of a wallet's storage statement via return data: live
+    /// files, their bytes and active shares, bytes served, and the fees the
+    /// wallet has paid. Walks the file index like `list_owner_files`.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with `(entry, file_record)` pairs as remaining accounts
+    /// * `cursor` - Sequence number to start from (0, or a previous `next_cursor`)
+    /// * `limit` - Maximum entries to read (capped at `MAX_STATEMENT_FILES_PER_PAGE`)
+    pub fn get_storage_statement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetStorageStatement<'info>>,
+        cursor: u64,
+        limit: u8,
+    ) -> Result<StorageStatement> {
+        instructions::owner_index::get_storage_statement_handler(ctx, cursor, limit)
+    }
+
+    /// Return a page of a wallet's incoming shares from its inbox via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context, with inbox entries as remaining accounts
+    /// * `start_seq` - Sequence number of the first entry
+    /// * `limit` - Maximum entries to read (capped at `MAX_INBOX_ENTRIES_PER_PAGE`)
+    pub fn list_inbox<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListInbox<'info>>,
+        start_seq: u64,
+        limit: u8,
+    ) -> Result<InboxPage> {
+        instructions::inbox::list_inbox_handler(ctx, start_seq, limit)
+    }
+
+    /// Return the current status of a share link via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the share link and file record
+    pub fn get_share_status(ctx: Context<GetShareStatus>) -> Result<ShareStatusView> {
+        instructions::create_share::get_share_status_handler(ctx)
+    }
+
+    /// Quote the registration fee for a file of the given size via return data.
+    /// 
+    /// # Arguments
+    /// * `ctx` - The context containing the registry account
+    /// * `size` - File size in bytes
+    pub fn estimate_fee(ctx: Context<ReadRegistry>, size: u64) -> Result<FeeQuote> {
+        instructions::initialize::estimate_fee_handler(ctx, size)
     }
 }
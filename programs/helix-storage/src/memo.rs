@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use anchor_spl::memo::{build_memo, BuildMemo, Memo};
+
+use crate::error::{validate_byte_length, HelixError};
+use crate::state::MAX_MEMO_LEN;
+
+/// Validate an optional memo and, when present, attach it to the
+/// transaction through the SPL Memo program.
+/// 
+/// The memo is only written to the transaction, never to program accounts.
+pub fn process_memo<'info>(
+    memo_program: &Option<Program<'info, Memo>>,
+    memo: Option<String>,
+) -> Result<()> {
+    let memo = match memo {
+        Some(memo) => memo,
+        None => return Ok(()),
+    };
+
+    require!(!memo.is_empty(), HelixError::EmptyMemo);
+    validate_byte_length("memo", &memo, MAX_MEMO_LEN, HelixError::MemoTooLong)?;
+
+    let memo_program = memo_program
+        .as_ref()
+        .ok_or(HelixError::MemoProgramMissing)?;
+
+    build_memo(
+        CpiContext::new(memo_program.to_account_info(), BuildMemo {}),
+        memo.as_bytes(),
+    )
+}
@@ -0,0 +1,160 @@
+use crate::state::MAX_TRANSACTION_ID_LEN;
+
+// Byte offsets of account fields for `getProgramAccounts` memcmp filters.
+//
+// Borsh encodes strings and options with variable lengths, so only fields
+// that precede the first variable-length field have a fixed offset. Fields
+// after `FileRecord.encrypted_name` and `ShareLink.encrypted_key` (such as
+// the `flags` bitfield) cannot be filtered by memcmp with the current layout.
+// A `None` option is a single tag byte, so a field after `ShareLink.recipient`
+// moves with the tag and its offset comes from a function of the tag.
+
+/// Length of the Anchor account discriminator
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+/// Offset of the account discriminator (all accounts)
+pub const DISCRIMINATOR: usize = 0;
+
+/// Offset of `StorageRegistry.authority`
+pub const REGISTRY_AUTHORITY: usize = DISCRIMINATOR_LEN;
+
+/// Offset of `FileRecord.owner`
+pub const FILE_RECORD_OWNER: usize = DISCRIMINATOR_LEN;
+
+/// Offset of the `FileRecord.transaction_id` length prefix (u32)
+pub const FILE_RECORD_TRANSACTION_ID_LEN: usize = FILE_RECORD_OWNER + 32;
+
+/// Offset of the `FileRecord.transaction_id` bytes
+pub const FILE_RECORD_TRANSACTION_ID: usize = FILE_RECORD_TRANSACTION_ID_LEN + 4;
+
+/// Offset of the `FileRecord.encrypted_name` option tag.
+/// Stable because transaction IDs are always exactly 43 bytes.
+pub const FILE_RECORD_ENCRYPTED_NAME: usize = FILE_RECORD_TRANSACTION_ID + MAX_TRANSACTION_ID_LEN;
+
+/// Offset of `OwnerFileEntry.owner`
+pub const OWNER_FILE_ENTRY_OWNER: usize = DISCRIMINATOR_LEN;
+
+/// Offset of `OwnerFileEntry.archived` (1 = archived). Filter on 0 here
+/// together with the owner to list a wallet's files without archived ones.
+pub const OWNER_FILE_ENTRY_ARCHIVED: usize = OWNER_FILE_ENTRY_OWNER + 32 + 8 + 32 + 1;
+
+/// Offset of `ShareLink.file`
+pub const SHARE_LINK_FILE: usize = DISCRIMINATOR_LEN;
+
+/// Offset of `ShareLink.owner`
+pub const SHARE_LINK_OWNER: usize = SHARE_LINK_FILE + 32;
+
+/// Offset of the `ShareLink.recipient` option tag (1 = recipient set)
+pub const SHARE_LINK_RECIPIENT_TAG: usize = SHARE_LINK_OWNER + 32;
+
+/// Offset of the `ShareLink.recipient` pubkey, valid when the tag is 1
+pub const SHARE_LINK_RECIPIENT: usize = SHARE_LINK_RECIPIENT_TAG + 1;
+
+/// Offset of the `ShareLink.encrypted_key` option tag, given the byte at
+/// `SHARE_LINK_RECIPIENT_TAG`: 32 bytes further for a recipient-locked share
+/// (tag 1) than for a public one (tag 0)
+pub const fn share_link_encrypted_key(recipient_tag: u8) -> usize {
+    if recipient_tag == 1 {
+        SHARE_LINK_RECIPIENT + 32
+    } else {
+        SHARE_LINK_RECIPIENT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::prelude::*;
+    use anchor_lang::Discriminator;
+
+    use super::*;
+    use crate::state::{FileRecord, OwnerFileEntry, ShareLink, StorageRegistry};
+    use crate::test_utils::{
+        account_data, sample_file, sample_registry, sample_share, zeroed, SAMPLE_TX_ID,
+    };
+
+    fn bytes_at(data: &[u8], offset: usize, len: usize) -> &[u8] {
+        &data[offset..offset + len]
+    }
+
+    #[test]
+    fn registry_offsets_match_a_serialized_registry() {
+        let authority = Pubkey::new_unique();
+        let (_, registry) = sample_registry(authority, 5_000);
+        let data = account_data(&registry, StorageRegistry::LEN);
+
+        assert_eq!(
+            bytes_at(&data, DISCRIMINATOR, DISCRIMINATOR_LEN),
+            StorageRegistry::DISCRIMINATOR
+        );
+        assert_eq!(bytes_at(&data, REGISTRY_AUTHORITY, 32), authority.as_ref());
+    }
+
+    #[test]
+    fn file_record_offsets_match_a_serialized_record() {
+        let owner = Pubkey::new_unique();
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 10);
+        file_record.encrypted_name = Some("name".to_string());
+        let data = account_data(&file_record, FileRecord::LEN);
+
+        assert_eq!(bytes_at(&data, DISCRIMINATOR, DISCRIMINATOR_LEN), FileRecord::DISCRIMINATOR);
+        assert_eq!(bytes_at(&data, FILE_RECORD_OWNER, 32), owner.as_ref());
+        assert_eq!(bytes_at(&data, FILE_RECORD_TRANSACTION_ID_LEN, 4), 43u32.to_le_bytes());
+        assert_eq!(bytes_at(&data, FILE_RECORD_TRANSACTION_ID, 43), SAMPLE_TX_ID.as_bytes());
+        assert_eq!(data[FILE_RECORD_ENCRYPTED_NAME], 1);
+        assert_eq!(bytes_at(&data, FILE_RECORD_ENCRYPTED_NAME + 1, 4), 4u32.to_le_bytes());
+    }
+
+    #[test]
+    fn owner_file_entry_offsets_match_a_serialized_entry() {
+        let owner = Pubkey::new_unique();
+        let mut entry = zeroed::<OwnerFileEntry>(OwnerFileEntry::LEN);
+        entry.owner = owner;
+        entry.seq = 3;
+        entry.file = Pubkey::new_unique();
+        entry.bump = 255;
+        entry.archived = true;
+        let data = account_data(&entry, OwnerFileEntry::LEN);
+
+        assert_eq!(bytes_at(&data, OWNER_FILE_ENTRY_OWNER, 32), owner.as_ref());
+        assert_eq!(data[OWNER_FILE_ENTRY_ARCHIVED], 1);
+        assert_eq!(data[OWNER_FILE_ENTRY_ARCHIVED - 1], 255);
+    }
+
+    #[test]
+    fn share_link_offsets_match_a_recipient_share() {
+        let file = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let (_, mut share_link) = sample_share(&file, owner, Some(recipient), 0);
+        share_link.encrypted_key = Some("key".to_string());
+        let data = account_data(&share_link, ShareLink::LEN);
+
+        assert_eq!(bytes_at(&data, DISCRIMINATOR, DISCRIMINATOR_LEN), ShareLink::DISCRIMINATOR);
+        assert_eq!(bytes_at(&data, SHARE_LINK_FILE, 32), file.as_ref());
+        assert_eq!(bytes_at(&data, SHARE_LINK_OWNER, 32), owner.as_ref());
+        assert_eq!(data[SHARE_LINK_RECIPIENT_TAG], 1);
+        assert_eq!(bytes_at(&data, SHARE_LINK_RECIPIENT, 32), recipient.as_ref());
+
+        let key = share_link_encrypted_key(data[SHARE_LINK_RECIPIENT_TAG]);
+        assert_eq!(data[key], 1);
+        assert_eq!(bytes_at(&data, key + 1, 4), 3u32.to_le_bytes());
+        assert_eq!(bytes_at(&data, key + 5, 3), b"key");
+    }
+
+    #[test]
+    fn share_link_offsets_match_a_public_share() {
+        let file = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (_, mut share_link) = sample_share(&file, owner, None, 0);
+        share_link.encrypted_key = Some("key".to_string());
+        let data = account_data(&share_link, ShareLink::LEN);
+
+        assert_eq!(bytes_at(&data, SHARE_LINK_OWNER, 32), owner.as_ref());
+        assert_eq!(data[SHARE_LINK_RECIPIENT_TAG], 0);
+
+        let key = share_link_encrypted_key(data[SHARE_LINK_RECIPIENT_TAG]);
+        assert_eq!(key, SHARE_LINK_RECIPIENT);
+        assert_eq!(data[key], 1);
+        assert_eq!(bytes_at(&data, key + 5, 3), b"key");
+    }
+}
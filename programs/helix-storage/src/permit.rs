@@ -0,0 +1,143 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::error::HelixError;
+use crate::types::EncryptedKey;
+
+/// Domain prefix of every share permit message
+pub const SHARE_PERMIT_DOMAIN: &[u8] = b"helix-share-permit";
+
+/// Domain prefix of every copyright statement message
+pub const COPYRIGHT_DOMAIN: &[u8] = b"helix-copyright";
+
+/// Size of the ed25519 instruction header (count, padding, one offsets entry)
+const ED25519_HEADER_LEN: usize = 2 + 14;
+
+/// Share creation terms signed off-chain by a file owner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SharePermit {
+    pub file: Pubkey,
+    pub recipient: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub max_downloads: Option<u32>,
+    pub encrypted_key: Option<EncryptedKey>,
+    pub nonce: u64,
+    pub deadline: i64,
+}
+
+impl SharePermit {
+    /// Canonical message the owner signs: the domain prefix, the program ID
+    /// and the Borsh encoding of the permit.
+    pub fn message(&self) -> Result<Vec<u8>> {
+        let mut message = SHARE_PERMIT_DOMAIN.to_vec();
+        message.extend_from_slice(crate::ID.as_ref());
+        self.serialize(&mut message)?;
+        Ok(message)
+    }
+}
+
+/// Canonical message a rights holder signs to claim copyright over a file's
+/// content: the domain prefix, the program ID, the file record address and
+/// the content hash.
+pub fn copyright_message(file: &Pubkey, content_hash: &[u8; 32]) -> Vec<u8> {
+    let mut message = COPYRIGHT_DOMAIN.to_vec();
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(file.as_ref());
+    message.extend_from_slice(content_hash);
+    message
+}
+
+/// Read a little-endian u16 from instruction data
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Verify that the instruction immediately before the current one is an
+/// Ed25519 program instruction checking `signer`'s signature over `message`.
+/// 
+/// The Ed25519 program has already verified the signature when this runs;
+/// this only confirms it covered the expected key and message.
+pub fn verify_ed25519_signature(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    require!(
+        previous_ed25519_matches(instructions_sysvar, signer, message, None)?,
+        HelixError::InvalidPermitSignature
+    );
+    Ok(())
+}
+
+/// Like `verify_ed25519_signature`, but also requires the Ed25519
+/// instruction to carry exactly `signature`, failing with `error` otherwise.
+pub fn verify_ed25519_signature_bytes(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+    error: HelixError,
+) -> Result<()> {
+    if !previous_ed25519_matches(instructions_sysvar, signer, message, Some(signature))? {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+/// Check whether the instruction preceding the current one is a single
+/// Ed25519 verification of `signer` over `message` (and `signature`, if given)
+fn previous_ed25519_matches(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: Option<&[u8; 64]>,
+) -> Result<bool> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    let Some(previous_index) = current_index.checked_sub(1) else {
+        return Ok(false);
+    };
+    let ix = load_instruction_at_checked(previous_index as usize, instructions_sysvar)?;
+
+    if ix.program_id != ed25519_program::ID || !ix.accounts.is_empty() {
+        return Ok(false);
+    }
+
+    Ok(ed25519_data_matches(&ix.data, signer, message, signature).unwrap_or(false))
+}
+
+/// Match the data of an Ed25519 program instruction against the expected
+/// key, message and signature. `None` means the data is malformed.
+fn ed25519_data_matches(
+    data: &[u8],
+    signer: &Pubkey,
+    message: &[u8],
+    signature: Option<&[u8; 64]>,
+) -> Option<bool> {
+    if data.len() < ED25519_HEADER_LEN || data[0] != 1 {
+        return Some(false);
+    }
+
+    // All offsets must point into this same instruction
+    let signature_offset = read_u16(data, 2)? as usize;
+    let public_key_offset = read_u16(data, 6)? as usize;
+    let message_offset = read_u16(data, 10)? as usize;
+    let message_size = read_u16(data, 12)? as usize;
+    for index_offset in [4, 8, 14] {
+        if read_u16(data, index_offset)? != u16::MAX {
+            return Some(false);
+        }
+    }
+
+    let public_key = data.get(public_key_offset..public_key_offset + 32)?;
+    let signed_message = data.get(message_offset..message_offset + message_size)?;
+    let signature_matches = match signature {
+        Some(signature) => data.get(signature_offset..signature_offset + 64)? == signature,
+        None => true,
+    };
+
+    Some(public_key == signer.as_ref() && signed_message == message && signature_matches)
+}
@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+
+use crate::error::HelixError;
+use crate::state::{FileRecord, ShareLink};
+
+// Checked readers for other programs that receive Helix accounts as plain
+// `AccountInfo`s. Deserializing alone only checks the discriminator, which
+// any program can copy into an account it owns; these also require the
+// account to be owned by the Helix program.
+
+impl FileRecord {
+    /// Deserialize a file record from an account, checking that it is owned
+    /// by the Helix program and carries the `FileRecord` discriminator
+    pub fn try_deserialize_checked(info: &AccountInfo) -> Result<Self> {
+        require_helix_owned(info)?;
+        Self::try_from_bytes(&info.try_borrow_data()?)
+    }
+}
+
+impl ShareLink {
+    /// Deserialize a share link from an account, checking that it is owned
+    /// by the Helix program and carries the `ShareLink` discriminator
+    pub fn try_deserialize_checked(info: &AccountInfo) -> Result<Self> {
+        require_helix_owned(info)?;
+        Self::try_from_bytes(&info.try_borrow_data()?)
+    }
+}
+
+/// Read a file record, failing unless it is a Helix file owned by
+/// `expected_owner`
+pub fn assert_file_owner(info: &AccountInfo, expected_owner: &Pubkey) -> Result<FileRecord> {
+    let file_record = FileRecord::try_deserialize_checked(info)?;
+    require_keys_eq!(file_record.owner, *expected_owner, HelixError::UnauthorizedOwner);
+    Ok(file_record)
+}
+
+/// Read a share link, failing unless it is a Helix share created by
+/// `expected_owner`
+pub fn assert_share_owner(info: &AccountInfo, expected_owner: &Pubkey) -> Result<ShareLink> {
+    let share_link = ShareLink::try_deserialize_checked(info)?;
+    require_keys_eq!(share_link.owner, *expected_owner, HelixError::UnauthorizedOwner);
+    Ok(share_link)
+}
+
+/// Fail with `AccountOwnedByWrongProgram` unless the Helix program owns `info`
+fn require_helix_owned(info: &AccountInfo) -> Result<()> {
+    if info.owner != &crate::ID {
+        return Err(error!(ErrorCode::AccountOwnedByWrongProgram)
+            .with_pubkeys((*info.owner, crate::ID)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{sample_file, sample_share, TestAccount, SAMPLE_TX_ID};
+
+    /// A Helix file and share of `owner`, as accounts
+    fn accounts(owner: Pubkey) -> (TestAccount, TestAccount) {
+        let file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let (address, share_link) = sample_share(&Pubkey::new_unique(), owner, None, 0);
+        (
+            TestAccount::new(Pubkey::new_unique(), &file_record, FileRecord::LEN),
+            TestAccount::new(address, &share_link, ShareLink::LEN),
+        )
+    }
+
+    /// Drop a read's account, keeping only whether it succeeded
+    fn outcome<T>(read: Result<T>) -> Result<()> {
+        read.map(|_| ())
+    }
+
+    #[test]
+    fn helix_owned_accounts_read_back() {
+        let owner = Pubkey::new_unique();
+        let (mut file_account, mut share_account) = accounts(owner);
+
+        assert_eq!(assert_file_owner(&file_account.info(), &owner).unwrap().size, 1024);
+        assert_eq!(assert_share_owner(&share_account.info(), &owner).unwrap().owner, owner);
+    }
+
+    #[test]
+    fn spoofed_copies_are_rejected() {
+        let owner = Pubkey::new_unique();
+        let (file_account, share_account) = accounts(owner);
+        let wrong_program = Err(ErrorCode::AccountOwnedByWrongProgram.into());
+
+        // Byte-for-byte copies owned by another program carry the right
+        // discriminator but must still fail
+        let spoofer = Pubkey::new_unique();
+        let mut file_account = file_account.owned_by(spoofer);
+        let mut share_account = share_account.owned_by(spoofer);
+        let file_info = file_account.info();
+        let share_info = share_account.info();
+        assert_eq!(outcome(FileRecord::try_deserialize_checked(&file_info)), wrong_program);
+        assert_eq!(outcome(ShareLink::try_deserialize_checked(&share_info)), wrong_program);
+        assert_eq!(outcome(assert_file_owner(&file_info, &owner)), wrong_program);
+        assert_eq!(outcome(assert_share_owner(&share_info, &owner)), wrong_program);
+    }
+
+    #[test]
+    fn other_accounts_and_owners_are_rejected() {
+        let owner = Pubkey::new_unique();
+        let (mut file_account, mut share_account) = accounts(owner);
+        let file_info = file_account.info();
+        let share_info = share_account.info();
+
+        // A Helix account of the other type fails on its discriminator
+        let mismatch = Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        assert_eq!(outcome(ShareLink::try_deserialize_checked(&file_info)), mismatch);
+        assert_eq!(outcome(FileRecord::try_deserialize_checked(&share_info)), mismatch);
+
+        // The right account held by someone else fails on its owner
+        let stranger = Pubkey::new_unique();
+        let unauthorized = Err(HelixError::UnauthorizedOwner.into());
+        assert_eq!(outcome(assert_file_owner(&file_info, &stranger)), unauthorized);
+        assert_eq!(outcome(assert_share_owner(&share_info, &stranger)), unauthorized);
+    }
+}
@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::pubkey;
+
+use crate::error::HelixError;
+use crate::state::{RegistryExt, REGISTRY_SEED};
+
+/// Metaplex Bubblegum program
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+/// Anchor discriminator of Bubblegum's `mint_v1` instruction
+const MINT_V1_DISCRIMINATOR: [u8; 8] = [145, 98, 192, 118, 184, 147, 118, 104];
+
+/// Maximum length of a compressed NFT name
+const MAX_RECEIPT_NAME_LEN: usize = 32;
+
+/// Symbol used for registration receipts
+const RECEIPT_SYMBOL: &str = "HELIX";
+
+/// Bubblegum `Collection` (only the fields Helix sets)
+#[derive(AnchorSerialize)]
+struct ReceiptCollection {
+    verified: bool,
+    key: Pubkey,
+}
+
+/// Bubblegum `MetadataArgs`, with the optional fields Helix never sets
+/// encoded as `None` and an empty creators list.
+#[derive(AnchorSerialize)]
+struct ReceiptMetadata {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    edition_nonce: Option<u8>,
+    /// `TokenStandard::NonFungible`
+    token_standard: Option<u8>,
+    collection: Option<ReceiptCollection>,
+    /// `Uses` is never set
+    uses: Option<u8>,
+    /// `TokenProgramVersion::Original`
+    token_program_version: u8,
+    /// `Vec<Creator>`, always empty
+    creators: Vec<u8>,
+}
+
+/// Accounts needed to mint a registration receipt
+pub struct ReceiptAccounts<'a, 'info> {
+    pub registry: &'a AccountInfo<'info>,
+    pub registry_bump: u8,
+    pub tree_authority: &'a AccountInfo<'info>,
+    pub merkle_tree: &'a AccountInfo<'info>,
+    pub owner: &'a AccountInfo<'info>,
+    pub bubblegum_program: &'a AccountInfo<'info>,
+    pub log_wrapper: &'a AccountInfo<'info>,
+    pub compression_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Build the `mint_v1` instruction data for a receipt named after
+/// `transaction_id` and pointing at `uri`, in `collection` unless it is the
+/// default pubkey.
+fn mint_v1_data(collection: Pubkey, transaction_id: &str, uri: String) -> Result<Vec<u8>> {
+    let collection = if collection == Pubkey::default() {
+        None
+    } else {
+        Some(ReceiptCollection {
+            verified: false,
+            key: collection,
+        })
+    };
+
+    let metadata = ReceiptMetadata {
+        name: transaction_id.chars().take(MAX_RECEIPT_NAME_LEN).collect(),
+        symbol: RECEIPT_SYMBOL.to_string(),
+        uri,
+        seller_fee_basis_points: 0,
+        primary_sale_happened: false,
+        is_mutable: false,
+        edition_nonce: None,
+        token_standard: Some(0),
+        collection,
+        uses: None,
+        token_program_version: 0,
+        creators: Vec::new(),
+    };
+
+    let mut data = MINT_V1_DISCRIMINATOR.to_vec();
+    metadata.serialize(&mut data)?;
+    Ok(data)
+}
+
+/// Mint a compressed NFT receipt for a registered file to its owner.
+/// 
+/// The registry PDA signs as the tree delegate, so the configured tree must
+/// have the registry set as its delegate. The leaf is named after the
+/// transaction ID and points at the file's Arweave URL. A failed mint fails
+/// the whole instruction, rolling back the registration with it.
+pub fn mint_receipt(
+    accounts: ReceiptAccounts,
+    registry_ext: &RegistryExt,
+    transaction_id: &str,
+    uri: String,
+) -> Result<()> {
+    require_keys_eq!(
+        accounts.bubblegum_program.key(),
+        BUBBLEGUM_PROGRAM_ID,
+        HelixError::InvalidReceiptAccount
+    );
+    require_keys_eq!(
+        accounts.merkle_tree.key(),
+        registry_ext.receipt_tree,
+        HelixError::InvalidReceiptAccount
+    );
+
+    let ix = Instruction {
+        program_id: BUBBLEGUM_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.tree_authority.key(), false),
+            AccountMeta::new_readonly(accounts.owner.key(), false),
+            AccountMeta::new_readonly(accounts.owner.key(), false),
+            AccountMeta::new(accounts.merkle_tree.key(), false),
+            AccountMeta::new(accounts.owner.key(), true),
+            AccountMeta::new_readonly(accounts.registry.key(), true),
+            AccountMeta::new_readonly(accounts.log_wrapper.key(), false),
+            AccountMeta::new_readonly(accounts.compression_program.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data: mint_v1_data(registry_ext.receipt_collection, transaction_id, uri)?,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            accounts.tree_authority.clone(),
+            accounts.owner.clone(),
+            accounts.merkle_tree.clone(),
+            accounts.registry.clone(),
+            accounts.log_wrapper.clone(),
+            accounts.compression_program.clone(),
+            accounts.system_program.clone(),
+            accounts.bubblegum_program.clone(),
+        ],
+        &[&[REGISTRY_SEED, &[accounts.registry_bump]]],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `mint_v1` data up to the collection, for `tx_id` and `uri`
+    fn expected_prefix(tx_id: &str, uri: &str) -> Vec<u8> {
+        let mut expected = MINT_V1_DISCRIMINATOR.to_vec();
+        for field in [&tx_id[..32], "HELIX", uri] {
+            expected.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            expected.extend_from_slice(field.as_bytes());
+        }
+        // Royalties, not sold, immutable, no edition nonce, non-fungible
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 1, 0]);
+        expected
+    }
+
+    #[test]
+    fn mint_v1_data_matches_metadata_args() {
+        let tx_id = "a".repeat(43);
+        let uri = format!("https://arweave.net/{tx_id}");
+        let data = mint_v1_data(Pubkey::default(), &tx_id, uri.clone()).unwrap();
+
+        let mut expected = expected_prefix(&tx_id, &uri);
+        // No collection or uses, original token program, no creators
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn mint_v1_data_records_an_unverified_collection() {
+        let tx_id = "b".repeat(43);
+        let uri = format!("https://arweave.net/{tx_id}");
+        let collection = Pubkey::new_unique();
+        let data = mint_v1_data(collection, &tx_id, uri.clone()).unwrap();
+
+        let mut expected = expected_prefix(&tx_id, &uri);
+        expected.extend_from_slice(&[1, 0]);
+        expected.extend_from_slice(collection.as_ref());
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+        assert_eq!(data, expected);
+    }
+}
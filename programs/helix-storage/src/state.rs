@@ -1,30 +1,415 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::{hash, hashv};
+
+use crate::error::HelixError;
+use crate::types::{Cid, MimeType, TxId};
 
 /// Maximum length of Arweave transaction ID (base64url encoded)
 pub const MAX_TRANSACTION_ID_LEN: usize = 43;
 
-/// Maximum length of encrypted filename
+/// Default maximum length of encrypted filename
 pub const MAX_ENCRYPTED_NAME_LEN: usize = 256;
 
+/// Hard ceiling for the registry-configurable encrypted filename limit
+pub const ENCRYPTED_NAME_LEN_CEILING: usize = 1024;
+
 /// Maximum length of MIME type string
 pub const MAX_MIME_TYPE_LEN: usize = 128;
 
 /// Maximum length of encrypted key for sharing
 pub const MAX_ENCRYPTED_KEY_LEN: usize = 512;
 
+/// Maximum length of the encrypted message attached to a share
+pub const MAX_SHARE_MESSAGE_LEN: usize = 256;
+
+/// Maximum length of an SPL Memo note attached to a transaction
+pub const MAX_MEMO_LEN: usize = 256;
+
 /// Seed for StorageRegistry PDA
 pub const REGISTRY_SEED: &[u8] = b"registry";
 
+/// Seed for RegistryExt PDA
+pub const REGISTRY_EXT_SEED: &[u8] = b"registry_ext";
+
 /// Seed for FileRecord PDA
 pub const FILE_SEED: &[u8] = b"file";
 
+/// The seed after `FILE_SEED` of the record for `transaction_id`: the
+/// SHA-256 of the ID, since its 43 characters exceed the 32-byte seed limit
+pub fn file_seed(transaction_id: &str) -> [u8; 32] {
+    hash(transaction_id.as_bytes()).to_bytes()
+}
+
 /// Seed for ShareLink PDA
 pub const SHARE_SEED: &[u8] = b"share";
 
+/// Seed for the per-file ownership token mint PDA
+pub const FILE_MINT_SEED: &[u8] = b"file_mint";
+
+/// FileRecord flag: file content is encrypted (the legacy `is_encrypted` byte)
+pub const FILE_FLAG_ENCRYPTED: u16 = 1 << 0;
+
+/// FileRecord flag: the deletion was performed by the program authority
+pub const FILE_FLAG_DELETED_BY_AUTHORITY: u16 = 1 << 1;
+
+/// FileRecord flag: ownership is represented by the file's token mint
+pub const FILE_FLAG_TOKENIZED: u16 = 1 << 2;
+
+/// FileRecord flag: record was allocated without the fixed MIME type slot
+pub const FILE_FLAG_COMPACT_MIME: u16 = 1 << 3;
+
+/// FileRecord flag: file has an entry in its owner's file index
+pub const FILE_FLAG_INDEXED: u16 = 1 << 4;
+
+/// FileRecord flag: metadata was wiped when the file was deleted
+pub const FILE_FLAG_SCRUBBED: u16 = 1 << 5;
+
+/// FileRecord flag: `owner` holds an owner commitment instead of a wallet
+pub const FILE_FLAG_STEALTH: u16 = 1 << 6;
+
+/// FileRecord flag: file is archived (mirrors `FileStatus::Archived`)
+pub const FILE_FLAG_ARCHIVED: u16 = 1 << 7;
+
+/// Seconds an ownership transfer proposal stays acceptable (7 days)
+pub const OWNERSHIP_TRANSFER_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Gateway serving base-layer Arweave transactions
+pub const ARWEAVE_GATEWAY_URL: &str = "https://arweave.net";
+
+/// Gateway serving data items of Irys bundles
+pub const IRYS_GATEWAY_URL: &str = "https://gateway.irys.xyz";
+
+/// Gateway serving IPFS content
+pub const IPFS_GATEWAY_URL: &str = "https://ipfs.io";
+
+/// Maximum length of an IPFS CID string
+pub const MAX_CID_LEN: usize = 100;
+
+/// Minimum length of a base32 CIDv1 string: a CID over a 256-bit digest
+pub const MIN_CID_V1_LEN: usize = 59;
+
+/// Domain prefix of the record IDs of IPFS content
+pub const IPFS_RECORD_DOMAIN: &[u8] = b"helix-ipfs-record";
+
+/// Domain prefix of stealth owner commitments
+pub const STEALTH_OWNER_DOMAIN: &[u8] = b"helix-stealth-owner";
+
+/// Domain prefix of stealth recipient commitments
+pub const STEALTH_RECIPIENT_DOMAIN: &[u8] = b"helix-stealth-recipient";
+
+/// Domain prefix of claimable share hashes
+pub const SHARE_CLAIM_DOMAIN: &[u8] = b"helix-share-claim";
+
+/// MIME type reported for records whose metadata has been scrubbed
+pub const SCRUBBED_MIME_TYPE: &str = "application/octet-stream";
+
+/// FileRecord flag: file is marked as deleted (the legacy `is_deleted` byte)
+pub const FILE_FLAG_DELETED: u16 = 1 << 8;
+
+/// FileRecord flag: file has an open endowment account
+pub const FILE_FLAG_ENDOWED: u16 = 1 << 9;
+
+/// FileRecord flag: `size` was confirmed by the program authority rather
+/// than only self-reported at registration
+pub const FILE_FLAG_SIZE_ATTESTED: u16 = 1 << 10;
+
+/// ShareLink flag: share has been revoked (the legacy `is_revoked` byte)
+pub const SHARE_FLAG_REVOKED: u8 = 1 << 0;
+
+/// ShareLink flag: the encrypted key was cleared when the share was revoked
+pub const SHARE_FLAG_KEY_CLEARED: u8 = 1 << 1;
+
+/// ShareLink flag: the share's file and registry share counters were
+/// released. Links revoked before the flag existed read it clear, but every
+/// release path only runs for a link it revokes itself.
+pub const SHARE_FLAG_COUNTERS_RELEASED: u8 = 1 << 2;
+
+/// ShareLink flag: share allows one download and is revoked by it
+pub const SHARE_FLAG_SINGLE_USE: u8 = 1 << 3;
+
+/// ShareLink flag: the recipient was warned that the share expires soon
+pub const SHARE_FLAG_EXPIRY_WARNED: u8 = 1 << 4;
+
+/// Seed for MimeTable PDA
+pub const MIME_TABLE_SEED: &[u8] = b"mime_table";
+
+/// Maximum number of interned MIME types
+pub const MAX_MIME_TABLE_ENTRIES: usize = 32;
+
+/// Seed for SessionKey PDA
+pub const SESSION_SEED: &[u8] = b"session";
+
+/// Seed for UserProfile PDA
+pub const USER_PROFILE_SEED: &[u8] = b"user_profile";
+
+/// Number of retired encryption keys kept on a user profile
+pub const MAX_PREVIOUS_ENCRYPTION_KEYS: usize = 3;
+
+/// Maximum length of a profile's public display name
+pub const MAX_DISPLAY_NAME_LEN: usize = 48;
+
+/// Seed for Contact PDA
+pub const CONTACT_SEED: &[u8] = b"contact";
+
+/// Maximum length of a contact's encrypted alias
+pub const MAX_CONTACT_ALIAS_LEN: usize = 64;
+
+/// Seed for FileAlias PDA
+pub const ALIAS_SEED: &[u8] = b"alias";
+
+/// Maximum length of a file alias's encrypted name
+pub const MAX_FILE_ALIAS_LEN: usize = 128;
+
+/// Default maximum number of aliases per file
+pub const DEFAULT_MAX_ALIASES_PER_FILE: u16 = 8;
+
+/// Seed for Mirror PDA
+pub const MIRROR_SEED: &[u8] = b"mirror";
+
+/// Default maximum number of mirrors per file
+pub const DEFAULT_MAX_MIRRORS_PER_FILE: u16 = 4;
+
+/// Seed for FileReference PDA
+pub const REFERENCE_SEED: &[u8] = b"reference";
+
+/// Maximum length of a file reference's encrypted note
+pub const MAX_REFERENCE_NOTE_LEN: usize = 256;
+
+/// Seed for NameRecord PDA
+pub const NAME_SEED: &[u8] = b"name";
+
+/// Maximum length of a registered name
+pub const MAX_NAME_LEN: usize = 32;
+
+/// Seed for Treasury PDA
+pub const TREASURY_SEED: &[u8] = b"treasury";
+
+/// Seed for ShortCode PDA
+pub const SHORT_CODE_SEED: &[u8] = b"short";
+
+/// Minimum length of a short code
+pub const MIN_SHORT_CODE_LEN: usize = 4;
+
+/// Maximum length of a short code
+pub const MAX_SHORT_CODE_LEN: usize = 16;
+
+/// Seed for GalleryEntry PDA
+pub const GALLERY_SEED: &[u8] = b"gallery";
+
+/// Seed for LikeReceipt PDA
+pub const LIKE_SEED: &[u8] = b"like";
+
+/// Seed for Endowment PDA
+pub const ENDOWMENT_SEED: &[u8] = b"endowment";
+
+/// Seed for FileAccessLog PDA
+pub const ACCESS_LOG_SEED: &[u8] = b"access_log";
+
+/// Number of entries kept in a file's access log
+pub const ACCESS_LOG_ENTRIES: usize = 16;
+
+/// Seed for TimedRelease PDA
+pub const TIMED_RELEASE_SEED: &[u8] = b"timed_release";
+
+/// Seed for InboxEntry PDA
+pub const INBOX_SEED: &[u8] = b"inbox";
+
+/// Maximum entries returned by one `list_inbox` call. Each entry takes
+/// 40 bytes of return data, which is capped at 1024 bytes.
+pub const MAX_INBOX_ENTRIES_PER_PAGE: u8 = 25;
+
+/// Seed for OwnerFileEntry PDA
+pub const OWNER_FILE_SEED: &[u8] = b"owner_file";
+
+/// Maximum entries returned by one `list_owner_files` call. Each entry
+/// takes 77 bytes of return data, which is capped at 1024 bytes.
+pub const MAX_OWNER_FILES_PER_PAGE: u8 = 12;
+
+/// Maximum index entries read by one `get_storage_statement` call
+pub const MAX_STATEMENT_FILES_PER_PAGE: u8 = 16;
+
+/// Default maximum session key lifetime in seconds (7 days)
+pub const DEFAULT_MAX_SESSION_LIFETIME: i64 = 7 * 24 * 60 * 60;
+
+/// Default window in seconds during which deleted files can be restored (30 days)
+pub const DEFAULT_TRASH_RETENTION_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Session scope: record downloads on shares addressed to the wallet
+pub const SESSION_SCOPE_RECORD_DOWNLOAD: u8 = 1 << 0;
+
+/// All defined session scope bits
+pub const SESSION_SCOPE_ALL: u8 = SESSION_SCOPE_RECORD_DOWNLOAD;
+
+/// Number of per-category counters held by the registry extension
+pub const MAX_CATEGORIES: usize = 16;
+
+/// Maximum number of operators on the registry
+pub const MAX_OPERATORS: usize = 4;
+
+/// Maximum number of programs on the registry's CPI allowlist
+pub const MAX_CPI_ALLOWLIST: usize = 8;
+
+/// Maximum number of gateway attestors in the registry extension
+pub const MAX_ATTESTORS: usize = 8;
+
+/// Seed for Attestation PDA
+pub const ATTESTATION_SEED: &[u8] = b"attest";
+
+/// Seed for AuditGrant PDA
+pub const AUDIT_GRANT_SEED: &[u8] = b"audit";
+
+/// Maximum depth of a chunk Merkle tree, bounding `verify_chunk` proofs
+pub const MAX_CHUNK_PROOF_DEPTH: usize = 20;
+
+/// Number of recently accessed files kept on a user profile
+pub const MAX_RECENT_FILES: usize = 8;
+
+/// `expires_at` passed to `create_share` to opt out of the profile's default
+/// expiration
+pub const SHARE_NO_EXPIRY: i64 = 0;
+
+/// `max_downloads` passed to `create_share` to opt out of the profile's
+/// default download limit
+pub const SHARE_UNLIMITED_DOWNLOADS: u32 = 0;
+
+/// Seed for ShareTemplate PDA
+pub const SHARE_TEMPLATE_SEED: &[u8] = b"template";
+
+/// Seed for Challenge PDA
+pub const CHALLENGE_SEED: &[u8] = b"challenge";
+
+/// Bond a challenger locks in a proof-of-access challenge (0.01 SOL)
+pub const CHALLENGE_BOND_LAMPORTS: u64 = 10_000_000;
+
+/// Shortest response window a challenge may give the owner (1 hour)
+pub const MIN_CHALLENGE_WINDOW_SECS: i64 = 60 * 60;
+
+/// Longest response window a challenge may give the owner (7 days)
+pub const MAX_CHALLENGE_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Time after the deadline the challenger has to accept an answer (3 days)
+pub const CHALLENGE_REVIEW_SECS: i64 = 3 * 24 * 60 * 60;
+
+/// Maximum number of share/file pairs handled by one `process_expirations` call
+pub const MAX_EXPIRATIONS_PER_CALL: usize = 16;
+
+/// Maximum number of file records handled by one `restore_files_batch` call
+pub const MAX_RESTORES_PER_CALL: usize = 16;
+
+/// Maximum number of file records handled by one `update_names_batch` call
+pub const MAX_NAME_UPDATES_PER_CALL: usize = 16;
+
+/// Maximum number of file/share pairs handled by one `create_shares_batch`
+/// call. Kept low because each entry creates an account.
+pub const MAX_SHARES_PER_BATCH: usize = 8;
+
+/// Maximum number of files registered by one `register_files_compact` call.
+/// Kept low because each entry creates an account.
+pub const MAX_FILES_PER_COMPACT_BATCH: usize = 8;
+
+/// Maximum number of accounts handled by one `export_snapshot` call. Kept
+/// low because each entry emits an event carrying its encrypted blobs.
+pub const MAX_SNAPSHOT_ENTRIES_PER_CALL: usize = 8;
+
+/// Operator permission: force-delete file records
+pub const OPERATOR_DELETE_FILE: u8 = 1 << 0;
+
+/// Operator permission: force-revoke share links
+pub const OPERATOR_REVOKE_SHARE: u8 = 1 << 1;
+
+/// All operator permission bits currently defined
+pub const OPERATOR_ALL_PERMISSIONS: u8 = OPERATOR_DELETE_FILE | OPERATOR_REVOKE_SHARE;
+
+/// Lifetime counters of the registry that `reconcile_counters` can set
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct RegistryCounters {
+    /// Live files that are not archived
+    pub total_files: u64,
+    
+    /// Active share links
+    pub total_shares: u64,
+    
+    /// Bytes of live files
+    pub total_bytes: u64,
+    
+    /// Bytes served through counted downloads
+    pub total_bytes_served: u64,
+    
+    /// Archived files
+    pub archived_files: u64,
+}
+
+impl RegistryCounters {
+    /// Whether the counters are consistent with each other and with the
+    /// registry's `share_limit`: every live file holds at least one byte,
+    /// and no file holds more than `share_limit` shares (any number when
+    /// `None`). Without any files there can be no shares or stored bytes.
+    pub fn is_plausible(&self, share_limit: Option<u32>) -> bool {
+        let live_files = self.total_files.saturating_add(self.archived_files);
+        if live_files == 0 {
+            return self.total_shares == 0 && self.total_bytes == 0;
+        }
+
+        let shares_fit = share_limit.is_none_or(|limit| {
+            u128::from(self.total_shares) <= u128::from(live_files) * u128::from(limit)
+        });
+        shares_fit && self.total_bytes >= live_files
+    }
+}
+
+/// Activity counters for a single Solana epoch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct EpochStats {
+    /// Files registered during the epoch
+    pub files: u64,
+    
+    /// Share links created during the epoch
+    pub shares: u64,
+    
+    /// Downloads recorded during the epoch
+    pub downloads: u64,
+    
+    /// Bytes registered during the epoch
+    pub bytes: u64,
+}
+
+impl EpochStats {
+    pub const LEN: usize = 8  // files
+        + 8   // shares
+        + 8   // downloads
+        + 8;  // bytes
+}
+
+/// Secondary key allowed to perform a limited set of moderation actions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Operator {
+    /// Operator wallet address
+    pub key: Pubkey,
+    
+    /// Bitmask of `OPERATOR_*` permissions
+    pub permissions: u8,
+}
+
+impl Operator {
+    pub const LEN: usize = 32  // key
+        + 1;  // permissions
+}
+
+/// Which programs may invoke registration and share creation through CPI.
+/// Transaction-level calls are allowed under every policy.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpiPolicy {
+    /// Any program may call. Declared first so zeroed bytes decode as open.
+    Open,
+    /// No program may call
+    Deny,
+    /// Only programs on the registry's `cpi_allowlist` may call
+    Allowlist,
+}
+
 /// Global storage registry configuration.
 /// Stores program-wide settings and authority information.
 #[account]
-#[derive(Default)]
 pub struct StorageRegistry {
     /// Program authority who can update settings
     pub authority: Pubkey,
@@ -44,8 +429,87 @@ pub struct StorageRegistry {
     /// Bump seed for PDA derivation
     pub bump: u8,
     
+    /// Epoch the current stats bucket belongs to
+    pub current_epoch: u64,
+    
+    /// Activity counters for the current epoch
+    pub current_epoch_stats: EpochStats,
+    
+    /// Activity counters for the epoch before `current_epoch`
+    pub previous_epoch_stats: EpochStats,
+    
+    /// Operators with limited moderation permissions
+    pub operators: Vec<Operator>,
+    
+    /// Which programs may register files and create shares through CPI
+    pub cpi_policy: CpiPolicy,
+    
+    /// Programs allowed to call through CPI under `CpiPolicy::Allowlist`
+    pub cpi_allowlist: Vec<Pubkey>,
+    
+    /// Maximum encrypted filename length (0 = `MAX_ENCRYPTED_NAME_LEN`)
+    pub max_encrypted_name_len: u16,
+    
+    /// Files of at most this many bytes register for free (0 = disabled)
+    pub free_below_bytes: u64,
+    
+    /// Maximum session key lifetime in seconds (0 = `DEFAULT_MAX_SESSION_LIFETIME`)
+    pub max_session_lifetime: i64,
+    
+    /// Window in seconds during which deleted files can be restored
+    /// (0 = `DEFAULT_TRASH_RETENTION_SECS`)
+    pub trash_retention_secs: i64,
+    
+    /// Maximum number of aliases per file (0 = `DEFAULT_MAX_ALIASES_PER_FILE`)
+    pub max_aliases_per_file: u16,
+    
+    /// Fee in lamports paid to the treasury for claiming a short code
+    pub short_code_fee_lamports: u64,
+    
+    /// Total bytes served through counted downloads (saturating)
+    pub total_bytes_served: u64,
+    
+    /// Whether deleting a file wipes its metadata and share keys
+    pub scrub_on_delete: bool,
+    
+    /// Number of archived files, which `total_files` does not count
+    pub archived_files: u64,
+    
+    /// Fee in lamports paid to the treasury for listing a file in the gallery
+    pub gallery_fee_lamports: u64,
+    
+    /// Whether only live, unencrypted files can be liked
+    pub likes_require_public: bool,
+    
+    /// Seconds before a share's expiry during which `notify_expiring_share`
+    /// can warn its recipient (0 = warnings disabled)
+    pub expiry_warning_secs: i64,
+    
+    /// Lamports paid from the treasury to the caller of
+    /// `notify_expiring_share`, or of `process_expirations` for each unwarned
+    /// share it expires (0 = no bounty)
+    pub expiry_warning_bounty_lamports: u64,
+    
+    /// Maximum number of mirrors per file (0 = `DEFAULT_MAX_MIRRORS_PER_FILE`)
+    pub max_mirrors_per_file: u16,
+    
+    /// Throttle `record_download` applies to public shares
+    pub download_throttle: DownloadThrottle,
+    
+    /// Lifetime protocol fees paid into the treasury
+    pub total_fees_collected: u64,
+    
+    /// Wallet proposed as the next authority (None = no transfer proposed)
+    pub pending_authority: Option<Pubkey>,
+    
+    /// Total bytes across all non-deleted files
+    pub total_bytes: u64,
+    
+    /// Maximum number of live share links per file (0 = unlimited)
+    pub max_shares_per_file: u32,
+    
     /// Reserved space for future upgrades
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 2],
 }
 
 impl StorageRegistry {
@@ -56,9 +520,246 @@ impl StorageRegistry {
         + 8   // total_shares
         + 1   // is_paused
         + 1   // bump
-        + 64; // reserved
+        + 8   // current_epoch
+        + EpochStats::LEN  // current_epoch_stats
+        + EpochStats::LEN  // previous_epoch_stats
+        + 4 + MAX_OPERATORS * Operator::LEN  // operators (vec)
+        + 1   // cpi_policy
+        + 4 + MAX_CPI_ALLOWLIST * 32  // cpi_allowlist (vec)
+        + 2   // max_encrypted_name_len
+        + 8   // free_below_bytes
+        + 8   // max_session_lifetime
+        + 8   // trash_retention_secs
+        + 2   // max_aliases_per_file
+        + 8   // short_code_fee_lamports
+        + 8   // total_bytes_served
+        + 1   // scrub_on_delete
+        + 8   // archived_files
+        + 8   // gallery_fee_lamports
+        + 1   // likes_require_public
+        + 8   // expiry_warning_secs
+        + 8   // expiry_warning_bounty_lamports
+        + 2   // max_mirrors_per_file
+        + DownloadThrottle::LEN  // download_throttle
+        + 8   // total_fees_collected
+        + 33  // pending_authority (Option<Pubkey>)
+        + 8   // total_bytes
+        + 4   // max_shares_per_file
+        + 2;  // reserved
+
+    /// Whether a call made through CPI from a transaction whose top-level
+    /// instruction invokes `caller` is allowed by the CPI policy. `None`
+    /// means the calling program could not be determined.
+    pub fn allows_cpi_from(&self, caller: Option<&Pubkey>) -> bool {
+        match self.cpi_policy {
+            CpiPolicy::Open => true,
+            CpiPolicy::Deny => false,
+            CpiPolicy::Allowlist => caller.is_some_and(|caller| {
+                *caller == crate::ID || self.cpi_allowlist.contains(caller)
+            }),
+        }
+    }
+
+    /// Current values of the lifetime counters
+    pub fn counters(&self) -> RegistryCounters {
+        RegistryCounters {
+            total_files: self.total_files,
+            total_shares: self.total_shares,
+            total_bytes: self.total_bytes,
+            total_bytes_served: self.total_bytes_served,
+            archived_files: self.archived_files,
+        }
+    }
+
+    /// Overwrite the lifetime counters
+    pub fn set_counters(&mut self, counters: RegistryCounters) {
+        self.total_files = counters.total_files;
+        self.total_shares = counters.total_shares;
+        self.total_bytes = counters.total_bytes;
+        self.total_bytes_served = counters.total_bytes_served;
+        self.archived_files = counters.archived_files;
+    }
+
+    /// Advance the epoch buckets to `epoch`.
+    /// 
+    /// When the epoch has moved forward by exactly one, the current bucket
+    /// becomes the previous one; after a longer gap both buckets are zeroed.
+    /// Calling this again within the same epoch is a no-op.
+    /// 
+    /// Returns `true` if a rollover happened.
+    pub fn roll_epoch(&mut self, epoch: u64) -> bool {
+        if epoch <= self.current_epoch {
+            return false;
+        }
+
+        if self.current_epoch.checked_add(1) == Some(epoch) {
+            self.previous_epoch_stats = self.current_epoch_stats;
+        } else {
+            self.previous_epoch_stats = EpochStats::default();
+        }
+        self.current_epoch_stats = EpochStats::default();
+        self.current_epoch = epoch;
+
+        true
+    }
+
+    /// Effective maximum encrypted filename length
+    pub fn encrypted_name_limit(&self) -> usize {
+        match self.max_encrypted_name_len {
+            0 => MAX_ENCRYPTED_NAME_LEN,
+            len => len as usize,
+        }
+    }
+
+    /// Effective maximum session key lifetime in seconds
+    pub fn session_lifetime_limit(&self) -> i64 {
+        match self.max_session_lifetime {
+            0 => DEFAULT_MAX_SESSION_LIFETIME,
+            lifetime => lifetime,
+        }
+    }
+
+    /// Effective trash retention window in seconds
+    pub fn trash_retention(&self) -> i64 {
+        match self.trash_retention_secs {
+            0 => DEFAULT_TRASH_RETENTION_SECS,
+            secs => secs,
+        }
+    }
+
+    /// Effective maximum number of aliases per file
+    pub fn alias_limit(&self) -> u16 {
+        match self.max_aliases_per_file {
+            0 => DEFAULT_MAX_ALIASES_PER_FILE,
+            limit => limit,
+        }
+    }
+
+    /// Effective maximum number of mirrors per file
+    pub fn mirror_limit(&self) -> u16 {
+        match self.max_mirrors_per_file {
+            0 => DEFAULT_MAX_MIRRORS_PER_FILE,
+            limit => limit,
+        }
+    }
+
+    /// Maximum number of live share links per file, if one is configured
+    pub fn share_limit(&self) -> Option<u32> {
+        match self.max_shares_per_file {
+            0 => None,
+            limit => Some(limit),
+        }
+    }
+
+    /// Check that a file with `share_count` live links can take another
+    pub fn require_share_capacity(&self, share_count: u32) -> Result<()> {
+        if let Some(limit) = self.share_limit() {
+            require!(share_count < limit, HelixError::ShareLimitReached);
+        }
+        Ok(())
+    }
+
+    /// Registration fee in lamports for a file of the given size.
+    /// Files at or below `free_below_bytes` are waived entirely.
+    pub fn registration_fee(&self, size: u64) -> u64 {
+        if self.free_below_bytes > 0 && size <= self.free_below_bytes {
+            return 0;
+        }
+
+        self.base_fee_lamports
+    }
+
+    /// Check if a wallet may perform a moderation action.
+    /// The authority holds every permission; operators need the matching bit.
+    pub fn is_moderator(&self, wallet: &Pubkey, permission: u8) -> bool {
+        if *wallet == self.authority {
+            return true;
+        }
+
+        self.operators
+            .iter()
+            .any(|op| op.key == *wallet && op.permissions & permission == permission)
+    }
+}
+
+/// Zero-copy extension of the storage registry holding large fixed-size tables.
+/// Loaded through `AccountLoader` only by instructions that need the tables,
+/// so handlers touching core registry fields never pay to deserialize it.
+#[account(zero_copy)]
+pub struct RegistryExt {
+    /// The registry this extension belongs to
+    pub registry: Pubkey,
+    
+    /// Per-category counters
+    pub category_counters: [u64; MAX_CATEGORIES],
+    
+    /// Layout version of the extension
+    pub version: u8,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Explicit padding to keep the layout 8-byte aligned
+    pub _padding: [u8; 6],
+    
+    /// Merkle tree receiving compressed NFT registration receipts
+    /// (default pubkey when receipts are disabled)
+    pub receipt_tree: Pubkey,
+    
+    /// Collection recorded on receipts (default pubkey for none)
+    pub receipt_collection: Pubkey,
+    
+    /// Gateway operators allowed to attest file replicas; only the first
+    /// `attestor_count` entries are set
+    pub attestors: [Pubkey; MAX_ATTESTORS],
+    
+    /// Number of entries in `attestors`
+    pub attestor_count: u8,
+    
+    /// Explicit padding to keep the layout 8-byte aligned
+    pub _padding2: [u8; 7],
+    
+    /// Reserved space for future tables
+    pub _reserved: [u8; 696],
+}
+
+impl RegistryExt {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // registry
+        + 8 * MAX_CATEGORIES  // category_counters
+        + 1   // version
+        + 1   // bump
+        + 6   // padding
+        + 32  // receipt_tree
+        + 32  // receipt_collection
+        + 32 * MAX_ATTESTORS  // attestors
+        + 1   // attestor_count
+        + 7   // padding
+        + 696; // reserved
+
+    /// Current layout version
+    pub const VERSION: u8 = 1;
+
+    /// Whether registration receipts are configured
+    pub fn receipts_enabled(&self) -> bool {
+        self.receipt_tree != Pubkey::default()
+    }
+
+    /// The configured gateway attestors
+    pub fn attestors(&self) -> &[Pubkey] {
+        &self.attestors[..self.attestor_count as usize]
+    }
+
+    /// Check if a wallet is in the attestor set
+    pub fn is_attestor(&self, wallet: &Pubkey) -> bool {
+        self.attestors().contains(wallet)
+    }
 }
 
+// The zero-copy layout must match `LEN` exactly and contain no implicit padding.
+const _: () = assert!(RegistryExt::LEN == 8 + std::mem::size_of::<RegistryExt>());
+const _: () = assert!(std::mem::align_of::<RegistryExt>() == 8);
+
 /// Individual file record linking a wallet to an Arweave transaction.
 /// Stores metadata and access control information.
 #[account]
@@ -78,11 +779,10 @@ pub struct FileRecord {
     /// File size in bytes
     pub size: u64,
     
-    /// Whether the file content is encrypted
-    pub is_encrypted: bool,
-    
-    /// Whether the file is marked as deleted
-    pub is_deleted: bool,
+    /// Bitfield of `FILE_FLAG_*` values. Stored little-endian over the two
+    /// bytes that held the `is_encrypted` and `is_deleted` booleans, so older
+    /// records decode unchanged.
+    pub flags: u16,
     
     /// Unix timestamp when file was registered
     pub created_at: i64,
@@ -96,8 +796,86 @@ pub struct FileRecord {
     /// Bump seed for PDA derivation
     pub bump: u8,
     
+    /// Interned MIME type code in the `MimeTable` (0 = literal `mime_type`)
+    pub mime_code: u16,
+    
+    /// Unix timestamp when the file was last deleted (0 = not deleted)
+    pub deleted_at: i64,
+    
+    /// Number of aliases pointing at this file
+    pub alias_count: u16,
+    
+    /// Number of references saved to this file (saturating)
+    pub reference_count: u32,
+    
+    /// Total bytes served through counted downloads of its shares (saturating)
+    pub bytes_served: u64,
+    
+    /// Number of gateway attestations held for this file
+    pub attestation_count: u16,
+    
+    /// Number of proof-of-access challenges left unanswered (saturating)
+    pub failed_challenges: u32,
+    
+    /// Rights-holder key of the latest copyright statement (default if none)
+    pub copyright_holder: Pubkey,
+    
+    /// Unix timestamp of the latest copyright statement (0 if none)
+    pub copyright_attested_at: i64,
+    
+    /// The record this file was derived from (set only at registration)
+    pub derived_from: Option<Pubkey>,
+    
+    /// Number of files registered as derived from this one
+    pub derivative_count: u32,
+    
+    /// Merkle root over the file's chunk transaction IDs (None = not chunked)
+    pub chunk_root: Option<[u8; 32]>,
+    
+    /// Lifecycle status. Records deleted before the status was stored still
+    /// read `Active` here; `file_status` resolves them through the legacy
+    /// deleted flag, which `set_status` keeps in sync.
+    pub status: FileStatus,
+    
+    /// Number of wallets currently liking the file, one per `LikeReceipt`
+    /// (saturating)
+    pub like_count: u32,
+    
+    /// Where `transaction_id` resolves. Records registered before the
+    /// location was stored read `ArweaveL1` here.
+    pub storage_location: StorageLocation,
+    
+    /// Normalized CIDv1 of IPFS content (None = Arweave content, including
+    /// every record registered before IPFS support). When set,
+    /// `transaction_id` holds the CID's `ipfs_record_id` rather than an
+    /// Arweave transaction.
+    pub ipfs_cid: Option<String>,
+    
+    /// Number of mirrors of the file's content on other backends
+    pub mirror_count: u16,
+    
+    /// Wallet that paid the record's rent, refunded when it is closed
+    /// (default = the owner; see `rent_recipient`)
+    pub rent_payer: Pubkey,
+    
+    /// Wallet proposed as the next owner (None = no transfer proposed)
+    pub pending_owner: Option<Pubkey>,
+    
+    /// Unix timestamp after which the proposed transfer lapses
+    pub transfer_expires_at: i64,
+    
+    /// Registration sequence number from the registering owner's profile,
+    /// strictly increasing across their files (0 = not assigned, e.g. for
+    /// stealth records or registrations without the owner's profile)
+    pub owner_seq: u64,
+    
+    /// Nonce of the file's next share link PDA. Never decremented, so a
+    /// closed link's address is not reused and reconciling the registry's
+    /// share counter cannot move it.
+    pub share_nonce: u64,
+    
     /// Reserved space for future upgrades
-    pub _reserved: [u8; 32],
+    pub _reserved: [u8; 1],
 }
 
 impl FileRecord {
@@ -107,29 +885,387 @@ impl FileRecord {
         + 1 + 4 + MAX_ENCRYPTED_NAME_LEN  // encrypted_name (option + string)
         + 4 + MAX_MIME_TYPE_LEN  // mime_type (string)
         + 8   // size
-        + 1   // is_encrypted
-        + 1   // is_deleted
+        + 2   // flags
         + 8   // created_at
         + 8   // updated_at
         + 4   // share_count
         + 1   // bump
-        + 32; // reserved
+        + 2   // mime_code
+        + 8   // deleted_at
+        + 2   // alias_count
+        + 4   // reference_count
+        + 8   // bytes_served
+        + 2   // attestation_count
+        + 4   // failed_challenges
+        + 32  // copyright_holder
+        + 8   // copyright_attested_at
+        + 33  // derived_from (Option<Pubkey>)
+        + 4   // derivative_count
+        + 33  // chunk_root (Option<[u8; 32]>)
+        + 1   // status
+        + 4   // like_count
+        + StorageLocation::LEN  // storage_location
+        + 1   // ipfs_cid (option tag; `compact_space` adds the string)
+        + 2   // mirror_count
+        + 32  // rent_payer
+        + 33  // pending_owner (Option<Pubkey>)
+        + 8   // transfer_expires_at
+        + 8   // owner_seq
+        + 8   // share_nonce
+        + 1;  // reserved
 
-    /// Check if the file is accessible (not deleted)
-    pub fn is_accessible(&self) -> bool {
-        !self.is_deleted
+    /// Account space needed for a record with the given encrypted name.
+    /// Names up to `MAX_ENCRYPTED_NAME_LEN` fit in the default allocation;
+    /// only longer names pay rent for the extra bytes.
+    pub fn space<S: AsRef<str>>(encrypted_name: &Option<S>) -> usize {
+        let name_len = encrypted_name.as_ref().map_or(0, |name| name.as_ref().len());
+        Self::LEN + name_len.saturating_sub(MAX_ENCRYPTED_NAME_LEN)
     }
 
-    /// Get the Arweave URL for this file
-    pub fn arweave_url(&self) -> String {
-        format!("https://arweave.net/{}", self.transaction_id)
+    /// Space to give `init_if_needed` for the record account `info`: `space`
+    /// for a new record, or the current size of an existing one. Matching the
+    /// existing size lets the registration handlers reject a duplicate
+    /// transaction ID with `DuplicateTransactionId` rather than failing in
+    /// account creation or the space check.
+    pub fn init_space(info: &AccountInfo, space: usize) -> usize {
+        if info.data_is_empty() {
+            space
+        } else {
+            info.data_len()
+        }
     }
-}
 
-/// Share link for granting access to a file.
-/// Supports time-based expiration and download limits.
-#[account]
-pub struct ShareLink {
+    /// Account space for a record registered through `register_file_v2`.
+    /// Interned MIME types store no string bytes and literals pay only for
+    /// their own length instead of the fixed `MAX_MIME_TYPE_LEN` slot.
+    /// IPFS content adds the bytes of its CID.
+    pub fn compact_space<S: AsRef<str>>(
+        encrypted_name: &Option<S>,
+        mime_type: &MimeTypeArg,
+        content: &ContentLocator,
+    ) -> usize {
+        let mime_len = match mime_type {
+            MimeTypeArg::Code(_) => 0,
+            MimeTypeArg::Literal(mime) => mime.len().min(MAX_MIME_TYPE_LEN),
+        };
+        let cid_len = match content {
+            ContentLocator::Arweave(_) => 0,
+            ContentLocator::Ipfs { cid } => 4 + cid.len(),
+        };
+        Self::space(encrypted_name) - MAX_MIME_TYPE_LEN + mime_len + cid_len
+    }
+
+    /// Account space for this record after changing its encrypted name
+    pub fn resized_space<S: AsRef<str>>(&self, encrypted_name: &Option<S>) -> usize {
+        let cid_len = self.ipfs_cid.as_ref().map_or(0, |cid| 4 + cid.len());
+        if self.flags & FILE_FLAG_COMPACT_MIME != 0 {
+            Self::space(encrypted_name) - MAX_MIME_TYPE_LEN + self.mime_type.len() + cid_len
+        } else {
+            Self::space(encrypted_name) + cid_len
+        }
+    }
+
+    /// The seed after `FILE_SEED` of this record's address
+    pub fn file_seed(&self) -> [u8; 32] {
+        file_seed(&self.transaction_id)
+    }
+
+    /// The seed after the file key of the next share link's PDA
+    pub fn share_seed(&self) -> [u8; 8] {
+        self.share_nonce.to_le_bytes()
+    }
+
+    /// Move `share_nonce` past the share link just created at it
+    pub fn advance_share_nonce(&mut self) -> Result<()> {
+        self.share_nonce = self
+            .share_nonce
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Wallet refunded when the record is closed: the recorded rent payer,
+    /// or the owner for records that store none
+    pub fn rent_recipient(&self) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            self.owner
+        } else {
+            self.rent_payer
+        }
+    }
+
+    /// The proposed next owner, while the proposal has not lapsed.
+    /// A proposal can be accepted up to and including `transfer_expires_at`.
+    pub fn pending_transfer(&self, now: i64) -> Option<Pubkey> {
+        self.pending_owner.filter(|_| now <= self.transfer_expires_at)
+    }
+
+    /// Fail with `OwnershipTransferPending` while a transfer can be accepted
+    pub fn require_no_pending_transfer(&self, now: i64) -> Result<()> {
+        require!(
+            self.pending_transfer(now).is_none(),
+            HelixError::OwnershipTransferPending
+        );
+        Ok(())
+    }
+
+    /// Resolved lifecycle status of the file
+    pub fn file_status(&self) -> FileStatus {
+        if self.flags & FILE_FLAG_DELETED != 0 {
+            FileStatus::Deleted
+        } else {
+            self.status
+        }
+    }
+
+    /// Move the file to `status`, failing if the lifecycle does not allow
+    /// the transition from its current status
+    pub fn set_status(&mut self, status: FileStatus) -> Result<()> {
+        require!(
+            self.file_status().can_transition_to(status),
+            HelixError::InvalidStatusTransition
+        );
+        self.status = status;
+        self.set_flag(FILE_FLAG_DELETED, status == FileStatus::Deleted);
+        self.set_flag(FILE_FLAG_ARCHIVED, status == FileStatus::Archived);
+        Ok(())
+    }
+
+    /// Check if the file content is accessible (active or archived)
+    pub fn is_accessible(&self) -> bool {
+        matches!(self.file_status(), FileStatus::Active | FileStatus::Archived)
+    }
+
+    /// Whether the file can be modified or shared
+    pub fn is_active(&self) -> bool {
+        self.file_status() == FileStatus::Active
+    }
+
+    /// Unix timestamp when the file was deleted. Records deleted before the
+    /// timestamp was tracked fall back to `updated_at`, which deletion sets.
+    pub fn deletion_timestamp(&self) -> i64 {
+        match self.deleted_at {
+            0 => self.updated_at,
+            deleted_at => deleted_at,
+        }
+    }
+
+    /// Rights holder of the latest copyright statement, if any
+    pub fn copyright(&self) -> Option<Pubkey> {
+        (self.copyright_attested_at != 0).then_some(self.copyright_holder)
+    }
+
+    /// Check if a deleted file has outlived the trash retention window
+    pub fn retention_expired(&self, retention_secs: i64, current_timestamp: i64) -> bool {
+        current_timestamp >= self.deletion_timestamp().saturating_add(retention_secs)
+    }
+
+    /// Check if the file can serve as `owner`'s profile avatar: a live,
+    /// unencrypted image owned by `owner`. Interned MIME types are resolved
+    /// through `mime_table`; without it such records do not qualify.
+    pub fn is_usable_avatar(&self, owner: &Pubkey, mime_table: Option<&MimeTable>) -> bool {
+        let mime_type = match self.mime_code {
+            0 => Some(self.mime_type.as_str()),
+            code => mime_table.and_then(|table| table.resolve(code)),
+        };
+
+        self.owner == *owner
+            && self.is_accessible()
+            && !self.is_encrypted()
+            && mime_type.is_some_and(|mime| mime.starts_with("image/"))
+    }
+
+    /// Whether the file content is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & FILE_FLAG_ENCRYPTED != 0
+    }
+
+    /// Set whether the file content is encrypted
+    pub fn set_encrypted(&mut self, value: bool) {
+        self.set_flag(FILE_FLAG_ENCRYPTED, value);
+    }
+
+    /// Whether the file is marked as deleted
+    pub fn is_deleted(&self) -> bool {
+        self.file_status() == FileStatus::Deleted
+    }
+
+    /// Whether the deletion was performed by the program authority
+    pub fn is_deleted_by_authority(&self) -> bool {
+        self.flags & FILE_FLAG_DELETED_BY_AUTHORITY != 0
+    }
+
+    /// Set whether the deletion was performed by the program authority
+    pub fn set_deleted_by_authority(&mut self, value: bool) {
+        self.set_flag(FILE_FLAG_DELETED_BY_AUTHORITY, value);
+    }
+
+    /// Whether ownership is represented by the file's token mint
+    pub fn is_tokenized(&self) -> bool {
+        self.flags & FILE_FLAG_TOKENIZED != 0
+    }
+
+    /// Set whether ownership is represented by the file's token mint
+    pub fn set_tokenized(&mut self, value: bool) {
+        self.set_flag(FILE_FLAG_TOKENIZED, value);
+    }
+
+    /// Whether the file has an open endowment account
+    pub fn is_endowed(&self) -> bool {
+        self.flags & FILE_FLAG_ENDOWED != 0
+    }
+
+    /// Set whether the file has an open endowment account
+    pub fn set_endowed(&mut self, value: bool) {
+        self.set_flag(FILE_FLAG_ENDOWED, value);
+    }
+
+    /// Whether `size` was confirmed by the program authority
+    pub fn is_size_attested(&self) -> bool {
+        self.flags & FILE_FLAG_SIZE_ATTESTED != 0
+    }
+
+    /// Set whether `size` was confirmed by the program authority
+    pub fn set_size_attested(&mut self, value: bool) {
+        self.set_flag(FILE_FLAG_SIZE_ATTESTED, value);
+    }
+
+    /// Whether the file has an entry in its owner's file index
+    pub fn is_indexed(&self) -> bool {
+        self.flags & FILE_FLAG_INDEXED != 0
+    }
+
+    /// Set whether the file has an entry in its owner's file index
+    pub fn set_indexed(&mut self, value: bool) {
+        self.set_flag(FILE_FLAG_INDEXED, value);
+    }
+
+    /// Whether `owner` holds an owner commitment instead of a wallet
+    pub fn is_stealth(&self) -> bool {
+        self.flags & FILE_FLAG_STEALTH != 0
+    }
+
+    /// Whether the metadata was wiped when the file was deleted
+    pub fn is_scrubbed(&self) -> bool {
+        self.flags & FILE_FLAG_SCRUBBED != 0
+    }
+
+    /// Wipe the encrypted name, chunk root and MIME type. Compact records
+    /// have no room for a literal, so they store an empty MIME type and
+    /// report `SCRUBBED_MIME_TYPE` through the scrubbed flag.
+    pub fn scrub(&mut self) {
+        self.encrypted_name = None;
+        self.chunk_root = None;
+        self.mime_code = 0;
+        self.mime_type = if self.flags & FILE_FLAG_COMPACT_MIME != 0 {
+            String::new()
+        } else {
+            SCRUBBED_MIME_TYPE.to_string()
+        };
+        self.set_flag(FILE_FLAG_SCRUBBED, true);
+    }
+
+    fn set_flag(&mut self, flag: u16, value: bool) {
+        if value {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+    }
+
+    /// Whether the file's content lives on IPFS rather than Arweave
+    pub fn is_ipfs(&self) -> bool {
+        self.ipfs_cid.is_some()
+    }
+
+    /// Default gateway for this file's content: the IPFS gateway for IPFS
+    /// content, otherwise the Arweave gateway for L1 transactions and the
+    /// Irys gateway for bundled data items
+    pub fn default_gateway(&self) -> &'static str {
+        if self.is_ipfs() {
+            return IPFS_GATEWAY_URL;
+        }
+        match self.storage_location {
+            StorageLocation::ArweaveL1 => ARWEAVE_GATEWAY_URL,
+            StorageLocation::IrysDataItem { .. } => IRYS_GATEWAY_URL,
+        }
+    }
+
+    /// Get the URL of this file's content on `gateway`: the `/ipfs/` path of
+    /// its CID for IPFS content, the transaction ID for Arweave content
+    pub fn content_url(&self, gateway: &str) -> String {
+        match &self.ipfs_cid {
+            Some(cid) => format!("{}/ipfs/{}", gateway, cid),
+            None => format!("{}/{}", gateway, self.transaction_id),
+        }
+    }
+}
+
+/// Commitment stored as the owner of stealth records: the SHA-256 of the
+/// domain prefix, the owner's wallet and a client-chosen salt
+pub fn owner_commitment(owner: &Pubkey, salt: &[u8; 32]) -> Pubkey {
+    Pubkey::new_from_array(hashv(&[STEALTH_OWNER_DOMAIN, owner.as_ref(), salt]).to_bytes())
+}
+
+/// Commitment stored on stealth shares instead of the recipient: the SHA-256
+/// of the domain prefix, the recipient's wallet and a client-chosen salt
+pub fn recipient_commitment(recipient: &Pubkey, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[STEALTH_RECIPIENT_DOMAIN, recipient.as_ref(), salt]).to_bytes()
+}
+
+/// Hash stored on claimable shares until they are claimed: the SHA-256 of
+/// the domain prefix and the secret handed to the future recipient
+pub fn share_claim_hash(preimage: &[u8; 32]) -> [u8; 32] {
+    hashv(&[SHARE_CLAIM_DOMAIN, preimage]).to_bytes()
+}
+
+/// Leaf hash of a chunk in a chunked file's Merkle tree: the SHA-256 of a
+/// leaf tag, the little-endian chunk index and the chunk transaction ID
+pub fn chunk_leaf_hash(index: u16, chunk_tx_id: &str) -> [u8; 32] {
+    hashv(&[&[0u8], &index.to_le_bytes(), chunk_tx_id.as_bytes()]).to_bytes()
+}
+
+/// Interior node hash of a chunk Merkle tree. The node tag keeps leaves and
+/// nodes from being confused with each other.
+pub fn chunk_node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[1u8], left, right]).to_bytes()
+}
+
+/// Fold a chunk's Merkle proof from its leaf up and compare the result with
+/// `root`. Siblings are ordered bottom-up; the bits of `index` pick the side
+/// at each level. Proofs deeper than `MAX_CHUNK_PROOF_DEPTH` or too shallow
+/// to address `index` never verify.
+pub fn verify_chunk_proof(
+    root: &[u8; 32],
+    index: u16,
+    chunk_tx_id: &str,
+    proof: &[[u8; 32]],
+) -> bool {
+    if proof.len() > MAX_CHUNK_PROOF_DEPTH || u32::from(index) >> proof.len() != 0 {
+        return false;
+    }
+
+    let mut hash = chunk_leaf_hash(index, chunk_tx_id);
+    for (level, sibling) in proof.iter().enumerate() {
+        hash = if index >> level & 1 == 0 {
+            chunk_node_hash(&hash, sibling)
+        } else {
+            chunk_node_hash(sibling, &hash)
+        };
+    }
+
+    constant_time_eq(&hash, root)
+}
+
+/// Compare two hashes without an early exit on the first differing byte
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Share link for granting access to a file.
+/// Supports time-based expiration and download limits.
+#[account]
+pub struct ShareLink {
     /// The file being shared
     pub file: Pubkey,
     
@@ -139,7 +1275,7 @@ pub struct ShareLink {
     /// Specific recipient wallet (None = public link)
     pub recipient: Option<Pubkey>,
     
-    /// Encrypted decryption key (for encrypted files)
+    /// Encrypted decryption key (for encrypted files, cleared on revocation)
     pub encrypted_key: Option<String>,
     
     /// Unix timestamp when share expires (None = never)
@@ -151,8 +1287,9 @@ pub struct ShareLink {
     /// Current download count
     pub download_count: u32,
     
-    /// Whether the share is revoked
-    pub is_revoked: bool,
+    /// Bitfield of `SHARE_FLAG_*` values, in the byte that held the
+    /// `is_revoked` boolean
+    pub flags: u8,
     
     /// Unix timestamp when share was created
     pub created_at: i64,
@@ -160,8 +1297,37 @@ pub struct ShareLink {
     /// Bump seed for PDA derivation
     pub bump: u8,
     
+    /// Recipient's encryption key generation the key was wrapped to
+    /// (0 = not recorded)
+    pub recipient_key_generation: u32,
+    
+    /// Total bytes served through counted downloads (saturating)
+    pub bytes_served: u64,
+    
+    /// Commitment to the recipient, used instead of `recipient` to keep the
+    /// recipient off-chain (see `recipient_commitment`)
+    pub recipient_commitment: Option<[u8; 32]>,
+    
+    /// Message from the owner, encrypted to the recipient like
+    /// `encrypted_key` (cleared on revocation)
+    pub encrypted_message: Option<String>,
+    
+    /// Hash of the claim secret of an unclaimed claimable share (see
+    /// `share_claim_hash`); cleared when a wallet claims the share
+    pub claim_hash: Option<[u8; 32]>,
+    
+    /// Wallet that paid the share's rent, refunded when it is closed
+    /// (default = the owner; see `rent_recipient`)
+    pub rent_payer: Pubkey,
+    
+    /// Wallet of the latest counted download (default = none yet)
+    pub last_downloader: Pubkey,
+    
+    /// Unix timestamp of the latest counted download (0 = none yet)
+    pub last_download_at: i64,
+    
     /// Reserved space for future upgrades
-    pub _reserved: [u8; 16],
+    pub _reserved: [u8; 4],
 }
 
 impl ShareLink {
@@ -173,90 +1339,3362 @@ impl ShareLink {
         + 1 + 8   // expires_at (option + i64)
         + 1 + 4   // max_downloads (option + u32)
         + 4   // download_count
-        + 1   // is_revoked
+        + 1   // flags
         + 8   // created_at
         + 1   // bump
-        + 16; // reserved
+        + 4   // recipient_key_generation
+        + 8   // bytes_served
+        + 1 + 32  // recipient_commitment (option + hash)
+        + 1 + 4 + MAX_SHARE_MESSAGE_LEN  // encrypted_message (option + string)
+        + 1 + 32  // claim_hash (option + hash)
+        + 32  // rent_payer
+        + 32  // last_downloader
+        + 8   // last_download_at
+        + 4;  // reserved
 
-    /// Check if the share link is still valid
-    pub fn is_valid(&self, current_timestamp: i64) -> bool {
-        if self.is_revoked {
-            return false;
+    /// Wallet refunded when the share is closed: the recorded rent payer,
+    /// or the owner for shares that store none
+    pub fn rent_recipient(&self) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            self.owner
+        } else {
+            self.rent_payer
+        }
+    }
+
+    /// Resolve the canonical status of the share link.
+    /// 
+    /// Precedence: revoked, file deleted, not yet active, expired, exhausted.
+    pub fn status(&self, current_timestamp: i64, file_deleted: bool) -> ShareStatus {
+        if self.is_revoked() {
+            return ShareStatus::Revoked;
+        }
+
+        if file_deleted {
+            return ShareStatus::FileDeleted;
+        }
+
+        if current_timestamp < self.created_at {
+            return ShareStatus::NotYetActive;
         }
 
         if let Some(expires_at) = self.expires_at {
             if current_timestamp > expires_at {
-                return false;
+                return ShareStatus::Expired;
             }
         }
 
         if let Some(max) = self.max_downloads {
             if self.download_count >= max {
-                return false;
+                return ShareStatus::Exhausted;
             }
         }
 
-        true
+        ShareStatus::Active
+    }
+
+    /// Whether the share has been revoked
+    pub fn is_revoked(&self) -> bool {
+        self.flags & SHARE_FLAG_REVOKED != 0
+    }
+
+    /// Set whether the share has been revoked
+    pub fn set_revoked(&mut self, value: bool) {
+        if value {
+            self.flags |= SHARE_FLAG_REVOKED;
+        } else {
+            self.flags &= !SHARE_FLAG_REVOKED;
+        }
+    }
+
+    /// Whether the share is revoked by its first download
+    pub fn is_single_use(&self) -> bool {
+        self.flags & SHARE_FLAG_SINGLE_USE != 0
+    }
+
+    /// Set whether the share is revoked by its first download
+    pub fn set_single_use(&mut self, value: bool) {
+        if value {
+            self.flags |= SHARE_FLAG_SINGLE_USE;
+        } else {
+            self.flags &= !SHARE_FLAG_SINGLE_USE;
+        }
+    }
+
+    /// Whether the recipient was warned of the share's expiry
+    pub fn is_expiry_warned(&self) -> bool {
+        self.flags & SHARE_FLAG_EXPIRY_WARNED != 0
+    }
+
+    /// Mark the recipient as warned of the share's expiry
+    pub fn set_expiry_warned(&mut self) {
+        self.flags |= SHARE_FLAG_EXPIRY_WARNED;
+    }
+
+    /// Whether the share's counters have been released
+    pub fn counters_released(&self) -> bool {
+        self.flags & SHARE_FLAG_COUNTERS_RELEASED != 0
+    }
+
+    /// Mark the share's counters as released. Returns `false` if they were
+    /// already released, in which case the caller must not decrement them.
+    pub fn release_counters(&mut self) -> bool {
+        let released = !self.counters_released();
+        self.flags |= SHARE_FLAG_COUNTERS_RELEASED;
+        released
+    }
+
+    /// Whether the encrypted key was cleared on revocation
+    pub fn is_key_cleared(&self) -> bool {
+        self.flags & SHARE_FLAG_KEY_CLEARED != 0
+    }
+
+    /// Drop the encrypted key and message, flagging that the key was
+    /// cleared. Returns whether a key or message was present.
+    pub fn clear_key(&mut self) -> bool {
+        let had_key = self.encrypted_key.take().is_some();
+        let had_message = self.encrypted_message.take().is_some();
+        if had_key {
+            self.flags |= SHARE_FLAG_KEY_CLEARED;
+        }
+        had_key || had_message
     }
 
-    /// Check if a given wallet can access this share
+    /// Check if the share link is still valid
+    pub fn is_valid(&self, current_timestamp: i64) -> bool {
+        self.status(current_timestamp, false) == ShareStatus::Active
+    }
+
+    /// Whether anyone can use the share (no recipient, recipient commitment
+    /// or pending claim)
+    pub fn is_public(&self) -> bool {
+        self.recipient.is_none() && self.recipient_commitment.is_none() && !self.is_claimable()
+    }
+
+    /// Whether the share is waiting to be claimed by a recipient
+    pub fn is_claimable(&self) -> bool {
+        self.claim_hash.is_some()
+    }
+
+    /// Check if a given wallet can access this share. Shares locked to a
+    /// recipient commitment are never accessible without the salt, and
+    /// unclaimed claimable shares are not accessible at all.
     pub fn can_access(&self, wallet: &Pubkey, current_timestamp: i64) -> bool {
-        if !self.is_valid(current_timestamp) {
+        self.can_access_with_salt(wallet, None, current_timestamp)
+    }
+
+    /// Check if a given wallet can access this share, recomputing the
+    /// recipient commitment from `wallet` and `salt` when the share has one
+    pub fn can_access_with_salt(
+        &self,
+        wallet: &Pubkey,
+        salt: Option<&[u8; 32]>,
+        current_timestamp: i64,
+    ) -> bool {
+        self.is_valid(current_timestamp) && self.is_recipient(wallet, salt)
+    }
+
+    /// Fail unless `wallet` can download through this share now. The status
+    /// is checked first, so a share that is used up, expired or revoked
+    /// fails with the error naming that state for any wallet, and only an
+    /// active share fails with `ShareAccessDenied` for the wrong wallet.
+    pub fn require_download_access(
+        &self,
+        wallet: &Pubkey,
+        salt: Option<&[u8; 32]>,
+        current_timestamp: i64,
+        file_deleted: bool,
+    ) -> Result<()> {
+        self.status(current_timestamp, file_deleted).require_active()?;
+        require!(self.is_recipient(wallet, salt), HelixError::ShareAccessDenied);
+        Ok(())
+    }
+
+    /// Check whether `wallet` is the share's recipient, whatever its status.
+    /// Public shares admit every wallet; unclaimed claimable shares admit
+    /// none.
+    pub fn is_recipient(&self, wallet: &Pubkey, salt: Option<&[u8; 32]>) -> bool {
+        if self.is_claimable() {
             return false;
         }
 
-        match &self.recipient {
-            Some(recipient) => wallet == recipient,
-            None => true, // Public link
+        match (&self.recipient, &self.recipient_commitment, salt) {
+            (Some(recipient), _, _) => wallet == recipient,
+            (None, Some(commitment), Some(salt)) => {
+                constant_time_eq(commitment, &recipient_commitment(wallet, salt))
+            }
+            (None, Some(_), None) => false,
+            (None, None, _) => true, // Public link
         }
     }
 
-    /// Increment download count and check if still valid
-    pub fn record_download(&mut self) -> bool {
-        self.download_count = self.download_count.saturating_add(1);
-        
-        if let Some(max) = self.max_downloads {
-            self.download_count <= max
-        } else {
-            true
+    /// Bind `wallet` as the recipient of a claimable share if `preimage`
+    /// hashes to its claim hash. Clearing the hash makes the claim
+    /// single-use: once claimed, the share is a normal recipient-locked share
+    /// and the preimage no longer matches anything.
+    pub fn claim(&mut self, wallet: Pubkey, preimage: &[u8; 32]) -> Result<()> {
+        let claim_hash = self.claim_hash.ok_or(HelixError::ShareNotClaimable)?;
+        require!(
+            constant_time_eq(&claim_hash, &share_claim_hash(preimage)),
+            HelixError::InvalidClaimSecret
+        );
+
+        self.recipient = Some(wallet);
+        self.claim_hash = None;
+        Ok(())
+    }
+
+    /// Count a download by `wallet` and add `size` to the bytes served,
+    /// failing with the status error unless the share is active. This is the
+    /// one check of the download limit: `max_downloads = N` allows exactly N
+    /// downloads, and the N+1th fails with `MaxDownloadsReached`. The count
+    /// never saturates; an unlimited share fails with `ArithmeticOverflow`
+    /// after `u32::MAX` downloads.
+    pub fn record_download(
+        &mut self,
+        wallet: Pubkey,
+        current_timestamp: i64,
+        file_deleted: bool,
+        size: u64,
+    ) -> Result<()> {
+        self.status(current_timestamp, file_deleted).require_active()?;
+
+        self.download_count = self
+            .download_count
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        self.bytes_served = self.bytes_served.saturating_add(size);
+        self.last_downloader = wallet;
+        self.last_download_at = current_timestamp;
+        Ok(())
+    }
+
+    /// Fail with `DownloadCooldownActive` if `wallet` made the latest counted
+    /// download less than `cooldown_secs` ago. A download exactly
+    /// `cooldown_secs` after the last one is allowed. Only the latest
+    /// downloader is tracked.
+    pub fn require_download_cooldown(
+        &self,
+        wallet: &Pubkey,
+        cooldown_secs: i64,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        let cooling_down = self.last_downloader == *wallet
+            && current_timestamp < self.last_download_at.saturating_add(cooldown_secs);
+        require!(!cooling_down, HelixError::DownloadCooldownActive);
+        Ok(())
+    }
+}
+
+/// How `record_download` throttles counted downloads of public shares by
+/// wallets other than the file owner
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DownloadThrottle {
+    /// No throttle. Declared first so zeroed bytes decode as unthrottled.
+    None,
+    /// Reject a counted download by the share's latest downloader within
+    /// `cooldown_secs` of their last one
+    Cooldown { cooldown_secs: i64 },
+    /// Charge the downloader `fee_lamports`, paid to the treasury, per
+    /// counted download
+    Fee { fee_lamports: u64 },
+}
+
+impl DownloadThrottle {
+    pub const LEN: usize = 1 + 8;  // variant + largest payload
+
+    /// Whether the throttle's parameter is positive
+    pub fn is_valid(&self) -> bool {
+        match self {
+            DownloadThrottle::None => true,
+            DownloadThrottle::Cooldown { cooldown_secs } => *cooldown_secs > 0,
+            DownloadThrottle::Fee { fee_lamports } => *fee_lamports > 0,
         }
     }
 }
 
-/// Event emitted when a new file is registered
-#[event]
-pub struct FileRegistered {
-    pub owner: Pubkey,
-    pub transaction_id: String,
-    pub size: u64,
-    pub is_encrypted: bool,
-    pub timestamp: i64,
+/// Lifecycle status of a file record
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file is live. Declared first so zeroed bytes decode as active.
+    Active,
+    /// The file is registered but not yet usable
+    Pending,
+    /// The file is kept out of active counts and default listings, but its
+    /// content and shares stay fully usable
+    Archived,
+    /// The file is marked as deleted and can be restored until purged
+    Deleted,
 }
 
-/// Event emitted when a share link is created
-#[event]
-pub struct ShareCreated {
-    pub file: Pubkey,
-    pub owner: Pubkey,
-    pub recipient: Option<Pubkey>,
-    pub expires_at: Option<i64>,
-    pub timestamp: i64,
+impl FileStatus {
+    /// Whether a file may move from this status to `next`
+    pub fn can_transition_to(self, next: FileStatus) -> bool {
+        matches!(
+            (self, next),
+            (FileStatus::Pending, FileStatus::Active)
+                | (FileStatus::Pending, FileStatus::Deleted)
+                | (FileStatus::Active, FileStatus::Archived)
+                | (FileStatus::Active, FileStatus::Deleted)
+                | (FileStatus::Archived, FileStatus::Active)
+                | (FileStatus::Archived, FileStatus::Deleted)
+                | (FileStatus::Deleted, FileStatus::Active)
+        )
+    }
 }
 
-/// Event emitted when a share link is revoked
-#[event]
-pub struct ShareRevoked {
-    pub share: Pubkey,
-    pub owner: Pubkey,
-    pub timestamp: i64,
+/// Canonical status of a share link, as resolved by `ShareLink::status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShareStatus {
+    /// The share can be used
+    Active,
+    /// The share's activation time has not been reached yet
+    NotYetActive,
+    /// The share is past its expiration time
+    Expired,
+    /// The share was revoked by its owner or a moderator
+    Revoked,
+    /// The share reached its maximum download count
+    Exhausted,
+    /// The shared file has been deleted
+    FileDeleted,
 }
 
-/// Event emitted when a file is marked as deleted
-#[event]
-pub struct FileDeleted {
-    pub file: Pubkey,
+impl ShareStatus {
+    /// Succeed for an active share, or fail with the error matching the
+    /// reason it cannot be used
+    pub fn require_active(self) -> Result<()> {
+        let error = match self {
+            ShareStatus::Active => return Ok(()),
+            ShareStatus::NotYetActive => HelixError::ShareNotYetActive,
+            ShareStatus::Expired => HelixError::ShareExpired,
+            ShareStatus::Revoked => HelixError::ShareRevoked,
+            ShareStatus::Exhausted => HelixError::MaxDownloadsReached,
+            ShareStatus::FileDeleted => HelixError::SharedFileDeleted,
+        };
+        Err(error.into())
+    }
+}
+
+/// Per-wallet state that is not tied to a single file
+#[account]
+pub struct UserProfile {
+    /// Wallet the profile belongs to
     pub owner: Pubkey,
-    pub timestamp: i64,
+    
+    /// Next nonce accepted for an off-chain signed share permit
+    pub permit_nonce: u64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Number of entries in the wallet's file index (the next sequence number)
+    pub file_count: u64,
+    
+    /// X25519 public key senders use to wrap content keys for this wallet
+    pub encryption_pubkey: Option<[u8; 32]>,
+    
+    /// When the encryption key was last set or cleared
+    pub key_updated_at: i64,
+    
+    /// Generation of the current encryption key, incremented on every set
+    /// or clear (0 = never set)
+    pub key_generation: u32,
+    
+    /// Most recently retired encryption keys, newest first
+    pub previous_keys: [RetiredEncryptionKey; MAX_PREVIOUS_ENCRYPTION_KEYS],
+    
+    /// Number of contacts in the wallet's address book
+    pub contact_count: u32,
+    
+    /// Number of entries ever added to the wallet's inbox (the next sequence number)
+    pub inbox_count: u64,
+    
+    /// Public plaintext display name shown in the directory
+    pub display_name: Option<String>,
+    
+    /// File record of the profile's avatar image. Readers treat a reference
+    /// that no longer passes `FileRecord::is_usable_avatar` as unset.
+    pub avatar_file: Option<Pubkey>,
+    
+    /// Number of the wallet's archived files (saturating)
+    pub archived_files: u16,
+    
+    /// Limits `create_share` falls back to when asked to use profile defaults
+    pub share_defaults: ShareDefaults,
+    
+    /// The wallet's most recently accessed files, newest first
+    pub recent_files: [RecentFile; MAX_RECENT_FILES],
+    
+    /// Number of inbox notifications not yet acknowledged or closed
+    pub unread_notifications: u32,
+    
+    /// Backup key allowed to freeze, but not unfreeze, the wallet's account
+    pub guardian: Option<Pubkey>,
+    
+    /// Whether the wallet's account is frozen: no new shares, downloads of
+    /// its shares or file metadata changes until the wallet unfreezes it
+    pub is_frozen: bool,
+    
+    /// Total protocol fees the wallet has paid to the treasury
+    pub fees_paid_lamports: u64,
+    
+    /// Sequence number given to the wallet's latest registration (0 = none).
+    /// Never decremented, so numbers are not reused after deletions.
+    pub last_file_seq: u64,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 1],
 }
 
-// Account structures
+impl UserProfile {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 8   // permit_nonce
+        + 1   // bump
+        + 8   // file_count
+        + 33  // encryption_pubkey (Option<[u8; 32]>)
+        + 8   // key_updated_at
+        + 4   // key_generation
+        + RetiredEncryptionKey::LEN * MAX_PREVIOUS_ENCRYPTION_KEYS  // previous_keys
+        + 4   // contact_count
+        + 8   // inbox_count
+        + 1 + 4 + MAX_DISPLAY_NAME_LEN  // display_name (option + string)
+        + 33  // avatar_file (Option<Pubkey>)
+        + 2   // archived_files
+        + ShareDefaults::LEN  // share_defaults
+        + RecentFile::LEN * MAX_RECENT_FILES  // recent_files
+        + 4   // unread_notifications
+        + 33  // guardian (Option<Pubkey>)
+        + 1   // is_frozen
+        + 8   // fees_paid_lamports
+        + 8   // last_file_seq
+        + 1;  // reserved
+
+    /// Take the sequence number for the wallet's next registration
+    pub fn next_file_seq(&mut self) -> Result<u64> {
+        self.last_file_seq = self
+            .last_file_seq
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        Ok(self.last_file_seq)
+    }
+
+    /// Check if `signer` may freeze the account: the wallet or its guardian
+    pub fn can_freeze(&self, signer: &Pubkey) -> bool {
+        *signer == self.owner || self.guardian == Some(*signer)
+    }
+
+    /// Replace the encryption key, retiring the current one (if any) into
+    /// `previous_keys` and evicting the oldest retired key
+    pub fn rotate_encryption_key(
+        &mut self,
+        encryption_pubkey: Option<[u8; 32]>,
+        current_timestamp: i64,
+    ) -> Result<()> {
+        if let Some(pubkey) = self.encryption_pubkey {
+            self.previous_keys.rotate_right(1);
+            self.previous_keys[0] = RetiredEncryptionKey {
+                pubkey,
+                generation: self.key_generation,
+                retired_at: current_timestamp,
+            };
+        }
+
+        self.key_generation = self
+            .key_generation
+            .checked_add(1)
+            .ok_or(HelixError::ArithmeticOverflow)?;
+        self.encryption_pubkey = encryption_pubkey;
+        self.key_updated_at = current_timestamp;
+
+        Ok(())
+    }
+
+    /// Move a file to the front of `recent_files`, dropping its older entry
+    /// or, if it is not listed yet, the oldest entry
+    pub fn touch_recent_file(&mut self, file: Pubkey, accessed_at: i64) {
+        let end = self
+            .recent_files
+            .iter()
+            .position(|recent| recent.file == file)
+            .unwrap_or(MAX_RECENT_FILES - 1);
+        self.recent_files[..=end].rotate_right(1);
+        self.recent_files[0] = RecentFile { file, accessed_at };
+    }
+}
+
+/// Recently accessed file on a user profile. Unused slots are all zeros.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct RecentFile {
+    /// The accessed file record
+    pub file: Pubkey,
+    
+    /// When the file was last accessed
+    pub accessed_at: i64,
+}
+
+impl RecentFile {
+    pub const LEN: usize = 32 + 8;
+}
+
+/// Encryption key retired from a user profile. Unused slots are all zeros.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct RetiredEncryptionKey {
+    /// The retired X25519 public key
+    pub pubkey: [u8; 32],
+    
+    /// Generation the key was published under
+    pub generation: u32,
+    
+    /// When the key was replaced or cleared
+    pub retired_at: i64,
+}
+
+impl RetiredEncryptionKey {
+    pub const LEN: usize = 32 + 4 + 8;
+}
+
+/// Limits applied to every share created by `create_shares_batch`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShareBatchSettings {
+    /// Unix timestamp when the shares expire (None = never)
+    pub expires_at: Option<i64>,
+    
+    /// Maximum download count of each share (None = unlimited)
+    pub max_downloads: Option<u32>,
+}
+
+/// A wallet's default share limits, applied by `create_share` to limits
+/// left unset when the caller opts in. `None` means no limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct ShareDefaults {
+    /// Seconds from creation until a share expires
+    pub expiry_duration: Option<i64>,
+    
+    /// Maximum number of downloads per share
+    pub max_downloads: Option<u32>,
+    
+    /// Whether shares must name a recipient (no public shares)
+    pub require_recipient: bool,
+}
+
+impl ShareDefaults {
+    pub const LEN: usize = 9  // expiry_duration (Option<i64>)
+        + 5   // max_downloads (Option<u32>)
+        + 1;  // require_recipient
+
+    /// Resolve a requested expiration against the default. `None` takes the
+    /// default and `SHARE_NO_EXPIRY` explicitly asks for no expiration.
+    pub fn expires_at(&self, requested: Option<i64>, current_timestamp: i64) -> Option<i64> {
+        match requested {
+            Some(SHARE_NO_EXPIRY) => None,
+            Some(expires_at) => Some(expires_at),
+            None => self
+                .expiry_duration
+                .map(|duration| current_timestamp.saturating_add(duration)),
+        }
+    }
+
+    /// Resolve a requested download limit against the default. `None` takes
+    /// the default and `SHARE_UNLIMITED_DOWNLOADS` explicitly asks for none.
+    pub fn max_downloads(&self, requested: Option<u32>) -> Option<u32> {
+        match requested {
+            Some(SHARE_UNLIMITED_DOWNLOADS) => None,
+            Some(max_downloads) => Some(max_downloads),
+            None => self.max_downloads,
+        }
+    }
+}
+
+/// Settings stored in a `ShareTemplate`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct ShareTemplateSettings {
+    /// Seconds from creation until a share expires
+    pub expiry_duration: Option<i64>,
+    
+    /// Maximum number of downloads per share
+    pub max_downloads: Option<u32>,
+    
+    /// Whether shares must name a recipient (no public shares)
+    pub require_recipient: bool,
+    
+    /// Whether shares are revoked by their first download
+    pub single_use: bool,
+}
+
+impl ShareTemplateSettings {
+    pub const LEN: usize = ShareDefaults::LEN  // expiry, downloads, recipient
+        + 1;  // single_use
+
+    /// The template's limits, resolved like profile share defaults
+    pub fn limits(&self) -> ShareDefaults {
+        ShareDefaults {
+            expiry_duration: self.expiry_duration,
+            max_downloads: self.max_downloads,
+            require_recipient: self.require_recipient,
+        }
+    }
+
+    /// Whether the limits are positive and agree with `single_use`
+    pub fn is_valid(&self) -> bool {
+        let positive = !matches!(self.expiry_duration, Some(duration) if duration <= 0)
+            && !matches!(self.max_downloads, Some(0));
+        positive && (!self.single_use || matches!(self.max_downloads, None | Some(1)))
+    }
+}
+
+/// Named share settings a wallet reuses across `create_share` calls,
+/// addressed by the owner and a client-chosen template ID
+#[account]
+pub struct ShareTemplate {
+    /// Wallet that owns the template
+    pub owner: Pubkey,
+    
+    /// Client-chosen ID of the template (PDA seed)
+    pub template_id: u32,
+    
+    /// Public label shown by clients
+    pub name: String,
+    
+    /// Settings applied by `create_share`
+    pub settings: ShareTemplateSettings,
+    
+    /// Unix timestamp when the template was created
+    pub created_at: i64,
+    
+    /// Unix timestamp of the last update
+    pub updated_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl ShareTemplate {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 4   // template_id
+        + 4 + MAX_DISPLAY_NAME_LEN  // name (string)
+        + ShareTemplateSettings::LEN  // settings
+        + 8   // created_at
+        + 8   // updated_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Address book entry of a wallet, addressed by the contact's wallet
+#[account]
+pub struct Contact {
+    /// Wallet whose address book the contact belongs to
+    pub owner: Pubkey,
+    
+    /// The contact's wallet
+    pub contact: Pubkey,
+    
+    /// Client-encrypted display name for the contact
+    pub encrypted_alias: String,
+    
+    /// Unix timestamp when the contact was added
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl Contact {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // contact
+        + 4 + MAX_CONTACT_ALIAS_LEN  // encrypted_alias (string)
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Additional lookup handle for a file, addressed by a client-computed hash
+/// of the alias so it resolves with a single address derivation
+#[account]
+pub struct FileAlias {
+    /// Wallet that created the alias
+    pub owner: Pubkey,
+    
+    /// The file record the alias points to
+    pub file: Pubkey,
+    
+    /// Client-computed hash of the plaintext alias (PDA seed)
+    pub alias_hash: [u8; 32],
+    
+    /// Client-encrypted alias name
+    pub encrypted_alias: String,
+    
+    /// Unix timestamp when the alias was created
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl FileAlias {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // file
+        + 32  // alias_hash
+        + 4 + MAX_FILE_ALIAS_LEN  // encrypted_alias (string)
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Copy of a file's content on another backend or transaction, attached by
+/// the file's owner so clients can fail over. Shares and access control stay
+/// on the primary record. Addressed by the mirror's `record_key`, so the same
+/// content cannot be attached twice.
+#[account]
+pub struct Mirror {
+    /// The file record the content mirrors
+    pub file: Pubkey,
+    
+    /// `record_key` of the mirror's locator (PDA seed)
+    pub mirror_key: [u8; 32],
+    
+    /// Where the mirrored content lives
+    pub locator: MirrorLocator,
+    
+    /// Unix timestamp when the mirror was added
+    pub added_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl Mirror {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 32  // mirror_key
+        + MirrorLocator::LEN  // locator
+        + 8   // added_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// A wallet's bookmark of a file registered by someone else.
+/// References grant no access to the file; that still requires a share.
+/// A reference outlives its file, which then reads as deleted or missing.
+#[account]
+pub struct FileReference {
+    /// Wallet that saved the reference
+    pub referrer: Pubkey,
+    
+    /// The file record the reference points to
+    pub file: Pubkey,
+    
+    /// Client-encrypted note (optional)
+    pub encrypted_note: Option<String>,
+    
+    /// Unix timestamp when the reference was created
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl FileReference {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // referrer
+        + 32  // file
+        + 1 + 4 + MAX_REFERENCE_NOTE_LEN  // encrypted_note (option + string)
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Human-readable name in its owner's namespace pointing at one of the
+/// owner's files, so `owner/name` resolves with a single address derivation
+#[account]
+pub struct NameRecord {
+    /// Wallet whose namespace the name belongs to
+    pub owner: Pubkey,
+    
+    /// The name (lowercase, PDA seed)
+    pub name: String,
+    
+    /// The file record the name points to
+    pub file: Pubkey,
+    
+    /// Unix timestamp when the name was registered
+    pub created_at: i64,
+    
+    /// Unix timestamp when the target last changed
+    pub updated_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl NameRecord {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 4 + MAX_NAME_LEN  // name (string)
+        + 32  // file
+        + 8   // created_at
+        + 8   // updated_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// A gateway operator's statement that it holds a replica of a file
+#[account]
+pub struct Attestation {
+    /// The attested file record
+    pub file: Pubkey,
+    
+    /// The gateway operator that attested
+    pub attestor: Pubkey,
+    
+    /// Unix timestamp when the replica was last checked
+    pub checked_at: i64,
+    
+    /// Hash of the content the attestor verified (optional)
+    pub content_hash: Option<[u8; 32]>,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl Attestation {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 32  // attestor
+        + 8   // checked_at
+        + 33  // content_hash (Option<[u8; 32]>)
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Files an audit grant lets the auditor view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditScope {
+    /// A single file record
+    File(Pubkey),
+    /// Every file record of the grant's owner
+    AllFiles,
+}
+
+/// A revocable grant letting an auditor view (not download) an owner's files
+#[account]
+pub struct AuditGrant {
+    /// The owner granting access
+    pub owner: Pubkey,
+    
+    /// The auditor allowed to view
+    pub auditor: Pubkey,
+    
+    /// Owner-chosen ID, allowing several (overlapping) grants
+    pub grant_id: u64,
+    
+    /// The files covered
+    pub scope: AuditScope,
+    
+    /// Unix timestamp when the grant was created
+    pub created_at: i64,
+    
+    /// Unix timestamp when the grant expires
+    pub expires_at: i64,
+    
+    /// Unix timestamp when the grant was revoked (0 = not revoked)
+    pub revoked_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl AuditGrant {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // auditor
+        + 8   // grant_id
+        + 1 + 32  // scope (variant + pubkey)
+        + 8   // created_at
+        + 8   // expires_at
+        + 8   // revoked_at
+        + 1   // bump
+        + 16; // reserved
+
+    /// Check if the grant is neither revoked nor expired
+    pub fn is_active(&self, current_timestamp: i64) -> bool {
+        self.revoked_at == 0 && current_timestamp < self.expires_at
+    }
+
+    /// Check if the grant currently lets its auditor view `file_record`
+    pub fn grants_view(
+        &self,
+        file: &Pubkey,
+        file_record: &FileRecord,
+        current_timestamp: i64,
+    ) -> bool {
+        let in_scope = match self.scope {
+            AuditScope::File(scoped) => scoped == *file,
+            AuditScope::AllFiles => true,
+        };
+
+        self.is_active(current_timestamp) && file_record.owner == self.owner && in_scope
+    }
+}
+
+/// A proof-of-access challenge asking the owner for the hash of a byte range
+#[account]
+pub struct Challenge {
+    /// The challenged file record
+    pub file: Pubkey,
+    
+    /// The wallet that opened the challenge (posted the bond)
+    pub challenger: Pubkey,
+    
+    /// The file owner expected to answer
+    pub owner: Pubkey,
+    
+    /// First byte of the challenged range
+    pub offset: u64,
+    
+    /// Length of the challenged range in bytes
+    pub length: u64,
+    
+    /// Lamports locked on top of rent
+    pub bond: u64,
+    
+    /// Unix timestamp when the challenge was opened
+    pub created_at: i64,
+    
+    /// Unix timestamp by which the owner must answer
+    pub deadline: i64,
+    
+    /// SHA-256 of the range as answered by the owner
+    pub answer: Option<[u8; 32]>,
+    
+    /// Unix timestamp when the owner answered (0 if unanswered)
+    pub answered_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl Challenge {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 32  // challenger
+        + 32  // owner
+        + 8   // offset
+        + 8   // length
+        + 8   // bond
+        + 8   // created_at
+        + 8   // deadline
+        + 33  // answer (Option<[u8; 32]>)
+        + 8   // answered_at
+        + 1   // bump
+        + 16; // reserved
+
+    /// Check if the owner has answered
+    pub fn is_answered(&self) -> bool {
+        self.answer.is_some()
+    }
+
+    /// Check if the challenge can be settled without the challenger.
+    /// Unanswered challenges settle once the deadline passes; answered ones
+    /// once the review period after the deadline has passed too.
+    pub fn is_settleable(&self, now: i64) -> bool {
+        if self.is_answered() {
+            now > self.deadline.saturating_add(CHALLENGE_REVIEW_SECS)
+        } else {
+            now > self.deadline
+        }
+    }
+
+    /// Split the bond at settlement into (to challenger, to owner).
+    /// An unanswered challenge returns the bond to the challenger; an answer
+    /// left unaccepted past review pays it to the owner for their trouble.
+    pub fn settlement(&self) -> (u64, u64) {
+        if self.is_answered() {
+            (0, self.bond)
+        } else {
+            (self.bond, 0)
+        }
+    }
+}
+
+/// Program-owned account collecting protocol fees
+#[account]
+pub struct Treasury {
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 32],
+}
+
+impl Treasury {
+    pub const LEN: usize = 8  // discriminator
+        + 1   // bump
+        + 32; // reserved
+}
+
+/// Event emitted when the registry authority withdraws fees from the
+/// treasury
+#[event]
+pub struct FeesWithdrawn {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    /// Lamports left in the treasury above its rent reserve
+    pub remaining: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an account created under an older layout is grown to
+/// the current one
+#[event]
+pub struct AccountMigrated {
+    pub version: u8,
+    pub account: Pubkey,
+    /// Wallet that paid for the added space
+    pub payer: Pubkey,
+    pub old_len: u32,
+    pub new_len: u32,
+    pub timestamp: i64,
+}
+
+/// Global vanity code pointing at a public share link, claimed first come
+/// first served and resolved with a single address derivation
+#[account]
+pub struct ShortCode {
+    /// The code (lowercase alphanumerics, PDA seed)
+    pub code: String,
+    
+    /// The share link the code points to
+    pub share: Pubkey,
+    
+    /// Wallet that claimed the code (receives the rent on release)
+    pub claimer: Pubkey,
+    
+    /// Unix timestamp when the code was claimed
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl ShortCode {
+    pub const LEN: usize = 8  // discriminator
+        + 4 + MAX_SHORT_CODE_LEN  // code (string)
+        + 32  // share
+        + 32  // claimer
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Fixed categories of the public gallery
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GalleryCategory {
+    Art,
+    Photography,
+    Music,
+    Video,
+    Documents,
+    Other,
+}
+
+impl GalleryCategory {
+    /// Position of the category's sequence counter in
+    /// `RegistryExt::category_counters`
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Opt-in listing of a public file in a gallery category, addressed by the
+/// category and its sequence number. Readers check the listed record, which
+/// may have been deleted or encrypted since.
+#[account]
+pub struct GalleryEntry {
+    /// Category the file is listed in
+    pub category: GalleryCategory,
+    
+    /// Sequence number within the category
+    pub seq: u64,
+    
+    /// The listed file record
+    pub file: Pubkey,
+    
+    /// Wallet that listed the file (receives the rent on removal)
+    pub owner: Pubkey,
+    
+    /// Unix timestamp when the file was listed
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl GalleryEntry {
+    pub const LEN: usize = 8  // discriminator
+        + 1   // category
+        + 8   // seq
+        + 32  // file
+        + 32  // owner
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Proof that a wallet likes a file, addressed by the file and the wallet so
+/// each wallet can like a file once
+#[account]
+pub struct LikeReceipt {
+    /// The liked file record
+    pub file: Pubkey,
+    
+    /// Wallet that liked the file (pays and receives the rent)
+    pub wallet: Pubkey,
+    
+    /// Unix timestamp when the file was liked
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 8],
+}
+
+impl LikeReceipt {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 32  // wallet
+        + 8   // created_at
+        + 1   // bump
+        + 8;  // reserved
+}
+
+/// Lamports raised for a file by anyone and withdrawable by the file's
+/// owner, e.g. to pay for gateway pinning. Survives the file's deletion;
+/// it must be closed before the file can be purged.
+#[account]
+pub struct Endowment {
+    /// The endowed file record
+    pub file: Pubkey,
+    
+    /// Lamports contributed in total (excluding the account's rent)
+    pub total_raised: u64,
+    
+    /// Lamports withdrawn by the owner in total
+    pub total_withdrawn: u64,
+    
+    /// Unix timestamp of the first contribution
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl Endowment {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 8   // total_raised
+        + 8   // total_withdrawn
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+}
+
+/// Kind of access recorded in a file's access log
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub enum AccessKind {
+    /// Content downloaded through a share
+    #[default]
+    Download,
+    /// Metadata viewed without downloading
+    MetadataView,
+    /// Share link created
+    ShareCreated,
+}
+
+/// One access of a file. Unused slots have a default `actor`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct AccessLogEntry {
+    /// Wallet that accessed the file
+    pub actor: Pubkey,
+    
+    /// Share link used or created
+    pub share: Pubkey,
+    
+    /// When the access happened
+    pub timestamp: i64,
+    
+    /// What kind of access it was
+    pub kind: AccessKind,
+}
+
+impl AccessLogEntry {
+    pub const LEN: usize = 32 + 32 + 8 + 1;
+}
+
+/// Optional companion of a file record keeping its most recent accesses
+/// across all shares. Logging is best-effort: instructions append only when
+/// the log is supplied, and the oldest entry is overwritten when full.
+#[account]
+pub struct FileAccessLog {
+    /// The logged file record
+    pub file: Pubkey,
+    
+    /// Wallet that created the log (receives the rent on close)
+    pub owner: Pubkey,
+    
+    /// Ring of entries; `head` is the next slot to write
+    pub entries: [AccessLogEntry; ACCESS_LOG_ENTRIES],
+    
+    /// Index of the oldest entry, overwritten next
+    pub head: u8,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 8],
+}
+
+impl FileAccessLog {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 32  // owner
+        + AccessLogEntry::LEN * ACCESS_LOG_ENTRIES  // entries
+        + 1   // head
+        + 1   // bump
+        + 8;  // reserved
+
+    /// Append an access for this log's file, overwriting the oldest entry.
+    /// Logs of another file are left untouched.
+    pub fn record(&mut self, file: &Pubkey, entry: AccessLogEntry) {
+        if self.file != *file {
+            return;
+        }
+
+        let head = usize::from(self.head) % ACCESS_LOG_ENTRIES;
+        self.entries[head] = entry;
+        self.head = ((head + 1) % ACCESS_LOG_ENTRIES) as u8;
+    }
+
+    /// Recorded entries, newest first
+    pub fn recent(&self) -> impl Iterator<Item = &AccessLogEntry> {
+        let head = usize::from(self.head) % ACCESS_LOG_ENTRIES;
+        self.entries[..head]
+            .iter()
+            .rev()
+            .chain(self.entries[head..].iter().rev())
+            .filter(|entry| entry.actor != Pubkey::default())
+    }
+}
+
+/// Key blob of an encrypted file scheduled to become public at `release_at`.
+/// 
+/// Account data is readable by anyone, so `reveal_key` refusing the blob
+/// before release is a convention rather than secrecy: the blob must be
+/// wrapped so it is useless on its own until the deadline, e.g. with
+/// time-lock encryption.
+#[account]
+pub struct TimedRelease {
+    /// The encrypted file record
+    pub file: Pubkey,
+    
+    /// The file owner who scheduled the release (receives the rent on cancel)
+    pub owner: Pubkey,
+    
+    /// Unix timestamp from which the release can be triggered
+    pub release_at: i64,
+    
+    /// Whether the release was triggered; never cleared once set
+    pub released: bool,
+    
+    /// The key blob to publish
+    pub key_blob: String,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 8],
+}
+
+impl TimedRelease {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // file
+        + 32  // owner
+        + 8   // release_at
+        + 1   // released
+        + 4 + MAX_ENCRYPTED_KEY_LEN  // key_blob (string)
+        + 1   // bump
+        + 8;  // reserved
+
+    /// Fail with `ReleaseDeadlinePassed` unless the release can still be
+    /// cancelled, which is only before `release_at`
+    pub fn require_cancellable(&self, current_timestamp: i64) -> Result<()> {
+        require!(current_timestamp < self.release_at, HelixError::ReleaseDeadlinePassed);
+        Ok(())
+    }
+
+    /// Mark the key as released, from `release_at` on. Fails with
+    /// `KeyAlreadyReleased` once triggered: the release is one-way.
+    pub fn trigger(&mut self, current_timestamp: i64) -> Result<()> {
+        require!(!self.released, HelixError::KeyAlreadyReleased);
+        require!(current_timestamp >= self.release_at, HelixError::ReleaseNotDue);
+        self.released = true;
+        Ok(())
+    }
+}
+
+/// What an inbox notification is about
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// A share was addressed to the recipient. Declared first so entries
+    /// written before notifications had a kind decode as this.
+    ShareReceived,
+    /// A share addressed to the recipient was revoked
+    ShareRevoked,
+}
+
+/// Notification in a wallet's inbox, addressed by sequence number
+#[account]
+pub struct InboxEntry {
+    /// Wallet the notification is addressed to
+    pub recipient: Pubkey,
+    
+    /// Position of the entry in the inbox
+    pub seq: u64,
+    
+    /// The share link the notification is about
+    pub share: Pubkey,
+    
+    /// Wallet that paid the rent and receives it back on close
+    pub payer: Pubkey,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// What the notification is about
+    pub kind: NotificationKind,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 7],
+}
+
+impl InboxEntry {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // recipient
+        + 8   // seq
+        + 32  // share
+        + 32  // payer
+        + 1   // bump
+        + 1   // kind
+        + 7;  // reserved
+}
+
+/// Entry of a wallet's file index, addressed by sequence number.
+/// Entries are append-only, so sequence numbers are dense.
+#[account]
+pub struct OwnerFileEntry {
+    /// Wallet whose index the entry belongs to
+    pub owner: Pubkey,
+    
+    /// Position of the entry in the index
+    pub seq: u64,
+    
+    /// The indexed file record
+    pub file: Pubkey,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Whether the file is archived, at a fixed offset so default listings
+    /// can leave archived files out with a memcmp filter
+    pub archived: bool,
+    
+    /// User-defined position for manual ordering, starting at `seq`.
+    /// Duplicate keys are allowed; clients break ties themselves.
+    pub sort_key: u32,
+    
+    /// Skip pointer to the next entry whose file has not been purged, moved
+    /// past purged files when they are spliced out on purge. Read through
+    /// `next_live`.
+    pub next_live_seq: u64,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 3],
+}
+
+impl OwnerFileEntry {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 8   // seq
+        + 32  // file
+        + 1   // bump
+        + 1   // archived
+        + 4   // sort_key
+        + 8   // next_live_seq
+        + 3;  // reserved
+
+    /// Sequence number of the next entry to visit when paging. Entries
+    /// created before the skip pointer existed store 0 and point at `seq + 1`.
+    pub fn next_live(&self) -> u64 {
+        if self.next_live_seq > self.seq {
+            self.next_live_seq
+        } else {
+            self.seq.saturating_add(1)
+        }
+    }
+}
+
+/// Where a file record's transaction ID resolves
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageLocation {
+    /// A base-layer Arweave transaction. Declared first so zeroed bytes
+    /// decode as L1.
+    ArweaveL1,
+    /// A data item inside an Irys bundle, served by the gateway that indexed
+    /// it. `bundle_tx` is the bundle's transaction ID, when known.
+    IrysDataItem { bundle_tx: Option<[u8; 32]> },
+}
+
+impl StorageLocation {
+    pub const LEN: usize = 1 + 1 + 32;  // variant + bundle_tx (option + ID)
+}
+
+/// Storage location argument of the registration instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum StorageLocationArg {
+    /// A base-layer Arweave transaction
+    ArweaveL1,
+    /// A data item inside an Irys bundle, with the bundle's transaction ID
+    /// when known
+    IrysDataItem { bundle_tx: Option<TxId> },
+}
+
+impl StorageLocationArg {
+    /// The stored location, with the bundle transaction ID decoded
+    pub fn into_location(self) -> Result<StorageLocation> {
+        Ok(match self {
+            StorageLocationArg::ArweaveL1 => StorageLocation::ArweaveL1,
+            StorageLocationArg::IrysDataItem { bundle_tx } => StorageLocation::IrysDataItem {
+                bundle_tx: bundle_tx.as_ref().map(TxId::to_bytes).transpose()?,
+            },
+        })
+    }
+}
+
+/// Content locator argument of `register_file_v2`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ContentLocator {
+    /// An Arweave transaction, by the 32 bytes its ID encodes
+    Arweave([u8; 32]),
+    /// IPFS content. The CID is normalized to base32 CIDv1 on
+    /// deserialization, so both versions of a CID locate the same record.
+    Ipfs { cid: Cid },
+}
+
+impl ContentLocator {
+    /// The ID the record's PDA is derived from and stored as its
+    /// `transaction_id`: the Arweave transaction ID, or `ipfs_record_id`
+    /// of the CID
+    pub fn record_id(&self) -> TxId {
+        TxId::from_bytes(&self.record_key())
+    }
+
+    /// The 32 bytes `record_id` encodes
+    pub fn record_key(&self) -> [u8; 32] {
+        match self {
+            ContentLocator::Arweave(bytes) => *bytes,
+            ContentLocator::Ipfs { cid } => ipfs_record_key(cid),
+        }
+    }
+
+    /// The locator as stored in a `Mirror`
+    pub fn into_mirror(self) -> MirrorLocator {
+        match self {
+            ContentLocator::Arweave(bytes) => MirrorLocator::Arweave(bytes),
+            ContentLocator::Ipfs { cid } => MirrorLocator::Ipfs { cid: cid.into_inner() },
+        }
+    }
+}
+
+/// Record ID of IPFS content: the SHA-256 of the domain prefix and the
+/// normalized CID, encoded like an Arweave transaction ID so every account
+/// seeded by `transaction_id` works unchanged
+pub fn ipfs_record_id(cid: &Cid) -> TxId {
+    TxId::from_bytes(&ipfs_record_key(cid))
+}
+
+/// The 32 bytes of `ipfs_record_id`
+pub fn ipfs_record_key(cid: &Cid) -> [u8; 32] {
+    hashv(&[IPFS_RECORD_DOMAIN, cid.as_bytes()]).to_bytes()
+}
+
+/// Content locator of a mirror, stored with a plain CID string
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum MirrorLocator {
+    /// An Arweave base-layer transaction, by the 32 bytes its ID encodes
+    Arweave([u8; 32]),
+    /// IPFS content, by its normalized CIDv1
+    Ipfs { cid: String },
+}
+
+impl MirrorLocator {
+    pub const LEN: usize = 1 + 4 + MAX_CID_LEN;  // variant + largest payload (CID string)
+
+    /// Default gateway for the mirrored content
+    pub fn default_gateway(&self) -> &'static str {
+        match self {
+            MirrorLocator::Arweave(_) => ARWEAVE_GATEWAY_URL,
+            MirrorLocator::Ipfs { .. } => IPFS_GATEWAY_URL,
+        }
+    }
+
+    /// Get the URL of the mirrored content on `gateway`
+    pub fn content_url(&self, gateway: &str) -> String {
+        match self {
+            MirrorLocator::Arweave(bytes) => format!("{}/{}", gateway, TxId::from_bytes(bytes)),
+            MirrorLocator::Ipfs { cid } => format!("{}/ipfs/{}", gateway, cid),
+        }
+    }
+}
+
+/// MIME type argument of `register_file_v2`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum MimeTypeArg {
+    /// Code of an interned MIME type in the `MimeTable`
+    Code(u16),
+    /// Literal MIME type string for types that are not interned
+    Literal(MimeType),
+}
+
+/// Append-only table of interned MIME types.
+/// The code of an entry is its index plus one; code 0 means "not interned".
+#[account]
+pub struct MimeTable {
+    /// The registry this table belongs to
+    pub registry: Pubkey,
+    
+    /// Canonical MIME type strings, in code order
+    pub entries: Vec<String>,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 32],
+}
+
+impl MimeTable {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // registry
+        + 4 + MAX_MIME_TABLE_ENTRIES * (4 + MAX_MIME_TYPE_LEN)  // entries (vec)
+        + 1   // bump
+        + 32; // reserved
+
+    /// Resolve a code to its MIME type, if it has been interned
+    pub fn resolve(&self, code: u16) -> Option<&str> {
+        let index = (code as usize).checked_sub(1)?;
+        self.entries.get(index).map(String::as_str)
+    }
+
+    /// Resolve a code to its MIME type, failing with `UnknownMimeCode` if
+    /// it has not been interned
+    pub fn resolve_code(&self, code: u16) -> Result<&str> {
+        self.resolve(code).ok_or(HelixError::UnknownMimeCode.into())
+    }
+
+    /// Append a MIME type, returning its code. Existing codes never change:
+    /// a type already in the table fails with `DuplicateMimeType`, and a
+    /// full table with `MimeTableFull`.
+    pub fn intern(&mut self, mime_type: String) -> Result<u16> {
+        require!(
+            self.entries.len() < MAX_MIME_TABLE_ENTRIES,
+            HelixError::MimeTableFull
+        );
+        require!(
+            !self.entries.contains(&mime_type),
+            HelixError::DuplicateMimeType
+        );
+
+        self.entries.push(mime_type);
+        Ok(self.entries.len() as u16)
+    }
+}
+
+/// Ephemeral keypair authorized to act for a wallet within a scope
+#[account]
+pub struct SessionKey {
+    /// Wallet the session acts for
+    pub wallet: Pubkey,
+    
+    /// Ephemeral public key allowed to sign for the wallet
+    pub session_key: Pubkey,
+    
+    /// Unix timestamp after which the session is no longer valid
+    pub expires_at: i64,
+    
+    /// Bitmask of `SESSION_SCOPE_*` actions the session may perform
+    pub scope: u8,
+    
+    /// Unix timestamp when the session was created
+    pub created_at: i64,
+    
+    /// Bump seed for PDA derivation
+    pub bump: u8,
+    
+    /// Reserved space for future upgrades
+    pub _reserved: [u8; 16],
+}
+
+impl SessionKey {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // wallet
+        + 32  // session_key
+        + 8   // expires_at
+        + 1   // scope
+        + 8   // created_at
+        + 1   // bump
+        + 16; // reserved
+
+    /// Check if the session authorizes `signer` for `scope` at the given time
+    pub fn authorizes(&self, signer: &Pubkey, scope: u8, current_timestamp: i64) -> bool {
+        self.session_key == *signer
+            && self.scope & scope == scope
+            && current_timestamp <= self.expires_at
+    }
+}
+
+/// Layout version written as the first field of every event.
+/// Version 1 is the original layout without a version field; version 3
+/// appends `bytes_served` to `DownloadRecorded`; version 4 appends
+/// `has_message` to `ShareCreated`; version 5 appends `storage_location` to
+/// `FileRegistered`; version 6 appends `template_id` to `ShareCreated`;
+/// version 7 appends `owner_seq` to `FileRegistered`.
+pub const EVENT_VERSION: u8 = 7;
+
+/// Event emitted when a new file is registered
+#[event]
+pub struct FileRegistered {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub transaction_id: String,
+    pub size: u64,
+    pub is_encrypted: bool,
+    pub timestamp: i64,
+    pub storage_location: StorageLocation,
+    /// The file's `owner_seq` (0 for stealth records and registrations
+    /// without the owner's profile)
+    pub owner_seq: u64,
+}
+
+/// Event emitted when a share link is created
+#[event]
+pub struct ShareCreated {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub recipient: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub timestamp: i64,
+    /// Whether an encrypted message is attached (its contents stay on the
+    /// share link)
+    pub has_message: bool,
+    /// ID of the owner's `ShareTemplate` the share was created from, if any
+    pub template_id: Option<u32>,
+}
+
+/// Event emitted when a share link is created from an off-chain signed permit
+#[event]
+pub struct SharePermitUsed {
+    pub version: u8,
+    pub share: Pubkey,
+    pub owner: Pubkey,
+    pub relayer: Pubkey,
+    pub nonce: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a share link is revoked
+#[event]
+pub struct ShareRevoked {
+    pub version: u8,
+    pub share: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet claims a claimable share and becomes its
+/// recipient
+#[event]
+pub struct ShareClaimed {
+    pub version: u8,
+    pub share: Pubkey,
+    pub file: Pubkey,
+    pub recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a keeper warns a share's recipient of its upcoming
+/// expiry, at most once per share
+#[event]
+pub struct ShareExpiringSoon {
+    pub version: u8,
+    pub share: Pubkey,
+    pub file: Pubkey,
+    pub recipient: Option<Pubkey>,
+    pub expires_at: i64,
+    pub keeper: Pubkey,
+    /// Bounty paid to the keeper from the treasury
+    pub bounty_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a keeper processes an expired share link
+#[event]
+pub struct ShareExpired {
+    pub version: u8,
+    pub share: Pubkey,
+    pub file: Pubkey,
+    pub keeper: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a download is recorded on a share link
+#[event]
+pub struct DownloadRecorded {
+    pub version: u8,
+    pub share: Pubkey,
+    pub wallet: Pubkey,
+    pub session: Option<Pubkey>,
+    pub download_count: u32,
+    pub timestamp: i64,
+    pub bytes_served: u64,
+}
+
+/// Event emitted when a wallet creates a session key
+#[event]
+pub struct SessionCreated {
+    pub version: u8,
+    pub session: Pubkey,
+    pub wallet: Pubkey,
+    pub session_key: Pubkey,
+    pub expires_at: i64,
+    pub scope: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet revokes a session key
+#[event]
+pub struct SessionRevoked {
+    pub version: u8,
+    pub session: Pubkey,
+    pub wallet: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a deleted file is restored by its owner
+#[event]
+pub struct FileRestored {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Summary emitted by `restore_files_batch`, listing the accounts it skipped.
+/// Each restored file also emits its own `FileRestored` event.
+#[event]
+pub struct FilesRestoredBatch {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub restored: u32,
+    pub skipped: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Summary emitted by `create_shares_batch`, listing every new share link
+#[event]
+pub struct SharesBatchCreated {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub shares: Vec<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub timestamp: i64,
+}
+
+/// Summary emitted by `update_names_batch`, listing every renamed record
+#[event]
+pub struct FilesUpdated {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub files: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Essential fields of an exported account, enough to recreate it elsewhere
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotRecord {
+    /// A file record. `mime_type` is empty for interned types, which are
+    /// identified by `mime_code` instead.
+    File {
+        transaction_id: String,
+        encrypted_name: Option<String>,
+        mime_type: String,
+        mime_code: u16,
+        size: u64,
+        flags: u16,
+        status: FileStatus,
+        created_at: i64,
+        updated_at: i64,
+    },
+    /// A share link
+    Share {
+        file: Pubkey,
+        recipient: Option<Pubkey>,
+        recipient_commitment: Option<[u8; 32]>,
+        claim_hash: Option<[u8; 32]>,
+        encrypted_key: Option<String>,
+        expires_at: Option<i64>,
+        max_downloads: Option<u32>,
+        download_count: u32,
+        flags: u8,
+        created_at: i64,
+    },
+}
+
+/// Event emitted by `export_snapshot` for every exported account. `seq`
+/// runs on across calls from the caller's `start_seq`, so an indexer can
+/// tell when a snapshot is complete and in order.
+#[event]
+pub struct SnapshotEntry {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub snapshot_id: u64,
+    pub seq: u64,
+    pub account: Pubkey,
+    pub record: SnapshotRecord,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a deleted file is purged and its record closed.
+/// `forced` is set when the owner purged it before the retention window ended.
+#[event]
+pub struct FilePurged {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub purged_by: Pubkey,
+    pub forced: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner closes a deleted file's record. Carries the
+/// transaction ID so indexers can prune the file without another lookup.
+#[event]
+pub struct FileClosed {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub transaction_id: String,
+    pub rent_recipient: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority or an operator force-deletes a file
+#[event]
+pub struct FileAdminDeleted {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub moderator: Pubkey,
+    pub reason: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority or an operator force-revokes a share
+#[event]
+pub struct ShareForceRevoked {
+    pub version: u8,
+    pub share: Pubkey,
+    pub file: Pubkey,
+    pub moderator: Pubkey,
+    pub reason: u8,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an operator is added to the registry
+#[event]
+pub struct OperatorAdded {
+    pub version: u8,
+    pub operator: Pubkey,
+    pub permissions: u8,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a gateway attestor is added to the registry extension
+#[event]
+pub struct AttestorAdded {
+    pub version: u8,
+    pub attestor: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a gateway attestor is removed from the registry extension
+#[event]
+pub struct AttestorRemoved {
+    pub version: u8,
+    pub attestor: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a gateway attests or re-attests a file replica
+#[event]
+pub struct FileAttested {
+    pub version: u8,
+    pub attestation: Pubkey,
+    pub file: Pubkey,
+    pub attestor: Pubkey,
+    pub content_hash: Option<[u8; 32]>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an attestation is revoked
+#[event]
+pub struct AttestationRevoked {
+    pub version: u8,
+    pub attestation: Pubkey,
+    pub file: Pubkey,
+    pub attestor: Pubkey,
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner grants an auditor view access
+#[event]
+pub struct AuditGrantCreated {
+    pub version: u8,
+    pub grant: Pubkey,
+    pub owner: Pubkey,
+    pub auditor: Pubkey,
+    pub scope: AuditScope,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an audit grant is revoked
+#[event]
+pub struct AuditGrantRevoked {
+    pub version: u8,
+    pub grant: Pubkey,
+    pub owner: Pubkey,
+    pub auditor: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a proof-of-access challenge is opened
+#[event]
+pub struct ChallengeOpened {
+    pub version: u8,
+    pub challenge: Pubkey,
+    pub file: Pubkey,
+    pub challenger: Pubkey,
+    pub offset: u64,
+    pub length: u64,
+    pub bond: u64,
+    pub deadline: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the owner answers a challenge
+#[event]
+pub struct ChallengeAnswered {
+    pub version: u8,
+    pub challenge: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub answer: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Event emitted when the challenger accepts an answer
+#[event]
+pub struct ChallengeAccepted {
+    pub version: u8,
+    pub challenge: Pubkey,
+    pub file: Pubkey,
+    pub challenger: Pubkey,
+    pub bond_returned: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a challenge is settled after its deadline
+#[event]
+pub struct ChallengeSettled {
+    pub version: u8,
+    pub challenge: Pubkey,
+    pub file: Pubkey,
+    pub answered: bool,
+    pub bond_to_challenger: u64,
+    pub bond_to_owner: u64,
+    pub failed_challenges: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file is registered as derived from another record
+#[event]
+pub struct FileDerived {
+    pub version: u8,
+    pub file: Pubkey,
+    pub source: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted after `FileRegistered` when the registered content lives
+/// on IPFS; `transaction_id` in that event is the CID's record ID
+#[event]
+pub struct IpfsContentRegistered {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub cid: String,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a copyright statement is attested on a file
+#[event]
+pub struct CopyrightAttested {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub rights_holder: Pubkey,
+    pub content_hash: [u8; 32],
+    pub signature: [u8; 64],
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file's lifecycle status changes outside of deletion
+/// and restoration, i.e. when it is archived or unarchived
+#[event]
+pub struct FileStatusChanged {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub from: FileStatus,
+    pub to: FileStatus,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file's chunk Merkle root is set or cleared
+#[event]
+pub struct ChunkRootSet {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub chunk_root: Option<[u8; 32]>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a MIME type is interned in the MIME table
+#[event]
+pub struct MimeTypeAdded {
+    pub version: u8,
+    pub code: u16,
+    pub mime_type: String,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an operator is removed from the registry
+#[event]
+pub struct OperatorRemoved {
+    pub version: u8,
+    pub operator: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority proposes a successor
+#[event]
+pub struct AuthorityTransferProposed {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the proposed authority accepts the registry
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub version: u8,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority withdraws a proposed successor
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority overwrites drifted registry
+/// counters
+#[event]
+pub struct CountersReconciled {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub old_counters: RegistryCounters,
+    pub new_counters: RegistryCounters,
+    /// Hash of the off-chain explanation of the correction
+    pub reason_hash: [u8; 32],
+    pub timestamp: i64,
+}
+
+/// Event emitted when the program authority corrects a file's size
+#[event]
+pub struct FileSizeCorrected {
+    pub version: u8,
+    pub file: Pubkey,
+    pub authority: Pubkey,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the authority attests a file's recorded size
+#[event]
+pub struct FileSizeAttested {
+    pub version: u8,
+    pub file: Pubkey,
+    pub authority: Pubkey,
+    pub size: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the registry rolls over to a new epoch
+#[event]
+pub struct EpochRolledOver {
+    pub version: u8,
+    pub epoch: u64,
+    pub previous_epoch_stats: EpochStats,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file is marked as deleted
+#[event]
+pub struct FileDeleted {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a deleted file's metadata and share keys are wiped
+#[event]
+pub struct FileScrubbed {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub shares_scrubbed: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file's ownership is minted as a token
+#[event]
+pub struct FileTokenized {
+    pub version: u8,
+    pub file: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner proposes handing a file to another wallet
+#[event]
+pub struct OwnershipTransferProposed {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the proposed owner accepts a file
+#[event]
+pub struct OwnershipTransferAccepted {
+    pub version: u8,
+    pub file: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner withdraws a transfer proposal
+#[event]
+pub struct OwnershipTransferCancelled {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the holder of a file token claims ownership of the record
+#[event]
+pub struct TokenizedFileClaimed {
+    pub version: u8,
+    pub file: Pubkey,
+    pub mint: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file token is burned and plain ownership restored
+#[event]
+pub struct FileDetokenized {
+    pub version: u8,
+    pub file: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file is appended to its owner's file index
+#[event]
+pub struct FileIndexed {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub seq: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the owner reorders a file in its file index
+#[event]
+pub struct FileSortKeySet {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub seq: u64,
+    pub old_sort_key: u32,
+    pub sort_key: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet sets or clears its encryption key.
+/// Senders holding keys wrapped for the old key should re-wrap them.
+#[event]
+pub struct EncryptionKeyUpdated {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub encryption_pubkey: Option<[u8; 32]>,
+    pub key_generation: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet adds a contact to its address book
+#[event]
+pub struct ContactAdded {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub contact: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet removes a contact from its address book
+#[event]
+pub struct ContactRemoved {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub contact: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a recipient closes an inbox entry
+#[event]
+pub struct InboxEntryClosed {
+    pub version: u8,
+    pub recipient: Pubkey,
+    pub share: Pubkey,
+    pub seq: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner adds an alias to a file
+#[event]
+pub struct AliasCreated {
+    pub version: u8,
+    pub alias: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner removes an alias from a file
+#[event]
+pub struct AliasRemoved {
+    pub version: u8,
+    pub alias: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner attaches a mirror to a file
+#[event]
+pub struct MirrorAdded {
+    pub version: u8,
+    pub mirror: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub locator: MirrorLocator,
+    pub timestamp: i64,
+}
+
+/// Event emitted when an owner removes a mirror from a file
+#[event]
+pub struct MirrorRemoved {
+    pub version: u8,
+    pub mirror: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet saves a reference to a file
+#[event]
+pub struct ReferenceCreated {
+    pub version: u8,
+    pub reference: Pubkey,
+    pub file: Pubkey,
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet closes its reference to a file
+#[event]
+pub struct ReferenceClosed {
+    pub version: u8,
+    pub reference: Pubkey,
+    pub file: Pubkey,
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet registers a name in its namespace
+#[event]
+pub struct NameRegistered {
+    pub version: u8,
+    pub name_record: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub file: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a name is pointed at a different file
+#[event]
+pub struct NameTargetUpdated {
+    pub version: u8,
+    pub name_record: Pubkey,
+    pub owner: Pubkey,
+    pub old_file: Pubkey,
+    pub new_file: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet releases a name
+#[event]
+pub struct NameReleased {
+    pub version: u8,
+    pub name_record: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a short code is claimed
+#[event]
+pub struct ShortCodeClaimed {
+    pub version: u8,
+    pub short_code: Pubkey,
+    pub code: String,
+    pub share: Pubkey,
+    pub claimer: Pubkey,
+    pub fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a short code is released by its claimer or reaped
+/// after its share stopped being usable
+#[event]
+pub struct ShortCodeReleased {
+    pub version: u8,
+    pub short_code: Pubkey,
+    pub code: String,
+    pub share: Pubkey,
+    pub claimer: Pubkey,
+    pub released_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file is listed in the gallery
+#[event]
+pub struct GalleryEntryAdded {
+    pub version: u8,
+    pub entry: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub category: GalleryCategory,
+    pub seq: u64,
+    pub fee_lamports: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a gallery entry is removed by its owner or the authority
+#[event]
+pub struct GalleryEntryRemoved {
+    pub version: u8,
+    pub entry: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub removed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet likes a file
+#[event]
+pub struct FileLiked {
+    pub version: u8,
+    pub file: Pubkey,
+    pub wallet: Pubkey,
+    pub like_count: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet withdraws its like. `like_count` is `None`
+/// when the file record no longer exists.
+#[event]
+pub struct FileUnliked {
+    pub version: u8,
+    pub file: Pubkey,
+    pub wallet: Pubkey,
+    pub like_count: Option<u32>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when lamports are contributed to a file's endowment
+#[event]
+pub struct EndowmentContributed {
+    pub version: u8,
+    pub endowment: Pubkey,
+    pub file: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+    pub total_raised: u64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the file owner withdraws from an endowment. Closing
+/// the endowment is reported as a final withdrawal with `closed` set.
+#[event]
+pub struct EndowmentWithdrawn {
+    pub version: u8,
+    pub endowment: Pubkey,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    pub memo: Option<String>,
+    pub closed: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the owner replaces or removes a share's message
+#[event]
+pub struct ShareMessageUpdated {
+    pub version: u8,
+    pub share: Pubkey,
+    pub owner: Pubkey,
+    pub has_message: bool,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet creates a share template
+#[event]
+pub struct ShareTemplateCreated {
+    pub version: u8,
+    pub template: Pubkey,
+    pub owner: Pubkey,
+    pub template_id: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet changes a share template
+#[event]
+pub struct ShareTemplateUpdated {
+    pub version: u8,
+    pub template: Pubkey,
+    pub owner: Pubkey,
+    pub template_id: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet deletes a share template
+#[event]
+pub struct ShareTemplateDeleted {
+    pub version: u8,
+    pub template: Pubkey,
+    pub owner: Pubkey,
+    pub template_id: u32,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet updates its default share limits
+#[event]
+pub struct ShareDefaultsUpdated {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub share_defaults: ShareDefaults,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet sets or clears its guardian
+#[event]
+pub struct GuardianUpdated {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub guardian: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet's account is frozen
+#[event]
+pub struct AccountFrozen {
+    pub version: u8,
+    pub wallet: Pubkey,
+    /// The wallet itself or its guardian
+    pub frozen_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet unfreezes its account
+#[event]
+pub struct AccountUnfrozen {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file's key release is scheduled
+#[event]
+pub struct TimedReleaseCreated {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub release_at: i64,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the owner cancels a key release before its deadline
+#[event]
+pub struct TimedReleaseCancelled {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a file's key release is triggered, publishing the key
+#[event]
+pub struct KeyReleased {
+    pub version: u8,
+    pub file: Pubkey,
+    pub key_blob: String,
+    pub triggered_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Event emitted when a wallet updates its public profile metadata
+#[event]
+pub struct ProfileUpdated {
+    pub version: u8,
+    pub wallet: Pubkey,
+    pub display_name: Option<String>,
+    pub avatar_file: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+/// Event emitted when the wallet that created a file's access log closes it
+#[event]
+pub struct AccessLogClosed {
+    pub version: u8,
+    pub file: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+// Account structures
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        account_data, sample_file, sample_profile, sample_registry, sample_share, zeroed, DAY,
+        NOW, SAMPLE_TX_ID,
+    };
+    use crate::types::MimeType;
+
+    fn stats(files: u64) -> EpochStats {
+        EpochStats { files, shares: files, downloads: files, bytes: files }
+    }
+
+    #[test]
+    fn roll_epoch_is_idempotent_within_an_epoch() {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        registry.current_epoch = 10;
+        registry.current_epoch_stats = stats(3);
+        registry.previous_epoch_stats = stats(1);
+
+        assert!(!registry.roll_epoch(10));
+        assert!(!registry.roll_epoch(9));
+        assert_eq!(registry.current_epoch, 10);
+        assert_eq!(registry.current_epoch_stats, stats(3));
+        assert_eq!(registry.previous_epoch_stats, stats(1));
+    }
+
+    #[test]
+    fn roll_epoch_by_one_moves_current_into_previous() {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        registry.current_epoch = 10;
+        registry.current_epoch_stats = stats(3);
+        registry.previous_epoch_stats = stats(1);
+
+        assert!(registry.roll_epoch(11));
+        assert_eq!(registry.current_epoch, 11);
+        assert_eq!(registry.current_epoch_stats, EpochStats::default());
+        assert_eq!(registry.previous_epoch_stats, stats(3));
+        assert!(!registry.roll_epoch(11));
+    }
+
+    #[test]
+    fn roll_epoch_over_skipped_epochs_zeroes_both_buckets() {
+        for epoch in [12, 100, u64::MAX] {
+            let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+            registry.current_epoch = 10;
+            registry.current_epoch_stats = stats(3);
+            registry.previous_epoch_stats = stats(1);
+
+            assert!(registry.roll_epoch(epoch));
+            assert_eq!(registry.current_epoch, epoch);
+            assert_eq!(registry.current_epoch_stats, EpochStats::default());
+            assert_eq!(registry.previous_epoch_stats, EpochStats::default());
+        }
+    }
+
+    #[test]
+    fn registration_fee_is_waived_up_to_and_at_the_threshold() {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 5_000);
+        registry.free_below_bytes = 1_024;
+
+        assert_eq!(registry.registration_fee(0), 0);
+        assert_eq!(registry.registration_fee(1_023), 0);
+        assert_eq!(registry.registration_fee(1_024), 0);
+        assert_eq!(registry.registration_fee(1_025), 5_000);
+        assert_eq!(registry.registration_fee(u64::MAX), 5_000);
+    }
+
+    #[test]
+    fn registration_fee_threshold_of_zero_waives_nothing() {
+        let (_, registry) = sample_registry(Pubkey::new_unique(), 5_000);
+
+        assert_eq!(registry.registration_fee(0), 5_000);
+        assert_eq!(registry.registration_fee(1), 5_000);
+    }
+
+    #[test]
+    fn roll_epoch_from_a_fresh_registry() {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        registry.current_epoch_stats = stats(2);
+
+        assert!(registry.roll_epoch(1));
+        assert_eq!(registry.previous_epoch_stats, stats(2));
+    }
+
+    /// A public share created at `NOW` that is revoked, expired and
+    /// exhausted as requested
+    fn share(revoked: bool, expired: bool, exhausted: bool) -> ShareLink {
+        let (_, mut share_link) =
+            sample_share(&Pubkey::new_unique(), Pubkey::new_unique(), None, 0);
+        share_link.set_revoked(revoked);
+        share_link.expires_at = Some(if expired { NOW - 1 } else { NOW + 1 });
+        share_link.max_downloads = Some(2);
+        share_link.download_count = if exhausted { 2 } else { 1 };
+        share_link
+    }
+
+    #[test]
+    fn share_status_precedence() {
+        use ShareStatus::*;
+
+        // (revoked, file deleted, not yet active, expired, exhausted) => status
+        let cases = [
+            ((false, false, false, false, false), Active),
+            ((false, false, false, false, true), Exhausted),
+            ((false, false, false, true, false), Expired),
+            ((false, false, false, true, true), Expired),
+            ((false, false, true, true, true), NotYetActive),
+            ((false, true, true, true, true), FileDeleted),
+            ((true, false, false, false, false), Revoked),
+            ((true, false, false, true, true), Revoked),
+            ((true, true, true, true, true), Revoked),
+        ];
+
+        for ((revoked, file_deleted, pending, expired, exhausted), expected) in cases {
+            let mut share_link = share(revoked, expired, exhausted);
+            if pending {
+                share_link.created_at = NOW + 1;
+            }
+            assert_eq!(
+                share_link.status(NOW, file_deleted),
+                expected,
+                "revoked={} file_deleted={} pending={} expired={} exhausted={}",
+                revoked,
+                file_deleted,
+                pending,
+                expired,
+                exhausted
+            );
+        }
+    }
+
+    #[test]
+    fn share_status_boundaries() {
+        let share_link = share(false, false, false);
+        let expires_at = share_link.expires_at.unwrap();
+
+        assert_eq!(share_link.status(expires_at, false), ShareStatus::Active);
+        assert_eq!(share_link.status(expires_at + 1, false), ShareStatus::Expired);
+        assert_eq!(
+            share_link.status(share_link.created_at - 1, false),
+            ShareStatus::NotYetActive
+        );
+        assert_eq!(share_link.status(share_link.created_at, false), ShareStatus::Active);
+    }
+
+    #[test]
+    fn share_status_drives_is_valid() {
+        assert!(share(false, false, false).is_valid(NOW));
+        assert!(!share(true, false, false).is_valid(NOW));
+        assert!(!share(false, true, false).is_valid(NOW));
+        assert!(!share(false, false, true).is_valid(NOW));
+    }
+
+    #[test]
+    fn only_the_revoked_flag_bit_changes_share_status() {
+        for flags in 0u8..=u8::MAX {
+            let mut share_link = share(false, false, false);
+            share_link.flags = flags;
+            let expected = if flags & SHARE_FLAG_REVOKED != 0 {
+                ShareStatus::Revoked
+            } else {
+                ShareStatus::Active
+            };
+            assert_eq!(share_link.status(NOW, false), expected, "flags={:#010b}", flags);
+        }
+    }
+
+    #[test]
+    fn share_status_errors_name_the_cause() {
+        let error = |status: ShareStatus| status.require_active().unwrap_err();
+
+        assert!(ShareStatus::Active.require_active().is_ok());
+        assert_eq!(error(ShareStatus::Revoked), HelixError::ShareRevoked.into());
+        assert_eq!(error(ShareStatus::Expired), HelixError::ShareExpired.into());
+        assert_eq!(error(ShareStatus::Exhausted), HelixError::MaxDownloadsReached.into());
+        assert_eq!(error(ShareStatus::NotYetActive), HelixError::ShareNotYetActive.into());
+        assert_eq!(error(ShareStatus::FileDeleted), HelixError::SharedFileDeleted.into());
+    }
+
+    #[test]
+    fn download_limit_allows_exactly_n_downloads() {
+        let recipient = Pubkey::new_unique();
+
+        for max in [1, 2, u32::MAX] {
+            let (_, mut share_link) =
+                sample_share(&Pubkey::new_unique(), Pubkey::new_unique(), Some(recipient), 0);
+            share_link.max_downloads = Some(max);
+            // Start two short of the limit so u32::MAX runs in two steps
+            share_link.download_count = max.saturating_sub(2);
+
+            for _ in share_link.download_count..max {
+                share_link.require_download_access(&recipient, None, NOW, false).unwrap();
+                share_link.record_download(recipient, NOW, false, 10).unwrap();
+            }
+            assert_eq!(share_link.download_count, max, "max_downloads = {}", max);
+
+            // The N+1th download fails with the limit, for any wallet
+            let exhausted = Err(HelixError::MaxDownloadsReached.into());
+            let stranger = Pubkey::new_unique();
+            assert_eq!(share_link.require_download_access(&recipient, None, NOW, false), exhausted);
+            assert_eq!(share_link.require_download_access(&stranger, None, NOW, false), exhausted);
+            assert_eq!(share_link.record_download(recipient, NOW, false, 10), exhausted);
+            assert_eq!(share_link.download_count, max);
+        }
+    }
+
+    #[test]
+    fn unlimited_download_count_does_not_saturate() {
+        let (_, mut share_link) =
+            sample_share(&Pubkey::new_unique(), Pubkey::new_unique(), None, 0);
+        share_link.download_count = u32::MAX - 1;
+
+        share_link.record_download(Pubkey::new_unique(), NOW, false, 10).unwrap();
+        assert_eq!(
+            share_link.record_download(Pubkey::new_unique(), NOW, false, 10),
+            Err(HelixError::ArithmeticOverflow.into())
+        );
+        assert_eq!(share_link.download_count, u32::MAX);
+    }
+
+    #[test]
+    fn wrong_wallet_is_denied_only_on_a_usable_share() {
+        let recipient = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let (_, mut share_link) =
+            sample_share(&Pubkey::new_unique(), Pubkey::new_unique(), Some(recipient), 0);
+
+        assert_eq!(
+            share_link.require_download_access(&stranger, None, NOW, false),
+            Err(HelixError::ShareAccessDenied.into())
+        );
+        assert_eq!(
+            share_link.require_download_access(&stranger, None, NOW, true),
+            Err(HelixError::SharedFileDeleted.into())
+        );
+        share_link.set_revoked(true);
+        assert_eq!(
+            share_link.require_download_access(&recipient, None, NOW, false),
+            Err(HelixError::ShareRevoked.into())
+        );
+        assert!(share_link.is_recipient(&recipient, None));
+        assert!(!share_link.can_access(&recipient, NOW));
+    }
+
+    /// A flag bit with its getter and setter
+    type FlagAccessor<T, F> = (F, fn(&T) -> bool, fn(&mut T, bool));
+
+    /// Each `FileRecord` flag with its getter and setter
+    fn file_flag_accessors() -> [FlagAccessor<FileRecord, u16>; 6] {
+        [
+            (FILE_FLAG_ENCRYPTED, FileRecord::is_encrypted, FileRecord::set_encrypted),
+            (
+                FILE_FLAG_DELETED_BY_AUTHORITY,
+                FileRecord::is_deleted_by_authority,
+                FileRecord::set_deleted_by_authority,
+            ),
+            (FILE_FLAG_TOKENIZED, FileRecord::is_tokenized, FileRecord::set_tokenized),
+            (FILE_FLAG_INDEXED, FileRecord::is_indexed, FileRecord::set_indexed),
+            (FILE_FLAG_ENDOWED, FileRecord::is_endowed, FileRecord::set_endowed),
+            (
+                FILE_FLAG_SIZE_ATTESTED,
+                FileRecord::is_size_attested,
+                FileRecord::set_size_attested,
+            ),
+        ]
+    }
+
+    #[test]
+    fn file_flag_accessors_touch_only_their_bit() {
+        let mut file = sample_file(Pubkey::new_unique(), SAMPLE_TX_ID, 1);
+        for flags in 0..=u16::MAX {
+            for (bit, get, set) in file_flag_accessors() {
+                file.flags = flags;
+                assert_eq!(get(&file), flags & bit != 0, "{bit:#x} of {flags:#x}");
+                set(&mut file, true);
+                assert_eq!(file.flags, flags | bit);
+                set(&mut file, false);
+                assert_eq!(file.flags, flags & !bit);
+            }
+        }
+    }
+
+    #[test]
+    fn file_flag_bits_are_distinct() {
+        let bits = [
+            FILE_FLAG_ENCRYPTED,
+            FILE_FLAG_DELETED_BY_AUTHORITY,
+            FILE_FLAG_TOKENIZED,
+            FILE_FLAG_COMPACT_MIME,
+            FILE_FLAG_INDEXED,
+            FILE_FLAG_SCRUBBED,
+            FILE_FLAG_STEALTH,
+            FILE_FLAG_ARCHIVED,
+            FILE_FLAG_DELETED,
+            FILE_FLAG_ENDOWED,
+            FILE_FLAG_SIZE_ATTESTED,
+        ];
+        let union = bits.iter().fold(0u16, |union, bit| {
+            assert_eq!(bit.count_ones(), 1);
+            assert_eq!(union & bit, 0, "{bit:#x} is reused");
+            union | bit
+        });
+        assert_eq!(union.count_ones() as usize, bits.len());
+    }
+
+    #[test]
+    fn deleted_bit_overrides_the_stored_status() {
+        let mut file = sample_file(Pubkey::new_unique(), SAMPLE_TX_ID, 1);
+        for status in [FileStatus::Active, FileStatus::Archived, FileStatus::Deleted] {
+            file.status = status;
+            file.flags = FILE_FLAG_DELETED;
+            assert!(file.is_deleted());
+            assert!(!file.is_accessible());
+            file.flags = !FILE_FLAG_DELETED;
+            assert_eq!(file.is_deleted(), status == FileStatus::Deleted);
+            assert_eq!(file.file_status(), status);
+        }
+    }
+
+    #[test]
+    fn set_status_keeps_the_status_bits_in_step() {
+        let mut file = sample_file(Pubkey::new_unique(), SAMPLE_TX_ID, 1);
+        file.flags = FILE_FLAG_ENCRYPTED | FILE_FLAG_INDEXED;
+
+        file.set_status(FileStatus::Archived).unwrap();
+        assert_eq!(file.flags, FILE_FLAG_ENCRYPTED | FILE_FLAG_INDEXED | FILE_FLAG_ARCHIVED);
+        file.set_status(FileStatus::Active).unwrap();
+        file.set_status(FileStatus::Deleted).unwrap();
+        assert_eq!(file.flags, FILE_FLAG_ENCRYPTED | FILE_FLAG_INDEXED | FILE_FLAG_DELETED);
+        assert!(file.is_encrypted() && file.is_indexed() && file.is_deleted());
+    }
+
+    #[test]
+    fn share_flag_accessors_touch_only_their_bit() {
+        let (_, mut share_link) =
+            sample_share(&Pubkey::new_unique(), Pubkey::new_unique(), None, 0);
+        let accessors: [FlagAccessor<ShareLink, u8>; 2] = [
+            (SHARE_FLAG_REVOKED, ShareLink::is_revoked, ShareLink::set_revoked),
+            (SHARE_FLAG_SINGLE_USE, ShareLink::is_single_use, ShareLink::set_single_use),
+        ];
+
+        for flags in 0..=u8::MAX {
+            for (bit, get, set) in accessors {
+                share_link.flags = flags;
+                assert_eq!(get(&share_link), flags & bit != 0, "{bit:#x} of {flags:#x}");
+                set(&mut share_link, true);
+                assert_eq!(share_link.flags, flags | bit);
+                set(&mut share_link, false);
+                assert_eq!(share_link.flags, flags & !bit);
+            }
+
+            share_link.flags = flags;
+            assert_eq!(share_link.is_expiry_warned(), flags & SHARE_FLAG_EXPIRY_WARNED != 0);
+            share_link.set_expiry_warned();
+            assert_eq!(share_link.flags, flags | SHARE_FLAG_EXPIRY_WARNED);
+
+            share_link.flags = flags;
+            let released = flags & SHARE_FLAG_COUNTERS_RELEASED != 0;
+            assert_eq!(share_link.counters_released(), released);
+            assert_eq!(share_link.release_counters(), !released);
+            assert_eq!(share_link.flags, flags | SHARE_FLAG_COUNTERS_RELEASED);
+            assert!(!share_link.release_counters());
+
+            share_link.flags = flags;
+            assert_eq!(share_link.is_key_cleared(), flags & SHARE_FLAG_KEY_CLEARED != 0);
+        }
+    }
+
+    #[test]
+    fn clearing_the_key_sets_only_the_key_cleared_bit() {
+        let (_, mut share_link) =
+            sample_share(&Pubkey::new_unique(), Pubkey::new_unique(), None, 0);
+        share_link.flags = SHARE_FLAG_REVOKED;
+        share_link.encrypted_key = None;
+        share_link.encrypted_message = None;
+        assert!(!share_link.clear_key());
+        assert_eq!(share_link.flags, SHARE_FLAG_REVOKED);
+
+        share_link.encrypted_key = Some("key".to_string());
+        assert!(share_link.clear_key());
+        assert_eq!(share_link.flags, SHARE_FLAG_REVOKED | SHARE_FLAG_KEY_CLEARED);
+        assert_eq!(share_link.encrypted_key, None);
+    }
+
+    #[test]
+    fn mime_codes_are_assigned_in_order_and_never_change() {
+        let mut table = zeroed::<MimeTable>(MimeTable::LEN);
+        assert_eq!(table.intern("image/png".to_string()), Ok(1));
+        assert_eq!(table.intern("text/plain".to_string()), Ok(2));
+
+        assert_eq!(
+            table.intern("image/png".to_string()),
+            Err(HelixError::DuplicateMimeType.into())
+        );
+        assert_eq!(table.resolve_code(1), Ok("image/png"));
+        assert_eq!(table.resolve_code(2), Ok("text/plain"));
+
+        for code in 3..=MAX_MIME_TABLE_ENTRIES as u16 {
+            assert_eq!(table.intern(format!("application/x-{code}")), Ok(code));
+        }
+        assert_eq!(
+            table.intern("application/x-full".to_string()),
+            Err(HelixError::MimeTableFull.into())
+        );
+        assert_eq!(table.resolve_code(1), Ok("image/png"));
+
+        // A full table of maximum-length types still fits its account
+        table.entries.fill("a/".repeat(MAX_MIME_TYPE_LEN / 2));
+        account_data(&table, MimeTable::LEN);
+    }
+
+    #[test]
+    fn unknown_mime_codes_fail_cleanly() {
+        let mut table = zeroed::<MimeTable>(MimeTable::LEN);
+        let unknown = Err(HelixError::UnknownMimeCode.into());
+        assert_eq!(table.resolve_code(1), unknown);
+
+        table.intern("image/png".to_string()).unwrap();
+        for code in [0, 2, u16::MAX] {
+            assert_eq!(table.resolve(code), None);
+            assert_eq!(table.resolve_code(code), unknown);
+        }
+    }
+
+    #[test]
+    fn interned_mime_types_shrink_the_record() {
+        let tx_id = [7u8; 32];
+        let content = ContentLocator::Arweave(tx_id);
+        let name = None::<&str>;
+        let literal = MimeTypeArg::Literal(MimeType::try_from("image/png".to_string()).unwrap());
+
+        let full = FileRecord::space(&name);
+        let interned = FileRecord::compact_space(&name, &MimeTypeArg::Code(1), &content);
+        let compact_literal = FileRecord::compact_space(&name, &literal, &content);
+        assert_eq!(full - interned, MAX_MIME_TYPE_LEN);
+        assert_eq!(compact_literal - interned, "image/png".len());
+
+        // A compact record with an interned type fits its smaller account
+        let mut file = sample_file(Pubkey::new_unique(), SAMPLE_TX_ID, 1);
+        file.mime_type = String::new();
+        file.mime_code = 1;
+        file.flags = FILE_FLAG_COMPACT_MIME;
+        assert_eq!(account_data(&file, interned).len(), interned);
+    }
+
+    #[test]
+    fn key_rotation_evicts_the_oldest_retired_key() {
+        let (_, mut profile) = sample_profile(Pubkey::new_unique());
+        let key = |n: u8| Some([n; 32]);
+
+        // Four consecutive rotations: keys 1 to 4 published at NOW + 1..=4
+        for n in 1..=4u8 {
+            profile.rotate_encryption_key(key(n), NOW + i64::from(n)).unwrap();
+            assert_eq!(profile.key_generation, u32::from(n));
+            assert_eq!(profile.encryption_pubkey, key(n));
+        }
+
+        // Keys 1 to 3 were retired, newest first, when their successors
+        // were set; none has been evicted yet
+        let retired = |n: u8| RetiredEncryptionKey {
+            pubkey: [n; 32],
+            generation: u32::from(n),
+            retired_at: NOW + i64::from(n) + 1,
+        };
+        assert_eq!(profile.previous_keys, [retired(3), retired(2), retired(1)]);
+
+        // A fifth key retires key 4 and evicts key 1, the oldest
+        profile.rotate_encryption_key(key(5), NOW + 5).unwrap();
+        assert_eq!(profile.previous_keys, [retired(4), retired(3), retired(2)]);
+        assert_eq!(profile.key_generation, 5);
+    }
+
+    #[test]
+    fn clearing_the_key_retires_it_and_bumps_the_generation() {
+        let (_, mut profile) = sample_profile(Pubkey::new_unique());
+
+        // Setting the first key retires nothing
+        profile.rotate_encryption_key(Some([1; 32]), NOW).unwrap();
+        assert_eq!(profile.previous_keys, [RetiredEncryptionKey::default(); 3]);
+
+        profile.rotate_encryption_key(None, NOW + 1).unwrap();
+        assert_eq!(profile.encryption_pubkey, None);
+        assert_eq!(profile.key_generation, 2);
+        assert_eq!(profile.previous_keys[0].pubkey, [1; 32]);
+
+        // Clearing again has nothing to retire, but the generation still moves
+        profile.rotate_encryption_key(None, NOW + 2).unwrap();
+        assert_eq!(profile.key_generation, 3);
+        assert_eq!(profile.previous_keys[1], RetiredEncryptionKey::default());
+    }
+
+    #[test]
+    fn key_generation_never_wraps() {
+        let (_, mut profile) = sample_profile(Pubkey::new_unique());
+        profile.key_generation = u32::MAX;
+        assert_eq!(
+            profile.rotate_encryption_key(Some([1; 32]), NOW),
+            Err(HelixError::ArithmeticOverflow.into())
+        );
+    }
+
+    #[test]
+    fn timed_release_is_one_way() {
+        let mut timed_release = zeroed::<TimedRelease>(TimedRelease::LEN);
+        timed_release.release_at = NOW + DAY;
+        let release_at = timed_release.release_at;
+
+        // Cancellable strictly before the deadline, triggerable from it on
+        assert_eq!(timed_release.require_cancellable(release_at - 1), Ok(()));
+        assert_eq!(
+            timed_release.trigger(release_at - 1),
+            Err(HelixError::ReleaseNotDue.into())
+        );
+        assert!(!timed_release.released);
+        assert_eq!(
+            timed_release.require_cancellable(release_at),
+            Err(HelixError::ReleaseDeadlinePassed.into())
+        );
+        assert_eq!(timed_release.trigger(release_at), Ok(()));
+        assert!(timed_release.released);
+
+        // Once released it stays released: no second trigger and no cancel,
+        // however late
+        for now in [release_at, release_at + DAY, i64::MAX] {
+            assert_eq!(
+                timed_release.trigger(now),
+                Err(HelixError::KeyAlreadyReleased.into())
+            );
+            assert_eq!(
+                timed_release.require_cancellable(now),
+                Err(HelixError::ReleaseDeadlinePassed.into())
+            );
+            assert!(timed_release.released);
+        }
+    }
+
+    #[test]
+    fn rent_goes_back_to_the_sponsor() {
+        let owner = Pubkey::new_unique();
+        let sponsor = Pubkey::new_unique();
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let (_, mut share_link) = sample_share(&Pubkey::new_unique(), owner, None, 0);
+
+        // Self-funded accounts refund their owner
+        assert_eq!(file_record.rent_recipient(), owner);
+        assert_eq!(share_link.rent_recipient(), owner);
+
+        // Sponsored ones refund the sponsor, even though the owner closes them
+        file_record.rent_payer = sponsor;
+        share_link.rent_payer = sponsor;
+        assert_eq!(file_record.rent_recipient(), sponsor);
+        assert_eq!(share_link.rent_recipient(), sponsor);
+
+        // Accounts from before the payer was recorded fall back to the owner
+        file_record.rent_payer = Pubkey::default();
+        share_link.rent_payer = Pubkey::default();
+        assert_eq!(file_record.rent_recipient(), owner);
+        assert_eq!(share_link.rent_recipient(), owner);
+    }
+
+    #[test]
+    fn ownership_transfer_lapses_after_its_expiry() {
+        let owner = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let mut file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let pending = Err(HelixError::OwnershipTransferPending.into());
+        assert_eq!(file_record.pending_transfer(NOW), None);
+        assert_eq!(file_record.require_no_pending_transfer(NOW), Ok(()));
+
+        file_record.pending_owner = Some(new_owner);
+        file_record.transfer_expires_at = NOW + OWNERSHIP_TRANSFER_WINDOW_SECS;
+        let expires_at = file_record.transfer_expires_at;
+
+        // Acceptable, and blocking destructive actions, up to and including
+        // the expiry
+        for now in [NOW, expires_at - 1, expires_at] {
+            assert_eq!(file_record.pending_transfer(now), Some(new_owner));
+            assert_eq!(file_record.require_no_pending_transfer(now), pending);
+        }
+
+        // Lapsed one second later, without anyone clearing it
+        for now in [expires_at + 1, i64::MAX] {
+            assert_eq!(file_record.pending_transfer(now), None);
+            assert_eq!(file_record.require_no_pending_transfer(now), Ok(()));
+        }
+        assert_eq!(file_record.pending_owner, Some(new_owner));
+
+        // A cleared proposal blocks nothing, whatever expiry is left behind
+        file_record.pending_owner = None;
+        assert_eq!(file_record.pending_transfer(NOW), None);
+        assert_eq!(file_record.require_no_pending_transfer(NOW), Ok(()));
+    }
+
+    #[test]
+    fn download_cooldown_ends_exactly_cooldown_secs_later() {
+        let cooldown_secs = 60;
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (_, mut share_link) = sample_share(&Pubkey::new_unique(), owner, None, 0);
+        let cooling_down = Err(HelixError::DownloadCooldownActive.into());
+
+        // Nothing has been downloaded yet
+        assert_eq!(share_link.require_download_cooldown(&first, cooldown_secs, NOW), Ok(()));
+
+        share_link.record_download(first, NOW, false, 1024).unwrap();
+        assert_eq!(share_link.require_download_cooldown(&first, cooldown_secs, NOW), cooling_down);
+        assert_eq!(
+            share_link.require_download_cooldown(&first, cooldown_secs, NOW + cooldown_secs - 1),
+            cooling_down
+        );
+        assert_eq!(
+            share_link.require_download_cooldown(&first, cooldown_secs, NOW + cooldown_secs),
+            Ok(())
+        );
+
+        // Other wallets are never held back, and only the latest downloader
+        // is tracked
+        assert_eq!(share_link.require_download_cooldown(&second, cooldown_secs, NOW), Ok(()));
+        share_link.record_download(second, NOW + 1, false, 1024).unwrap();
+        assert_eq!(share_link.require_download_cooldown(&first, cooldown_secs, NOW + 1), Ok(()));
+        assert_eq!(
+            share_link.require_download_cooldown(&second, cooldown_secs, NOW + 1),
+            cooling_down
+        );
+
+        // A cooldown reaching past the end of time saturates rather than
+        // overflowing
+        assert_eq!(
+            share_link.require_download_cooldown(&second, i64::MAX, i64::MAX - 1),
+            cooling_down
+        );
+    }
+
+    #[test]
+    fn counters_need_files_to_hold_shares_or_bytes() {
+        let counters = |total_files, archived_files, total_shares, total_bytes| RegistryCounters {
+            total_files,
+            total_shares,
+            total_bytes,
+            total_bytes_served: 0,
+            archived_files,
+        };
+
+        assert!(RegistryCounters::default().is_plausible(None));
+        assert!(counters(1, 0, 5, 1024).is_plausible(None));
+        // Archived files keep their shares and bytes too
+        assert!(counters(0, 1, 5, 1024).is_plausible(None));
+        assert!(!counters(0, 0, 1, 0).is_plausible(None));
+        assert!(!counters(0, 0, 0, 1).is_plausible(None));
+
+        // Bytes served outlive the files they were served from
+        let served = RegistryCounters { total_bytes_served: 1 << 40, ..Default::default() };
+        assert!(served.is_plausible(None));
+    }
+
+    #[test]
+    fn counters_stay_within_the_share_limit_and_a_byte_per_file() {
+        let counters = |total_files, archived_files, total_shares, total_bytes| RegistryCounters {
+            total_files,
+            total_shares,
+            total_bytes,
+            total_bytes_served: 0,
+            archived_files,
+        };
+
+        // Two files under a limit of three hold at most six shares
+        assert!(counters(1, 1, 6, 1024).is_plausible(Some(3)));
+        assert!(!counters(1, 1, 7, 1024).is_plausible(Some(3)));
+        assert!(counters(1, 1, 7, 1024).is_plausible(None));
+        assert!(counters(u64::MAX, 0, u64::MAX, u64::MAX).is_plausible(Some(1)));
+
+        // Every live file holds at least one byte
+        assert!(counters(2, 1, 0, 3).is_plausible(None));
+        assert!(!counters(2, 1, 0, 2).is_plausible(None));
+    }
+
+    #[test]
+    fn share_limit_is_enforced_only_when_configured() {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        assert_eq!(registry.share_limit(), None);
+        assert_eq!(registry.require_share_capacity(u32::MAX), Ok(()));
+
+        registry.max_shares_per_file = 2;
+        assert_eq!(registry.require_share_capacity(1), Ok(()));
+        assert_eq!(
+            registry.require_share_capacity(2),
+            Err(HelixError::ShareLimitReached.into())
+        );
+    }
+
+    #[test]
+    fn set_counters_round_trips_and_leaves_epochs_alone() {
+        let (_, mut registry) = sample_registry(Pubkey::new_unique(), 0);
+        registry.current_epoch_stats = stats(3);
+        let counters = RegistryCounters {
+            total_files: 1,
+            total_shares: 2,
+            total_bytes: 3,
+            total_bytes_served: 4,
+            archived_files: 5,
+        };
+
+        registry.set_counters(counters);
+        assert_eq!(registry.counters(), counters);
+        assert_eq!(registry.current_epoch_stats, stats(3));
+    }
+}
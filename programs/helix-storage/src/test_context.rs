@@ -0,0 +1,602 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::{system_program, InstructionData};
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+use crate::client::{
+    find_file_address, find_file_mint_address, find_inbox_address, find_registry_address,
+    find_share_address, find_user_profile_address,
+};
+#[cfg(feature = "event-cpi")]
+use crate::client::find_event_authority_address;
+use crate::instructions::create_share::CreateShareArgs;
+use crate::state::{FileRecord, ShareLink, StorageLocationArg, StorageRegistry, UserProfile};
+use crate::token_metadata::{find_metadata_address, METADATA_SEED, TOKEN_METADATA_PROGRAM_ID};
+use crate::types::{MimeType, TxId};
+
+// A bank running this crate's program natively, for tests that need real
+// instructions: account constraints, CPIs, sysvars and the clock. Unlike the
+// `test_utils` fixtures it executes whole transactions, so a test sees what a
+// client would. Enable the `program-test` feature to use it from another
+// crate. Tests using it belong in integration test files: the bank replaces
+// the process-wide syscall stubs, which unit tests in the same binary rely on.
+
+/// Lamports given to each wallet from `funded_wallet`
+pub const TEST_WALLET_LAMPORTS: u64 = 10_000_000_000;
+
+thread_local! {
+    /// Events the program sent itself through self-CPI on this thread, as
+    /// the inner instructions' data after Anchor's event tag
+    static EMITTED_EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Run the program's entrypoint for the test bank. Anchor ties the accounts
+/// to the lifetime of their slice, which the bank's processor signature
+/// cannot name, so the slice is leaked for the instruction's duration.
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    if let Some(event) = data.strip_prefix(&anchor_lang::event::EVENT_IX_TAG_LE[..]) {
+        EMITTED_EVENTS.with(|events| events.borrow_mut().push(event.to_vec()));
+    }
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    crate::entry(program_id, accounts, data)
+}
+
+/// Stand-in for Metaplex Token Metadata, which the bank does not bundle.
+/// `CreateMetadataAccountV3` creates the mint's metadata account holding
+/// the instruction's arguments as sent; anything else fails.
+fn process_token_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let [metadata, mint, mint_authority, payer, _update_authority, system_program, ..] = accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let Some((33, args)) = data.split_first() else {
+        return Err(ProgramError::InvalidInstructionData);
+    };
+    if !mint_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (address, bump) = find_metadata_address(mint.key);
+    if *metadata.key != address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            metadata.key,
+            Rent::get()?.minimum_balance(args.len()),
+            args.len() as u64,
+            program_id,
+        ),
+        &[payer.clone(), metadata.clone(), system_program.clone()],
+        &[&[METADATA_SEED, program_id.as_ref(), mint.key.as_ref(), &[bump]]],
+    )?;
+    metadata.try_borrow_mut_data()?.copy_from_slice(args);
+    Ok(())
+}
+
+/// The custom error code a transaction failed with, e.g. to compare with
+/// `u32::from(HelixError::ShareExpired)`. `None` if it succeeded or failed
+/// for another reason.
+pub fn custom_error(result: std::result::Result<(), BanksClientError>) -> Option<u32> {
+    match result.err()?.unwrap() {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => Some(code),
+        _ => None,
+    }
+}
+
+/// A started test bank with an initialized registry
+pub struct HelixTestContext {
+    /// The running bank; its payer is the registry authority
+    pub context: ProgramTestContext,
+    /// Owner of each file registered through `register_test_file`
+    file_owners: HashMap<Pubkey, Keypair>,
+    /// Transaction IDs handed out so far
+    tx_id_count: u64,
+}
+
+impl HelixTestContext {
+    /// A bank with the program and a Token Metadata stand-in loaded, before
+    /// it starts. Add accounts or other programs to it and pass it to
+    /// `start_with`.
+    pub fn program_test() -> ProgramTest {
+        let mut program_test =
+            ProgramTest::new("helix_storage", crate::ID, processor!(process_instruction));
+        program_test.prefer_bpf(false);
+        program_test.add_program(
+            "token_metadata",
+            TOKEN_METADATA_PROGRAM_ID,
+            processor!(process_token_metadata),
+        );
+        program_test
+    }
+
+    /// Start a bank with just the program and initialize its registry
+    pub async fn new() -> Self {
+        Self::start_with(Self::program_test()).await
+    }
+
+    /// Start `program_test` and initialize the registry without fees, with
+    /// the bank's payer as authority
+    pub async fn start_with(program_test: ProgramTest) -> Self {
+        let mut test = Self {
+            context: program_test.start_with_context().await,
+            file_owners: HashMap::new(),
+            tx_id_count: 0,
+        };
+
+        let authority = test.authority();
+        let initialize = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::Initialize {
+                registry: find_registry_address().0,
+                authority,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::Initialize { base_fee_lamports: 0 }.data(),
+        };
+        test.process(&[initialize], &[])
+            .await
+            .expect("registry initializes");
+        test
+    }
+
+    /// The registry authority, which also pays for every transaction
+    pub fn authority(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// A new wallet holding `TEST_WALLET_LAMPORTS`
+    pub async fn funded_wallet(&mut self) -> Keypair {
+        let wallet = Keypair::new();
+        let transfer =
+            system_instruction::transfer(&self.authority(), &wallet.pubkey(), TEST_WALLET_LAMPORTS);
+        self.process(&[transfer], &[])
+            .await
+            .expect("wallet is funded");
+        wallet
+    }
+
+    /// Send `instructions` in one transaction paid by the authority and
+    /// signed by `signers`. The bank rejects a transaction identical to one
+    /// it already processed until a warp moves it to a new blockhash.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<(), BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await?;
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Send `instructions` as `process` does, returning the events the
+    /// program emitted through self-CPI, e.g. for `decode_file_registered`.
+    /// The bank runs the transaction on the test's thread here, which is
+    /// where the events are collected.
+    pub async fn process_for_events(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<Vec<Vec<u8>>, BanksClientError> {
+        let transaction = self.transaction(instructions, signers).await?;
+        EMITTED_EVENTS.with(|events| events.borrow_mut().clear());
+        self.context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?
+            .result?;
+        Ok(EMITTED_EVENTS.with(|events| events.take()))
+    }
+
+    /// `instructions` in a transaction at the latest blockhash, paid by the
+    /// authority and signed by `signers`
+    async fn transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> std::result::Result<Transaction, BanksClientError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        ))
+    }
+
+    /// A transaction ID not yet used in this bank
+    pub fn next_transaction_id(&mut self) -> TxId {
+        self.tx_id_count += 1;
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&self.tx_id_count.to_le_bytes());
+        TxId::from_bytes(&bytes)
+    }
+
+    /// A `register_file` instruction for an unencrypted Arweave file
+    pub fn register_file_ix(
+        &self,
+        owner: &Pubkey,
+        transaction_id: &TxId,
+        size: u64,
+    ) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::RegisterFile {
+                registry: find_registry_address().0,
+                file_record: find_file_address(transaction_id).0,
+                owner: *owner,
+                system_program: system_program::ID,
+                memo_program: None,
+                registry_ext: None,
+                receipt_merkle_tree: None,
+                receipt_tree_authority: None,
+                bubblegum_program: None,
+                log_wrapper: None,
+                compression_program: None,
+                source_record: None,
+                user_profile: None,
+                treasury: None,
+                instructions: None,
+                #[cfg(feature = "event-cpi")]
+                event_authority: find_event_authority_address().0,
+                #[cfg(feature = "event-cpi")]
+                program: crate::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::RegisterFile {
+                transaction_id: transaction_id.clone(),
+                encrypted_name: None,
+                mime_type: MimeType::try_from("application/octet-stream".to_string())
+                    .expect("valid MIME type"),
+                size,
+                is_encrypted: false,
+                memo: None,
+                storage_location: StorageLocationArg::ArweaveL1,
+            }
+            .data(),
+        }
+    }
+
+    /// Register a file of `size` bytes owned by `owner`, returning its
+    /// record's address
+    pub async fn register_test_file(&mut self, owner: &Keypair, size: u64) -> Pubkey {
+        let transaction_id = self.next_transaction_id();
+        let register = self.register_file_ix(&owner.pubkey(), &transaction_id, size);
+        self.process(&[register], &[owner])
+            .await
+            .expect("file registers");
+
+        let file = find_file_address(&transaction_id).0;
+        self.file_owners.insert(file, owner.insecure_clone());
+        file
+    }
+
+    /// A `create_share` instruction for `file` by `owner` with `args`, at
+    /// the file's next share address
+    pub async fn create_share_ix(
+        &mut self,
+        file: &Pubkey,
+        owner: &Pubkey,
+        args: CreateShareArgs,
+    ) -> Instruction {
+        let share_nonce = self.file_record(file).await.share_nonce;
+        let (recipient_profile, inbox_entry) = match args.recipient {
+            Some(recipient) => {
+                let profile = find_user_profile_address(&recipient).0;
+                let inbox_count = self
+                    .account::<UserProfile>(&profile)
+                    .await
+                    .map_or(0, |profile| profile.inbox_count);
+                (Some(profile), Some(find_inbox_address(&recipient, inbox_count).0))
+            }
+            None => (None, None),
+        };
+        let owner_token_account = self.owner_token_account(file, owner).await;
+
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::CreateShare {
+                registry: find_registry_address().0,
+                file_record: *file,
+                share_link: find_share_address(file, share_nonce).0,
+                owner: *owner,
+                system_program: system_program::ID,
+                memo_program: None,
+                recipient_profile,
+                inbox_entry,
+                contact: None,
+                owner_profile: find_user_profile_address(owner).0,
+                access_log: None,
+                template: None,
+                instructions: None,
+                owner_token_account,
+                #[cfg(feature = "event-cpi")]
+                event_authority: find_event_authority_address().0,
+                #[cfg(feature = "event-cpi")]
+                program: crate::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::CreateShare { args }.data(),
+        }
+    }
+
+    /// Share a file registered through `register_test_file` with
+    /// `recipient`, or publicly when `None`, returning the link's address
+    pub async fn create_test_share(&mut self, file: &Pubkey, recipient: Option<Pubkey>) -> Pubkey {
+        let owner = self.file_owner(file);
+        let share_nonce = self.file_record(file).await.share_nonce;
+        let args = CreateShareArgs {
+            recipient,
+            ..CreateShareArgs::default()
+        };
+        let create = self.create_share_ix(file, &owner.pubkey(), args).await;
+        self.process(&[create], &[&owner])
+            .await
+            .expect("share is created");
+
+        find_share_address(file, share_nonce).0
+    }
+
+    /// The owner of a file registered through `register_test_file`
+    pub fn file_owner(&self, file: &Pubkey) -> Keypair {
+        self.file_owners
+            .get(file)
+            .expect("file was registered through register_test_file")
+            .insecure_clone()
+    }
+
+    /// Revoke `share` of a file registered through `register_test_file`,
+    /// without notifying its recipient
+    pub async fn revoke_test_share(&mut self, file: &Pubkey, share: &Pubkey) {
+        let owner = self.file_owner(file);
+        let revoke = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::RevokeShare {
+                registry: find_registry_address().0,
+                file_record: *file,
+                share_link: *share,
+                owner: owner.pubkey(),
+                recipient_profile: None,
+                notification: None,
+                system_program: None,
+                #[cfg(feature = "event-cpi")]
+                event_authority: find_event_authority_address().0,
+                #[cfg(feature = "event-cpi")]
+                program: crate::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::RevokeShare {}.data(),
+        };
+        self.process(&[revoke], &[&owner])
+            .await
+            .expect("share is revoked");
+    }
+
+    /// A `tokenize_file` instruction minting `file`'s ownership token to
+    /// `owner`'s associated token account
+    pub fn tokenize_file_ix(&self, file: &Pubkey, owner: &Pubkey) -> Instruction {
+        let mint = find_file_mint_address(file).0;
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::TokenizeFile {
+                file_record: *file,
+                mint,
+                owner_token_account: get_associated_token_address(owner, &mint),
+                metadata: find_metadata_address(&mint).0,
+                owner: *owner,
+                token_program: anchor_spl::token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                token_metadata_program: TOKEN_METADATA_PROGRAM_ID,
+                system_program: system_program::ID,
+                #[cfg(feature = "event-cpi")]
+                event_authority: find_event_authority_address().0,
+                #[cfg(feature = "event-cpi")]
+                program: crate::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::TokenizeFile {}.data(),
+        }
+    }
+
+    /// Tokenize a file registered through `register_test_file`, returning
+    /// the owner's token account holding the ownership token
+    pub async fn tokenize_test_file(&mut self, file: &Pubkey) -> Pubkey {
+        let owner = self.file_owner(file);
+        let tokenize = self.tokenize_file_ix(file, &owner.pubkey());
+        self.process(&[tokenize], &[&owner])
+            .await
+            .expect("file is tokenized");
+
+        get_associated_token_address(&owner.pubkey(), &find_file_mint_address(file).0)
+    }
+
+    /// The token account `owner` presents for owner-gated instructions on
+    /// `file`: their associated token account once the file is tokenized
+    pub async fn owner_token_account(&mut self, file: &Pubkey, owner: &Pubkey) -> Option<Pubkey> {
+        self.file_record(file)
+            .await
+            .is_tokenized()
+            .then(|| get_associated_token_address(owner, &find_file_mint_address(file).0))
+    }
+
+    /// An instruction the registry authority signs, such as
+    /// `set_max_shares_per_file` or `reconcile_counters`; send it with
+    /// `process`, which signs as the authority
+    pub fn authority_ix<I: InstructionData>(&self, data: I) -> Instruction {
+        Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::UpdateRegistry {
+                registry: find_registry_address().0,
+                authority: self.authority(),
+                #[cfg(feature = "event-cpi")]
+                event_authority: find_event_authority_address().0,
+                #[cfg(feature = "event-cpi")]
+                program: crate::ID,
+            }
+            .to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    /// A `delete_file` instruction for `file` by `owner`, revoking `shares`
+    /// with it
+    pub async fn delete_file_ix(
+        &mut self,
+        file: &Pubkey,
+        owner: &Pubkey,
+        shares: &[Pubkey],
+    ) -> Instruction {
+        let owner_token_account = self.owner_token_account(file, owner).await;
+        let mut delete = Instruction {
+            program_id: crate::ID,
+            accounts: crate::accounts::DeleteFile {
+                registry: find_registry_address().0,
+                file_record: *file,
+                owner: *owner,
+                memo_program: None,
+                user_profile: None,
+                owner_token_account,
+                #[cfg(feature = "event-cpi")]
+                event_authority: find_event_authority_address().0,
+                #[cfg(feature = "event-cpi")]
+                program: crate::ID,
+            }
+            .to_account_metas(None),
+            data: crate::instruction::DeleteFile { memo: None }.data(),
+        };
+        delete
+            .accounts
+            .extend(shares.iter().map(|share| AccountMeta::new(*share, false)));
+        delete
+    }
+
+    /// Move a file registered through `register_test_file` to the trash,
+    /// revoking `shares` with it
+    pub async fn delete_test_file(&mut self, file: &Pubkey, shares: &[Pubkey]) {
+        let owner = self.file_owner(file);
+        let delete = self.delete_file_ix(file, &owner.pubkey(), shares).await;
+        self.process(&[delete], &[&owner])
+            .await
+            .expect("file is deleted");
+    }
+
+    /// Overwrite the program account at `address` with `account`, to set up
+    /// state no instruction reaches directly (e.g. a sponsored rent payer)
+    pub async fn write_account<T: AccountSerialize>(&mut self, address: &Pubkey, account: &T) {
+        let mut stored = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .expect("bank is reachable")
+            .expect("account exists");
+        account
+            .try_serialize(&mut &mut stored.data[..])
+            .expect("account fits its allocation");
+        self.context.set_account(address, &stored.into());
+    }
+
+    /// The lamports held at `address` (0 when it does not exist)
+    pub async fn balance(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .expect("bank is reachable")
+    }
+
+    /// The program account of type `T` at `address`, if one exists
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> Option<T> {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .expect("bank is reachable")?;
+        T::try_deserialize(&mut &account.data[..]).ok()
+    }
+
+    /// The file record at `address`
+    pub async fn file_record(&mut self, address: &Pubkey) -> FileRecord {
+        self.account(address).await.expect("file record exists")
+    }
+
+    /// The share link at `address`
+    pub async fn share_link(&mut self, address: &Pubkey) -> ShareLink {
+        self.account(address).await.expect("share link exists")
+    }
+
+    /// The storage registry
+    pub async fn registry(&mut self) -> StorageRegistry {
+        self.account(&find_registry_address().0)
+            .await
+            .expect("registry exists")
+    }
+
+    /// The bank's clock
+    pub async fn clock(&mut self) -> Clock {
+        self.context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .expect("clock is readable")
+    }
+
+    /// The bank's current Unix timestamp
+    pub async fn now(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    /// Move the bank's clock `seconds` forward, e.g. past a share's expiry
+    pub async fn warp_forward(&mut self, seconds: i64) {
+        let clock = self.next_slot().await;
+        self.context.set_sysvar(&Clock {
+            unix_timestamp: clock.unix_timestamp + seconds,
+            ..clock
+        });
+    }
+
+    /// Move the bank's clock into the next epoch, keeping its timestamp
+    pub async fn warp_to_next_epoch(&mut self) {
+        let clock = self.next_slot().await;
+        self.context.set_sysvar(&Clock {
+            epoch: clock.epoch + 1,
+            ..clock
+        });
+    }
+
+    /// Advance the bank a slot, which also gives it a new blockhash so a
+    /// transaction sent before the warp can be sent again after it. Returns
+    /// the clock from before the warp at the new slot, for the caller to set.
+    async fn next_slot(&mut self) -> Clock {
+        let clock = self.clock().await;
+        self.context
+            .warp_to_slot(clock.slot + 1)
+            .expect("bank warps forward");
+        Clock {
+            slot: clock.slot + 1,
+            ..clock
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+use crate::client::{find_registry_address, find_share_address, find_user_profile_address};
+use crate::state::{FileRecord, ShareLink, StorageRegistry, UserProfile};
+#[cfg(all(feature = "program-test", not(feature = "wasm-client")))]
+pub use crate::test_context::{custom_error, HelixTestContext};
+
+// Fixtures for unit tests of this crate and of crates built on it (enable the
+// `test-utils` feature). They build accounts in memory and hand them to code
+// as `AccountInfo`s, so they need neither a validator nor network access;
+// time is an explicit timestamp rather than a clock sysvar.
+
+/// Timestamp fixtures treat as "now"
+pub const NOW: i64 = 1_700_000_000;
+
+/// One day in seconds, for moving a fixture timestamp forward
+pub const DAY: i64 = 86_400;
+
+/// A valid transaction ID (43 base64url characters)
+pub const SAMPLE_TX_ID: &str = "HeLiXtestFixtureTransactionId0000000000000A";
+
+/// An account of type `T` with every field zeroed, which is how a newly
+/// allocated account decodes before its handler fills it in
+pub fn zeroed<T: AccountDeserialize + Discriminator>(len: usize) -> T {
+    let mut data = vec![0u8; len];
+    data[..8].copy_from_slice(&T::DISCRIMINATOR);
+    T::try_deserialize(&mut &data[..]).expect("zeroed bytes decode as defaults")
+}
+
+/// The registry as `initialize` leaves it, with `authority` and a base fee
+/// of `base_fee_lamports`
+pub fn sample_registry(authority: Pubkey, base_fee_lamports: u64) -> (Pubkey, StorageRegistry) {
+    let (address, bump) = find_registry_address();
+    let mut registry = zeroed::<StorageRegistry>(StorageRegistry::LEN);
+    registry.authority = authority;
+    registry.base_fee_lamports = base_fee_lamports;
+    registry.bump = bump;
+    (address, registry)
+}
+
+/// A live, unencrypted file of `size` bytes registered by `owner` at `NOW`.
+/// Pair it with any key: the record's own address plays no part in the
+/// logic under test.
+pub fn sample_file(owner: Pubkey, transaction_id: &str, size: u64) -> FileRecord {
+    let mut file_record = zeroed::<FileRecord>(FileRecord::LEN);
+    file_record.owner = owner;
+    file_record.transaction_id = transaction_id.to_string();
+    file_record.mime_type = "application/octet-stream".to_string();
+    file_record.size = size;
+    file_record.created_at = NOW;
+    file_record.updated_at = NOW;
+    file_record.rent_payer = owner;
+    file_record.owner_seq = 1;
+    file_record
+}
+
+/// A share of `file` created by `owner` at `NOW` under `nonce`, locked to
+/// `recipient` or public when `None`, without expiry or download limit
+pub fn sample_share(
+    file: &Pubkey,
+    owner: Pubkey,
+    recipient: Option<Pubkey>,
+    nonce: u64,
+) -> (Pubkey, ShareLink) {
+    let (address, bump) = find_share_address(file, nonce);
+    let mut share_link = zeroed::<ShareLink>(ShareLink::LEN);
+    share_link.file = *file;
+    share_link.owner = owner;
+    share_link.recipient = recipient;
+    share_link.created_at = NOW;
+    share_link.bump = bump;
+    share_link.rent_payer = owner;
+    (address, share_link)
+}
+
+/// The profile `owner` gets on first use
+pub fn sample_profile(owner: Pubkey) -> (Pubkey, UserProfile) {
+    let (address, bump) = find_user_profile_address(&owner);
+    let mut profile = zeroed::<UserProfile>(UserProfile::LEN);
+    profile.owner = owner;
+    profile.bump = bump;
+    (address, profile)
+}
+
+/// Serialize `account` with its discriminator, zero-padded to `len` bytes
+/// like an allocated account
+pub fn account_data<T: AccountSerialize>(account: &T, len: usize) -> Vec<u8> {
+    let mut data = Vec::with_capacity(len);
+    account
+        .try_serialize(&mut data)
+        .expect("fixture account serializes");
+    assert!(data.len() <= len, "fixture account exceeds {} bytes", len);
+    data.resize(len, 0);
+    data
+}
+
+/// Backing storage for an `AccountInfo` handed to code under test
+pub struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+impl TestAccount {
+    /// A Helix-owned account at `key` holding `account`, padded to `len`
+    pub fn new<T: AccountSerialize>(key: Pubkey, account: &T, len: usize) -> Self {
+        Self {
+            key,
+            owner: crate::ID,
+            lamports: 1_000_000_000,
+            data: account_data(account, len),
+        }
+    }
+
+    /// The same account owned by another program, as a spoofed copy would be
+    pub fn owned_by(mut self, owner: Pubkey) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Borrow the account as a writable, non-signer `AccountInfo`
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            false,
+            true,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+            0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::readers::assert_file_owner;
+
+    #[test]
+    fn fixtures_derive_the_client_addresses() {
+        let owner = Pubkey::new_unique();
+        let file = Pubkey::new_unique();
+        let (registry, sample) = sample_registry(owner, 5_000);
+        let (share, share_link) = sample_share(&file, owner, None, 7);
+        let (profile, _) = sample_profile(owner);
+
+        assert_eq!((registry, sample.bump), find_registry_address());
+        assert_eq!(share, find_share_address(&file, 7).0);
+        assert_eq!(profile, find_user_profile_address(&owner).0);
+        assert_eq!(share_link.bump, find_share_address(&file, 7).1);
+    }
+
+    #[test]
+    fn fixtures_round_trip_through_account_data() {
+        let owner = Pubkey::new_unique();
+        let file_record = sample_file(owner, SAMPLE_TX_ID, 1024);
+        let mut account = TestAccount::new(Pubkey::new_unique(), &file_record, FileRecord::LEN);
+
+        let read = assert_file_owner(&account.info(), &owner).unwrap();
+        assert_eq!(read.transaction_id, SAMPLE_TX_ID);
+        assert_eq!(read.size, 1024);
+        assert!(read.is_active());
+    }
+}
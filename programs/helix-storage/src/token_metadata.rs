@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::pubkey;
+
+/// Metaplex Token Metadata program
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Seed prefix of Token Metadata's metadata PDAs
+pub const METADATA_SEED: &[u8] = b"metadata";
+
+/// Instruction index of Token Metadata's `CreateMetadataAccountV3`
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+
+/// Maximum length of a metadata name
+const MAX_TOKEN_NAME_LEN: usize = 32;
+
+/// Symbol used for file ownership tokens
+const TOKEN_SYMBOL: &str = "HELIX";
+
+/// Token Metadata `DataV2`, with the optional fields Helix never sets
+/// encoded as `None`.
+#[derive(AnchorSerialize)]
+struct FileTokenData {
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: u16,
+    /// `Option<Vec<Creator>>`, never set
+    creators: Option<u8>,
+    /// `Option<Collection>`, never set
+    collection: Option<u8>,
+    /// `Option<Uses>`, never set
+    uses: Option<u8>,
+}
+
+/// Token Metadata `CreateMetadataAccountArgsV3`
+#[derive(AnchorSerialize)]
+struct CreateFileTokenMetadata {
+    data: FileTokenData,
+    is_mutable: bool,
+    /// `Option<CollectionDetails>`, never set
+    collection_details: Option<u8>,
+}
+
+/// Get the metadata account address of `mint`
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[METADATA_SEED, TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+}
+
+/// Accounts needed to create a file token's metadata
+pub struct FileTokenMetadataAccounts<'a, 'info> {
+    pub metadata: &'a AccountInfo<'info>,
+    pub mint: &'a AccountInfo<'info>,
+    /// The file record PDA, mint and update authority
+    pub file_record: &'a AccountInfo<'info>,
+    pub payer: &'a AccountInfo<'info>,
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+/// Build the `CreateMetadataAccountV3` instruction data for a file token
+/// named after `transaction_id` and pointing at `uri`.
+fn create_metadata_data(transaction_id: &str, uri: String) -> Result<Vec<u8>> {
+    let args = CreateFileTokenMetadata {
+        data: FileTokenData {
+            name: transaction_id.chars().take(MAX_TOKEN_NAME_LEN).collect(),
+            symbol: TOKEN_SYMBOL.to_string(),
+            uri,
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        is_mutable: false,
+        collection_details: None,
+    };
+
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    args.serialize(&mut data)?;
+    Ok(data)
+}
+
+/// Create the Metaplex metadata of a file's ownership token.
+/// 
+/// The file record PDA, signing with `file_record_seeds`, is both the mint
+/// and update authority. The token is named after the transaction ID and
+/// points at the file's Arweave URL. Metadata is immutable, so a mint that
+/// already has it (a file tokenized before) is left as is.
+pub fn create_file_token_metadata(
+    accounts: FileTokenMetadataAccounts,
+    file_record_seeds: &[&[u8]],
+    transaction_id: &str,
+    uri: String,
+) -> Result<()> {
+    if !accounts.metadata.data_is_empty() {
+        return Ok(());
+    }
+
+    let ix = Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(accounts.metadata.key(), false),
+            AccountMeta::new_readonly(accounts.mint.key(), false),
+            AccountMeta::new_readonly(accounts.file_record.key(), true),
+            AccountMeta::new(accounts.payer.key(), true),
+            AccountMeta::new_readonly(accounts.file_record.key(), true),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+        ],
+        data: create_metadata_data(transaction_id, uri)?,
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            accounts.metadata.clone(),
+            accounts.mint.clone(),
+            accounts.file_record.clone(),
+            accounts.payer.clone(),
+            accounts.system_program.clone(),
+            accounts.token_metadata_program.clone(),
+        ],
+        &[file_record_seeds],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_data_matches_create_metadata_account_v3() {
+        let tx_id = "a".repeat(43);
+        let uri = format!("https://arweave.net/{tx_id}");
+        let data = create_metadata_data(&tx_id, uri.clone()).unwrap();
+
+        let mut expected = vec![33];
+        for field in [&tx_id[..32], "HELIX", &uri] {
+            expected.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            expected.extend_from_slice(field.as_bytes());
+        }
+        // Royalties, then no creators, collection or uses
+        expected.extend_from_slice(&[0, 0, 0, 0, 0]);
+        // Immutable, no collection details
+        expected.extend_from_slice(&[0, 0]);
+        assert_eq!(data, expected);
+    }
+}
@@ -0,0 +1,450 @@
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+
+use anchor_lang::prelude::*;
+
+use crate::error::{
+    validate_cid, validate_mime_type, validate_name, validate_no_control_chars,
+    validate_short_code, validate_byte_length, validate_transaction_id, HelixError,
+};
+use crate::state::{
+    ENCRYPTED_NAME_LEN_CEILING, MAX_CID_LEN, MAX_CONTACT_ALIAS_LEN, MAX_DISPLAY_NAME_LEN,
+    MAX_ENCRYPTED_KEY_LEN, MAX_FILE_ALIAS_LEN, MAX_MIME_TYPE_LEN, MAX_NAME_LEN,
+    MAX_REFERENCE_NOTE_LEN, MAX_SHARE_MESSAGE_LEN, MAX_SHORT_CODE_LEN, MIN_CID_V1_LEN,
+    MIN_SHORT_CODE_LEN,
+};
+
+// Bounded string types for instruction arguments.
+//
+// Each type borsh-encodes exactly like a plain `String`, so clients build
+// the same instruction data as before, but deserialization rejects values
+// that fail validation. Invalid input therefore fails with
+// `InstructionDidNotDeserialize` before any handler runs, with the reason
+// logged by the validator. Account fields stay plain strings.
+
+/// Deserialize a string and validate it with `try_from`, mapping failures
+/// to an `InvalidData` I/O error as borsh expects
+fn deserialize_validated<R, T>(reader: &mut R) -> io::Result<T>
+where
+    R: io::Read,
+    T: TryFrom<String, Error = Error>,
+{
+    let value = String::deserialize_reader(reader)?;
+    T::try_from(value).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+/// Implement the string accessors shared by every bounded string type
+macro_rules! bounded_string {
+    ($name:ident $(<const $max:ident: usize>)?) => {
+        impl$(<const $max: usize>)? $name$(<$max>)? {
+            /// The validated string
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            /// Consume the value, returning the validated string
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl$(<const $max: usize>)? Deref for $name$(<$max>)? {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl$(<const $max: usize>)? AsRef<str> for $name$(<$max>)? {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl$(<const $max: usize>)? From<$name$(<$max>)?> for String {
+            fn from(value: $name$(<$max>)?) -> String {
+                value.0
+            }
+        }
+
+        impl$(<const $max: usize>)? fmt::Display for $name$(<$max>)? {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl$(<const $max: usize>)? AnchorDeserialize for $name$(<$max>)? {
+            fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+                deserialize_validated(reader)
+            }
+        }
+    };
+}
+
+/// Arweave transaction ID: 43 base64url characters
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct TxId(String);
+
+bounded_string!(TxId);
+
+impl TryFrom<String> for TxId {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_transaction_id(&value)?;
+        Ok(Self(value))
+    }
+}
+
+impl TxId {
+    /// The 32 bytes the ID encodes. The 43 characters carry two bits past
+    /// the 256th, which must be zero as in every real transaction ID.
+    pub fn to_bytes(&self) -> Result<[u8; 32]> {
+        let mut bytes = [0u8; 32];
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        let mut index = 0;
+        for c in self.0.bytes() {
+            let value = match c {
+                b'A'..=b'Z' => c - b'A',
+                b'a'..=b'z' => c - b'a' + 26,
+                b'0'..=b'9' => c - b'0' + 52,
+                b'-' => 62,
+                _ => 63,
+            };
+            acc = acc << 6 | u32::from(value);
+            bits += 6;
+            if bits >= 8 && index < bytes.len() {
+                bits -= 8;
+                bytes[index] = (acc >> bits) as u8;
+                acc &= (1 << bits) - 1;
+                index += 1;
+            }
+        }
+
+        require!(acc == 0, HelixError::InvalidTransactionId);
+        Ok(bytes)
+    }
+
+    /// The seed after `FILE_SEED` of the record registered under this ID
+    pub fn file_seed(&self) -> [u8; 32] {
+        crate::state::file_seed(&self.0)
+    }
+
+    /// The ID encoding `bytes`, the inverse of `to_bytes`
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        const ALPHABET: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+        let mut id = String::with_capacity(43);
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        for &byte in bytes {
+            acc = acc << 8 | u32::from(byte);
+            bits += 8;
+            while bits >= 6 {
+                bits -= 6;
+                id.push(ALPHABET[(acc >> bits) as usize & 63] as char);
+            }
+            acc &= (1 << bits) - 1;
+        }
+        id.push(ALPHABET[(acc << (6 - bits)) as usize] as char);
+        Self(id)
+    }
+}
+
+/// IPFS CID, normalized to lowercase base32 CIDv1 ("bafy...") on
+/// deserialization. CIDv0 values are rewritten as the equivalent dag-pb
+/// CIDv1, so both spellings of the same content compare equal.
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct Cid(String);
+
+bounded_string!(Cid);
+
+impl TryFrom<String> for Cid {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_cid(&value, MIN_CID_V1_LEN, MAX_CID_LEN)?;
+        if value.starts_with("Qm") {
+            return Ok(Self(cid_v0_to_v1(&value)?));
+        }
+
+        // Reject bits past the last whole byte so each CID has one spelling
+        let value = value.to_ascii_lowercase();
+        let bits = (value.len() - 1) * 5 % 8;
+        let last = value.as_bytes()[value.len() - 1];
+        let last = if last.is_ascii_lowercase() { last - b'a' } else { last - b'2' + 26 };
+        require!(last & ((1 << bits) - 1) == 0, HelixError::InvalidCid);
+        Ok(Self(value))
+    }
+}
+
+/// Rewrite a CIDv0 (base58btc sha2-256 multihash) as the base32 CIDv1 of
+/// the same multihash under the dag-pb codec
+fn cid_v0_to_v1(cid: &str) -> Result<String> {
+    const BASE58: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    const BASE32: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+    // Version 1, dag-pb codec, then the 34-byte multihash
+    let mut bytes = [0u8; 36];
+    bytes[0] = 0x01;
+    bytes[1] = 0x70;
+    for c in cid.bytes() {
+        let mut carry = BASE58
+            .iter()
+            .position(|&d| d == c)
+            .ok_or(HelixError::InvalidCid)? as u32;
+        for byte in bytes[2..].iter_mut().rev() {
+            carry += u32::from(*byte) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        require!(carry == 0, HelixError::InvalidCid);
+    }
+    require!(bytes[2] == 0x12 && bytes[3] == 0x20, HelixError::InvalidCid);
+
+    let mut v1 = String::with_capacity(MIN_CID_V1_LEN);
+    v1.push('b');
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for &byte in &bytes {
+        acc = acc << 8 | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            v1.push(BASE32[(acc >> bits) as usize & 31] as char);
+        }
+        acc &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        v1.push(BASE32[(acc << (5 - bits)) as usize] as char);
+    }
+    Ok(v1)
+}
+
+/// MIME type in "type/subtype" form, at most `MAX_MIME_TYPE_LEN` bytes
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct MimeType(String);
+
+bounded_string!(MimeType);
+
+impl TryFrom<String> for MimeType {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_byte_length(
+            "mime_type",
+            &value,
+            MAX_MIME_TYPE_LEN,
+            HelixError::MimeTypeTooLong,
+        )?;
+        validate_mime_type(&value)?;
+        Ok(Self(value))
+    }
+}
+
+/// Namespace name: 1 to `MAX_NAME_LEN` bytes of lowercase ASCII letters,
+/// digits, '.', '_' or '-'
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct Name(String);
+
+bounded_string!(Name);
+
+impl TryFrom<String> for Name {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_name(&value, MAX_NAME_LEN)?;
+        Ok(Self(value))
+    }
+}
+
+/// Global short code: `MIN_SHORT_CODE_LEN` to `MAX_SHORT_CODE_LEN`
+/// lowercase ASCII letters or digits
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct Slug(String);
+
+bounded_string!(Slug);
+
+impl TryFrom<String> for Slug {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_short_code(&value, MIN_SHORT_CODE_LEN, MAX_SHORT_CODE_LEN)?;
+        Ok(Self(value))
+    }
+}
+
+/// Public plaintext display name, at most `MAX_DISPLAY_NAME_LEN` bytes and
+/// free of control characters
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct DisplayName(String);
+
+bounded_string!(DisplayName);
+
+impl TryFrom<String> for DisplayName {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_byte_length(
+            "display_name",
+            &value,
+            MAX_DISPLAY_NAME_LEN,
+            HelixError::DisplayNameTooLong,
+        )?;
+        validate_no_control_chars("display_name", &value, HelixError::InvalidDisplayName)?;
+        Ok(Self(value))
+    }
+}
+
+/// Client-encrypted payload of at most `MAX` bytes
+#[derive(AnchorSerialize, Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedBlob<const MAX: usize>(String);
+
+bounded_string!(EncryptedBlob<const MAX: usize>);
+
+impl<const MAX: usize> TryFrom<String> for EncryptedBlob<MAX> {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        validate_byte_length("encrypted_data", &value, MAX, HelixError::EncryptedDataTooLong)?;
+        Ok(Self(value))
+    }
+}
+
+/// Encrypted filename, bounded by the hard ceiling. The registry's
+/// configured limit is still checked by the handlers.
+pub type EncryptedName = EncryptedBlob<ENCRYPTED_NAME_LEN_CEILING>;
+
+/// Encrypted decryption key for a share recipient
+pub type EncryptedKey = EncryptedBlob<MAX_ENCRYPTED_KEY_LEN>;
+
+/// Message attached to a share, encrypted to the recipient
+pub type EncryptedShareMessage = EncryptedBlob<MAX_SHARE_MESSAGE_LEN>;
+
+/// Encrypted display name of an address book contact
+pub type EncryptedAlias = EncryptedBlob<MAX_CONTACT_ALIAS_LEN>;
+
+/// Encrypted name of a file alias
+pub type EncryptedFileAlias = EncryptedBlob<MAX_FILE_ALIAS_LEN>;
+
+/// Encrypted note attached to a file reference
+pub type EncryptedNote = EncryptedBlob<MAX_REFERENCE_NOTE_LEN>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::SAMPLE_TX_ID;
+
+    /// Borsh bytes of `value` as a plain `String` argument
+    fn encoded(value: &str) -> Vec<u8> {
+        value.to_string().try_to_vec().unwrap()
+    }
+
+    /// Check `T` accepts `valid`, encodes it like a `String`, and rejects
+    /// each of `invalid` as well as every truncation of `valid`
+    fn check_newtype<T>(valid: &str, invalid: &[&str])
+    where
+        T: AnchorSerialize + AnchorDeserialize + Deref<Target = str> + fmt::Debug,
+    {
+        let bytes = encoded(valid);
+        let value = T::try_from_slice(&bytes).unwrap();
+        assert_eq!(&*value, valid);
+        assert_eq!(value.try_to_vec().unwrap(), bytes);
+
+        for len in 0..bytes.len() {
+            assert!(T::try_from_slice(&bytes[..len]).is_err(), "{valid:?} cut to {len}");
+        }
+        for value in invalid {
+            assert!(T::try_from_slice(&encoded(value)).is_err(), "{value:?}");
+        }
+    }
+
+    #[test]
+    fn newtypes_reject_invalid_values_at_deserialization() {
+        let over = |max: usize| "a".repeat(max + 1);
+
+        check_newtype::<TxId>(SAMPLE_TX_ID, &["", &SAMPLE_TX_ID[..42], &"a+".repeat(22)[..43]]);
+        check_newtype::<MimeType>(
+            "image/png",
+            &["", "image", "image/", &format!("a/{}", over(MAX_MIME_TYPE_LEN - 2))],
+        );
+        check_newtype::<Name>("reports", &["", "Reports", "re ports", &over(MAX_NAME_LEN)]);
+        check_newtype::<Slug>("abc123", &["a", "abc-123", &over(MAX_SHORT_CODE_LEN)]);
+        check_newtype::<DisplayName>("Ada 🚀", &["line\nbreak", &over(MAX_DISPLAY_NAME_LEN)]);
+        check_newtype::<EncryptedKey>("c2VjcmV0", &[&over(MAX_ENCRYPTED_KEY_LEN)]);
+        check_newtype::<EncryptedShareMessage>("", &[&over(MAX_SHARE_MESSAGE_LEN)]);
+    }
+
+    #[test]
+    fn malformed_borsh_strings_are_rejected() {
+        // Length prefix past the end of the data
+        let mut bytes = encoded(SAMPLE_TX_ID);
+        bytes[0] += 1;
+        assert!(TxId::try_from_slice(&bytes).is_err());
+        assert!(TxId::try_from_slice(&[0xff, 0xff, 0xff, 0xff]).is_err());
+
+        // Invalid UTF-8 of a valid length
+        let mut bytes = encoded("image/png");
+        bytes[4] = 0xff;
+        assert!(MimeType::try_from_slice(&bytes).is_err());
+        assert!(EncryptedKey::try_from_slice(&bytes).is_err());
+
+        // Trailing bytes after a valid value
+        let mut bytes = encoded("image/png");
+        bytes.push(0);
+        assert!(MimeType::try_from_slice(&bytes).is_err());
+    }
+
+    #[test]
+    fn arbitrary_bytes_never_panic() {
+        // Deterministic xorshift stream standing in for a fuzzer
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..2_000 {
+            let len = (next() % 64) as usize;
+            let mut bytes = vec![(next() % 64) as u8, 0, 0, 0];
+            bytes.extend((0..len).map(|_| next() as u8));
+            let _ = TxId::try_from_slice(&bytes);
+            let _ = Cid::try_from_slice(&bytes);
+            let _ = MimeType::try_from_slice(&bytes);
+            let _ = DisplayName::try_from_slice(&bytes);
+        }
+    }
+
+    #[test]
+    fn tx_id_bytes_round_trip() {
+        let tx_id = TxId::try_from(SAMPLE_TX_ID.to_string()).unwrap();
+        assert_eq!(TxId::from_bytes(&tx_id.to_bytes().unwrap()), tx_id);
+
+        for bytes in [[0u8; 32], [0xff; 32], [0x5a; 32]] {
+            assert_eq!(TxId::from_bytes(&bytes).to_bytes(), Ok(bytes));
+        }
+
+        // The last character carries two bits past the 256th, which must be zero
+        let stray_bits = TxId::try_from(format!("{}B", &SAMPLE_TX_ID[..42])).unwrap();
+        assert_eq!(stray_bits.to_bytes(), Err(HelixError::InvalidTransactionId.into()));
+    }
+
+    #[test]
+    fn cids_are_normalized_to_lowercase_v1() {
+        let v0 = "QmbWqxBEKC3P8tqsKc98xmWNzrzDtRLMiMPL8wBuTGsMnR";
+        let v1 = "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+
+        assert_eq!(Cid::try_from(v0.to_string()).unwrap().as_str(), v1);
+        assert_eq!(Cid::try_from(v1.to_string()).unwrap().as_str(), v1);
+        assert_eq!(Cid::try_from(v1.to_uppercase()).unwrap().as_str(), v1);
+
+        // A stray bit past the last whole byte gives a second spelling
+        let stray = format!("{}j", &v1[..v1.len() - 1]);
+        assert_eq!(Cid::try_from(stray), Err(HelixError::InvalidCid.into()));
+    }
+}
@@ -0,0 +1,337 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{
+    AccessLogEntry, EpochStats, FileStatus, MirrorLocator, NotificationKind, RecentFile,
+    ShareStatus, StorageLocation,
+};
+
+/// Layout version written as the first byte of every view struct.
+/// Bumped whenever a view layout changes so clients can branch on it.
+pub const VIEW_VERSION: u8 = 2;
+
+/// File metadata returned by the `get_file` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FileInfo {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub transaction_id: String,
+    pub mime_type: String,
+    pub size: u64,
+    pub is_encrypted: bool,
+    pub is_deleted: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub share_count: u32,
+    /// Resolved lifecycle status; `is_deleted` is kept for older clients
+    pub status: FileStatus,
+    pub like_count: u32,
+    /// Whether `size` was confirmed by the authority; self-reported otherwise
+    pub size_attested: bool,
+    pub storage_location: StorageLocation,
+    /// Normalized CIDv1 of IPFS content (None for Arweave content)
+    pub ipfs_cid: Option<String>,
+    /// Content URL on the default gateway for the file's content
+    pub url: String,
+    /// Every mirror of the content, for clients to fail over to
+    pub mirrors: Vec<MirrorInfo>,
+    /// Per-owner registration sequence number (0 = not assigned)
+    pub owner_seq: u64,
+}
+
+/// A file mirror returned as part of `FileInfo`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MirrorInfo {
+    pub mirror: Pubkey,
+    pub locator: MirrorLocator,
+    /// Content URL on the default gateway for the mirror's backend
+    pub url: String,
+    pub added_at: i64,
+}
+
+/// Share link state returned by the `get_share_status` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ShareStatusView {
+    pub version: u8,
+    pub file: Pubkey,
+    pub recipient: Option<Pubkey>,
+    pub status: ShareStatus,
+    pub is_valid: bool,
+    pub is_revoked: bool,
+    pub file_deleted: bool,
+    pub expires_at: Option<i64>,
+    pub max_downloads: Option<u32>,
+    pub download_count: u32,
+    /// Whether the supplied audit grant lets its auditor view the file
+    pub audit_view: bool,
+}
+
+/// Registration fee quote returned by the `estimate_fee` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FeeQuote {
+    pub version: u8,
+    pub size: u64,
+    pub base_fee_lamports: u64,
+    pub fee_lamports: u64,
+    pub waived: bool,
+}
+
+/// One file of an `OwnerFilesPage`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OwnerFileSummary {
+    pub transaction_id: String,
+    pub size: u64,
+    /// Raw `FILE_FLAG_*` bitfield, including the deleted flag
+    pub flags: u16,
+    pub updated_at: i64,
+    /// Manual ordering key from the index entry
+    pub sort_key: u32,
+    /// Per-owner registration sequence number, a stable tiebreak for files
+    /// registered in the same second (0 = not assigned)
+    pub owner_seq: u64,
+}
+
+/// Page of a wallet's file index returned by the `list_owner_files` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OwnerFilesPage {
+    pub version: u8,
+    /// Cursor the page started from
+    pub start_seq: u64,
+    /// Total entries in the index when the page was read
+    pub file_count: u64,
+    pub entries: Vec<OwnerFileSummary>,
+    /// Cursor to pass for the next page (at or past `file_count` at the end)
+    pub next_cursor: u64,
+}
+
+/// Part of a wallet's storage statement returned by the
+/// `get_storage_statement` view. File totals cover the page of the file
+/// index from `start_seq` to `next_cursor`; summing the pages of a full
+/// walk gives the wallet's totals.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct StorageStatement {
+    pub version: u8,
+    pub owner: Pubkey,
+    /// Cursor the page started from
+    pub start_seq: u64,
+    /// Cursor to pass for the next page (at or past `file_count` at the end)
+    pub next_cursor: u64,
+    /// Total entries in the index when the statement was read
+    pub file_count: u64,
+    /// Files in the page that are not deleted (archived files included)
+    pub active_files: u32,
+    /// Bytes of those files
+    pub total_bytes: u64,
+    /// Active share links of those files
+    pub active_shares: u64,
+    /// Bytes served through counted downloads of every file in the page
+    pub bytes_served: u64,
+    /// Protocol fees the wallet has paid to date (not paged)
+    pub fees_paid_lamports: u64,
+    /// Unix timestamp the statement was read at
+    pub as_of: i64,
+}
+
+/// One notification of an `InboxPage`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InboxEntrySummary {
+    pub seq: u64,
+    pub share: Pubkey,
+    pub kind: NotificationKind,
+}
+
+/// Page of a wallet's inbox returned by the `list_inbox` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct InboxPage {
+    pub version: u8,
+    /// Sequence number of the first entry requested
+    pub start_seq: u64,
+    /// Total entries ever added to the inbox when the page was read
+    pub inbox_count: u64,
+    /// Open entries in the requested range; closed entries are omitted
+    pub entries: Vec<InboxEntrySummary>,
+    /// Notifications not yet acknowledged or closed
+    pub unread_notifications: u32,
+}
+
+/// Name resolution returned by the `resolve_name` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NameResolution {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub name: String,
+    pub file: Pubkey,
+    pub transaction_id: String,
+    pub is_deleted: bool,
+}
+
+/// Public profile metadata returned by the `get_profile` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProfileView {
+    pub version: u8,
+    pub owner: Pubkey,
+    pub display_name: Option<String>,
+    /// The avatar file, or `None` if unset or no longer a usable avatar
+    pub avatar_file: Option<Pubkey>,
+    pub encryption_pubkey: Option<[u8; 32]>,
+    pub key_generation: u32,
+    pub archived_files: u16,
+    pub is_frozen: bool,
+}
+
+/// A wallet's recently accessed files returned by the `get_recent_files` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecentFilesView {
+    pub version: u8,
+    pub owner: Pubkey,
+    /// Newest first, without unused slots
+    pub entries: Vec<RecentFile>,
+}
+
+/// Endowment accounting returned by the `get_endowment` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EndowmentView {
+    pub version: u8,
+    pub file: Pubkey,
+    /// Lamports the owner can currently withdraw
+    pub balance: u64,
+    pub total_raised: u64,
+    pub total_withdrawn: u64,
+}
+
+/// A file's recent accesses returned by the `get_access_log` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AccessLogView {
+    pub version: u8,
+    pub file: Pubkey,
+    /// Newest first, without unused slots
+    pub entries: Vec<AccessLogEntry>,
+}
+
+/// A released file key returned by the `reveal_key` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReleasedKey {
+    pub version: u8,
+    pub file: Pubkey,
+    pub release_at: i64,
+    pub key_blob: String,
+}
+
+/// Registry statistics returned by the `get_registry_stats` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RegistryStats {
+    pub version: u8,
+    pub total_files: u64,
+    pub total_shares: u64,
+    pub total_bytes: u64,
+    pub current_epoch: u64,
+    pub current_epoch_stats: EpochStats,
+    pub previous_epoch_stats: EpochStats,
+    /// Archived files, not included in `total_files`
+    pub archived_files: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialize `view` as `set_return_data` does and decode it back,
+    /// checking that the version byte leads the payload
+    fn round_trip<T: AnchorSerialize + AnchorDeserialize>(view: &T) -> T {
+        let bytes = view.try_to_vec().unwrap();
+        assert_eq!(bytes[0], VIEW_VERSION);
+        T::try_from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn file_info_round_trips() {
+        let info = FileInfo {
+            version: VIEW_VERSION,
+            owner: Pubkey::new_unique(),
+            transaction_id: "a".repeat(43),
+            mime_type: "image/png".to_string(),
+            size: 4096,
+            is_encrypted: true,
+            is_deleted: false,
+            created_at: 1,
+            updated_at: 2,
+            share_count: 3,
+            status: FileStatus::Archived,
+            like_count: 4,
+            size_attested: true,
+            storage_location: StorageLocation::IrysDataItem { bundle_tx: Some([7; 32]) },
+            ipfs_cid: None,
+            url: "https://arweave.net/a".to_string(),
+            mirrors: vec![MirrorInfo {
+                mirror: Pubkey::new_unique(),
+                locator: MirrorLocator::Ipfs { cid: "bafy".to_string() },
+                url: "https://ipfs.io/ipfs/bafy".to_string(),
+                added_at: 5,
+            }],
+            owner_seq: 6,
+        };
+
+        assert_eq!(round_trip(&info), info);
+    }
+
+    #[test]
+    fn share_status_view_round_trips() {
+        let view = ShareStatusView {
+            version: VIEW_VERSION,
+            file: Pubkey::new_unique(),
+            recipient: Some(Pubkey::new_unique()),
+            status: ShareStatus::Exhausted,
+            is_valid: false,
+            is_revoked: false,
+            file_deleted: false,
+            expires_at: Some(-1),
+            max_downloads: Some(u32::MAX),
+            download_count: u32::MAX,
+            audit_view: true,
+        };
+
+        assert_eq!(round_trip(&view), view);
+    }
+
+    #[test]
+    fn fee_quote_round_trips() {
+        let quote = FeeQuote {
+            version: VIEW_VERSION,
+            size: u64::MAX,
+            base_fee_lamports: 5_000,
+            fee_lamports: 0,
+            waived: true,
+        };
+
+        assert_eq!(round_trip(&quote), quote);
+    }
+
+    #[test]
+    fn registry_stats_round_trips() {
+        let stats = RegistryStats {
+            version: VIEW_VERSION,
+            total_files: 1,
+            total_shares: 2,
+            total_bytes: 3,
+            current_epoch: 4,
+            current_epoch_stats: EpochStats { files: 5, shares: 6, downloads: 7, bytes: 8 },
+            previous_epoch_stats: EpochStats::default(),
+            archived_files: 9,
+        };
+
+        assert_eq!(round_trip(&stats), stats);
+    }
+
+    #[test]
+    fn truncated_views_fail_to_decode() {
+        let quote = FeeQuote {
+            version: VIEW_VERSION,
+            size: 1,
+            base_fee_lamports: 2,
+            fee_lamports: 2,
+            waived: false,
+        };
+        let bytes = quote.try_to_vec().unwrap();
+
+        assert!(FeeQuote::try_from_slice(&bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "event-cpi")]
+
+use helix_storage::client::find_file_address;
+use helix_storage::events::{decode_file_registered, decode_share_created};
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::state::{StorageLocation, EVENT_VERSION};
+use helix_storage::test_utils::HelixTestContext;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn file_registered_decodes_from_inner_instruction_data() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let transaction_id = test.next_transaction_id();
+
+    let register = test.register_file_ix(&owner.pubkey(), &transaction_id, 4096);
+    let events = test.process_for_events(&[register], &[&owner]).await.unwrap();
+
+    let event = events
+        .iter()
+        .find_map(|data| decode_file_registered(data).ok())
+        .expect("FileRegistered is emitted");
+    assert_eq!(event.version, EVENT_VERSION);
+    assert_eq!(event.owner, owner.pubkey());
+    assert_eq!(event.transaction_id, &*transaction_id);
+    assert_eq!(event.size, 4096);
+    assert!(!event.is_encrypted);
+    assert_eq!(event.timestamp, test.now().await);
+    assert_eq!(event.storage_location, StorageLocation::ArweaveL1);
+    let file_record = test.file_record(&find_file_address(&transaction_id).0).await;
+    assert_eq!(event.owner_seq, file_record.owner_seq);
+}
+
+#[tokio::test]
+async fn share_created_decodes_from_inner_instruction_data() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+
+    let create = test
+        .create_share_ix(&file, &owner.pubkey(), CreateShareArgs::default())
+        .await;
+    let events = test.process_for_events(&[create], &[&owner]).await.unwrap();
+
+    let event = events
+        .iter()
+        .find_map(|data| decode_share_created(data).ok())
+        .expect("ShareCreated is emitted");
+    assert_eq!(event.version, EVENT_VERSION);
+    assert_eq!(event.file, file);
+    assert_eq!(event.owner, owner.pubkey());
+    assert_eq!(event.recipient, None);
+    assert_eq!(event.expires_at, None);
+    assert!(!event.has_message);
+}
@@ -0,0 +1,222 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::{system_program, InstructionData};
+#[cfg(feature = "event-cpi")]
+use helix_storage::client::find_event_authority_address;
+use helix_storage::client::{find_registry_address, find_share_address, find_treasury_address};
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::test_utils::{HelixTestContext, DAY};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// Keeper bounty configured for these tests, well below a share's rent
+const BOUNTY: u64 = 1_000;
+
+/// Create the treasury holding `lamports` above its rent reserve and offer
+/// `BOUNTY` to keepers, with warnings `DAY` ahead of expiry
+async fn fund_bounties(test: &mut HelixTestContext, lamports: u64) {
+    let initialize = Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::InitializeTreasury {
+            registry: find_registry_address().0,
+            treasury: find_treasury_address().0,
+            authority: test.authority(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::InitializeTreasury {}.data(),
+    };
+    let fund =
+        system_instruction::transfer(&test.authority(), &find_treasury_address().0, lamports);
+    let set_bounty = test.authority_ix(helix_storage::instruction::SetExpiryWarning {
+        window_secs: DAY,
+        bounty_lamports: BOUNTY,
+    });
+    test.process(&[initialize, fund, set_bounty], &[]).await.unwrap();
+}
+
+/// Create a share of `file` expiring `seconds` from now
+async fn create_expiring_share(test: &mut HelixTestContext, file: &Pubkey, seconds: i64) -> Pubkey {
+    let owner = test.file_owner(file);
+    let share_nonce = test.file_record(file).await.share_nonce;
+    let args = CreateShareArgs {
+        expires_at: Some(test.now().await + seconds),
+        ..CreateShareArgs::default()
+    };
+    let create = test.create_share_ix(file, &owner.pubkey(), args).await;
+    test.process(&[create], &[&owner]).await.unwrap();
+    find_share_address(file, share_nonce).0
+}
+
+/// Run `process_expirations` for `keeper` over `remaining` accounts,
+/// returning the number of share links it processed
+async fn process_expirations(
+    test: &mut HelixTestContext,
+    keeper: &Keypair,
+    remaining: Vec<AccountMeta>,
+) -> u32 {
+    let mut accounts = helix_storage::accounts::ProcessExpirations {
+        registry: find_registry_address().0,
+        treasury: Some(find_treasury_address().0),
+        keeper: keeper.pubkey(),
+        #[cfg(feature = "event-cpi")]
+        event_authority: find_event_authority_address().0,
+        #[cfg(feature = "event-cpi")]
+        program: helix_storage::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(remaining);
+    let process = Instruction {
+        program_id: helix_storage::ID,
+        accounts,
+        data: helix_storage::instruction::ProcessExpirations {}.data(),
+    };
+
+    let banks_client = &mut test.context.banks_client;
+    let blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[process],
+        Some(&test.context.payer.pubkey()),
+        &[&test.context.payer, keeper],
+        blockhash,
+    );
+    let outcome = banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    outcome.result.unwrap();
+    let return_data = outcome.metadata.unwrap().return_data.unwrap();
+    u32::from_le_bytes(return_data.data.try_into().unwrap())
+}
+
+/// A writable `(share_link, file_record)` pair
+fn pair(share: Pubkey, file: Pubkey) -> [AccountMeta; 2] {
+    [AccountMeta::new(share, false), AccountMeta::new(file, false)]
+}
+
+#[tokio::test]
+async fn only_expired_pairs_are_processed_from_a_mixed_batch() {
+    let mut test = HelixTestContext::new().await;
+    fund_bounties(&mut test, 10 * BOUNTY).await;
+    let owner = test.funded_wallet().await;
+    let keeper = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let other = test.register_test_file(&owner, 2048).await;
+    let expired = create_expiring_share(&mut test, &file, DAY).await;
+    let mismatched = create_expiring_share(&mut test, &other, DAY).await;
+    let read_only = create_expiring_share(&mut test, &file, DAY).await;
+    let live = create_expiring_share(&mut test, &file, 10 * DAY).await;
+    let permanent = test.create_test_share(&file, None).await;
+    test.warp_forward(2 * DAY).await;
+    let balance = test.balance(&keeper.pubkey()).await;
+
+    let mut remaining = Vec::new();
+    // Not program accounts, or not a share link and file record
+    remaining.extend(pair(keeper.pubkey(), file));
+    remaining.extend(pair(find_registry_address().0, file));
+    remaining.extend(pair(file, expired));
+    // Expired, but paired with another file or not writable
+    remaining.extend(pair(mismatched, file));
+    remaining.extend([AccountMeta::new_readonly(read_only, false), AccountMeta::new(file, false)]);
+    // Not expired
+    remaining.extend(pair(live, file));
+    remaining.extend(pair(permanent, file));
+    remaining.extend(pair(expired, file));
+    // A share without its file record
+    remaining.push(AccountMeta::new(mismatched, false));
+
+    assert_eq!(process_expirations(&mut test, &keeper, remaining).await, 1);
+
+    assert!(test.share_link(&expired).await.is_revoked());
+    for share in [mismatched, read_only, live, permanent] {
+        assert!(!test.share_link(&share).await.is_revoked());
+    }
+    assert_eq!(test.file_record(&file).await.share_count, 3);
+    assert_eq!(test.file_record(&other).await.share_count, 1);
+    assert_eq!(test.registry().await.total_shares, 4);
+    assert_eq!(test.balance(&keeper.pubkey()).await, balance + BOUNTY);
+}
+
+#[tokio::test]
+async fn processing_expirations_again_changes_nothing() {
+    let mut test = HelixTestContext::new().await;
+    fund_bounties(&mut test, 10 * BOUNTY).await;
+    let owner = test.funded_wallet().await;
+    let keeper = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let first = create_expiring_share(&mut test, &file, DAY).await;
+    let second = create_expiring_share(&mut test, &file, DAY).await;
+    test.warp_forward(2 * DAY).await;
+    let balance = test.balance(&keeper.pubkey()).await;
+
+    let remaining = [pair(first, file), pair(second, file)].concat();
+    assert_eq!(process_expirations(&mut test, &keeper, remaining.clone()).await, 2);
+    let share_link = test.share_link(&first).await;
+    let treasury = test.balance(&find_treasury_address().0).await;
+    assert_eq!(test.balance(&keeper.pubkey()).await, balance + 2 * BOUNTY);
+    assert_eq!(test.registry().await.total_shares, 0);
+
+    // A new blockhash lets the same batch be sent again
+    test.warp_forward(1).await;
+    assert_eq!(process_expirations(&mut test, &keeper, remaining).await, 0);
+    assert_eq!(test.share_link(&first).await.flags, share_link.flags);
+    assert_eq!(test.file_record(&file).await.share_count, 0);
+    assert_eq!(test.registry().await.total_shares, 0);
+    assert_eq!(test.balance(&find_treasury_address().0).await, treasury);
+    assert_eq!(test.balance(&keeper.pubkey()).await, balance + 2 * BOUNTY);
+}
+
+#[tokio::test]
+async fn bounty_stops_when_the_treasury_runs_dry() {
+    let mut test = HelixTestContext::new().await;
+    fund_bounties(&mut test, BOUNTY).await;
+    let owner = test.funded_wallet().await;
+    let keeper = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let first = create_expiring_share(&mut test, &file, DAY).await;
+    let second = create_expiring_share(&mut test, &file, DAY).await;
+    test.warp_forward(2 * DAY).await;
+    let balance = test.balance(&keeper.pubkey()).await;
+
+    let remaining = [pair(first, file), pair(second, file)].concat();
+    assert_eq!(process_expirations(&mut test, &keeper, remaining).await, 2);
+    assert_eq!(test.balance(&keeper.pubkey()).await, balance + BOUNTY);
+}
+
+#[tokio::test]
+async fn warned_shares_earn_no_second_bounty() {
+    let mut test = HelixTestContext::new().await;
+    fund_bounties(&mut test, 10 * BOUNTY).await;
+    let owner = test.funded_wallet().await;
+    let keeper = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let warned = create_expiring_share(&mut test, &file, 2 * DAY).await;
+    let unwarned = create_expiring_share(&mut test, &file, 2 * DAY).await;
+    test.warp_forward(DAY + 1).await;
+    let balance = test.balance(&keeper.pubkey()).await;
+
+    let notify = Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::NotifyExpiringShare {
+            registry: find_registry_address().0,
+            share_link: warned,
+            file_record: file,
+            treasury: Some(find_treasury_address().0),
+            keeper: keeper.pubkey(),
+            #[cfg(feature = "event-cpi")]
+            event_authority: find_event_authority_address().0,
+            #[cfg(feature = "event-cpi")]
+            program: helix_storage::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::NotifyExpiringShare {}.data(),
+    };
+    test.process(&[notify], &[&keeper]).await.unwrap();
+    assert_eq!(test.balance(&keeper.pubkey()).await, balance + BOUNTY);
+
+    test.warp_forward(DAY).await;
+    let remaining = [pair(warned, file), pair(unwarned, file)].concat();
+    assert_eq!(process_expirations(&mut test, &keeper, remaining).await, 2);
+    assert_eq!(test.balance(&keeper.pubkey()).await, balance + 2 * BOUNTY);
+}
@@ -0,0 +1,269 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::InstructionData;
+#[cfg(feature = "event-cpi")]
+use helix_storage::client::find_event_authority_address;
+use helix_storage::client::find_registry_address;
+use helix_storage::error::HelixError;
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::state::RegistryCounters;
+use helix_storage::test_utils::{custom_error, HelixTestContext};
+use solana_sdk::signature::{Keypair, Signer};
+
+/// A `restore_file` instruction for `file` by its owner
+fn restore_file_ix(file: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::RestoreFile {
+            registry: find_registry_address().0,
+            file_record: *file,
+            owner: *owner,
+            authority: None,
+            owner_token_account: None,
+            #[cfg(feature = "event-cpi")]
+            event_authority: find_event_authority_address().0,
+            #[cfg(feature = "event-cpi")]
+            program: helix_storage::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::RestoreFile {}.data(),
+    }
+}
+
+/// The registry counters recounted from `files` and their share links
+async fn recount(test: &mut HelixTestContext, files: &[Pubkey]) -> RegistryCounters {
+    let mut counters = RegistryCounters::default();
+    for file in files {
+        let file_record = test.file_record(file).await;
+        if file_record.is_active() {
+            counters.total_files += 1;
+            counters.total_bytes += file_record.size;
+        }
+        counters.total_shares += u64::from(file_record.share_count);
+    }
+    counters
+}
+
+/// A `close_file` instruction for `file`, refunding `rent_payer`
+fn close_file_ix(file: &Pubkey, owner: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::CloseFile {
+            registry: find_registry_address().0,
+            file_record: *file,
+            owner: *owner,
+            rent_payer: *rent_payer,
+            index_entry: None,
+            previous_entry: None,
+            #[cfg(feature = "event-cpi")]
+            event_authority: find_event_authority_address().0,
+            #[cfg(feature = "event-cpi")]
+            program: helix_storage::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::CloseFile {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn owner_close_of_a_sponsored_file_refunds_the_sponsor() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let sponsor = Keypair::new().pubkey();
+    let file = test.register_test_file(&owner, 1024).await;
+
+    // Record the sponsor as the wallet that funded the record
+    let mut file_record = test.file_record(&file).await;
+    file_record.rent_payer = sponsor;
+    test.write_account(&file, &file_record).await;
+    test.delete_test_file(&file, &[]).await;
+    let rent = test.balance(&file).await;
+
+    // The owner cannot redirect the sponsor's rent to themselves
+    let to_owner = close_file_ix(&file, &owner.pubkey(), &owner.pubkey());
+    let result = test.process(&[to_owner], &[&owner]).await;
+    assert_eq!(custom_error(result), Some(HelixError::RentPayerMismatch.into()));
+
+    let close = close_file_ix(&file, &owner.pubkey(), &sponsor);
+    test.process(&[close], &[&owner]).await.unwrap();
+    assert_eq!(test.balance(&sponsor).await, rent);
+    assert_eq!(test.balance(&file).await, 0);
+}
+
+#[tokio::test]
+async fn owner_close_of_an_unsponsored_file_refunds_the_owner() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    test.delete_test_file(&file, &[]).await;
+    let rent = test.balance(&file).await;
+    let before = test.balance(&owner.pubkey()).await;
+
+    let close = close_file_ix(&file, &owner.pubkey(), &owner.pubkey());
+    test.process(&[close], &[&owner]).await.unwrap();
+    assert_eq!(test.balance(&owner.pubkey()).await, before + rent);
+}
+
+#[tokio::test]
+async fn sharing_revoking_and_registering_work_after_a_reconcile() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let first = test.create_test_share(&file, None).await;
+    let second = test.create_test_share(&file, None).await;
+
+    // Recount the registry as if it had lost track of both shares
+    let counters = RegistryCounters {
+        total_files: 1,
+        total_bytes: 1024,
+        ..RegistryCounters::default()
+    };
+    let reconcile = test.authority_ix(helix_storage::instruction::ReconcileCounters {
+        counters,
+        reason_hash: [7; 32],
+    });
+    test.process(&[reconcile], &[]).await.unwrap();
+    assert_eq!(test.registry().await.counters(), counters);
+
+    // New shares take the file's next nonce, clear of the existing links
+    let third = test.create_test_share(&file, None).await;
+    assert_ne!(third, first);
+    assert_ne!(third, second);
+    assert_eq!(test.file_record(&file).await.share_nonce, 3);
+    assert_eq!(test.registry().await.total_shares, 1);
+
+    // Revoking releases each link once, without underflowing the recount
+    test.revoke_test_share(&file, &first).await;
+    test.revoke_test_share(&file, &third).await;
+    assert_eq!(test.registry().await.total_shares, 0);
+    assert_eq!(test.file_record(&file).await.share_count, 1);
+
+    let other = test.register_test_file(&owner, 2048).await;
+    assert!(test.file_record(&other).await.is_active());
+    let registry = test.registry().await;
+    assert_eq!(registry.total_files, 2);
+    assert_eq!(registry.total_bytes, 3072);
+}
+
+#[tokio::test]
+async fn reconcile_rejects_counters_beyond_the_share_limit() {
+    let mut test = HelixTestContext::new().await;
+    let set_limit =
+        test.authority_ix(helix_storage::instruction::SetMaxSharesPerFile { max_shares: 2 });
+    test.process(&[set_limit], &[]).await.unwrap();
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+
+    let over_limit = RegistryCounters {
+        total_files: 1,
+        total_shares: 3,
+        total_bytes: 1024,
+        ..RegistryCounters::default()
+    };
+    let reconcile = test.authority_ix(helix_storage::instruction::ReconcileCounters {
+        counters: over_limit,
+        reason_hash: [0; 32],
+    });
+    let result = test.process(&[reconcile], &[]).await;
+    assert_eq!(custom_error(result), Some(HelixError::ImplausibleCounters.into()));
+
+    // The limit also caps the shares a file can hold
+    test.create_test_share(&file, None).await;
+    test.create_test_share(&file, None).await;
+    let share_nonce = test.file_record(&file).await.share_nonce;
+    let create = test
+        .create_share_ix(&file, &owner.pubkey(), CreateShareArgs::default())
+        .await;
+    let result = test.process(&[create], &[&owner]).await;
+    assert_eq!(custom_error(result), Some(HelixError::ShareLimitReached.into()));
+    assert_eq!(test.file_record(&file).await.share_nonce, share_nonce);
+}
+
+#[tokio::test]
+async fn deleting_a_shared_file_revokes_every_live_share() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let first = test.create_test_share(&file, None).await;
+    let second = test.create_test_share(&file, None).await;
+
+    // A live share left out blocks the deletion
+    let partial = test.delete_file_ix(&file, &owner.pubkey(), &[first]).await;
+    let result = test.process(&[partial], &[&owner]).await;
+    assert_eq!(custom_error(result), Some(HelixError::FileHasActiveShares.into()));
+    assert!(!test.share_link(&first).await.is_revoked());
+    assert_eq!(test.registry().await.total_shares, 2);
+
+    test.delete_test_file(&file, &[first, second]).await;
+    assert!(test.file_record(&file).await.is_deleted());
+    assert_eq!(test.file_record(&file).await.share_count, 0);
+    assert!(test.share_link(&first).await.is_revoked());
+    assert!(test.share_link(&second).await.is_revoked());
+    assert_eq!(test.registry().await.counters(), RegistryCounters::default());
+}
+
+#[tokio::test]
+async fn restoring_a_deleted_file_keeps_its_shares_revoked() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let share = test.create_test_share(&file, None).await;
+    test.delete_test_file(&file, &[share]).await;
+
+    let restore = restore_file_ix(&file, &owner.pubkey());
+    test.process(&[restore], &[&owner]).await.unwrap();
+
+    let file_record = test.file_record(&file).await;
+    assert!(file_record.is_active());
+    assert_eq!(file_record.deleted_at, 0);
+    assert_eq!(file_record.share_count, 0);
+    assert!(test.share_link(&share).await.is_revoked());
+    let registry = test.registry().await;
+    assert_eq!(registry.total_files, 1);
+    assert_eq!(registry.total_bytes, 1024);
+    assert_eq!(registry.total_shares, 0);
+
+    // The restored file shares again from its next nonce
+    let next = test.create_test_share(&file, None).await;
+    assert_ne!(next, share);
+    assert_eq!(test.registry().await.total_shares, 1);
+}
+
+#[tokio::test]
+async fn counters_match_a_recount_through_delete_and_restore() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let kept = test.register_test_file(&owner, 1024).await;
+    let deleted = test.register_test_file(&owner, 2048).await;
+    let restored = test.register_test_file(&owner, 4096).await;
+    let files = [kept, deleted, restored];
+    test.create_test_share(&kept, None).await;
+    let deleted_share = test.create_test_share(&deleted, None).await;
+    let restored_share = test.create_test_share(&restored, None).await;
+
+    test.delete_test_file(&deleted, &[deleted_share]).await;
+    test.delete_test_file(&restored, &[restored_share]).await;
+    let restore = restore_file_ix(&restored, &owner.pubkey());
+    test.process(&[restore], &[&owner]).await.unwrap();
+    test.create_test_share(&restored, None).await;
+
+    let counters = recount(&mut test, &files).await;
+    assert_eq!(
+        counters,
+        RegistryCounters {
+            total_files: 2,
+            total_shares: 2,
+            total_bytes: 5120,
+            ..RegistryCounters::default()
+        }
+    );
+    assert_eq!(test.registry().await.counters(), counters);
+
+    // Reconciling to the recount leaves the counters as they are
+    let reconcile = test.authority_ix(helix_storage::instruction::ReconcileCounters {
+        counters,
+        reason_hash: [1; 32],
+    });
+    test.process(&[reconcile], &[]).await.unwrap();
+    assert_eq!(test.registry().await.counters(), counters);
+}
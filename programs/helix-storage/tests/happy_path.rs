@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData};
+#[cfg(feature = "event-cpi")]
+use helix_storage::client::find_event_authority_address;
+use helix_storage::client::{find_registry_address, find_share_address};
+use helix_storage::error::HelixError;
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::test_utils::{custom_error, HelixTestContext, DAY};
+use solana_sdk::signature::Signer;
+
+/// A `record_download` instruction for `downloader` on `share`
+fn record_download_ix(file: &Pubkey, share: &Pubkey, downloader: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::RecordDownload {
+            registry: find_registry_address().0,
+            share_link: *share,
+            file_record: *file,
+            downloader: *downloader,
+            session: None,
+            downloader_profile: None,
+            owner_profile: None,
+            access_log: None,
+            treasury: None,
+            system_program: system_program::ID,
+            fee_profile: None,
+            #[cfg(feature = "event-cpi")]
+            event_authority: find_event_authority_address().0,
+            #[cfg(feature = "event-cpi")]
+            program: helix_storage::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::RecordDownload { recipient_salt: None }.data(),
+    }
+}
+
+#[tokio::test]
+async fn registration_records_the_file() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+
+    let file = test.register_test_file(&owner, 4096).await;
+
+    let file_record = test.file_record(&file).await;
+    assert_eq!(file_record.owner, owner.pubkey());
+    assert_eq!(file_record.size, 4096);
+    assert_eq!(file_record.created_at, test.now().await);
+    assert!(file_record.is_active());
+    let registry = test.registry().await;
+    assert_eq!(registry.total_files, 1);
+    assert_eq!(registry.total_bytes, 4096);
+}
+
+#[tokio::test]
+async fn share_is_downloadable_until_expiry() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let recipient = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let expires_at = test.now().await + DAY;
+    let create = test
+        .create_share_ix(
+            &file,
+            &owner.pubkey(),
+            CreateShareArgs {
+                recipient: Some(recipient.pubkey()),
+                expires_at: Some(expires_at),
+                ..CreateShareArgs::default()
+            },
+        )
+        .await;
+    test.process(&[create], &[&owner]).await.unwrap();
+    let share = find_share_address(&file, 0).0;
+
+    let download = record_download_ix(&file, &share, &recipient.pubkey());
+    test.process(std::slice::from_ref(&download), &[&recipient])
+        .await
+        .unwrap();
+    let share_link = test.share_link(&share).await;
+    assert_eq!(share_link.download_count, 1);
+    assert_eq!(share_link.bytes_served, 1024);
+
+    test.warp_forward(DAY + 1).await;
+    let result = test.process(&[download], &[&recipient]).await;
+    assert_eq!(custom_error(result), Some(HelixError::ShareExpired.into()));
+}
+
+#[tokio::test]
+async fn shares_take_consecutive_nonces() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let recipient = Pubkey::new_unique();
+    let file = test.register_test_file(&owner, 1024).await;
+
+    let first = test.create_test_share(&file, Some(recipient)).await;
+    let second = test.create_test_share(&file, None).await;
+
+    assert_eq!(first, find_share_address(&file, 0).0);
+    assert_eq!(second, find_share_address(&file, 1).0);
+    assert_eq!(test.share_link(&first).await.recipient, Some(recipient));
+    assert_eq!(test.file_record(&file).await.share_count, 2);
+    assert_eq!(test.registry().await.total_shares, 2);
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{system_program, InstructionData};
+use helix_storage::client::{find_file_address, find_registry_address, find_registry_ext_address};
+use helix_storage::receipt::BUBBLEGUM_PROGRAM_ID;
+use helix_storage::test_utils::{custom_error, HelixTestContext};
+use solana_program_test::processor;
+use solana_sdk::signature::Signer;
+
+/// Error code the Bubblegum stand-in fails every instruction with
+const MINT_REJECTED: u32 = 0xb0b;
+
+/// Stand-in for Bubblegum that rejects every mint
+fn process_bubblegum(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    Err(ProgramError::Custom(MINT_REJECTED))
+}
+
+/// Start a bank with the Bubblegum stand-in loaded and receipts minted
+/// into `tree`
+async fn start_with_receipts(tree: Pubkey) -> HelixTestContext {
+    let mut program_test = HelixTestContext::program_test();
+    program_test.add_program("bubblegum", BUBBLEGUM_PROGRAM_ID, processor!(process_bubblegum));
+    let mut test = HelixTestContext::start_with(program_test).await;
+
+    let initialize = Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::InitializeRegistryExt {
+            registry: find_registry_address().0,
+            registry_ext: find_registry_ext_address().0,
+            authority: test.authority(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::InitializeRegistryExt {}.data(),
+    };
+    let configure = Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::UpdateRegistryExt {
+            registry: find_registry_address().0,
+            registry_ext: find_registry_ext_address().0,
+            authority: test.authority(),
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::SetReceiptConfig {
+            receipt_tree: tree,
+            receipt_collection: Pubkey::default(),
+        }
+        .data(),
+    };
+    test.process(&[initialize, configure], &[]).await.unwrap();
+    test
+}
+
+#[tokio::test]
+async fn failed_receipt_mint_fails_the_registration() {
+    let tree = Pubkey::new_unique();
+    let mut test = start_with_receipts(tree).await;
+    let owner = test.funded_wallet().await;
+    let transaction_id = test.next_transaction_id();
+
+    // Request a receipt by supplying the receipt accounts
+    let mut register = test.register_file_ix(&owner.pubkey(), &transaction_id, 1024);
+    let accounts = helix_storage::accounts::RegisterFile {
+        registry: find_registry_address().0,
+        file_record: find_file_address(&transaction_id).0,
+        owner: owner.pubkey(),
+        system_program: system_program::ID,
+        memo_program: None,
+        registry_ext: Some(find_registry_ext_address().0),
+        receipt_merkle_tree: Some(tree),
+        receipt_tree_authority: Some(Pubkey::new_unique()),
+        bubblegum_program: Some(BUBBLEGUM_PROGRAM_ID),
+        log_wrapper: Some(Pubkey::new_unique()),
+        compression_program: Some(Pubkey::new_unique()),
+        source_record: None,
+        user_profile: None,
+        treasury: None,
+        instructions: None,
+        #[cfg(feature = "event-cpi")]
+        event_authority: helix_storage::client::find_event_authority_address().0,
+        #[cfg(feature = "event-cpi")]
+        program: helix_storage::ID,
+    };
+    register.accounts = accounts.to_account_metas(None);
+
+    let result = test.process(&[register], &[&owner]).await;
+    assert_eq!(custom_error(result), Some(MINT_REJECTED));
+
+    // The registration rolled back with the mint
+    let file = find_file_address(&transaction_id).0;
+    let account = test.context.banks_client.get_account(file).await.unwrap();
+    assert!(account.is_none());
+    assert_eq!(test.registry().await.total_files, 0);
+}
@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_lang::InstructionData;
+use anchor_spl::token::spl_token;
+use anchor_spl::token::spl_token::state::{Account as SplTokenAccount, AccountState};
+use helix_storage::client::find_file_mint_address;
+use helix_storage::error::HelixError;
+use helix_storage::instructions::create_share::CreateShareArgs;
+use helix_storage::test_utils::{custom_error, HelixTestContext};
+use helix_storage::token_metadata::find_metadata_address;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// A `claim_tokenized_file` instruction for `holder` presenting `token_account`
+fn claim_ix(file: &Pubkey, holder: &Pubkey, token_account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: helix_storage::ID,
+        accounts: helix_storage::accounts::ClaimTokenizedFile {
+            file_record: *file,
+            mint: find_file_mint_address(file).0,
+            holder_token_account: *token_account,
+            holder: *holder,
+            #[cfg(feature = "event-cpi")]
+            event_authority: helix_storage::client::find_event_authority_address().0,
+            #[cfg(feature = "event-cpi")]
+            program: helix_storage::ID,
+        }
+        .to_account_metas(None),
+        data: helix_storage::instruction::ClaimTokenizedFile {}.data(),
+    }
+}
+
+/// Move `file`'s ownership token from `from` into a new token account of
+/// `escrow`, returning its address
+async fn escrow_token(
+    test: &mut HelixTestContext,
+    file: &Pubkey,
+    owner: &Keypair,
+    from: &Pubkey,
+    escrow: &Pubkey,
+) -> Pubkey {
+    let escrow_account = Keypair::new();
+    let rent = test
+        .context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(SplTokenAccount::LEN);
+    let instructions = [
+        system_instruction::create_account(
+            &test.authority(),
+            &escrow_account.pubkey(),
+            rent,
+            SplTokenAccount::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_account3(
+            &spl_token::ID,
+            &escrow_account.pubkey(),
+            &find_file_mint_address(file).0,
+            escrow,
+        )
+        .unwrap(),
+        spl_token::instruction::transfer(
+            &spl_token::ID,
+            from,
+            &escrow_account.pubkey(),
+            &owner.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap(),
+    ];
+    test.process(&instructions, &[owner, &escrow_account])
+        .await
+        .unwrap();
+    escrow_account.pubkey()
+}
+
+#[tokio::test]
+async fn tokenization_attaches_metadata_naming_the_file() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+
+    test.tokenize_test_file(&file).await;
+
+    let metadata = find_metadata_address(&find_file_mint_address(&file).0).0;
+    let account = test
+        .context
+        .banks_client
+        .get_account(metadata)
+        .await
+        .unwrap()
+        .expect("metadata is created");
+    let mut data = &account.data[..];
+    let name = String::deserialize(&mut data).unwrap();
+    let symbol = String::deserialize(&mut data).unwrap();
+    let uri = String::deserialize(&mut data).unwrap();
+    let file_record = test.file_record(&file).await;
+    assert_eq!(name, file_record.transaction_id[..32]);
+    assert_eq!(symbol, "HELIX");
+    assert_eq!(uri, format!("https://arweave.net/{}", file_record.transaction_id));
+}
+
+#[tokio::test]
+async fn escrowed_token_locks_out_the_recorded_owner() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let owner_token_account = test.tokenize_test_file(&file).await;
+
+    // Holding the token, the recorded owner still acts on the file
+    test.create_test_share(&file, None).await;
+
+    let escrow = test.funded_wallet().await;
+    let escrow_account =
+        escrow_token(&mut test, &file, &owner, &owner_token_account, &escrow.pubkey()).await;
+    assert_eq!(test.file_record(&file).await.owner, owner.pubkey());
+
+    let create = test
+        .create_share_ix(&file, &owner.pubkey(), CreateShareArgs::default())
+        .await;
+    let result = test.process(&[create], &[&owner]).await;
+    assert_eq!(custom_error(result), Some(HelixError::FileTokenRequired.into()));
+
+    // Control follows the token once the escrow claims the record
+    let claim = claim_ix(&file, &escrow.pubkey(), &escrow_account);
+    test.process(&[claim], &[&escrow]).await.unwrap();
+    let mut create = test
+        .create_share_ix(&file, &escrow.pubkey(), CreateShareArgs::default())
+        .await;
+    let escrow_ata = test.owner_token_account(&file, &escrow.pubkey()).await;
+    let token_meta = create
+        .accounts
+        .iter_mut()
+        .find(|meta| Some(meta.pubkey) == escrow_ata)
+        .expect("associated token account is presented");
+    token_meta.pubkey = escrow_account;
+    test.process(&[create], &[&escrow]).await.unwrap();
+    assert_eq!(test.file_record(&file).await.share_count, 2);
+}
+
+#[tokio::test]
+async fn frozen_token_locks_out_the_holder() {
+    let mut test = HelixTestContext::new().await;
+    let owner = test.funded_wallet().await;
+    let file = test.register_test_file(&owner, 1024).await;
+    let owner_token_account = test.tokenize_test_file(&file).await;
+
+    // File mints have no freeze authority, so freeze the account in place
+    let mut account = test
+        .context
+        .banks_client
+        .get_account(owner_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut token_account = SplTokenAccount::unpack(&account.data).unwrap();
+    token_account.state = AccountState::Frozen;
+    SplTokenAccount::pack(token_account, &mut account.data).unwrap();
+    test.context.set_account(&owner_token_account, &account.into());
+
+    let create = test
+        .create_share_ix(&file, &owner.pubkey(), CreateShareArgs::default())
+        .await;
+    let result = test.process(&[create], &[&owner]).await;
+    assert_eq!(custom_error(result), Some(HelixError::FileTokenRequired.into()));
+    assert_eq!(test.file_record(&file).await.share_count, 0);
+}
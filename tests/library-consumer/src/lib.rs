@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use helix_storage::{FileRecord, HelixError};
+
+// Compiles against helix-storage with the entrypoint disabled, the way another
+// program in the workspace would depend on it for its account types and errors.
+
+/// Require that a file record belongs to `owner` and has not been deleted
+pub fn require_live_file(file: &FileRecord, owner: &Pubkey) -> Result<()> {
+    require_keys_eq!(file.owner, *owner, HelixError::UnauthorizedOwner);
+    require!(!file.is_deleted(), HelixError::FileAlreadyDeleted);
+    Ok(())
+}
+
+/// Program ID of the Helix Storage program this crate was built against
+pub fn helix_program_id() -> Pubkey {
+    helix_storage::ID
+}